@@ -0,0 +1,108 @@
+//! Criterion benchmarks: `cargo bench`, or `cargo bench -- porter/word` for
+//! one group, with the reports in target/criterion
+//!
+//! The words are Martin Porter's test vocabulary, and the text is its words
+//! run together into lines of prose. The corpus repeats the first words of
+//! the vocabulary as natural text repeats its words, by Zipf's law: the
+//! word of rank r appears 1/r as often as the first.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use m_porter_stemmer_rust::pipeline::Pipeline;
+use m_porter_stemmer_rust::text::{stem_text, stem_text_with, TextOptions};
+use m_porter_stemmer_rust::corpus::stem_corpus;
+use m_porter_stemmer_rust::{fixed, Algorithm, PorterStemmer, Stemmer};
+
+const VOCABULARY: &str = include_str!("../test_data/porter/voc.txt");
+
+/// The vocabulary as text: sentences of twelve words, four to a line
+fn prose() -> String {
+    let mut text = String::new();
+    for (i, word) in VOCABULARY.lines().enumerate() {
+        text.push_str(word);
+        text.push_str(match i % 48 {
+            47 => ".\n",
+            n if n % 12 == 11 => ". ",
+            5 => ", ",
+            _ => " ",
+        });
+    }
+    text
+}
+
+fn single_words(c: &mut Criterion) {
+    let mut group = c.benchmark_group("porter/word");
+    let mut stemmer = PorterStemmer::new();
+    let mut buffer = [0; 32];
+    for word in ["cats", "running", "generalizations", "counterrevolutionaries"] {
+        group.bench_with_input(BenchmarkId::new("stem", word), word, |b, word| {
+            b.iter(|| stemmer.stem(black_box(word)))
+        });
+        group.bench_with_input(BenchmarkId::new("fixed", word), word, |b, word| {
+            b.iter(|| fixed::stem_into(black_box(word), &mut buffer).map(str::len))
+        });
+    }
+    group.finish();
+}
+
+fn batch(c: &mut Criterion) {
+    let words: Vec<&str> = VOCABULARY.lines().collect();
+    let mut group = c.benchmark_group("batch");
+    group.throughput(Throughput::Elements(words.len() as u64));
+    for &algorithm in Algorithm::ALL {
+        let mut stemmer = algorithm.stemmer(false);
+        group.bench_function(algorithm.name(), |b| {
+            b.iter(|| words.iter().map(|word| stemmer.stem(black_box(word)).len()).sum::<usize>())
+        });
+    }
+    let mut folding = PorterStemmer::new().with_diacritic_folding(true);
+    group.bench_function("porter+fold", |b| {
+        b.iter(|| words.iter().map(|word| folding.stem(black_box(word)).len()).sum::<usize>())
+    });
+    group.finish();
+}
+
+fn text_pipeline(c: &mut Criterion) {
+    let text = prose();
+    let mut group = c.benchmark_group("text");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.sample_size(20);
+    let mut stemmer = PorterStemmer::new();
+    group.bench_function("stem_text", |b| b.iter(|| stem_text(&mut stemmer, black_box(&text))));
+    let options = TextOptions::new().preserve_case(true).min_length(4);
+    group.bench_function("stem_text_with", |b| b.iter(|| stem_text_with(&mut stemmer, black_box(&text), &options)));
+    for workers in [1, 4] {
+        let pipeline = Pipeline::new().workers(workers);
+        group.bench_with_input(BenchmarkId::new("pipeline", workers), &pipeline, |b, pipeline| {
+            b.iter(|| {
+                let mut output = Vec::with_capacity(text.len());
+                pipeline.run(PorterStemmer::new, text.as_bytes(), &mut output, &TextOptions::new()).unwrap();
+                output
+            })
+        });
+    }
+    group.finish();
+}
+
+fn corpus(c: &mut Criterion) {
+    const DISTINCT: usize = 2000;
+    let words: Vec<&str> = VOCABULARY.lines().take(DISTINCT).collect();
+    let mut tokens = Vec::new();
+    for i in 0..DISTINCT {
+        tokens.extend((1..=DISTINCT).filter(|rank| i % rank == 0).map(|rank| words[rank - 1]));
+    }
+    let mut group = c.benchmark_group("corpus");
+    group.throughput(Throughput::Elements(tokens.len() as u64));
+    let mut stemmer = PorterStemmer::new();
+    group.bench_function("each_token", |b| {
+        b.iter(|| tokens.iter().map(|token| stemmer.stem(black_box(token))).collect::<Vec<String>>())
+    });
+    group.bench_function("stem_corpus", |b| {
+        b.iter(|| stem_corpus(&mut stemmer as &mut dyn Stemmer, black_box(&tokens).iter().copied()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, single_words, batch, text_pipeline, corpus);
+criterion_main!(benches);
@@ -0,0 +1,13 @@
+//! Generates the gRPC service of `proto/porterstem.proto` for the binary's
+//! `serve --grpc`, when built with the `grpc` feature. The `.proto` file is
+//! parsed by protox, so no `protoc` has to be installed.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto");
+        let descriptors = protox::compile(["porterstem.proto"], ["proto"]).expect("parsing proto/porterstem.proto");
+        tonic_prost_build::configure().compile_fds(descriptors).expect("generating the gRPC service");
+    }
+}
@@ -0,0 +1,227 @@
+//! Smoke test on a whole book: `cargo run --release --example smoke -- pg1342.txt`
+//!
+//! Stems every word of each file given, such as a Project Gutenberg plain
+//! text book, and checks each stem against invariants that hold for any
+//! input: stemming does not panic, the stem is no longer than its word, a
+//! word of ASCII letters has an ASCII stem, and the Porter stemmer agrees
+//! with the allocation-free `fixed` core. Then it prints statistics of the
+//! book's words and stems, and each word that broke an invariant, for a
+//! quick check on realistic prose, with its archaic spellings, odd
+//! punctuation and typesetting leftovers, beyond the test vocabulary.
+//!
+//! `--algorithm NAME` checks another English stemmer instead of Porter.
+//! The exit status is 1 if any word broke an invariant, and 2 for invalid
+//! arguments or a file that cannot be read.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+use std::time::{Duration, Instant};
+
+use m_porter_stemmer_rust::{fixed, text, Algorithm, Stemmer};
+
+/// Broken invariants printed at most, per book
+const MAX_SHOWN: usize = 20;
+
+/// Most frequent stems printed
+const TOP_STEMS: usize = 10;
+
+/// What stemming one book found
+#[derive(Debug, Default)]
+struct Report {
+    tokens: usize,
+    /// Occurrences of each distinct word, as it appears in the text
+    words: HashMap<String, usize>,
+    /// Occurrences of each distinct stem
+    stems: HashMap<String, usize>,
+    /// Tokens whose stem is the token lowercased
+    unchanged: usize,
+    token_chars: usize,
+    stem_chars: usize,
+    elapsed: Duration,
+    /// Each word that broke an invariant, with the invariant
+    violations: Vec<(String, String)>,
+}
+
+/// Stems `word`, checking the invariants, and returns the stem or the
+/// invariant broken
+fn check(stemmer: &mut dyn Stemmer, algorithm: Algorithm, word: &str) -> Result<String, String> {
+    let stem = panic::catch_unwind(AssertUnwindSafe(|| stemmer.stem(word))).map_err(|payload| {
+        let message = payload.downcast_ref::<&str>().copied().or(payload.downcast_ref::<String>().map(String::as_str));
+        format!("panicked: {}", message.unwrap_or("(no message)"))
+    })?;
+    if stem.chars().count() > word.chars().count() {
+        return Err(format!("stem {:?} is longer than the word", stem));
+    }
+    if word.is_ascii() && !stem.is_ascii() {
+        return Err(format!("stem {:?} of an ASCII word is not ASCII", stem));
+    }
+    if algorithm == Algorithm::Porter && word.is_ascii() {
+        let mut buffer = vec![0; word.len()];
+        match fixed::stem_into(word, &mut buffer) {
+            Ok(fixed) if fixed == stem => {},
+            other => return Err(format!("stem {:?}, but the fixed core gives {:?}", stem, other)),
+        }
+    }
+    Ok(stem)
+}
+
+/// Stems every word of `book`
+fn smoke(algorithm: Algorithm, book: &str) -> Report {
+    let mut report = Report::default();
+    let mut stemmer = algorithm.stemmer(false);
+    let start = Instant::now();
+    for token in text::tokens_for(&*stemmer, book) {
+        report.tokens += 1;
+        report.token_chars += token.text.chars().count();
+        *report.words.entry(token.text.to_string()).or_default() += 1;
+        match check(&mut *stemmer, algorithm, token.text) {
+            Ok(stem) => {
+                report.stem_chars += stem.chars().count();
+                report.unchanged += usize::from(stem == token.text.to_lowercase());
+                *report.stems.entry(stem).or_default() += 1;
+            },
+            Err(violation) => {
+                report.violations.push((token.text.to_string(), violation));
+                // A stemmer that panicked may be left in any state
+                stemmer = algorithm.stemmer(false);
+            },
+        }
+    }
+    report.elapsed = start.elapsed();
+    report
+}
+
+/// Prints `report` for the book `name` of `bytes` bytes
+fn print_report(
+    name: &str,
+    bytes: usize,
+    algorithm: Algorithm,
+    report: &Report,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let per_token = |chars: usize| chars as f64 / report.tokens.max(1) as f64;
+    let seconds = report.elapsed.as_secs_f64();
+    writeln!(out, "book:        {} ({:.1} MB)", name, bytes as f64 / 1e6)?;
+    writeln!(out, "stemmer:     {}", algorithm.name())?;
+    writeln!(
+        out,
+        "tokens:      {} ({} distinct words, {} distinct stems)",
+        report.tokens,
+        report.words.len(),
+        report.stems.len()
+    )?;
+    writeln!(out, "unchanged:   {:.1}% of tokens", 100.0 * per_token(report.unchanged))?;
+    writeln!(
+        out,
+        "length:      {:.2} characters per word, {:.2} per stem",
+        per_token(report.token_chars),
+        per_token(report.stem_chars)
+    )?;
+    writeln!(
+        out,
+        "time:        {:.1} ms ({:.0} tokens/s)",
+        seconds * 1e3,
+        report.tokens as f64 / seconds.max(f64::MIN_POSITIVE)
+    )?;
+    let mut top: Vec<(&String, &usize)> = report.stems.iter().collect();
+    top.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let top: Vec<String> = top.iter().take(TOP_STEMS).map(|(stem, n)| format!("{} ({})", stem, n)).collect();
+    writeln!(out, "top stems:   {}", top.join(", "))?;
+    writeln!(out, "violations:  {}", report.violations.len())?;
+    for (word, violation) in report.violations.iter().take(MAX_SHOWN) {
+        writeln!(out, "  {:?}: {}", word, violation)?;
+    }
+    if report.violations.len() > MAX_SHOWN {
+        writeln!(out, "  ... and {} more", report.violations.len() - MAX_SHOWN)?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let mut algorithm = Algorithm::Porter;
+    let mut books = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--algorithm" => match args.next().map(|name| name.parse()) {
+                Some(Ok(name)) => algorithm = name,
+                Some(Err(message)) => fail(&message),
+                None => fail("--algorithm needs a value"),
+            },
+            _ if arg.starts_with('-') => fail(&format!("unknown argument: {}", arg)),
+            _ => books.push(arg),
+        }
+    }
+    if books.is_empty() {
+        fail("usage: smoke [--algorithm NAME] BOOK.txt...");
+    }
+
+    // Panics are reported with the word that caused them
+    panic::set_hook(Box::new(|_| {}));
+    let mut out = io::stdout().lock();
+    let mut clean = true;
+    for (i, name) in books.iter().enumerate() {
+        let book = std::fs::read(name).unwrap_or_else(|e| fail(&format!("{}: {}", name, e)));
+        let report = smoke(algorithm, &String::from_utf8_lossy(&book));
+        clean &= report.violations.is_empty();
+        let written = if i > 0 { writeln!(out) } else { Ok(()) };
+        if let Err(e) = written.and_then(|_| print_report(name, book.len(), algorithm, &report, &mut out)) {
+            fail(&format!("error: {}", e));
+        }
+    }
+    if !clean {
+        process::exit(1);
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoke() {
+        let book = "CHAPTER I.\n\nIt is a truth universally acknowledged, that a single man in possession \
+                    of a good fortune, must be in want of a wife.--Thou shalt not; 'tis connexion, shew'd.\n";
+        for &algorithm in Algorithm::ALL {
+            let report = smoke(algorithm, book);
+            assert!(report.violations.is_empty(), "{:?}", report.violations);
+            assert_eq!(report.tokens, 32);
+        }
+        let report = smoke(Algorithm::Porter, book);
+        assert_eq!(report.stems["truth"], 1);
+        assert_eq!(report.words["a"], 4);
+
+        let mut out = Vec::new();
+        print_report("pride.txt", book.len(), Algorithm::Porter, &report, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("tokens:      32 (") && out.ends_with("violations:  0\n"), "{}", out);
+    }
+
+    #[test]
+    fn test_check() {
+        /// A stemmer that breaks every invariant in turn
+        struct Broken;
+
+        impl Stemmer for Broken {
+            fn stem(&mut self, word: &str) -> String {
+                match word {
+                    "boom" => panic!("boom"),
+                    "grow" => "growing".to_string(),
+                    _ => "ü".to_string(),
+                }
+            }
+        }
+
+        let check = |word| check(&mut Broken, Algorithm::Lancaster, word).unwrap_err();
+        assert_eq!(check("boom"), "panicked: boom");
+        assert_eq!(check("grow"), "stem \"growing\" is longer than the word");
+        assert_eq!(check("cats"), "stem \"ü\" of an ASCII word is not ASCII");
+    }
+}
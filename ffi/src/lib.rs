@@ -0,0 +1,15 @@
+//! # C Library
+//!
+//! The shared library of `m_porter_stemmer_rust`'s C interface: the
+//! `extern "C"` functions of its `ffi` module, declared in
+//! `include/porterstem.h`, and with the default `plugin` feature the
+//! `porterstem_plugin` table of its `plugin` module.
+//!
+//! ```bash
+//! cargo build --release -p porterstem-ffi
+//! cc -I include app.c -L target/release -lporterstem_ffi
+//! ```
+
+pub use m_porter_stemmer_rust::ffi::*;
+#[cfg(feature = "plugin")]
+pub use m_porter_stemmer_rust::plugin::porterstem_plugin;
@@ -0,0 +1,22 @@
+//! Stems arbitrary input, as UTF-8 or lossily converted bytes, with every
+//! stemmer, and checks the Porter stemmer against the allocation-free core
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use m_porter_stemmer_rust::{fixed, Algorithm, PorterStemmer, Stemmer};
+
+fuzz_target!(|data: &[u8]| {
+    let word = String::from_utf8_lossy(data);
+    for algorithm in Algorithm::ALL {
+        algorithm.stemmer(false).stem(&word);
+        algorithm.stemmer(true).stem(&word);
+    }
+
+    let stem = PorterStemmer::new().stem(&word);
+    if word.is_ascii() {
+        assert!(stem.len() <= word.len(), "{:?} -> {:?}", word, stem);
+        let mut buffer = vec![0; word.len()];
+        assert_eq!(fixed::stem_into(&word, &mut buffer), Ok(stem.as_str()), "{:?}", word);
+    }
+});
@@ -0,0 +1,36 @@
+//! Runs arbitrary text through the text pipeline: the first byte picks the
+//! options, and the rest is stemmed as a string and as a stream of bytes
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use m_porter_stemmer_rust::text::{stem_stream, stem_text_with, TextOptions};
+use m_porter_stemmer_rust::token::{DigitPolicy, LongTokenPolicy, SymbolPolicy};
+use m_porter_stemmer_rust::PorterStemmer;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&choice, bytes)) = data.split_first() else { return };
+    let digits = [DigitPolicy::Split, DigitPolicy::Skip, DigitPolicy::Verbatim, DigitPolicy::StripDigits];
+    let symbols = [SymbolPolicy::Preserve, SymbolPolicy::Drop, SymbolPolicy::Placeholder("<sym>")];
+    let mut options = TextOptions::new()
+        .digits(digits[usize::from(choice & 3)])
+        .symbols(symbols[usize::from(choice >> 2 & 3) % 3])
+        .preserve_case(choice & 16 != 0)
+        .min_length(usize::from(choice >> 5 & 1) * 5);
+    if choice & 64 != 0 {
+        let policy = if choice & 128 != 0 { LongTokenPolicy::Skip } else { LongTokenPolicy::Verbatim };
+        options = options.max_length(12, policy);
+    }
+
+    let mut stemmer = PorterStemmer::new();
+    let text = String::from_utf8_lossy(bytes);
+    let stemmed = stem_text_with(&mut stemmer, &text, &options);
+
+    // The stream stems valid UTF-8 the same way, a piece at a time
+    let mut output = Vec::new();
+    let streamed = stem_stream(&mut stemmer, bytes, &mut output, &options);
+    if std::str::from_utf8(bytes).is_ok() {
+        streamed.unwrap();
+        assert_eq!(output, stemmed.as_bytes());
+    }
+});
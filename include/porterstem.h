@@ -0,0 +1,49 @@
+/*
+ * porterstem.h - C interface of m_porter_stemmer_rust (the `ffi` feature)
+ *
+ * Build the library with `cargo build --release -p porterstem-ffi` and link
+ * with -lporterstem_ffi. Words are UTF-8; stems are NUL-terminated.
+ */
+#ifndef PORTERSTEM_H
+#define PORTERSTEM_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Result of a call given a null pointer or a word that is not UTF-8 */
+#define PORTER_STEM_INVALID (-1)
+/* Result of a call whose stemming failed */
+#define PORTER_STEM_FAILED (-2)
+
+/*
+ * Stems the NUL-terminated word into out, followed by a NUL, if the stem is
+ * shorter than cap bytes, and returns the stem's length in bytes either way,
+ * like snprintf; or returns PORTER_STEM_INVALID or PORTER_STEM_FAILED.
+ */
+ptrdiff_t porter_stem(const char *word, char *out, size_t cap);
+
+/* A stemmer with a buffer for its stems, used by one thread at a time */
+typedef struct PorterStemmerHandle PorterStemmerHandle;
+
+/* Returns a new handle, to be freed with porter_stemmer_free */
+PorterStemmerHandle *porter_stemmer_new(void);
+
+/* Frees a handle from porter_stemmer_new; NULL is ignored */
+void porter_stemmer_free(PorterStemmerHandle *handle);
+
+/*
+ * Stems the len bytes at word and returns the stem, NUL-terminated, in the
+ * handle's buffer, valid until the next call with the handle; its length goes
+ * to *stem_len unless stem_len is NULL. Returns NULL if handle or word is
+ * NULL, the word is not UTF-8 or stemming fails.
+ */
+const char *porter_stemmer_stem(PorterStemmerHandle *handle, const char *word, size_t len, size_t *stem_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PORTERSTEM_H */
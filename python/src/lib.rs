@@ -0,0 +1,101 @@
+//! # Python Bindings
+//!
+//! The `porterstem` Python module, built with maturin (`maturin build
+//! --release` in this directory, or `maturin develop` into a virtualenv):
+//!
+//! ```python
+//! from porterstem import PorterStemmer
+//!
+//! stemmer = PorterStemmer()
+//! stemmer.stem("running")                   # 'run'
+//! stemmer.stem_many(["caresses", "ponies"])   # ['caress', 'poni']
+//! stemmer.stem_batch(words)                 # the same, without the GIL
+//! ```
+//!
+//! `PorterStemmer().stem(word)` is called like NLTK's, so it can replace
+//! `nltk.stem.PorterStemmer` in existing code. The stems are those of Martin
+//! Porter's C program, which NLTK gives with
+//! `PorterStemmer(mode=PorterStemmer.MARTIN_EXTENSIONS)`; NLTK's default
+//! mode adds rules of its own. `stem_batch` lets other Python threads run
+//! while it stems, for thread pools stemming large corpora.
+
+use m_porter_stemmer_rust::PorterStemmer;
+use pyo3::prelude::*;
+
+/// Stems every word of `words` in order
+fn stem_all(stemmer: &mut PorterStemmer, words: &[String]) -> Vec<String> {
+    words.iter().map(|word| stemmer.stem(word)).collect()
+}
+
+/// The Porter stemmer, lowercasing each word before stemming it
+#[pyclass(name = "PorterStemmer", module = "porterstem")]
+#[derive(Default)]
+pub struct PyPorterStemmer {
+    stemmer: PorterStemmer,
+}
+
+#[pymethods]
+impl PyPorterStemmer {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the stem of `word`
+    fn stem(&mut self, word: &str) -> String {
+        self.stemmer.stem(word)
+    }
+
+    /// Returns the stems of a list of words, in order
+    fn stem_many(&mut self, words: Vec<String>) -> Vec<String> {
+        stem_all(&mut self.stemmer, &words)
+    }
+
+    /// Returns the stems of a list of words, in order, releasing the GIL
+    /// while stemming so that other threads can run
+    fn stem_batch(&mut self, py: Python<'_>, words: Vec<String>) -> Vec<String> {
+        let stemmer = &mut self.stemmer;
+        py.detach(move || stem_all(stemmer, &words))
+    }
+
+    fn __repr__(&self) -> &'static str {
+        "<PorterStemmer>"
+    }
+}
+
+/// Returns the stem of `word` with a stemmer of its own
+#[pyfunction]
+fn stem(word: &str) -> String {
+    PorterStemmer::new().stem(word)
+}
+
+#[pymodule]
+fn porterstem(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyPorterStemmer>()?;
+    module.add_function(wrap_pyfunction!(stem, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    #[test]
+    fn test_module() {
+        pyo3::append_to_inittab!(porterstem);
+        Python::attach(|py| {
+            let locals = PyDict::new(py);
+            let code = c"import porterstem
+stemmer = porterstem.PorterStemmer()
+result = [stemmer.stem('Running'), stemmer.stem_many(['caresses', 'ponies']),
+          stemmer.stem_batch(['generalizations']), porterstem.stem('hopping'), repr(stemmer)]";
+            py.run(code, None, Some(&locals)).unwrap();
+            let result = locals.get_item("result").unwrap().unwrap();
+            assert_eq!(
+                result.to_string(),
+                "['run', ['caress', 'poni'], ['gener'], 'hop', '<PorterStemmer>']"
+            );
+        });
+    }
+}
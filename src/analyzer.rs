@@ -0,0 +1,283 @@
+//! # Analyzer Pipelines
+//!
+//! Lucene-style analysis chains: a [`Tokenizer`] splits text into terms and
+//! a sequence of [`TokenFilter`] stages rewrites or drops them. The crate
+//! ships the standard stages and a preconfigured English pipeline:
+//!
+//! ```text
+//! LetterTokenizer -> LowercaseFilter -> StopFilter -> StemFilter -> LengthFilter
+//! ```
+//!
+//! Stages are trait objects, so custom ones (including plain closures) can
+//! be mixed freely with the built-in ones.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::analyzer::{Analyzer, LetterTokenizer, LowercaseFilter, StemFilter};
+//! let mut english = Analyzer::english();
+//! assert_eq!(english.analyze_to_strings("The dogs are running!"), ["dog", "run"]);
+//!
+//! let mut custom = Analyzer::new(LetterTokenizer)
+//!     .filter(LowercaseFilter)
+//!     .filter(|term: &mut m_porter_stemmer_rust::analyzer::Term| term.text != "xyzzy")
+//!     .filter(StemFilter::new());
+//! assert_eq!(custom.analyze_to_strings("XYZZY jumps"), ["jump"]);
+//! ```
+
+use crate::stopwords::Stopwords;
+use crate::text::tokens;
+use crate::turkish::turkish_lowercase;
+use crate::{PorterStemmer, Stemmer};
+
+/// A term flowing through an analyzer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    /// Current text of the term, as rewritten by the stages so far
+    pub text: String,
+    /// Byte offset of the original token in the input
+    pub start: usize,
+    /// Byte offset just past the original token in the input
+    pub end: usize,
+    /// Index of the original token among all tokens of the input, which
+    /// stays put when earlier terms are dropped (for phrase matching)
+    pub position: usize,
+}
+
+/// Splits text into terms
+pub trait Tokenizer {
+    /// Returns the terms of `text`, numbered from position 0
+    fn tokenize(&self, text: &str) -> Vec<Term>;
+}
+
+/// A stage that rewrites a term in place or drops it
+pub trait TokenFilter {
+    /// Rewrites `term`; returns false to drop it from the stream
+    fn apply(&mut self, term: &mut Term) -> bool;
+}
+
+impl<F: FnMut(&mut Term) -> bool> TokenFilter for F {
+    fn apply(&mut self, term: &mut Term) -> bool {
+        self(term)
+    }
+}
+
+/// Tokenizes on runs of ASCII letters, like [`crate::text::stem_text`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LetterTokenizer;
+
+impl Tokenizer for LetterTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Term> {
+        tokens(text)
+            .enumerate()
+            .map(|(position, token)| Term {
+                text: token.text.to_string(),
+                start: token.start,
+                end: token.end(),
+                position,
+            })
+            .collect()
+    }
+}
+
+/// Tokenizes on whitespace, keeping punctuation and digits inside terms
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WhitespaceTokenizer;
+
+impl Tokenizer for WhitespaceTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Term> {
+        let mut terms = Vec::new();
+        let mut start = None;
+        for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+            match (start, c.is_whitespace()) {
+                (None, false) => start = Some(i),
+                (Some(s), true) => {
+                    let position = terms.len();
+                    terms.push(Term { text: text[s..i].to_string(), start: s, end: i, position });
+                    start = None;
+                },
+                _ => {}
+            }
+        }
+        terms
+    }
+}
+
+/// Lowercases every term
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LowercaseFilter;
+
+impl TokenFilter for LowercaseFilter {
+    fn apply(&mut self, term: &mut Term) -> bool {
+        if term.text.chars().any(char::is_uppercase) {
+            term.text = term.text.to_lowercase();
+        }
+        true
+    }
+}
+
+/// Lowercases every term by the Turkish rules, "I" to "ı" and "İ" to "i"
+///
+/// [`LowercaseFilter`] maps "I" to "i", which turns Turkish "ırmak" and
+/// "IRMAK" into different terms.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TurkishLowercaseFilter;
+
+impl TokenFilter for TurkishLowercaseFilter {
+    fn apply(&mut self, term: &mut Term) -> bool {
+        if term.text.chars().any(char::is_uppercase) {
+            term.text = turkish_lowercase(&term.text);
+        }
+        true
+    }
+}
+
+/// Drops stopwords
+#[derive(Debug, Clone)]
+pub struct StopFilter {
+    stopwords: Stopwords,
+}
+
+impl StopFilter {
+    /// Creates a filter dropping the given stopwords
+    pub fn new(stopwords: Stopwords) -> Self {
+        StopFilter { stopwords }
+    }
+}
+
+impl TokenFilter for StopFilter {
+    fn apply(&mut self, term: &mut Term) -> bool {
+        !self.stopwords.contains(&term.text)
+    }
+}
+
+/// Replaces every term by its stem
+pub struct StemFilter {
+    stemmer: Box<dyn Stemmer>,
+}
+
+impl StemFilter {
+    /// Creates a filter with a default Porter stemmer
+    pub fn new() -> Self {
+        Self::with_stemmer(PorterStemmer::new())
+    }
+
+    /// Creates a filter using any configured stemmer
+    pub fn with_stemmer<S: Stemmer + 'static>(stemmer: S) -> Self {
+        StemFilter { stemmer: Box::new(stemmer) }
+    }
+}
+
+impl Default for StemFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for StemFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StemFilter").finish_non_exhaustive()
+    }
+}
+
+impl TokenFilter for StemFilter {
+    fn apply(&mut self, term: &mut Term) -> bool {
+        term.text = self.stemmer.stem(&term.text);
+        true
+    }
+}
+
+/// Drops terms shorter or longer than the given number of characters
+#[derive(Debug, Clone, Copy)]
+pub struct LengthFilter {
+    min: usize,
+    max: usize,
+}
+
+impl LengthFilter {
+    /// Creates a filter keeping terms of `min..=max` characters
+    pub fn new(min: usize, max: usize) -> Self {
+        LengthFilter { min, max }
+    }
+}
+
+impl TokenFilter for LengthFilter {
+    fn apply(&mut self, term: &mut Term) -> bool {
+        let len = term.text.chars().count();
+        (self.min..=self.max).contains(&len)
+    }
+}
+
+/// A tokenizer followed by a chain of filters
+pub struct Analyzer {
+    tokenizer: Box<dyn Tokenizer>,
+    filters: Vec<Box<dyn TokenFilter>>,
+}
+
+impl Analyzer {
+    /// Creates an analyzer with the given tokenizer and no filters
+    pub fn new<T: Tokenizer + 'static>(tokenizer: T) -> Self {
+        Analyzer { tokenizer: Box::new(tokenizer), filters: Vec::new() }
+    }
+
+    /// Appends a filter stage to the chain
+    pub fn filter<F: TokenFilter + 'static>(mut self, filter: F) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// The standard English pipeline: letter tokenizer, lowercase, English
+    /// stopwords, Porter stemmer, and a length filter keeping stems of
+    /// 2 to 255 characters
+    pub fn english() -> Self {
+        Analyzer::new(LetterTokenizer)
+            .filter(LowercaseFilter)
+            .filter(StopFilter::new(Stopwords::english()))
+            .filter(StemFilter::new())
+            .filter(LengthFilter::new(2, 255))
+    }
+
+    /// Runs `text` through the tokenizer and every filter, in order
+    pub fn analyze(&mut self, text: &str) -> Vec<Term> {
+        let mut terms = self.tokenizer.tokenize(text);
+        terms.retain_mut(|term| self.filters.iter_mut().all(|filter| filter.apply(term)));
+        terms
+    }
+
+    /// Like [`Analyzer::analyze`], returning only the final term texts
+    pub fn analyze_to_strings(&mut self, text: &str) -> Vec<String> {
+        self.analyze(text).into_iter().map(|term| term.text).collect()
+    }
+}
+
+impl std::fmt::Debug for Analyzer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Analyzer").field("filters", &self.filters.len()).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_pipeline_keeps_offsets_and_positions() {
+        let mut analyzer = Analyzer::english();
+        let terms = analyzer.analyze("The Cats were RUNNING");
+        assert_eq!(
+            terms,
+            [
+                Term { text: "cat".to_string(), start: 4, end: 8, position: 1 },
+                Term { text: "run".to_string(), start: 14, end: 21, position: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_whitespace_tokenizer_and_length_filter() {
+        let mut analyzer = Analyzer::new(WhitespaceTokenizer).filter(LengthFilter::new(3, 5));
+        assert_eq!(analyzer.analyze_to_strings("  a b2b covid-19\tok "), ["b2b"]);
+        assert_eq!(WhitespaceTokenizer.tokenize("x  y")[1].start, 3);
+        let mut turkish = Analyzer::new(WhitespaceTokenizer).filter(TurkishLowercaseFilter);
+        assert_eq!(turkish.analyze_to_strings("IRMAK İzmir"), ["ırmak", "izmir"]);
+    }
+}
@@ -0,0 +1,76 @@
+//! # Arrow Columns
+//!
+//! Stems a whole Apache Arrow string column at once, for ETL jobs that read
+//! and write Arrow or Parquet: [`stem_string_array`] for `Utf8` columns and
+//! [`stem_large_string_array`] for `LargeUtf8` ones. Each value is stemmed
+//! as text, word by word, as [`crate::text::stem_text`] does, and nulls stay
+//! null. The output is built in one pass into a single values buffer sized
+//! from the input's, instead of one string per row, and comes out ready to
+//! write. Only available with the `arrow` feature.
+//!
+//! # Examples
+//! ```
+//! # use arrow_array::{Array, StringArray};
+//! # use m_porter_stemmer_rust::{arrow::stem_string_array, PorterStemmer};
+//! let titles = StringArray::from(vec![Some("Running ponies"), None, Some("caresses")]);
+//! let stems = stem_string_array(&mut PorterStemmer::new(), &titles);
+//! assert_eq!(stems.value(0), "run poni");
+//! assert!(stems.is_null(1));
+//! assert_eq!(stems.value(2), "caress");
+//! ```
+
+use arrow_array::builder::GenericStringBuilder;
+use arrow_array::{Array, GenericStringArray, LargeStringArray, OffsetSizeTrait, StringArray};
+
+use crate::stemmer::Stemmer;
+use crate::text::stem_text;
+
+/// Stems every value of a `Utf8` column
+pub fn stem_string_array(stemmer: &mut dyn Stemmer, array: &StringArray) -> StringArray {
+    stem_generic_string_array(stemmer, array)
+}
+
+/// Stems every value of a `LargeUtf8` column
+pub fn stem_large_string_array(stemmer: &mut dyn Stemmer, array: &LargeStringArray) -> LargeStringArray {
+    stem_generic_string_array(stemmer, array)
+}
+
+/// Stems every value of a string column with either offset width
+///
+/// Stems are rarely longer than their words, so the values buffer starts at
+/// the input's size and only grows for the odd lowercasing that adds bytes.
+pub fn stem_generic_string_array<O: OffsetSizeTrait>(
+    stemmer: &mut dyn Stemmer,
+    array: &GenericStringArray<O>,
+) -> GenericStringArray<O> {
+    let values = array.value_offsets();
+    let bytes = (values[array.len()] - values[0]).as_usize();
+    let mut builder = GenericStringBuilder::<O>::with_capacity(array.len(), bytes);
+    for value in array.iter() {
+        match value {
+            Some(text) => builder.append_value(stem_text(stemmer, text)),
+            None => builder.append_null(),
+        }
+    }
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_stem_arrays() {
+        let mut stemmer = PorterStemmer::new();
+        let words = StringArray::from(vec![Some("generalizations"), Some(""), None, Some("Hopping, skipping")]);
+        let expected = StringArray::from(vec![Some("gener"), Some(""), None, Some("hop, skip")]);
+        assert_eq!(stem_string_array(&mut stemmer, &words), expected);
+
+        // A slice of a column starts at a non-zero offset
+        let large = LargeStringArray::from(vec!["cats", "ponies", "agreed"]).slice(1, 2);
+        let stems = stem_large_string_array(&mut stemmer, &large);
+        assert_eq!(stems, LargeStringArray::from(vec!["poni", "agre"]));
+        assert_eq!(stems.offset(), 0);
+    }
+}
@@ -0,0 +1,167 @@
+//! The `bench` subcommand of the command line front end
+//!
+//! `porterstem bench [file]` stems a corpus, the file or a synthetic one,
+//! with the stemmer and text options of the other flags, as many times as
+//! fit in about a second, and reports words and megabytes per second and
+//! the heap allocations made per word. Allocations are counted only in a
+//! binary built with the `bench-alloc` feature, whose global allocator wraps
+//! the system one with two atomic counters; the default binary, which also
+//! serves and indexes, keeps the system allocator as it is.
+
+#[cfg(feature = "bench-alloc")]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{self, Write};
+#[cfg(feature = "bench-alloc")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use m_porter_stemmer_rust::text::{self, TextOptions};
+use m_porter_stemmer_rust::Stemmer;
+
+/// The system allocator, counting allocations and the bytes they ask for
+#[cfg(feature = "bench-alloc")]
+struct CountingAlloc;
+
+#[cfg(feature = "bench-alloc")]
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "bench-alloc")]
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "bench-alloc")]
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[cfg(feature = "bench-alloc")]
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+/// Allocations made so far and the bytes they asked for, if they are counted
+fn allocations() -> Option<(usize, usize)> {
+    #[cfg(feature = "bench-alloc")]
+    return Some((ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED_BYTES.load(Ordering::Relaxed)));
+    #[cfg(not(feature = "bench-alloc"))]
+    None
+}
+
+/// Size of the corpus benchmarked when no file is given
+pub const SYNTHETIC_SIZE: usize = 8 << 20;
+
+/// Benchmarks run for at least this long, unless one pass takes longer
+const MIN_DURATION: Duration = Duration::from_secs(1);
+
+/// Returns about `size` bytes of English-like text: words made of common
+/// roots and suffixes, with punctuation, capitals and line breaks, always
+/// the same for the same size
+pub fn synthetic_corpus(size: usize) -> String {
+    const ROOTS: &[&str] = &[
+        "connect", "generaliz", "run", "pony", "caress", "happ", "relat", "condition", "nation", "operat",
+        "digit", "hope", "sens", "formal", "electr", "adjust", "depend", "control", "feud", "agree",
+    ];
+    const SUFFIXES: &[&str] = &[
+        "", "s", "es", "ed", "ing", "ation", "ations", "ational", "ness", "ful", "ly", "ment", "ities", "izer",
+        "ive", "ence", "able", "ism",
+    ];
+    // A fixed linear congruential generator, so every run benchmarks the
+    // same text
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    let mut next = |n: usize| {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (state >> 33) as usize % n
+    };
+    let mut text = String::with_capacity(size + 32);
+    let mut words_in_line = 0;
+    while text.len() < size {
+        let root = ROOTS[next(ROOTS.len())];
+        if words_in_line == 0 {
+            text.push_str(&root[..1].to_uppercase());
+            text.push_str(&root[1..]);
+        } else {
+            text.push_str(root);
+        }
+        text.push_str(SUFFIXES[next(SUFFIXES.len())]);
+        words_in_line += 1;
+        match next(20) {
+            0 if words_in_line > 6 => {
+                text.push_str(".\n");
+                words_in_line = 0;
+            },
+            1 => text.push_str(", "),
+            _ => text.push(' '),
+        }
+    }
+    text
+}
+
+/// Stems `corpus` repeatedly with `stemmer` and writes the throughput and
+/// allocations per pass to `out`
+pub fn bench(
+    stemmer: &mut dyn Stemmer,
+    (corpus, corpus_name): (&str, &str),
+    options: &TextOptions,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let words = text::tokens_for(stemmer, corpus).count();
+    let mut passes = 0u32;
+    let before = allocations();
+    let start = Instant::now();
+    while passes == 0 || start.elapsed() < MIN_DURATION {
+        std::hint::black_box(text::stem_text_with(stemmer, std::hint::black_box(corpus), options));
+        passes += 1;
+    }
+    let elapsed = start.elapsed().as_secs_f64() / f64::from(passes);
+    let after = allocations();
+
+    let megabytes = corpus.len() as f64 / 1e6;
+    writeln!(out, "corpus:      {} ({:.1} MB, {} words)", corpus_name, megabytes, words)?;
+    writeln!(out, "time:        {:.3} ms per pass, {} passes", elapsed * 1e3, passes)?;
+    writeln!(out, "throughput:  {:.0} words/s, {:.1} MB/s", words as f64 / elapsed, megabytes / elapsed)?;
+    let (Some((allocations_before, bytes_before)), Some((allocations_after, bytes_after))) = (before, after) else {
+        return writeln!(out, "allocations: not counted (build with --features bench-alloc)");
+    };
+    let allocations = (allocations_after - allocations_before) as f64 / f64::from(passes);
+    let allocated = (bytes_after - bytes_before) as f64 / f64::from(passes);
+    writeln!(
+        out,
+        "allocations: {:.0} per pass, {:.2} per word, {:.1} MB per pass",
+        allocations,
+        allocations / words.max(1) as f64,
+        allocated / 1e6,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m_porter_stemmer_rust::PorterStemmer;
+
+    #[test]
+    fn test_bench() {
+        let corpus = synthetic_corpus(10_000);
+        assert!(corpus.len() >= 10_000 && corpus.len() < 10_100);
+        assert_eq!(corpus, synthetic_corpus(10_000));
+        assert!(corpus.starts_with(char::is_uppercase) && corpus.contains(".\n"));
+
+        let mut out = Vec::new();
+        bench(&mut PorterStemmer::new(), (&corpus, "synthetic"), &TextOptions::new(), &mut out).unwrap();
+        let report = String::from_utf8(out).unwrap();
+        assert!(report.starts_with("corpus:      synthetic (0.0 MB, "));
+        assert_eq!(report.lines().count(), 4);
+        assert!(report.contains(" words/s, "));
+        assert_eq!(report.contains(" per word, "), cfg!(feature = "bench-alloc"));
+    }
+}
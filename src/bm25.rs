@@ -0,0 +1,136 @@
+//! # BM25 Scoring
+//!
+//! Ranks the documents of a [`TfIdf`] collection against a query with the
+//! Okapi BM25 function. The query is stemmed with the same stemmer as the
+//! documents, so "running shoes" matches a document about a "runner's shoe".
+//!
+//! For each query stem `t` found `f` times in a document of `len` words:
+//!
+//! ```text
+//! idf(t) * f * (k1 + 1) / (f + k1 * (1 - b + b * len / avg_len))
+//! idf(t) = ln(1 + (N - df + 0.5) / (df + 0.5))
+//! ```
+//!
+//! `k1` controls term-frequency saturation and `b` length normalization;
+//! the defaults are the usual 1.2 and 0.75.
+
+use crate::text::tokens_for;
+use crate::tfidf::TfIdf;
+use crate::Stemmer;
+
+/// BM25 scorer over a collection of stemmed documents
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, bm25::Bm25Scorer, tfidf::TfIdf};
+/// let mut stemmer = PorterStemmer::new();
+/// let mut docs = TfIdf::new();
+/// docs.add_document(&mut stemmer, "a guide to cooking pasta");
+/// docs.add_document(&mut stemmer, "running shoes for runners who run daily");
+/// let scorer = Bm25Scorer::new(&docs);
+/// let ranked = scorer.rank(&mut stemmer, "run shoes");
+/// assert_eq!(ranked[0].0, 1);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Bm25Scorer<'a> {
+    documents: &'a TfIdf,
+    k1: f64,
+    b: f64,
+}
+
+impl<'a> Bm25Scorer<'a> {
+    /// Creates a scorer with `k1 = 1.2` and `b = 0.75`
+    pub fn new(documents: &'a TfIdf) -> Self {
+        Bm25Scorer { documents, k1: 1.2, b: 0.75 }
+    }
+
+    /// Sets the term-frequency saturation parameter
+    pub fn k1(mut self, k1: f64) -> Self {
+        self.k1 = k1;
+        self
+    }
+
+    /// Sets the length normalization parameter, from 0 (none) to 1 (full)
+    pub fn b(mut self, b: f64) -> Self {
+        self.b = b;
+        self
+    }
+
+    /// BM25 inverse document frequency of `stem`
+    pub fn idf(&self, stem: &str) -> f64 {
+        let n = self.documents.len() as f64;
+        let df = self.documents.document_frequency(stem) as f64;
+        (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+    }
+
+    /// Scores document `doc` against already stemmed query terms
+    ///
+    /// # Panics
+    /// Panics if `doc` is not the index of a document in the collection
+    pub fn score_stems<S: AsRef<str>>(&self, stems: &[S], doc: usize) -> f64 {
+        let avg_len = self.documents.average_document_len();
+        let len = self.documents.document_len(doc) as f64;
+        let norm = if avg_len > 0.0 { 1.0 - self.b + self.b * len / avg_len } else { 1.0 };
+        stems
+            .iter()
+            .map(|stem| {
+                let stem = stem.as_ref();
+                let f = self.documents.term_count(doc, stem) as f64;
+                if f == 0.0 { return 0.0; }
+                self.idf(stem) * f * (self.k1 + 1.0) / (f + self.k1 * norm)
+            })
+            .sum()
+    }
+
+    /// Stems `query` and scores document `doc` against it
+    ///
+    /// # Panics
+    /// Panics if `doc` is not the index of a document in the collection
+    pub fn score(&self, stemmer: &mut dyn Stemmer, query: &str, doc: usize) -> f64 {
+        self.score_stems(&query_terms(stemmer, query), doc)
+    }
+
+    /// Stems `query` and returns the matching documents with their scores,
+    /// best first; documents scoring 0 are left out
+    pub fn rank(&self, stemmer: &mut dyn Stemmer, query: &str) -> Vec<(usize, f64)> {
+        let stems = query_terms(stemmer, query);
+        let mut ranked: Vec<(usize, f64)> = (0..self.documents.len())
+            .map(|doc| (doc, self.score_stems(&stems, doc)))
+            .filter(|&(_, score)| score > 0.0)
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked
+    }
+}
+
+/// Stems every word of a query, keeping repeated words
+fn query_terms(stemmer: &mut dyn Stemmer, query: &str) -> Vec<String> {
+    tokens_for(stemmer, query).map(|token| stemmer.stem(token.text)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_parameters() {
+        let mut stemmer = PorterStemmer::new();
+        let mut docs = TfIdf::new();
+        docs.add_document(&mut stemmer, "cat");
+        docs.add_document(&mut stemmer, "cat dog dog dog dog dog dog dog");
+        docs.add_document(&mut stemmer, "bird");
+
+        let scorer = Bm25Scorer::new(&docs);
+        let idf = (1.0f64 + (3.0 - 2.0 + 0.5) / (2.0 + 0.5)).ln();
+        assert!((scorer.idf("cat") - idf).abs() < 1e-12);
+        // Length normalization favours the short document
+        assert!(scorer.score(&mut stemmer, "cats", 0) > scorer.score(&mut stemmer, "cats", 1));
+        // Without it, both match "cat" once and score the same
+        let flat = scorer.b(0.0);
+        let diff = flat.score(&mut stemmer, "cats", 0) - flat.score(&mut stemmer, "cats", 1);
+        assert!(diff.abs() < 1e-12);
+        assert!(scorer.rank(&mut stemmer, "fish").is_empty());
+        assert_eq!(scorer.k1(2.0).rank(&mut stemmer, "dogs bird").len(), 2);
+    }
+}
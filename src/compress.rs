@@ -0,0 +1,185 @@
+//! # Compressed Input and Output
+//!
+//! Chooses between gzip, with the `flate2` crate, and Zstandard, with the
+//! `zstd` crate: [`Compression::from_path`] by a file's extension and
+//! [`Compression::detect`] by its first bytes. [`detect_reader`] wraps a
+//! reader in the decoder its content calls for, and [`Encoder`] is a writer
+//! that compresses or not. Only available with the `compress` feature.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::compress::{self, Compression, Encoder};
+//! use std::io::{Read, Write};
+//!
+//! let mut encoder = Encoder::new(Vec::new(), Compression::from_path("stems.txt.zst")).unwrap();
+//! encoder.write_all(b"run run run").unwrap();
+//! let compressed = encoder.finish().unwrap();
+//! assert_eq!(Compression::detect(&compressed), Some(Compression::Zstd));
+//!
+//! let mut text = String::new();
+//! compress::detect_reader(&compressed[..]).unwrap().read_to_string(&mut text).unwrap();
+//! assert_eq!(text, "run run run");
+//! ```
+
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+
+use flate2::bufread::MultiGzDecoder;
+use flate2::write::GzEncoder;
+
+/// A compression format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the format named by the extension of `path`, `.gz` or `.zst`
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "gz" => Some(Compression::Gzip),
+            "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Returns the format whose magic number `bytes` start with
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x1F, 0x8B]) {
+            Some(Compression::Gzip)
+        } else if bytes.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Wraps `input` in a decoder for this format, which reads every
+    /// member or frame of the stream, as `cat a.gz b.gz` writes them
+    pub fn decoder<'a, R: BufRead + 'a>(self, input: R) -> io::Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            Compression::Gzip => Box::new(MultiGzDecoder::new(input)),
+            Compression::Zstd => Box::new(zstd::Decoder::with_buffer(input)?),
+        })
+    }
+}
+
+impl std::str::FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            _ => Err(format!("unknown compression: {:?} (expected gzip or zstd)", s)),
+        }
+    }
+}
+
+/// Returns `input` decompressed if it starts with a gzip or zstd magic
+/// number, or as it is otherwise
+pub fn detect_reader<'a, R: BufRead + 'a>(mut input: R) -> io::Result<Box<dyn Read + 'a>> {
+    Ok(match Compression::detect(input.fill_buf()?) {
+        Some(compression) => compression.decoder(input)?,
+        None => Box::new(input),
+    })
+}
+
+/// A writer that compresses to `W` in one of the formats, or passes the
+/// data through unchanged
+pub enum Encoder<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> Encoder<W> {
+    /// Creates a writer to `inner` compressing in the format given, if any,
+    /// at the default level, with a checksum of the content
+    pub fn new(inner: W, compression: Option<Compression>) -> io::Result<Self> {
+        Ok(match compression {
+            None => Encoder::Plain(inner),
+            Some(Compression::Gzip) => Encoder::Gzip(GzEncoder::new(inner, flate2::Compression::default())),
+            Some(Compression::Zstd) => {
+                let mut encoder = zstd::Encoder::new(inner, 0)?;
+                encoder.include_checksum(true)?;
+                Encoder::Zstd(encoder)
+            },
+        })
+    }
+
+    /// Ends the compressed stream and flushes it, returning the inner writer
+    pub fn finish(self) -> io::Result<W> {
+        let mut inner = match self {
+            Encoder::Plain(inner) => inner,
+            Encoder::Gzip(encoder) => encoder.finish()?,
+            Encoder::Zstd(encoder) => encoder.finish()?,
+        };
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<W: Write> fmt::Debug for Encoder<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let compression = match self {
+            Encoder::Plain(_) => None,
+            Encoder::Gzip(_) => Some(Compression::Gzip),
+            Encoder::Zstd(_) => Some(Compression::Zstd),
+        };
+        f.debug_struct("Encoder").field("compression", &compression).finish_non_exhaustive()
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Encoder::Plain(inner) => inner.write(buf),
+            Encoder::Gzip(encoder) => encoder.write(buf),
+            Encoder::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Encoder::Plain(inner) => inner.flush(),
+            Encoder::Gzip(encoder) => encoder.flush(),
+            Encoder::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_reader() {
+        assert_eq!(Compression::from_path("corpus/a.txt.gz"), Some(Compression::Gzip));
+        assert_eq!(Compression::from_path("a.txt"), None);
+        for compression in [None, Some(Compression::Gzip), Some(Compression::Zstd)] {
+            let mut encoder = Encoder::new(Vec::new(), compression).unwrap();
+            encoder.write_all(b"stems").unwrap();
+            let written = encoder.finish().unwrap();
+            assert_eq!(Compression::detect(&written), compression);
+            let mut text = String::new();
+            detect_reader(&written[..]).unwrap().read_to_string(&mut text).unwrap();
+            assert_eq!(text, "stems");
+        }
+        // Streams one after the other, as `cat a.gz b.gz` writes them, are
+        // read as one
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            let mut joined = Vec::new();
+            for part in ["run ", "runs"] {
+                let mut encoder = Encoder::new(Vec::new(), Some(compression)).unwrap();
+                encoder.write_all(part.as_bytes()).unwrap();
+                joined.extend(encoder.finish().unwrap());
+            }
+            let mut text = String::new();
+            detect_reader(&joined[..]).unwrap().read_to_string(&mut text).unwrap();
+            assert_eq!(text, "run runs");
+        }
+    }
+}
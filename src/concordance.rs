@@ -0,0 +1,108 @@
+//! # Concordance (Keyword in Context)
+//!
+//! Lists every occurrence of a word in a text together with the text around
+//! it, one line per occurrence. Occurrences are found by stem, so a
+//! concordance for "run" also lists "running" and "Runs".
+//!
+//! ```text
+//!          The dog was | running | in the park, and it
+//!   and it never stops | running | .
+//! ```
+
+use crate::text::tokens_for;
+use crate::Stemmer;
+
+/// One occurrence of the keyword and its surrounding context
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConcordanceLine<'a> {
+    /// Up to `window` characters of text before the keyword
+    pub left: &'a str,
+    /// The keyword as it appears in the text
+    pub keyword: &'a str,
+    /// Up to `window` characters of text after the keyword
+    pub right: &'a str,
+    /// Byte offset of the keyword in the text
+    pub start: usize,
+}
+
+impl ConcordanceLine<'_> {
+    /// Formats the line with the keyword in a fixed column
+    ///
+    /// The left context is right-aligned to `window` characters, whitespace
+    /// next to the keyword is trimmed, and line breaks and tabs in the
+    /// context are shown as spaces.
+    pub fn format(&self, window: usize) -> String {
+        let flat = |s: &str| s.replace(['\n', '\r', '\t'], " ");
+        let left = flat(self.left.trim_end());
+        let right = flat(self.right.trim_start());
+        format!("{:>window$} | {} | {}", left, self.keyword, right)
+    }
+}
+
+/// Returns the last `n` characters of `s`
+fn tail(s: &str, n: usize) -> &str {
+    if n == 0 { return ""; }
+    s.char_indices().rev().nth(n - 1).map_or(s, |(i, _)| &s[i..])
+}
+
+/// Returns the first `n` characters of `s`
+fn head(s: &str, n: usize) -> &str {
+    s.char_indices().nth(n).map_or(s, |(i, _)| &s[..i])
+}
+
+/// Finds every word of `text` sharing a stem with `word`
+///
+/// `word` may be a surface form ("running") or a stem ("run").
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for the query and the text
+/// * `text` - The text to search
+/// * `word` - The keyword to look for
+/// * `window` - Number of characters of context on each side
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, concordance::concordance};
+/// let mut stemmer = PorterStemmer::new();
+/// let lines = concordance(&mut stemmer, "I run. She was running late.", "runs", 8);
+/// assert_eq!(lines.len(), 2);
+/// assert_eq!((lines[1].left, lines[1].keyword, lines[1].right), ("She was ", "running", " late."));
+/// assert_eq!(lines[0].format(8), "       I | run | . She wa");
+/// ```
+pub fn concordance<'a>(
+    stemmer: &mut dyn Stemmer,
+    text: &'a str,
+    word: &str,
+    window: usize,
+) -> Vec<ConcordanceLine<'a>> {
+    let stem = stemmer.stem(word);
+    let raw = word.to_lowercase();
+    tokens_for(stemmer, text)
+        .filter(|token| {
+            let token_stem = stemmer.stem(token.text);
+            token_stem == stem || token_stem == raw
+        })
+        .map(|token| ConcordanceLine {
+            left: tail(&text[..token.start], window),
+            keyword: token.text,
+            right: head(&text[token.end()..], window),
+            start: token.start,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_context_is_cut_on_characters() {
+        let mut stemmer = PorterStemmer::new();
+        let lines = concordance(&mut stemmer, "café\nconnection, né", "connected", 3);
+        assert_eq!(lines.len(), 1);
+        assert_eq!((lines[0].left, lines[0].right), ("fé\n", ", n"));
+        assert_eq!(lines[0].format(4), "  fé | connection | , n");
+        assert!(concordance(&mut stemmer, "nothing here", "run", 5).is_empty());
+    }
+}
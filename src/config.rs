@@ -0,0 +1,219 @@
+//! Configuration files of the command line front end
+//!
+//! Settings that a project wants the same on every run can live in a
+//! `porterstem.toml`, given with `--config PATH` or found in the current
+//! directory or the nearest directory above it that has one (`--no-config`
+//! skips the search). Each key is the name of a long flag without its dashes,
+//! and its value the flag's value:
+//!
+//! ```toml
+//! algorithm = "porter2"
+//! stopwords = "stopwords.txt"   # relative to the file
+//! format = "tsv"                # the output mode: text, tsv, json, freq, ...
+//! fold = true                   # a flag without a value
+//! columns = [2, 5]              # a list given as 2,5
+//! ```
+//!
+//! The file's settings come before the command line's, so a flag given there
+//! wins over the file, and `--no-fold` (or `--no-` with any other key that is
+//! `true` in the file) turns a flag of the file off. The file is read by the
+//! toml crate; settings are strings, integers, booleans and arrays of them,
+//! at the top level, as tables are not supported.
+//!
+//! Configuration files need the `config` feature. Without it no file is
+//! looked for, and `--config` is an error.
+
+#[cfg(feature = "config")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "config")]
+use toml::{Table, Value};
+
+/// The file looked for in the current directory and those above it
+#[cfg(feature = "config")]
+const FILE_NAME: &str = "porterstem.toml";
+
+/// Settings whose values are paths, taken as relative to the file
+#[cfg(feature = "config")]
+const PATH_SETTINGS: [&str; 5] = ["stopwords", "output", "out-dir", "sqlite", "socket"];
+
+/// The binary's subcommands, which must stay ahead of the file's settings
+pub(crate) const SUBCOMMANDS: [&str; 6] = ["bench", "index", "search", "serve", "daemon", "verify"];
+
+/// Returns the value of a setting as a command line argument
+#[cfg(feature = "config")]
+fn to_arg(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Integer(n) => Some(n.to_string()),
+        Value::Boolean(b) => Some(b.to_string()),
+        Value::Array(values) => values.iter().map(to_arg).collect::<Option<Vec<_>>>().map(|args| args.join(",")),
+        _ => None,
+    }
+}
+
+/// Parses the text of a configuration file into its settings, in order
+#[cfg(feature = "config")]
+fn parse(text: &str) -> Result<Vec<(String, Value)>, String> {
+    let table: Table = text.parse().map_err(|e: toml::de::Error| e.to_string().trim_end().to_string())?;
+    let mut settings = Vec::new();
+    for (key, value) in table {
+        match value {
+            Value::Table(_) => return Err(format!("{}: tables are not supported; settings go at the top level", key)),
+            value if to_arg(&value).is_none() => {
+                return Err(format!("{}: expected a string, an integer, a boolean or an array of them", key))
+            },
+            value => settings.push((key, value)),
+        }
+    }
+    Ok(settings)
+}
+
+/// Returns the command line arguments of the settings of a file in
+/// `directory`, but for the flags that `--no-` arguments in `cli` turn off
+#[cfg(feature = "config")]
+fn settings_args(settings: Vec<(String, Value)>, directory: &Path, cli: &[String]) -> Vec<String> {
+    let mut args = Vec::new();
+    for (key, value) in settings {
+        match value {
+            Value::Boolean(false) => continue,
+            Value::Boolean(true) if cli.contains(&format!("--no-{}", key)) => continue,
+            Value::Boolean(true) => args.push(format!("--{}", key)),
+            Value::String(mode) if key == "format" => args.push(format!("--{}", mode)),
+            value => {
+                args.push(format!("--{}", key));
+                let arg = to_arg(&value).unwrap_or_default();
+                let relative = PATH_SETTINGS.contains(&key.as_str()) && Path::new(&arg).is_relative();
+                match relative && !(key == "stopwords" && arg == "builtin") {
+                    true => args.push(directory.join(arg).to_string_lossy().into_owned()),
+                    false => args.push(arg),
+                }
+            },
+        }
+    }
+    args
+}
+
+/// Returns the configuration file to use: the one `--config` names, else the
+/// nearest `porterstem.toml` from the current directory up, unless
+/// `--no-config` is given
+#[cfg(feature = "config")]
+fn find(args: &[String]) -> Result<Option<PathBuf>, String> {
+    if let Some(i) = args.iter().position(|arg| arg == "--config") {
+        let path = args.get(i + 1).ok_or("--config requires a value")?;
+        return Ok(Some(PathBuf::from(path)));
+    }
+    if args.iter().any(|arg| arg == "--no-config") {
+        return Ok(None);
+    }
+    let Ok(directory) = std::env::current_dir() else { return Ok(None) };
+    Ok(directory.ancestors().map(|directory| directory.join(FILE_NAME)).find(|path| path.is_file()))
+}
+
+/// Returns the command line with the settings of the configuration file
+/// inserted ahead of its flags, and `--config`, `--no-config` and the
+/// `--no-` arguments used up
+#[cfg(feature = "config")]
+pub fn with_config(args: Vec<String>) -> Result<Vec<String>, String> {
+    let Some(path) = find(&args)? else {
+        return Ok(args.into_iter().filter(|arg| arg != "--no-config").collect());
+    };
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let settings = parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let negated: Vec<String> = settings
+        .iter()
+        .filter(|(_, value)| *value == Value::Boolean(true))
+        .map(|(key, _)| format!("--no-{}", key))
+        .collect();
+    let mut cli = Vec::new();
+    let mut rest = args.into_iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--config" => {
+                rest.next();
+            },
+            _ => cli.push(arg),
+        }
+    }
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut config = settings_args(settings, directory, &cli);
+    cli.retain(|arg| !negated.contains(arg));
+    let start = usize::from(cli.first().is_some_and(|arg| SUBCOMMANDS.contains(&arg.as_str())));
+    let mut args: Vec<String> = cli.drain(..start).collect();
+    args.append(&mut config);
+    args.append(&mut cli);
+    Ok(args)
+}
+
+/// Returns the command line with `--no-config` used up, as there is no
+/// configuration file to read without the `config` feature
+#[cfg(not(feature = "config"))]
+pub fn with_config(args: Vec<String>) -> Result<Vec<String>, String> {
+    if args.iter().any(|arg| arg == "--config") {
+        return Err("--config requires porterstem built with the config feature".to_string());
+    }
+    Ok(args.into_iter().filter(|arg| arg != "--no-config").collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_parse() {
+        let text = concat!(
+            "# stems for the search index\n",
+            "algorithm = \"porter2\"  # Snowball English\n",
+            "\"min-length\" = 4\n",
+            "symbol-placeholder = '<sym>'\n",
+            "fold = true\n",
+            "columns = [\n  2, # title\n  5,\n]\n",
+            "join = \"\\u00b7\"\n",
+        );
+        let settings = parse(text).unwrap();
+        let keys: Vec<&str> = settings.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["algorithm", "min-length", "symbol-placeholder", "fold", "columns", "join"]);
+        assert_eq!(to_arg(&settings[4].1).as_deref(), Some("2,5"));
+        assert_eq!(settings[5].1, Value::String("·".to_string()));
+
+        let table = parse("[serve]\nport = 1").unwrap_err();
+        assert_eq!(table, "serve: tables are not supported; settings go at the top level");
+        let float = parse("fold = true\nratio = 0.5").unwrap_err();
+        assert_eq!(float, "ratio: expected a string, an integer, a boolean or an array of them");
+        let twice = parse("fold = true\nfold = false").unwrap_err();
+        assert!(twice.starts_with("TOML parse error at line 2, column 1") && twice.ends_with("duplicate key"));
+        assert!(parse("lang = fr").unwrap_err().starts_with("TOML parse error at line 1, column 8"));
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_with_config() {
+        let directory = std::env::temp_dir().join(format!("porterstem-config-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join(FILE_NAME);
+        let text = "format = \"tsv\"\nstopwords = \"stop.txt\"\nfold = true\nunique = false\nalgorithm = \"porter2\"\n";
+        std::fs::write(&path, text).unwrap();
+        let args = |list: &[&str]| -> Vec<String> { list.iter().map(|s| s.to_string()).collect() };
+
+        let config = path.to_string_lossy().into_owned();
+        let stopwords = directory.join("stop.txt").to_string_lossy().into_owned();
+        let given = with_config(args(&["--config", &config, "--algorithm", "lancaster", "doc.txt"])).unwrap();
+        let expected = ["--tsv", "--stopwords", &stopwords, "--fold", "--algorithm", "porter2"];
+        assert_eq!(given, args(&[&expected[..], &["--algorithm", "lancaster", "doc.txt"]].concat()));
+
+        let given = with_config(args(&["bench", "--no-fold", "--config", &config])).unwrap();
+        assert_eq!(given, args(&["bench", "--tsv", "--stopwords", &stopwords, "--algorithm", "porter2"]));
+        assert_eq!(with_config(args(&["--no-config", "--fold"])).unwrap(), args(&["--fold"]));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(not(feature = "config"))]
+    #[test]
+    fn test_without_config() {
+        let args = |list: &[&str]| -> Vec<String> { list.iter().map(|s| s.to_string()).collect() };
+        assert_eq!(with_config(args(&["--no-config", "--fold"])).unwrap(), args(&["--fold"]));
+        let error = with_config(args(&["--config", "porterstem.toml"])).unwrap_err();
+        assert_eq!(error, "--config requires porterstem built with the config feature");
+    }
+}
@@ -0,0 +1,151 @@
+//! # Corpus Stemming
+//!
+//! Natural text repeats itself: a few hundred words make up half of most
+//! corpora. [`stem_corpus`] stems each distinct token once, however often it
+//! occurs, and maps the stems back to the tokens' positions, so a corpus of
+//! a million tokens and fifty thousand distinct words costs fifty thousand
+//! stems and a million hash lookups.
+//!
+//! The stems are kept once each, in [`CorpusStems`], with the index of each
+//! token's stem; nothing is copied for a token that repeats another.
+//! [`stem_corpus_interned`] gives symbols of a [`StemInterner`] instead, for
+//! an index that keeps one pool of stems across many corpora.
+
+use std::collections::HashMap;
+
+use crate::intern::StemInterner;
+use crate::Stemmer;
+
+/// The stems of a corpus's tokens, each distinct one held once
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorpusStems {
+    /// The stem of each distinct token, in the order the tokens first occur
+    stems: Vec<String>,
+    /// For each token, the index of its stem in `stems`
+    ids: Vec<usize>,
+}
+
+impl CorpusStems {
+    /// The number of tokens
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns true if there were no tokens
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// The stem of token i
+    pub fn get(&self, i: usize) -> Option<&str> {
+        self.ids.get(i).map(|&id| self.stems[id].as_str())
+    }
+
+    /// The stem of each token, in order
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.ids.iter().map(|&id| self.stems[id].as_str())
+    }
+
+    /// The stem of each distinct token, in the order the tokens first occur;
+    /// two tokens may share a stem here, as "run" and "running" do
+    pub fn stems(&self) -> &[String] {
+        &self.stems
+    }
+
+    /// For each token, the index of its stem in [`CorpusStems::stems`]
+    pub fn ids(&self) -> &[usize] {
+        &self.ids
+    }
+}
+
+/// Stems `tokens`, each distinct token once, and gives each its stem
+///
+/// Tokens are compared as they are, so "Cats" and "cats" are stemmed once
+/// each. The stems are the ones stemming every token would give.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, corpus::stem_corpus};
+/// let mut stemmer = PorterStemmer::new();
+/// let stems = stem_corpus(&mut stemmer, "the cats saw the running cats".split(' '));
+/// assert_eq!(stems.iter().collect::<Vec<_>>(), ["the", "cat", "saw", "the", "run", "cat"]);
+/// assert_eq!(stems.stems(), ["the", "cat", "saw", "run"]);
+/// ```
+pub fn stem_corpus<'a>(stemmer: &mut dyn Stemmer, tokens: impl IntoIterator<Item = &'a str>) -> CorpusStems {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut corpus = CorpusStems::default();
+    for token in tokens {
+        let next = seen.len();
+        let id = *seen.entry(token).or_insert(next);
+        if id == next {
+            corpus.stems.push(stemmer.stem(token));
+        }
+        corpus.ids.push(id);
+    }
+    corpus
+}
+
+/// Stems `tokens` as [`stem_corpus`] does, and gives the symbol of each
+/// token's stem in `interner`
+///
+/// Tokens with the same stem get the same symbol, as do tokens of
+/// different calls with the same interner.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{corpus::stem_corpus_interned, intern::StemInterner, PorterStemmer};
+/// let mut stemmer = PorterStemmer::new();
+/// let mut interner = StemInterner::new();
+/// let symbols = stem_corpus_interned(&mut stemmer, "the cats saw the running cats".split(' '), &mut interner);
+/// assert_eq!(symbols, [0, 1, 2, 0, 3, 1]);
+/// assert_eq!(stem_corpus_interned(&mut stemmer, ["runs", "cat"], &mut interner), [3, 1]);
+/// assert_eq!(interner.resolve(3), Some("run"));
+/// ```
+pub fn stem_corpus_interned<'a>(
+    stemmer: &mut dyn Stemmer,
+    tokens: impl IntoIterator<Item = &'a str>,
+    interner: &mut StemInterner,
+) -> Vec<u32> {
+    let mut seen: HashMap<&str, u32> = HashMap::new();
+    tokens
+        .into_iter()
+        .map(|token| *seen.entry(token).or_insert_with(|| interner.stem(stemmer, token)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::tokens_for;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_stem_corpus() {
+        /// Counts the words it is asked to stem
+        struct Counting(PorterStemmer, usize);
+
+        impl Stemmer for Counting {
+            fn stem(&mut self, word: &str) -> String {
+                self.1 += 1;
+                self.0.stem(word)
+            }
+        }
+
+        let text = "Running ponies were hopping over the generalizations; the ponies ran.\n".repeat(50);
+        let mut stemmer = Counting(PorterStemmer::new(), 0);
+        let tokens: Vec<&str> = tokens_for(&stemmer, &text).map(|token| token.text).collect();
+        let corpus = stem_corpus(&mut stemmer, tokens.iter().copied());
+        assert_eq!((corpus.len(), stemmer.1), (500, 8));
+        let each: Vec<String> = tokens.iter().map(|token| stemmer.0.stem(token)).collect();
+        assert!(corpus.iter().eq(each.iter().map(String::as_str)));
+        assert_eq!(corpus.get(1), Some("poni"));
+        assert_eq!((corpus.get(500), corpus.ids()[11]), (None, 1));
+        assert!(stem_corpus(&mut stemmer, []).is_empty());
+
+        stemmer.1 = 0;
+        let mut interner = StemInterner::new();
+        let symbols = stem_corpus_interned(&mut stemmer, tokens.iter().copied(), &mut interner);
+        assert_eq!((symbols.len(), stemmer.1, interner.len()), (500, 8, 8));
+        assert!(symbols.iter().map(|&symbol| interner.resolve(symbol).unwrap()).eq(corpus.iter()));
+    }
+}
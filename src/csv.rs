@@ -0,0 +1,163 @@
+//! # CSV Column Stemming
+//!
+//! Stems selected text columns of CSV (or TSV and other delimited) input
+//! while copying everything else through byte for byte: untouched columns,
+//! quoting, embedded newlines, line endings and the header row.
+//!
+//! Stemming only ever rewrites runs of ASCII letters, so a selected field can
+//! be stemmed in its raw, still-quoted form without re-quoting it.
+
+use std::io::{self, BufRead, Write};
+
+use crate::text::stem_text;
+use crate::Stemmer;
+
+/// Options for [`stem_csv_with`]
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Field separator, `,` for CSV and `\t` for TSV
+    pub delimiter: u8,
+    /// Whether the first record is a header row that is copied unchanged
+    pub has_header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions { delimiter: b',', has_header: true }
+    }
+}
+
+/// Reads one raw record, which may span several lines inside quotes
+///
+/// Returns false at end of input.
+fn read_record<R: BufRead>(reader: &mut R, record: &mut String) -> io::Result<bool> {
+    record.clear();
+    let mut in_quotes = false;
+    loop {
+        let start = record.len();
+        if reader.read_line(record)? == 0 {
+            return Ok(!record.is_empty());
+        }
+        for b in record[start..].bytes() {
+            if b == b'"' { in_quotes = !in_quotes; }
+        }
+        if !in_quotes { return Ok(true); }
+    }
+}
+
+/// Writes a record with the selected (0-based) columns stemmed
+fn write_record<W: Write>(
+    stemmer: &mut dyn Stemmer,
+    writer: &mut W,
+    record: &str,
+    columns: &[usize],
+    delimiter: u8,
+) -> io::Result<()> {
+    let body = record.trim_end_matches(['\n', '\r']);
+    let line_end = &record[body.len()..];
+    let bytes = body.as_bytes();
+
+    let mut column = 0;
+    let mut field_start = 0;
+    let mut in_quotes = false;
+    for i in 0..=bytes.len() {
+        if i < bytes.len() {
+            if bytes[i] == b'"' { in_quotes = !in_quotes; }
+            if in_quotes || bytes[i] != delimiter { continue; }
+        }
+        let field = &body[field_start..i];
+        if columns.contains(&column) {
+            writer.write_all(stem_text(stemmer, field).as_bytes())?;
+        } else {
+            writer.write_all(field.as_bytes())?;
+        }
+        if i < bytes.len() { writer.write_all(&[delimiter])?; }
+        column += 1;
+        field_start = i + 1;
+    }
+
+    writer.write_all(line_end.as_bytes())
+}
+
+/// Stems the given columns of CSV input, preserving everything else
+///
+/// The first record is treated as a header and copied unchanged.
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each word in the selected columns
+/// * `reader` - CSV input
+/// * `writer` - Destination for the rewritten CSV
+/// * `columns` - 0-based indices of the columns to stem
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, csv::stem_csv};
+/// let mut stemmer = PorterStemmer::new();
+/// let mut out = Vec::new();
+/// stem_csv(&mut stemmer, "id,title\n7,\"Running, jumping\"\n".as_bytes(), &mut out, &[1]).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), "id,title\n7,\"run, jump\"\n");
+/// ```
+pub fn stem_csv<R: BufRead, W: Write>(
+    stemmer: &mut dyn Stemmer,
+    reader: R,
+    writer: W,
+    columns: &[usize],
+) -> io::Result<()> {
+    stem_csv_with(stemmer, reader, writer, columns, &CsvOptions::default())
+}
+
+/// Stems the given columns of delimited input using explicit options
+///
+/// See [`stem_csv`]; `options` selects the delimiter and header handling.
+pub fn stem_csv_with<R: BufRead, W: Write>(
+    stemmer: &mut dyn Stemmer,
+    mut reader: R,
+    mut writer: W,
+    columns: &[usize],
+    options: &CsvOptions,
+) -> io::Result<()> {
+    let mut record = String::new();
+    let mut first = true;
+    while read_record(&mut reader, &mut record)? {
+        if first && options.has_header {
+            writer.write_all(record.as_bytes())?;
+        } else {
+            write_record(stemmer, &mut writer, &record, columns, options.delimiter)?;
+        }
+        first = false;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    fn run(input: &str, columns: &[usize], options: &CsvOptions) -> String {
+        let mut stemmer = PorterStemmer::new();
+        let mut out = Vec::new();
+        stem_csv_with(&mut stemmer, input.as_bytes(), &mut out, columns, options).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_selected_columns_only() {
+        let input = "id,Title,Tags\r\n1,\"Running \"\"fast\"\" dogs\",running\r\n\
+            2,\"multi\nline cats\",flies\r\n3,,";
+        assert_eq!(
+            run(input, &[1], &CsvOptions::default()),
+            "id,Title,Tags\r\n1,\"run \"\"fast\"\" dog\",running\r\n\
+            2,\"multi\nline cat\",flies\r\n3,,"
+        );
+    }
+
+    #[test]
+    fn test_tsv_without_header() {
+        let options = CsvOptions { delimiter: b'\t', has_header: false };
+        assert_eq!(
+            run("ponies\tponies\tponies\n", &[0, 2], &options),
+            "poni\tponies\tponi\n"
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! The `daemon` subcommand of the command line front end
+//!
+//! `porterstem daemon` stays running and answers one line with one line, on
+//! stdin and stdout or, with `--socket PATH`, on each connection to a Unix
+//! domain socket, so that editors and other tools pay for starting the
+//! stemmer once instead of on every word. A line is either:
+//! - plain text, answered with its stems as `porterstem` would print them
+//!   (`Running ponies` -> `run poni`)
+//! - a JSON-RPC 2.0 request object, answered with a response object on one
+//!   line; the methods are those of `serve`, `stem`, `stem-batch` and
+//!   `stem-text`, with the same parameters, e.g.
+//!   `{"jsonrpc":"2.0","id":1,"method":"stem","params":{"word":"running"}}`
+//!   -> `{"jsonrpc":"2.0","id":1,"result":{"word":"running","stem":"run"}}`
+//!
+//! Requests without an `id` are notifications and get no answer, as JSON-RPC
+//! has it. Every answer is flushed at once. JSON-RPC requests are read by
+//! serde_json, and need the `jsonrpc` feature (which `serve` turns on);
+//! without it each is answered with an error.
+
+use std::io::{self, BufRead, Write};
+use std::panic::{self, AssertUnwindSafe};
+
+use m_porter_stemmer_rust::{text, Stemmer};
+#[cfg(feature = "jsonrpc")]
+use serde_json::{json, Value};
+
+/// JSON-RPC error codes
+#[cfg(feature = "jsonrpc")]
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+#[cfg(feature = "jsonrpc")]
+const METHOD_NOT_FOUND: i32 = -32601;
+#[cfg(feature = "jsonrpc")]
+const INVALID_PARAMS: i32 = -32602;
+const INTERNAL_ERROR: i32 = -32603;
+
+/// Runs the method `stem`, `stem-batch` or `stem-text` on its parameters,
+/// returning the result, or an error message for parameters of the wrong
+/// shape; None if there is no such method
+///
+/// The daemon's JSON-RPC methods and the endpoints of `serve` are both
+/// answered here. The word of `stem` and the text of `stem-text` may also be
+/// given as a bare string or as the one element of an array, as JSON-RPC
+/// positional parameters.
+#[cfg(feature = "jsonrpc")]
+pub fn call(stemmer: &mut dyn Stemmer, method: &str, params: &Value) -> Option<Result<Value, &'static str>> {
+    let string_param = |name: &str| match params {
+        Value::String(s) => Some(s.as_str()),
+        Value::Array(values) if values.len() == 1 => values[0].as_str(),
+        _ => params.get(name).and_then(Value::as_str),
+    };
+    let result = match method {
+        "stem" => {
+            let Some(word) = string_param("word") else {
+                return Some(Err("expected {\"word\": \"...\"}"));
+            };
+            json!({ "word": word, "stem": stemmer.stem(word) })
+        },
+        "stem-batch" => {
+            let words = params.as_array().or_else(|| params.get("words").and_then(Value::as_array));
+            let Some(words) = words.and_then(|words| words.iter().map(Value::as_str).collect::<Option<Vec<_>>>())
+            else {
+                return Some(Err("expected an array of strings"));
+            };
+            let stems: Vec<String> = words.into_iter().map(|word| stemmer.stem(word)).collect();
+            json!({ "stems": stems })
+        },
+        "stem-text" => {
+            let Some(input) = string_param("text") else {
+                return Some(Err("expected {\"text\": \"...\"}"));
+            };
+            json!({ "text": text::stem_text(stemmer, input) })
+        },
+        _ => return None,
+    };
+    Some(Ok(result))
+}
+
+/// Returns the JSON-RPC response for `id` with a result or an error
+#[cfg(feature = "jsonrpc")]
+fn response(id: Value, answer: Result<Value, (i32, &str)>) -> String {
+    let response = match answer {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+    };
+    response.to_string()
+}
+
+/// Returns the JSON-RPC error response to a request whose id is not known
+#[cfg(feature = "jsonrpc")]
+fn error_response(code: i32, message: &str) -> String {
+    response(Value::Null, Err((code, message)))
+}
+
+/// Returns the JSON-RPC error response to a request whose id is not known;
+/// `message` has nothing to escape
+#[cfg(not(feature = "jsonrpc"))]
+fn error_response(code: i32, message: &str) -> String {
+    format!("{{\"jsonrpc\":\"2.0\",\"id\":null,\"error\":{{\"code\":{},\"message\":\"{}\"}}}}", code, message)
+}
+
+/// Answers one line of input, or returns None for a notification
+fn answer(stemmer: &mut dyn Stemmer, line: &str) -> Option<String> {
+    if !line.trim_start().starts_with('{') {
+        return Some(text::stem_text(stemmer, line));
+    }
+    answer_request(stemmer, line)
+}
+
+/// Answers a JSON-RPC request, or returns None for a notification
+#[cfg(feature = "jsonrpc")]
+fn answer_request(stemmer: &mut dyn Stemmer, line: &str) -> Option<String> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return Some(error_response(PARSE_ERROR, &e.to_string())),
+    };
+    let id = request.get("id").cloned();
+    let answer = match request.get("method").and_then(Value::as_str) {
+        None => Err((INVALID_REQUEST, "expected a method name")),
+        Some(method) => match call(stemmer, method, request.get("params").unwrap_or(&Value::Null)) {
+            Some(Ok(result)) => Ok(result),
+            Some(Err(message)) => Err((INVALID_PARAMS, message)),
+            None => Err((METHOD_NOT_FOUND, "no such method")),
+        },
+    };
+    match (id, answer) {
+        (Some(id), answer) => Some(response(id, answer)),
+        // A malformed request gets an answer even without an id
+        (None, Err((INVALID_REQUEST, message))) => Some(error_response(INVALID_REQUEST, message)),
+        (None, _) => None,
+    }
+}
+
+/// Answers a JSON-RPC request with an error, as reading one needs serde_json
+#[cfg(not(feature = "jsonrpc"))]
+fn answer_request(_: &mut dyn Stemmer, _: &str) -> Option<String> {
+    Some(error_response(INVALID_REQUEST, "JSON-RPC requires porterstem built with the jsonrpc feature"))
+}
+
+/// Answers the lines of `input` on `output` until the input ends
+///
+/// A line that makes the stemmer panic is answered with an internal error
+/// (or an empty line, in the plain protocol), and the lines after it get a
+/// fresh stemmer.
+pub fn serve_lines(
+    make_stemmer: &(impl Fn() -> Box<dyn Stemmer> + Sync),
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut stemmer = make_stemmer();
+    for line in input.lines() {
+        let line = line?;
+        let answered = panic::catch_unwind(AssertUnwindSafe(|| answer(&mut *stemmer, &line)));
+        let answer = answered.unwrap_or_else(|_| {
+            stemmer = make_stemmer();
+            if line.trim_start().starts_with('{') {
+                Some(error_response(INTERNAL_ERROR, "stemming failed"))
+            } else {
+                Some(String::new())
+            }
+        });
+        if let Some(answer) = answer {
+            writeln!(output, "{}", answer)?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Serves each connection to the Unix domain socket at `path` on its own
+/// thread until accepting one fails
+///
+/// A socket file left at `path` by an earlier daemon that is gone is
+/// replaced; one that a daemon still answers on is an error.
+#[cfg(unix)]
+pub fn listen(path: &str, make_stemmer: impl Fn() -> Box<dyn Stemmer> + Sync) -> io::Error {
+    use std::io::BufReader;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    let mut bound = UnixListener::bind(path);
+    if matches!(&bound, Err(e) if e.kind() == io::ErrorKind::AddrInUse) && UnixStream::connect(path).is_err() {
+        if let Err(e) = std::fs::remove_file(path) {
+            return e;
+        }
+        bound = UnixListener::bind(path);
+    }
+    let listener = match bound {
+        Ok(listener) => listener,
+        Err(e) => return io::Error::new(e.kind(), format!("{}: {}", path, e)),
+    };
+    std::thread::scope(|scope| loop {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) => return e,
+        };
+        let make_stemmer = &make_stemmer;
+        // A client going away is its own business
+        scope.spawn(move || {
+            let output = stream.try_clone()?;
+            serve_lines(make_stemmer, BufReader::new(stream), output)
+        });
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m_porter_stemmer_rust::PorterStemmer;
+
+    #[cfg(feature = "jsonrpc")]
+    #[test]
+    fn test_serve_lines() {
+        let input = concat!(
+            "Running ponies\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"stem\",\"params\":{\"word\":\"running\"}}\n",
+            "{\"jsonrpc\":\"2.0\",\"method\":\"stem\",\"params\":[\"no\"]}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":0,\"method\":\"stem-text\",\"params\":[\"Ponies ran\"]}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":\"b\",\"method\":\"stem-batch\",\"params\":[\"caresses\",\"ponies\"]}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"lemmatize\"}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"stem\",\"params\":7}\n",
+            "{\"id\":\n",
+            "\n",
+        );
+        let mut output = Vec::new();
+        serve_lines(&|| Box::new(PorterStemmer::new()) as Box<dyn Stemmer>, input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines, [
+            "run poni",
+            r#"{"jsonrpc":"2.0","id":1,"result":{"word":"running","stem":"run"}}"#,
+            r#"{"jsonrpc":"2.0","id":0,"result":{"text":"poni ran"}}"#,
+            r#"{"jsonrpc":"2.0","id":"b","result":{"stems":["caress","poni"]}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"error":{"code":-32601,"message":"no such method"}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"error":{"code":-32602,"message":"expected {\"word\": \"...\"}"}}"#,
+            r#"{"jsonrpc":"2.0","id":null,"error":{"code":-32700,"message":"EOF while parsing a value at line 1 column 6"}}"#,
+            "",
+        ]);
+    }
+
+    #[cfg(not(feature = "jsonrpc"))]
+    #[test]
+    fn test_serve_lines() {
+        let input = "Running ponies\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"stem\",\"params\":[\"running\"]}\n";
+        let mut output = Vec::new();
+        serve_lines(&|| Box::new(PorterStemmer::new()) as Box<dyn Stemmer>, input.as_bytes(), &mut output).unwrap();
+        let error = r#"{"code":-32600,"message":"JSON-RPC requires porterstem built with the jsonrpc feature"}"#;
+        let expected = format!("run poni\n{{\"jsonrpc\":\"2.0\",\"id\":null,\"error\":{}}}\n", error);
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+}
@@ -0,0 +1,328 @@
+//! # Language Detection
+//!
+//! Guesses the language of a text so that mixed-language corpora can be
+//! stemmed without labelling every document: [`detect_language`] names the
+//! language and [`stem_text_auto`] stems each paragraph with the stemmer
+//! for its language. Only available with the `detect` feature.
+//!
+//! Greek, Russian and Hindi are told by their scripts. Text in Latin script
+//! is compared with a profile of each Latin-script language, its 300 most
+//! common letter trigrams in order ("_th" for a word starting "th"), by
+//! Cavnar and Trenkle's out-of-place measure: the sum, over the text's own
+//! most common trigrams, of how far each is from its rank in the profile.
+//! The profiles were ranked from sample text and, where there is one, the
+//! Snowball vocabulary of the language. A sentence or two is usually
+//! enough, though Norwegian and Danish are easily confused.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::stemmer::{Language, LanguageStemmers};
+use crate::text::stem_text;
+
+/// How many trigrams a profile and a text are compared by
+const PROFILE_LEN: usize = 300;
+
+/// Paragraphs with fewer letters than this take the language of the whole
+/// text
+const MIN_PARAGRAPH_LETTERS: usize = 20;
+
+const ENGLISH: &str = "\
+    the _th he_ ed_ _an nd_ ing and er_ es_ ng_ _in _of of_ in_ _re on_ _co _be re_ _ha _fa _to _ri \
+    it_ ver ty_ her le_ en_ rea ill ard _ho ear _wa _pr _bu ent ous _wh _ne ly_ ar_ ll_ as_ hou at_ \
+    ld_ nce not _ma to_ per _it st_ all ce_ _pe ry_ ion ter ity _mo end ts_ ers _en man an_ _sa sin \
+    ght _he _li igh con ne_ one age _fi _wi ise _di are _ar ons rit nt_ act _no ore tio ead _st _we \
+    oth ood ay_ son hil id_ rig whi rde od_ ot_ ati hat ut_ ild but has al_ men aid tha yed hem ess \
+    aye em_ _ye _pa sai _on ste yea pro _ch ies ive der ate ted sta enc _sh _se _br _fo tin _un nin \
+    tor rs_ for ve_ _de ove ds_ ls_ _sp gs_ ns_ _bo ind _so ses ast ngs sed den _si ss_ ces chi ili \
+    eve inc ert _fr har ge_ _ac _pl mis _ga ang eas res or_ pla ren ris _al _vi eat de_ ric est ave \
+    ple th_ ber gin ch_ sen ile ath han ice ome us_ pri nes war use _wo ade ree _hu owe qua ost uri \
+    _go ges spi cur ern ien lla ms_ ery ign lie nit sho ble wor omi me_ col orn _bi ey_ mor old ct_ \
+    fin is_ _or equ _at bro ith arm hoo ht_ mil _sm bil mal ual rn_ rds bor ext riv fre gar wed ami \
+    usi ory ny_ spa dy_ sec ape inn lin _ev ad_ dre ecu sma rot wit rom ndo vil cie lay lls gni nni \
+    fac hum ich pir way ee_ sci iri rst cto nea wil";
+
+const FRENCH: &str = "\
+    es_ nt_ ent _le _de de_ les _la it_ la_ ant _pr ns_ res ais re_ _co et_ men _et is_ _en ts_ ont \
+    té_ le_ _ma ait dan ne_ er_ rs_ _da eme on_ ien _a_ _à_ ill _pa our con _fa _re son ons ire ée_ \
+    rai te_ ans _qu mai _di du_ _il _pe ce_ ers és_ nts que _dé _vi _do lle _in ère pro _li us_ roi \
+    _so ava en_ ue_ _du tre il_ aie ver ure ine _l_ _sa ité sse age iss ite ter ain ion _pl est par \
+    pri _to ge_ eur rit _fr cha tra in_ onn _an tou van _es _lo ous end mes _no _au née tur as_ dit \
+    _tr al_ jou pas nte iso _jo mis _ce ern nce ser ier nné ut_ sai ie_ nou ren nit ann ivi era oit \
+    dro ron cou log _dr lai gem _un lib oge plu _po ign _on _ra tio nai nne ur_ ouv ez_ ux_ rem _se \
+    ard ir_ ins per tan ven sen air enc urs nta omm ati isa des ret uis out art pre nda ndi lla uve \
+    qui ind se_ mme rat ert ita ive che str _ha pou pen _al _ch _av eau ble don ail com erm vai tes \
+    nté rom _ré _fi ate tés ièr vie ssa fra esp ass _mi env roc aut cie fer rme ero len leu rri _ca \
+    _fe riv sin rav aux ess ala dou uai tit nve oup itu urn och _ou col rne _ri tai fai sa_ _na hai \
+    act nst ra_ urr enf abi bre mal rna _ag _go agi rdi alo ls_ éga _hu _be bit omi rt_ _ja vit déb \
+    hab nal rêt eté fro ten usi fin nem ran gni uir";
+
+const GERMAN: &str = "\
+    en_ er_ nd_ ten _ge der ie_ _un sch ich che und es_ eit gen cht ung _ha nde _si die _de _di it_ \
+    ste _be nge end ind ein hen ben ver ers te_ at_ ges hat ng_ _ve ht_ hre _re rei _er nun nen sin \
+    den lle eis ebe _au sen ech lic ern _le ch_ ens rec des ren _sc _an _me her auf lei len hnu de_ \
+    hei im_ _im _zu sse ege lie ter geb aus ert _wi se_ in_ ach _se ele gt_ _fr _wa ist le_ ber chn \
+    rte _ka ige hne iel _so men ahr mit rde _wo res fre _ei hte och _al abe woh beg leb _ni tig sie \
+    ier sei and _fa son est _ja lte ohn tte agt ang esa nic sag as_ _wü uf_ jah _es erl _we gel zu_ \
+    vor rst _vo fen rn_ _ab st_ elt ig_ ede sti ite ell el_ iss _en ne_ tel kei run chs nne itt eic \
+    lt_ ese erh uss alt ere _mi gew ehr ieb re_ all eru rli _sp nsc ine oll ft_ hau _fe ien ler art \
+    fer spr rbe hal _st hle ute _he _ze gem tun erd bei aue ger wer _ba _pr _br _da ieg ise tie ina \
+    ss_ _gl zei _in bli geg _fl äch rti on_ spi nte kle mme _kl eut sic mei ahl use hst he_ rhe wie \
+    um_ chk pie ent _ki chl bau reg pro per wir ing _fü bri hke vie isc arb nan ei_ mac ls_ ge_ leu \
+    ene gle _ar _ga bt_ ewi pre ond wis ar_ uen ili rsp _vi nft roc ähr aum gie war am_ wür ema lus \
+    _pe eih les hr_ flu _hö an_ gne _do fan ore _no";
+
+const PORTUGUESE: &str = "\
+    as_ os_ do_ _co _de em_ de_ _ma da_ ão_ _di _pr _e_ _do es_ ado _o_ _em ida to_ ia_ con _pe _os \
+    res ade dad am_ que _es tas dos no_ _qu nta _da com _in io_ nte ent is_ ira ess _ca rio _an est \
+    sta ou_ ria ito ar_ ais ra_ _as ano ant pre _na iss ava sso ue_ _pa pro mai ita _à_ eir ont des \
+    _su ica _se mos al_ sse na_ _re ada tra cas man fic ons se_ ara sa_ _fi ram _li dis eit mo_ _fr \
+    _to ir_ ção bri rei das nas mas ern nça cia _en dei anç per dir ire pes nto im_ asa men ran tad \
+    nid odo ter _ou _tr tod te_ soa açã não _nã _a_ va_ nci tar tes str ca_ par tos ndo esp _sa or_ \
+    _te cio ras ura _al ros ver and int _vi _po qui ári ios _fa ias ind ssa tro ndi ele ha_ ere lha \
+    rat ori inc _le cul ert por so_ ome vam rin ete ime _cr ian ate _fo gar ta_ nal _br ifi _ra ric \
+    vid nca ber ênc tem emo aba ser gad _el pri ena nst ist rit eve cri egu rim ron nos alh sas _ag \
+    rom _no ard eu_ imo ota lia rna _mu equ sal _go met ang ane omi bal aga ça_ rad tav ns_ fra rda \
+    taç abi omo seg ove ina div rto sub qua uin tru orn _ja _ri _jo asc _ha ald erd nga uma gua rão \
+    le_ dep nqu rdi uen agi sci lem ign nei gur cav epr ibe sce mid lib rab íci _un dev dif ior eia \
+    icu igu rno ças uir _hu ame pag zan cem lda bit";
+
+const SWEDISH: &str = "\
+    ar_ en_ _oc ch_ och _de _ha de_ et_ an_ _i_ _va er_ te_ har sta var _fö and la_ na_ tt_ _at _av \
+    _bo _in _li _me _rä _sa _st _ti ade att av_ bet den der det för ga_ gen han het ill ing nte om_ \
+    on_ ra_ rde rna ta_ _ar _by _bö _en _fa _fl _fr _ho _lä _pr _på _so _ut _vi _år all ara arn at_ \
+    bar bos bör da_ dan eda ern ers eta ete fle fri går hon int kte ler ll_ lla med men ner nga nge \
+    nin nna or_ ost på_ ran rät sad som stä tan tig til täd äde än_ äst ätt ård _al _an _ba _be _bl \
+    _br _el _ge _gi _gj _gå _he _hu _jo _ka _kl _le _lo _lö _ma _mo _mä _må _nä _om _pe _re _se _si \
+    _sl _sn _sv _sä _så _tr _ty _vä _än _är _ån abb abr ad_ ala ami amv ann ap_ arb are arg arj ats \
+    bba bbe bli bor bri bro byg byn ck_ ckt dar dda dem dgå dla dni dra ed_ ege ekt ell em_ emm emo \
+    ene ent era erh eri est fab fam ft_ föd gar ger gga ghe gic git gjo gon gs_ hem hur ia_ ick id_ \
+    idn ig_ igh igi ihe ika ike ilj ina inn ir_ ise isk it_ iv_ jan je_ jer job jor ka_ kal kap ken \
+    ker kla kni kor lar lek les lig lik lil lir liv lje lle llt lov lt_ lut län läs lön ma_ mat mil \
+    mma mor mot mve män mån nab nad nan nda nde ndl ndr nen ngs nis nli nni nuf näs obb ode ome onl \
+    ord org ot_ ova per pri pro rbe rda re_ reg ret";
+
+const NORWEGIAN: &str = "\
+    er_ en_ et_ ing ne_ _ha _me _de og_ _og te_ ar_ ste ene men nge de_ gen for le_ ske lig ere ver \
+    _sa _i_ kke nne ker het ret nes ikk nin _re re_ enn _fo sen ter _si est lle ige den ge_ har _av \
+    ett det and rin _va _bo ger esk han _fø reg ler _be an_ mme ed_ _hv ten ens ng_ sam ord ig_ tte \
+    _fr var ngs før ke_ ise _st els ide _le erd der _ma sin rde ans byg _li ska _fa til eri _en _ut \
+    ghe igh tig som med _by fri oli les nde ove _fl _på jon sjo nte tti ran va_ tal me_ or_ lse _so \
+    om_ _bl _ve dt_ _ti bol _år _el _er ør_ fle av_ kap sa_ hve _ik på_ _å_ ors rt_ ers kte ekt ale \
+    nsk gje _pr sik ell ang per eta _pe _in tt_ inn ved eid eli ete es_ rbe lov ris ns_ ren ill _ar \
+    arb ser ken bei jer _ka _br ets ygg _vi all sty bet ege gge eve tra get _fe age vis ine dig ier \
+    dle rsk del egn pri end era _lo ald ras amm _mo dre _gj fer nd_ ast lin _ba kel man ner sta rdi \
+    _sl van itt _ko _ne tyr int _al ndr _lø tet pet vet ts_ _sk ent ønn emm rst _ra uts ape rik gni \
+    kal slu lut nn_ utt ble mil ndl ørs da_ mot nel rie opp ort bar bor ons år_ pen nga beg løn lek \
+    _tr st_ jor liv vit sid tst ili jem iv_ rd_ dan na_ rna teg hje nli son ånd åre sti str nni _hj \
+    amv em_ mat bli enh il_ ot_ elv ft_ mvi rso arn";
+
+const DANISH: &str = "\
+    en_ er_ _de de_ og_ _og et_ _ha ed_ der ger _i_ _me _på ar_ den hed ige ne_ nge på_ ste _en _fø \
+    _li _re and ge_ ing le_ lig men ret te_ ver _at _bo _er _hu _hv _sa at_ det dt_ ede ege ere før \
+    gen ghe han har hve igh ikk kke lle nin re_ rne tig ør_ _af _ar _be _bl _bø _fa _fl _fr _gå _ik \
+    _la _ma _mo _si _st _va _ve _år ad_ af_ agd an_ ang arb bej bet bol bør dig ejd ene ern ers fle \
+    fri gde hun ide ise jde ke_ ker lan ler liv nd_ nde nne oli ran rbe rde rdi reg sag sen tti un_ \
+    var ved vær ærd æst _al _av _br _by _el _fo _fæ _gj _go _hj _ko _ku _le _lo _læ _lø _må _næ _pe \
+    _pr _sl _sv _så _ti _to _tu _ud _vi _vr _væ _åe _ån abe abr ale all ami amv ave avi beg ble bli \
+    bor bri bro bye byg del dem dle dre dsb dst egn egy ell els em_ emm end enh enn ens era erh eri \
+    es_ esk est eta ets ett ev_ fab fam for ft_ fær fød ged get gge gjo gni god gs_ gyn gå_ går hav \
+    hje hur hva ie_ ier ig_ ihe ik_ il_ ili ill itt iv_ ive jem jor kab kol kun ldt leg les lev lid \
+    lie lil lov lse lut læs løn mad man me_ med mil mme mod mor mvi måd ndl ndr nds nen nes ngs nhv \
+    nli nni ns_ nuf næs od_ ode odt old onl or_ ord org orn ove per pri red res rge rhe rie rih rik \
+    rin ris rnu rod rsk rso rst rt_ rti sam sby ser";
+
+const HUNGARIAN: &str = "\
+    _a_ _sz _és ek_ en_ sze és_ _az az_ _mi an_ el_ ere tt_ _el emb ett ind mbe min on_ rek ság _gy \
+    _la _ne _va ben den ell em_ ga_ gy_ hog lak let mél nde nek nem ogy ott unk zem őtt _am _eg _em \
+    _ho _jo _ke _ki _me _mo _tö _él _év aba ak_ aká bad ban ber egy elő emé ge_ gho gye hez hoz ik_ \
+    it_ jog kás lel lle lt_ lőt mon ni_ nk_ ny_ oga ok_ ond oz_ ret ri_ sal sok sza szü ta_ te_ tes \
+    tet ton tte tun töb van zab zel zer zül ágh ége éle ély ért év_ öbb _ah _bi _bé _bí _cs _de _do \
+    _dü _ez _fa _fe _fo _ha _hi _in _já _jö _ko _ké _le _lé _ma _mu _mé _nő _ol _ot _pa _re _so _sé \
+    _ta _te _tu _vi _vo _vé _ár _ép _ót _új _ők abb ado ads adt agy aho akó al_ alu alá ami amí ane \
+    any ara art ast ba_ bb_ bba bbs biz bsé bér bír csa de_ deg dja dol don dot dsá dta dtu dul düh \
+    eg_ egg egí ehe eje eke ele elk elm els elt ene enl eri ert ess est et_ ete eth eti etn ez_ eze \
+    ezé fal fel fiz fol gel gge got goz gya gym gyo gyá gér gíg han hid hon hít ide iel ifi iis ina \
+    int is_ ise ism isz ize izt ja_ je_ jsá ját jöv kek kel ker ket kif kii kis kon kor káb kés kói \
+    lej lem lgo lki ll_ lmi lne lni lső lte ltó lu_ lva lyi lyn lyó lád lái lén lő_ mar meg mel mer \
+    mie mis mit mlá mun mán más míg nak ndo ndt ndu";
+
+const ROMANIAN: &str = "\
+    le_ _în te_ ul_ ate și_ _de ele _și uri _co or_ la_ _la în_ ii_ re_ de_ _pr tul ile inț ea_ tat \
+    _se ui_ are _ce că_ _sa _fa at_ _a_ ță_ se_ lor _ma con ale ce_ au_ _fi _al _sp mul _mu rep lui \
+    nul ulu ita nte ril _re tur ei_ ai_ str iin mai lte ri_ rat nțe men rea esc ne_ tă_ une _cu rit \
+    tel elo ent sc_ nă_ _li să_ ie_ _gr nit _vi am_ cre ime ric itu _că _am man _an _fr ar_ _ci tor \
+    ane ept ult _fe din loc ocu _lo ern anu it_ ori spu _dr ere ali pus _um _nu ită cui dre sal uin \
+    uma ber iar us_ pre ptu cu_ _tr fii _ca tra lib ibe nu_ pro ter ață nță țel rii _su int per pri \
+    tea _di _mi înc ţi_ ică iun ză_ ica eni tre _pe est aţi rul _in res ază ili asc era ito ice eaz \
+    toa _pl _ne ari com cur _ră iri scu _cr ni_ ons iil tru ta_ tit _ar nt_ _ac car ra_ por eri _lu \
+    imp cit ame nce fer _or nst ver rim _va ci_ asă ria _st cep nel lul ort iat _să ede ca_ act lar \
+    ers tim me_ ata iti omp ecu dec _vo _un spi cut _to lim epe ină uie aru elu rni ut_ _sf ala rta \
+    _ra răm _au ată _ju ert dem gre cop emn ami oan mil _ti mic opi pir put erm rom rui _na alt ega \
+    rig sat înf mis tes _da tii ctu unt gă_ in_ vor âng mel fra luc ări ană rme zia fur ia_ _ga omi \
+    reb rte fac gat _el ebu epu mpo ist utu ilo bui";
+
+const TURKISH: &str = "\
+    lar _ve ve_ _ha an_ arı da_ dan _da _ya ar_ ard ler nda nla ın_ _ba _bi _bu _fa _ka _sa _so _sö \
+    aya bir de_ di_ ede en_ er_ eri et_ ine irl iye ne_ onu rla rle rın ınd _ar _et _he _hü _il _in \
+    _ki _ko _ok _ta _yü _yı _ço _ön ada aha ahi ak_ akı ala anl anı ark arl aşa aşl bah bu_ cak ce_ \
+    dah den duk dır ece erd eyi eği ha_ hak her il_ ile in_ kad kar ki_ kla kon kud le_ lın may nca \
+    nce nut oku or_ oğu rda rdi ri_ rin rke rı_ sah siy son söy te_ tme udu ukt ut_ ver ya_ yan yaş \
+    yet yle yor yıl önc öyl üme ğin ılı ır_ ıyo şla _ai _ak _an _be _bo _bü _de _do _ed _ev _eş _fi \
+    _ga _ge _gi _gü _gı _hı _iş _kö _kü _kı _ma _ne _ol _on _oy _se _va _vi _zi _zo _ça _öd _öz aaş \
+    aba abr abı aca ah_ ahç ail aki akk akl ald alı ama ana anc are art arş atl atu ava avs ays aze \
+    azl azı aşı bak baş ber biç boy bri bun büt bı_ ca_ cda cek ceğ cuk dak daş dec dem değ deş dik \
+    dir doğ dık edi ehr ek_ eke ekt ele eli eme enl erk esi ete eti etm ett evd eve eşi eşl fab fat \
+    faz fiy gaz gel git gür güv gıd har hav hay haz hil hip hni hri hçe hük hür hız icd idi ihn ik_ \
+    ika ikl ilk im_ imi ins inş ipt irb irç it_ ita itm iya iyd içi iği iş_ işe işi kal ken kes ket \
+    kit kiş kkı kle kta kte ktu köy küm küç kı_ kıl";
+
+/// The Latin-script languages and their profiles
+const PROFILES: &[(Language, &str)] = &[
+    (Language::English, ENGLISH),
+    (Language::French, FRENCH),
+    (Language::German, GERMAN),
+    (Language::Portuguese, PORTUGUESE),
+    (Language::Swedish, SWEDISH),
+    (Language::Norwegian, NORWEGIAN),
+    (Language::Danish, DANISH),
+    (Language::Hungarian, HUNGARIAN),
+    (Language::Romanian, ROMANIAN),
+    (Language::Turkish, TURKISH),
+];
+
+/// The rank of every trigram in each profile
+fn profile_ranks() -> &'static [(Language, HashMap<&'static str, usize>)] {
+    static RANKS: OnceLock<Vec<(Language, HashMap<&'static str, usize>)>> = OnceLock::new();
+    RANKS.get_or_init(|| {
+        PROFILES
+            .iter()
+            .map(|&(language, profile)| (language, profile.split(' ').enumerate().map(|(i, t)| (t, i)).collect()))
+            .collect()
+    })
+}
+
+/// The most common trigrams of the words of `text`, most common first
+fn trigrams(text: &str) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut word = vec!['_'];
+    for c in text.chars().flat_map(char::to_lowercase).chain([' ']) {
+        if c.is_alphabetic() {
+            word.push(c);
+            continue;
+        }
+        if word.len() > 1 {
+            word.push('_');
+            for t in word.windows(3) {
+                *counts.entry(t.iter().collect()).or_default() += 1;
+            }
+        }
+        word.truncate(1);
+    }
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    ranked.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.into_iter().take(PROFILE_LEN).map(|(t, _)| t).collect()
+}
+
+/// Returns the language `text` is most likely written in, or `None` if it
+/// has no letters
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{detect::detect_language, Language};
+/// assert_eq!(detect_language("Die Kinder spielten im Garten."), Some(Language::German));
+/// assert_eq!(detect_language("Τα παιδιά έπαιζαν στον κήπο."), Some(Language::Greek));
+/// assert_eq!(detect_language("1, 2, 3"), None);
+/// ```
+pub fn detect_language(text: &str) -> Option<Language> {
+    let (mut latin, mut greek, mut cyrillic, mut devanagari) = (0, 0, 0, 0);
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        match c {
+            '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => greek += 1,
+            '\u{0400}'..='\u{052F}' => cyrillic += 1,
+            '\u{0900}'..='\u{097F}' => devanagari += 1,
+            _ => latin += 1,
+        }
+    }
+    let most = *[latin, greek, cyrillic, devanagari].iter().max().unwrap();
+    if most == 0 {
+        return None;
+    }
+    if most != latin {
+        return Some(if most == greek {
+            Language::Greek
+        } else if most == cyrillic {
+            Language::Russian
+        } else {
+            Language::Hindi
+        });
+    }
+
+    let text = trigrams(text);
+    profile_ranks()
+        .iter()
+        .min_by_key(|(_, ranks)| {
+            text.iter().enumerate().map(|(i, t)| ranks.get(t.as_str()).map_or(PROFILE_LEN, |&r| r.abs_diff(i))).sum::<usize>()
+        })
+        .map(|&(language, _)| language)
+}
+
+/// Stems every word of `text` with the stemmer for its language, detected
+/// paragraph by paragraph
+///
+/// Paragraphs are separated by blank lines; one too short to tell (a
+/// heading, say) takes the language of the whole text, and a text with no
+/// letters is treated as English. Separators are copied through as by
+/// [`stem_text`].
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::detect::stem_text_auto;
+/// let text = "The children were playing in the garden.\n\nLes enfants jouaient dans les jardins.";
+/// assert_eq!(stem_text_auto(text), "the children were plai in the garden.\n\nle enfant jou dan le jardin.");
+/// ```
+pub fn stem_text_auto(text: &str) -> String {
+    let whole = detect_language(text).unwrap_or_default();
+    let mut stemmers = LanguageStemmers::new(false);
+    let mut out = String::with_capacity(text.len());
+    for paragraph in text.split_inclusive("\n\n") {
+        let letters = paragraph.chars().filter(|c| c.is_alphabetic()).count();
+        let language =
+            if letters < MIN_PARAGRAPH_LETTERS { whole } else { detect_language(paragraph).unwrap_or(whole) };
+        out.push_str(&stem_text(stemmers.stemmer(language), paragraph));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        let cases = [
+            ("The committee will meet again next week to discuss the budget.", Language::English),
+            ("Le comité se réunira de nouveau la semaine prochaine.", Language::French),
+            ("Der Ausschuss wird sich nächste Woche wieder treffen.", Language::German),
+            ("A comissão vai reunir-se outra vez na próxima semana.", Language::Portuguese),
+            ("Kommittén ska träffas igen nästa vecka för att diskutera budgeten.", Language::Swedish),
+            ("Komiteen skal møtes igjen neste uke for å diskutere budsjettet.", Language::Norwegian),
+            ("A bizottság jövő héten újra ülésezik.", Language::Hungarian),
+            ("Comitetul se va reuni din nou săptămâna viitoare.", Language::Romanian),
+            ("Komite yeni okulun bütçesini görüşmek için gelecek hafta toplanacak.", Language::Turkish),
+            ("Комитет снова соберётся на следующей неделе.", Language::Russian),
+            ("समिति अगले सप्ताह फिर से मिलेगी।", Language::Hindi),
+        ];
+        for (text, language) in cases {
+            assert_eq!(detect_language(text), Some(language), "{}", text);
+        }
+        assert_eq!(detect_language(""), None);
+    }
+
+    #[test]
+    fn test_stem_text_auto_by_paragraph() {
+        let text = "Menschen\n\nDie Kinder spielten im Garten des Hauses.\n\nΤα παιδιά έπαιζαν στον κήπο.";
+        assert_eq!(stem_text_auto(text), "mensch\n\ndie kind spielt im gart des haus.\n\nτα πα επαι στον κηπ.");
+        assert_eq!(stem_text_auto("3 + 4"), "3 + 4");
+    }
+}
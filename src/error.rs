@@ -0,0 +1,145 @@
+//! # Errors
+//!
+//! [`StemError`] is the error of every fallible (`try_`) API in the crate:
+//! [`crate::Stemmer::try_stem`], [`crate::PorterStemmer::stem_to_slice`],
+//! [`crate::text::try_stem_text_with`], [`crate::text::try_stem_stream`] and
+//! [`crate::pipeline::Pipeline::try_run`].
+//! Their infallible counterparts give the input back unchanged where these
+//! fail, so a caller that needs to know why a word was not stemmed uses the
+//! `try_` form and matches on the variant.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::{fold::NonAsciiPolicy, PorterStemmer, StemError, Stemmer};
+//! let mut stemmer = PorterStemmer::new().with_non_ascii(NonAsciiPolicy::Reject);
+//! assert_eq!(stemmer.try_stem("Running").unwrap(), "run");
+//! assert!(matches!(stemmer.try_stem(""), Err(StemError::EmptyInput)));
+//! match stemmer.try_stem("naïve") {
+//!     Err(StemError::NonAsciiLetter { character, position }) => assert_eq!((character, position), ('ï', 2)),
+//!     other => panic!("{:?}", other),
+//! }
+//! ```
+
+use std::fmt;
+use std::io;
+
+/// Why a word or text could not be stemmed
+#[derive(Debug)]
+pub enum StemError {
+    /// The word to stem was empty
+    EmptyInput,
+    /// A character the algorithm was not written for, rejected by
+    /// [`crate::fold::NonAsciiPolicy::Reject`]
+    NonAsciiLetter {
+        /// The first such character
+        character: char,
+        /// Its byte offset in the word, or in the text or stream
+        position: usize,
+    },
+    /// A token longer than the limit set for it, rejected by
+    /// [`crate::token::LongTokenPolicy::Reject`]
+    TokenTooLong {
+        /// Length of the token in characters
+        length: usize,
+        /// The most characters allowed
+        max: usize,
+    },
+    /// The stem did not fit in the buffer given for it
+    BufferTooSmall {
+        /// Length of the stem in bytes
+        needed: usize,
+        /// Size of the buffer in bytes
+        capacity: usize,
+    },
+    /// Reading the input or writing the output failed
+    IoError(io::Error),
+}
+
+impl StemError {
+    /// The error for the first non-ASCII character of `word`, if it has one
+    pub(crate) fn check_ascii(word: &str) -> Result<(), StemError> {
+        match word.char_indices().find(|(_, c)| !c.is_ascii()) {
+            Some((position, character)) => Err(StemError::NonAsciiLetter { character, position }),
+            None => Ok(()),
+        }
+    }
+
+    /// The same error for a word or piece found `offset` bytes into a
+    /// longer text
+    pub(crate) fn offset(self, offset: usize) -> StemError {
+        match self {
+            StemError::NonAsciiLetter { character, position } => {
+                StemError::NonAsciiLetter { character, position: position + offset }
+            },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for StemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StemError::EmptyInput => write!(f, "empty word"),
+            StemError::NonAsciiLetter { character, position } => {
+                write!(f, "non-ASCII character {:?} at byte {}", character, position)
+            },
+            StemError::TokenTooLong { length, max } => {
+                write!(f, "token of {} characters is longer than {}", length, max)
+            },
+            StemError::BufferTooSmall { needed, capacity } => {
+                write!(f, "stem of {} bytes does not fit in {} bytes", needed, capacity)
+            },
+            StemError::IoError(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for StemError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StemError::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StemError {
+    fn from(e: io::Error) -> Self {
+        StemError::IoError(e)
+    }
+}
+
+/// An `IoError` gives back its `io::Error`; the others become
+/// `InvalidData` errors carrying the `StemError`
+impl From<StemError> for io::Error {
+    fn from(e: StemError) -> Self {
+        match e {
+            StemError::IoError(e) => e,
+            other => io::Error::new(io::ErrorKind::InvalidData, other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let error = StemError::from(io::Error::new(io::ErrorKind::NotFound, "voc.txt: not found"));
+        assert_eq!(error.to_string(), "voc.txt: not found");
+        assert!(std::error::Error::source(&error).is_some());
+        assert_eq!(io::Error::from(error).kind(), io::ErrorKind::NotFound);
+
+        let error = io::Error::from(StemError::TokenTooLong { length: 80, max: 64 });
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let inner = error.get_ref().and_then(|e| e.downcast_ref::<StemError>());
+        assert!(matches!(inner, Some(StemError::TokenTooLong { length: 80, max: 64 })));
+        let error = StemError::BufferTooSmall { needed: 7, capacity: 4 };
+        assert_eq!(error.to_string(), "stem of 7 bytes does not fit in 4 bytes");
+
+        assert!(StemError::check_ascii("resume").is_ok());
+        let error = StemError::check_ascii("naïve").unwrap_err().offset(10);
+        assert_eq!(error.to_string(), "non-ASCII character 'ï' at byte 12");
+    }
+}
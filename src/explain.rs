@@ -0,0 +1,72 @@
+//! # Stemming Explanations
+//!
+//! [`crate::PorterStemmer::explain`] stems a word while recording every step
+//! of the algorithm that changed it, for answering "why does X stem to Y"
+//! questions. Each [`Step`] gives the word before and after, the ending that
+//! was replaced and the measure m of the stem it was replaced on, which is
+//! what the conditions of the rules ("(m>0) EED -> EE") test.
+//!
+//! The ending shown is the one the rule matched, except where the step went
+//! on to change the stem: removing "-ing" and then undoubling a consonant
+//! ("hopping" to "hop") shows as one replacement of "-ping".
+
+use std::fmt;
+
+/// A step of the algorithm that changed the word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Name of the step in Porter's paper: "1a", "1b", "1c", "2" to "5"
+    pub name: &'static str,
+    /// The word before the step
+    pub before: String,
+    /// The word after the step
+    pub after: String,
+    /// Ending of `before` that the step replaced
+    pub removed: String,
+    /// What replaced it in `after`
+    pub added: String,
+    /// Measure of the part of the word the step left alone
+    pub measure: usize,
+}
+
+/// A word, its stem and the steps that led from one to the other
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::PorterStemmer;
+/// let explanation = PorterStemmer::new().explain("Generalizations");
+/// assert_eq!(explanation.stem, "gener");
+/// assert_eq!(explanation.to_string(), "\
+/// Generalizations -> gener
+///   1a: generalizations -> generalization (-s -> -, m=6)
+///   2: generalization -> generalize (-ization -> -ize, m=3)
+///   3: generalize -> general (-alize -> -al, m=2)
+///   4: general -> gener (-al -> -, m=2)
+/// ");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// The word as given
+    pub word: String,
+    /// The steps that changed it, in order
+    pub steps: Vec<Step>,
+    /// The resulting stem
+    pub stem: String,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {} (-{} -> -{}, m={})", self.name, self.before, self.after, self.removed, self.added,
+               self.measure)
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} -> {}", self.word, self.stem)?;
+        for step in &self.steps {
+            writeln!(f, "  {}", step)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+//! # C Interface
+//!
+//! `extern "C"` functions for stemming from C and C++, declared in
+//! `include/porterstem.h`, as a drop-in for Martin Porter's `stem.c`. The
+//! shared library is the workspace's `porterstem-ffi` package: build it with
+//! `cargo build --release -p porterstem-ffi` and link against
+//! `libporterstem_ffi.so` (or `.dylib`, or `.dll`).
+//!
+//! - `porter_stem(word, out, cap)` stems a NUL-terminated word into a buffer
+//!   of `cap` bytes and returns the length of the stem, like `snprintf`: the
+//!   stem was written only if that length is less than `cap`, and a negative
+//!   result is an error
+//! - `porter_stemmer_new()` makes a handle whose `porter_stemmer_stem` stems
+//!   a word of a given length into a buffer the handle keeps and reuses, so
+//!   a loop over many words allocates nothing once the buffer has grown;
+//!   `porter_stemmer_free` frees it
+//!
+//! Input must be UTF-8. A panic never crosses into C: it makes the call fail.
+//! Only available with the `ffi` feature.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::ffi::*;
+//! let mut out = [0; 16];
+//! let length = unsafe { porter_stem(c"running".as_ptr(), out.as_mut_ptr(), out.len()) };
+//! assert_eq!(length, 3);
+//! assert_eq!(&out[..4], [b'r', b'u', b'n', 0].map(|byte| byte as std::ffi::c_char));
+//! ```
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::PorterStemmer;
+
+/// Result of a call given a null pointer or a word that is not UTF-8
+pub const PORTER_STEM_INVALID: isize = -1;
+
+/// Result of a call whose stemming failed
+pub const PORTER_STEM_FAILED: isize = -2;
+
+thread_local! {
+    static STEMMER: RefCell<PorterStemmer> = RefCell::new(PorterStemmer::new());
+}
+
+/// Stems `word` with `stemmer`, replacing the stemmer if it panics
+fn stem_guarded(stemmer: &mut PorterStemmer, word: &str) -> Option<String> {
+    let stemmed = panic::catch_unwind(AssertUnwindSafe(|| stemmer.stem(word)));
+    if stemmed.is_err() {
+        *stemmer = PorterStemmer::new();
+    }
+    stemmed.ok()
+}
+
+/// Stems the NUL-terminated `word` into `out`, followed by a NUL, if the
+/// stem is shorter than `cap` bytes, and returns the stem's length in bytes
+/// either way; or returns [`PORTER_STEM_INVALID`] or [`PORTER_STEM_FAILED`]
+///
+/// # Safety
+/// `word` must be null or point to a NUL-terminated string, and `out` must
+/// be null or point to `cap` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn porter_stem(word: *const c_char, out: *mut c_char, cap: usize) -> isize {
+    if word.is_null() {
+        return PORTER_STEM_INVALID;
+    }
+    // SAFETY: the caller passes a NUL-terminated string
+    let Ok(word) = unsafe { CStr::from_ptr(word) }.to_str() else { return PORTER_STEM_INVALID };
+    // A panic elsewhere on the thread may have left the stemmer borrowed
+    let stem = STEMMER.with(|stemmer| stemmer.try_borrow_mut().ok().and_then(|mut s| stem_guarded(&mut s, word)));
+    let Some(stem) = stem else { return PORTER_STEM_FAILED };
+    if !out.is_null() && stem.len() < cap {
+        // SAFETY: `out` has room for `cap` bytes, more than the stem
+        unsafe {
+            ptr::copy_nonoverlapping(stem.as_ptr(), out as *mut u8, stem.len());
+            *out.add(stem.len()) = 0;
+        }
+    }
+    stem.len() as isize
+}
+
+/// A stemmer with a buffer for its stems, for C callers
+#[derive(Debug, Default)]
+pub struct PorterStemmerHandle {
+    stemmer: PorterStemmer,
+    stem: Vec<u8>,
+}
+
+/// Returns a new handle, to be freed with [`porter_stemmer_free`]
+#[no_mangle]
+pub extern "C" fn porter_stemmer_new() -> *mut PorterStemmerHandle {
+    Box::into_raw(Box::default())
+}
+
+/// Frees a handle from [`porter_stemmer_new`]; null is ignored
+///
+/// # Safety
+/// `handle` must be null or a handle from [`porter_stemmer_new`] that has
+/// not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn porter_stemmer_free(handle: *mut PorterStemmerHandle) {
+    if !handle.is_null() {
+        // SAFETY: the handle came from Box::into_raw and is freed once
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+/// Stems the `len` bytes at `word` and returns the stem, NUL-terminated, in
+/// the handle's buffer, valid until the next call with the handle; its length
+/// goes to `stem_len` unless that is null. Returns null if `handle` or `word`
+/// is null, the word is not UTF-8 or stemming fails.
+///
+/// # Safety
+/// `handle` must be a live handle from [`porter_stemmer_new`], used by one
+/// thread at a time, `word` must point to `len` readable bytes, and
+/// `stem_len` must be null or point to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn porter_stemmer_stem(
+    handle: *mut PorterStemmerHandle,
+    word: *const c_char,
+    len: usize,
+    stem_len: *mut usize,
+) -> *const c_char {
+    if handle.is_null() || word.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: the caller passes a live handle that no one else is using, and
+    // `len` bytes at `word`
+    let (handle, word) = unsafe { (&mut *handle, std::slice::from_raw_parts(word as *const u8, len)) };
+    let Ok(word) = std::str::from_utf8(word) else { return ptr::null() };
+    let Some(stem) = stem_guarded(&mut handle.stemmer, word) else { return ptr::null() };
+    handle.stem.clear();
+    handle.stem.extend_from_slice(stem.as_bytes());
+    handle.stem.push(0);
+    if !stem_len.is_null() {
+        // SAFETY: the caller passes a writable size_t
+        unsafe { *stem_len = stem.len() };
+    }
+    handle.stem.as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_porter_stem() {
+        let mut out = [1 as c_char; 4];
+        let stem = |word: &CStr, out: &mut [c_char]| unsafe { porter_stem(word.as_ptr(), out.as_mut_ptr(), out.len()) };
+        assert_eq!(stem(c"ponies", &mut out), 4);
+        // Too small for "poni" and its NUL: nothing is written
+        assert_eq!(out, [1; 4]);
+        assert_eq!(stem(c"cats", &mut out), 3);
+        assert_eq!(unsafe { CStr::from_ptr(out.as_ptr()) }, c"cat");
+        assert_eq!(stem(c"caf\xe9", &mut out), PORTER_STEM_INVALID);
+        assert_eq!(unsafe { porter_stem(ptr::null(), ptr::null_mut(), 0) }, PORTER_STEM_INVALID);
+    }
+
+    #[test]
+    fn test_handle() {
+        let handle = porter_stemmer_new();
+        let mut length = 0;
+        for (word, expected) in [("generalizations", c"gener"), ("caresses", c"caress")] {
+            let stem = unsafe { porter_stemmer_stem(handle, word.as_ptr() as *const c_char, word.len(), &mut length) };
+            assert_eq!((unsafe { CStr::from_ptr(stem) }, length), (expected, expected.count_bytes()));
+        }
+        assert!(unsafe { porter_stemmer_stem(handle, ptr::null(), 0, ptr::null_mut()) }.is_null());
+        unsafe { porter_stemmer_free(handle) };
+
+        // Every function is declared in the header
+        let header = include_str!("../include/porterstem.h");
+        let exported = |line: &&str| line.starts_with("pub ") && line.contains("extern \"C\" fn");
+        for line in include_str!("ffi.rs").lines().filter(exported) {
+            let name = line.split("fn ").nth(1).and_then(|rest| rest.split('(').next()).unwrap();
+            assert!(header.contains(&format!("{}(", name)), "{} is missing from porterstem.h", name);
+        }
+    }
+}
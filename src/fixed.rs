@@ -0,0 +1,333 @@
+//! # Fixed-Capacity Stemming
+//!
+//! A second core for the Porter algorithm that never allocates: it stems a
+//! word in a byte buffer the caller provides, a `[u8; N]` on the stack or a
+//! `heapless::String<N>`, so it runs on microcontrollers with no heap, such
+//! as one spotting keywords on the device. The rest of the crate needs
+//! `std`; this module does not, so with `default-features = false` the
+//! crate builds for `no_std` targets with just this module:
+//!
+//! ```toml
+//! m_porter_stemmer_rust = { version = "0.1", default-features = false, features = ["heapless"] }
+//! ```
+//!
+//! It follows Martin Porter's C program as [`crate::PorterStemmer`] does
+//! and gives the same stems for ASCII words, which it lowercases first. It
+//! works on bytes, as the C program does, so words with non-ASCII bytes are
+//! returned as they are rather than cut inside a character.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::fixed;
+//! let mut buffer = [0; 32];
+//! assert_eq!(fixed::stem_into("Running", &mut buffer), Ok("run"));
+//!
+//! let mut word = *b"ponies";
+//! let length = fixed::stem_in_place(&mut word);
+//! assert_eq!(&word[..length], b"poni");
+//!
+//! assert!(fixed::stem_into("internationalization", &mut [0; 8]).is_err());
+//! ```
+
+use core::fmt;
+
+/// The error of a word longer than the buffer it is to be stemmed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError {
+    /// Length of the word in bytes
+    pub needed: usize,
+    /// Size of the buffer in bytes
+    pub capacity: usize,
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "word of {} bytes does not fit in {} bytes", self.needed, self.capacity)
+    }
+}
+
+impl core::error::Error for CapacityError {}
+
+/// Copies `word` into `buffer`, stems it there and returns the stem
+///
+/// The stem is never longer than the word, so a buffer as long as the
+/// longest expected word is enough.
+pub fn stem_into<'b>(word: &str, buffer: &'b mut [u8]) -> Result<&'b str, CapacityError> {
+    if word.len() > buffer.len() {
+        return Err(CapacityError { needed: word.len(), capacity: buffer.len() });
+    }
+    let buffer = &mut buffer[..word.len()];
+    buffer.copy_from_slice(word.as_bytes());
+    let length = stem_in_place(buffer);
+    // Only ASCII words are changed, and those only by ASCII letters
+    Ok(core::str::from_utf8(&buffer[..length]).unwrap_or_default())
+}
+
+/// Stems `word` into a `heapless::String` of capacity `N`
+#[cfg(feature = "heapless")]
+pub fn stem_heapless<const N: usize>(word: &str) -> Result<heapless::String<N>, CapacityError> {
+    let mut buffer = [0; N];
+    let stem = stem_into(word, &mut buffer)?;
+    let mut out = heapless::String::new();
+    // The stem is no longer than the word, which fit in N bytes
+    out.push_str(stem).map_err(|_| CapacityError { needed: word.len(), capacity: N })?;
+    Ok(out)
+}
+
+/// Stems the ASCII word in `word` in place and returns the stem's length
+///
+/// The word is lowercased; a word with a non-ASCII byte is left unchanged.
+pub fn stem_in_place(word: &mut [u8]) -> usize {
+    if !word.is_ascii() {
+        return word.len();
+    }
+    word.make_ascii_lowercase();
+    if word.len() <= 2 {
+        return word.len();
+    }
+    let k = word.len() - 1;
+    let mut stem = Word { b: word, k, j: 0 };
+    stem.step1ab();
+    if stem.k > 0 {
+        stem.step1c();
+        stem.step2();
+        stem.step3();
+        stem.step4();
+        stem.step5();
+    }
+    stem.k + 1
+}
+
+/// A word being stemmed: b[..=k] is the word so far, and j ends the stem
+/// before the ending last matched (wrapping to `usize::MAX` when the
+/// ending is the whole word, where the C program has -1)
+struct Word<'a> {
+    b: &'a mut [u8],
+    k: usize,
+    j: usize,
+}
+
+impl Word<'_> {
+    /// One past j: 0 when the ending is the whole word
+    fn stem_end(&self) -> usize {
+        self.j.wrapping_add(1)
+    }
+
+    fn is_consonant(&self, i: usize) -> bool {
+        match self.b[i] {
+            b'a' | b'e' | b'i' | b'o' | b'u' => false,
+            b'y' => i == 0 || !self.is_consonant(i - 1),
+            _ => true,
+        }
+    }
+
+    /// The number of vowel-consonant sequences in the stem b[..=j]
+    fn measure(&self) -> usize {
+        let end = self.stem_end();
+        let mut n = 0;
+        let mut i = 0;
+        while i < end && self.is_consonant(i) {
+            i += 1;
+        }
+        loop {
+            while i < end && !self.is_consonant(i) {
+                i += 1;
+            }
+            if i >= end {
+                return n;
+            }
+            while i < end && self.is_consonant(i) {
+                i += 1;
+            }
+            n += 1;
+        }
+    }
+
+    fn vowel_in_stem(&self) -> bool {
+        (0..self.stem_end()).any(|i| !self.is_consonant(i))
+    }
+
+    fn double_consonant(&self, i: usize) -> bool {
+        i >= 1 && self.b[i] == self.b[i - 1] && self.is_consonant(i)
+    }
+
+    /// Consonant-vowel-consonant ending at i, the last not w, x or y
+    fn cvc(&self, i: usize) -> bool {
+        i >= 2
+            && self.is_consonant(i)
+            && !self.is_consonant(i - 1)
+            && self.is_consonant(i - 2)
+            && !matches!(self.b[i], b'w' | b'x' | b'y')
+    }
+
+    fn ends_with(&mut self, s: &[u8]) -> bool {
+        if s.len() > self.k + 1 || &self.b[self.k + 1 - s.len()..=self.k] != s {
+            return false;
+        }
+        self.j = (self.k + 1 - s.len()).wrapping_sub(1);
+        true
+    }
+
+    fn set_to(&mut self, s: &[u8]) {
+        let start = self.stem_end();
+        self.b[start..start + s.len()].copy_from_slice(s);
+        self.k = self.j.wrapping_add(s.len());
+    }
+
+    fn replace_if_measured(&mut self, s: &[u8]) {
+        if self.measure() > 0 {
+            self.set_to(s);
+        }
+    }
+
+    /// Replaces the first of `rules` whose ending the word has, if the
+    /// stem before it has a measure above 0
+    fn replace_first(&mut self, rules: &[(&[u8], &[u8])]) {
+        if let Some(&(_, to)) = rules.iter().find(|(from, _)| self.ends_with(from)) {
+            self.replace_if_measured(to);
+        }
+    }
+
+    fn step1ab(&mut self) {
+        if self.b[self.k] == b's' {
+            if self.ends_with(b"sses") {
+                self.k -= 2;
+            } else if self.ends_with(b"ies") {
+                self.set_to(b"i");
+            } else if self.k > 0 && self.b[self.k - 1] != b's' {
+                self.k -= 1;
+            }
+        }
+        if self.ends_with(b"eed") {
+            if self.measure() > 0 {
+                self.k -= 1;
+            }
+        } else if (self.ends_with(b"ed") || self.ends_with(b"ing")) && self.vowel_in_stem() {
+            self.k = self.j;
+            if self.ends_with(b"at") {
+                self.set_to(b"ate");
+            } else if self.ends_with(b"bl") {
+                self.set_to(b"ble");
+            } else if self.ends_with(b"iz") {
+                self.set_to(b"ize");
+            } else if self.double_consonant(self.k) {
+                if !matches!(self.b[self.k], b'l' | b's' | b'z') {
+                    self.k -= 1;
+                }
+            } else if self.measure() == 1 && self.cvc(self.k) {
+                self.set_to(b"e");
+            }
+        }
+    }
+
+    fn step1c(&mut self) {
+        if self.ends_with(b"y") && self.vowel_in_stem() {
+            self.b[self.k] = b'i';
+        }
+    }
+
+    fn step2(&mut self) {
+        let rules: &[(&[u8], &[u8])] = match self.b[self.k - 1] {
+            b'a' => &[(b"ational", b"ate"), (b"tional", b"tion")],
+            b'c' => &[(b"enci", b"ence"), (b"anci", b"ance")],
+            b'e' => &[(b"izer", b"ize")],
+            b'l' => &[(b"bli", b"ble"), (b"alli", b"al"), (b"entli", b"ent"), (b"eli", b"e"), (b"ousli", b"ous")],
+            b'o' => &[(b"ization", b"ize"), (b"ation", b"ate"), (b"ator", b"ate")],
+            b's' => &[(b"alism", b"al"), (b"iveness", b"ive"), (b"fulness", b"ful"), (b"ousness", b"ous")],
+            b't' => &[(b"aliti", b"al"), (b"iviti", b"ive"), (b"biliti", b"ble")],
+            b'g' => &[(b"logi", b"log")],
+            _ => &[],
+        };
+        self.replace_first(rules);
+    }
+
+    fn step3(&mut self) {
+        let rules: &[(&[u8], &[u8])] = match self.b[self.k] {
+            b'e' => &[(b"icate", b"ic"), (b"ative", b""), (b"alize", b"al")],
+            b'i' => &[(b"iciti", b"ic")],
+            b'l' => &[(b"ical", b"ic"), (b"ful", b"")],
+            b's' => &[(b"ness", b"")],
+            _ => &[],
+        };
+        self.replace_first(rules);
+    }
+
+    fn step4(&mut self) {
+        let endings: &[&[u8]] = match self.b[self.k - 1] {
+            b'a' => &[b"al"],
+            b'c' => &[b"ance", b"ence"],
+            b'e' => &[b"er"],
+            b'i' => &[b"ic"],
+            b'l' => &[b"able", b"ible"],
+            b'n' => &[b"ant", b"ement", b"ment", b"ent"],
+            b'o' => {
+                let matched = (self.ends_with(b"ion") && self.stem_end() > 0 && matches!(self.b[self.j], b's' | b't'))
+                    || self.ends_with(b"ou");
+                if matched && self.measure() > 1 {
+                    self.k = self.j;
+                }
+                return;
+            }
+            b's' => &[b"ism"],
+            b't' => &[b"ate", b"iti"],
+            b'u' => &[b"ous"],
+            b'v' => &[b"ive"],
+            b'z' => &[b"ize"],
+            _ => &[],
+        };
+        if endings.iter().any(|ending| self.ends_with(ending)) && self.measure() > 1 {
+            self.k = self.j;
+        }
+    }
+
+    fn step5(&mut self) {
+        self.j = self.k;
+        if self.b[self.k] == b'e' {
+            let a = self.measure();
+            if a > 1 || (a == 1 && !self.k.checked_sub(1).is_some_and(|i| self.cvc(i))) {
+                self.k -= 1;
+            }
+        }
+        if self.b[self.k] == b'l' && self.double_consonant(self.k) && self.measure() > 1 {
+            self.k -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vocabulary() {
+        /// Stems into a buffer of its own
+        struct Fixed([u8; 32]);
+
+        impl crate::Stemmer for Fixed {
+            fn stem(&mut self, word: &str) -> String {
+                stem_into(word, &mut self.0).unwrap().to_string()
+            }
+        }
+
+        let words = include_str!("../test_data/porter/voc.txt");
+        let stems = include_str!("../test_data/porter/output.txt");
+        crate::assert_vocabulary(&mut Fixed([0; 32]), words, stems);
+    }
+
+    #[test]
+    fn test_edges() {
+        let mut buffer = [0; 8];
+        assert_eq!(stem_into("", &mut buffer), Ok(""));
+        assert_eq!(stem_into("IES", &mut buffer), Ok("i"));
+        assert_eq!(stem_into("ion", &mut buffer), Ok("ion"));
+        assert_eq!(stem_into("cafés", &mut buffer), Ok("cafés"));
+        let error = stem_into("connections", &mut buffer).unwrap_err();
+        assert_eq!(error, CapacityError { needed: 11, capacity: 8 });
+        #[cfg(feature = "heapless")]
+        {
+            assert_eq!(stem_heapless::<16>("Connections").unwrap().as_str(), "connect");
+            assert!(stem_heapless::<4>("ponies").is_err());
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! # Diacritic Folding
+//!
+//! An optional pre-pass that degrades accented Latin text to ASCII before
+//! stemming, so "résumé" is stemmed as "resume" instead of being split at
+//! the non-ASCII letters (which the algorithm would otherwise see as
+//! consonants or word breaks).
+//!
+//! Each character is replaced by its Unicode compatibility decomposition
+//! (NFKD) with the combining marks removed, restricted to results that are
+//! plain ASCII. Letters that have no decomposition but an obvious ASCII
+//! spelling are folded as well (`ø` → `o`, `ß` → `ss`, `æ` → `ae`, `ł` → `l`).
+//! Combining marks in already-decomposed input are dropped. Everything else,
+//! including non-Latin scripts, is left as it is.
+//!
+//! Coverage is the Latin-1 Supplement, Latin Extended-A/B, Latin Extended
+//! Additional, the Latin ligatures (`ﬁ`) and the fullwidth ASCII forms. The
+//! table was generated from the Unicode character database.
+//!
+//! [`NonAsciiPolicy`] says what [`crate::PorterStemmer`] does with the words
+//! the algorithm, defined over ASCII letters, was not written for.
+
+use std::borrow::Cow;
+
+/// What the Porter stemmer does with a word that is not all ASCII
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{fold::NonAsciiPolicy, PorterStemmer, Stemmer};
+/// let mut stem = |policy, word| PorterStemmer::new().with_non_ascii(policy).try_stem(word);
+/// assert_eq!(stem(NonAsciiPolicy::Stem, "Cafés").unwrap(), "café");
+/// assert_eq!(stem(NonAsciiPolicy::Transliterate, "Cafés").unwrap(), "cafe");
+/// assert_eq!(stem(NonAsciiPolicy::Verbatim, "Cafés").unwrap(), "Cafés");
+/// assert_eq!(stem(NonAsciiPolicy::Reject, "Cafés").unwrap_err().to_string(), "non-ASCII character 'é' at byte 3");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NonAsciiPolicy {
+    /// Lowercase it with the Unicode rules, which may lengthen it ("İ" to
+    /// "i̇"), and stem it with the other letters counted as consonants
+    #[default]
+    Stem,
+    /// Fold it to ASCII with [`fold_diacritics`] and stem it; a word that is
+    /// still not ASCII, such as one in another script, is kept as it is
+    Transliterate,
+    /// Keep it as it is, neither lowercased nor stemmed
+    Verbatim,
+    /// Keep it as it is from `stem`, and fail with
+    /// [`crate::StemError::NonAsciiLetter`] in the `try_` functions
+    Reject,
+}
+
+/// Folded ASCII spelling of each covered character, sorted by character
+const FOLDS: &[(char, &str)] = &[
+    ('ª', "a"), ('º', "o"), ('À', "A"), ('Á', "A"), ('Â', "A"), ('Ã', "A"), ('Ä', "A"),
+    ('Å', "A"), ('Æ', "AE"), ('Ç', "C"), ('È', "E"), ('É', "E"), ('Ê', "E"), ('Ë', "E"),
+    ('Ì', "I"), ('Í', "I"), ('Î', "I"), ('Ï', "I"), ('Ð', "D"), ('Ñ', "N"), ('Ò', "O"),
+    ('Ó', "O"), ('Ô', "O"), ('Õ', "O"), ('Ö', "O"), ('Ø', "O"), ('Ù', "U"), ('Ú', "U"),
+    ('Û', "U"), ('Ü', "U"), ('Ý', "Y"), ('Þ', "TH"), ('ß', "ss"), ('à', "a"), ('á', "a"),
+    ('â', "a"), ('ã', "a"), ('ä', "a"), ('å', "a"), ('æ', "ae"), ('ç', "c"), ('è', "e"),
+    ('é', "e"), ('ê', "e"), ('ë', "e"), ('ì', "i"), ('í', "i"), ('î', "i"), ('ï', "i"),
+    ('ð', "d"), ('ñ', "n"), ('ò', "o"), ('ó', "o"), ('ô', "o"), ('õ', "o"), ('ö', "o"),
+    ('ø', "o"), ('ù', "u"), ('ú', "u"), ('û', "u"), ('ü', "u"), ('ý', "y"), ('þ', "th"),
+    ('ÿ', "y"), ('Ā', "A"), ('ā', "a"), ('Ă', "A"), ('ă', "a"), ('Ą', "A"), ('ą', "a"),
+    ('Ć', "C"), ('ć', "c"), ('Ĉ', "C"), ('ĉ', "c"), ('Ċ', "C"), ('ċ', "c"), ('Č', "C"),
+    ('č', "c"), ('Ď', "D"), ('ď', "d"), ('Đ', "D"), ('đ', "d"), ('Ē', "E"), ('ē', "e"),
+    ('Ĕ', "E"), ('ĕ', "e"), ('Ė', "E"), ('ė', "e"), ('Ę', "E"), ('ę', "e"), ('Ě', "E"),
+    ('ě', "e"), ('Ĝ', "G"), ('ĝ', "g"), ('Ğ', "G"), ('ğ', "g"), ('Ġ', "G"), ('ġ', "g"),
+    ('Ģ', "G"), ('ģ', "g"), ('Ĥ', "H"), ('ĥ', "h"), ('Ħ', "H"), ('ħ', "h"), ('Ĩ', "I"),
+    ('ĩ', "i"), ('Ī', "I"), ('ī', "i"), ('Ĭ', "I"), ('ĭ', "i"), ('Į', "I"), ('į', "i"),
+    ('İ', "I"), ('ı', "i"), ('Ĳ', "IJ"), ('ĳ', "ij"), ('Ĵ', "J"), ('ĵ', "j"), ('Ķ', "K"),
+    ('ķ', "k"), ('ĸ', "k"), ('Ĺ', "L"), ('ĺ', "l"), ('Ļ', "L"), ('ļ', "l"), ('Ľ', "L"),
+    ('ľ', "l"), ('Ŀ', "L"), ('ŀ', "l"), ('Ł', "L"), ('ł', "l"), ('Ń', "N"), ('ń', "n"),
+    ('Ņ', "N"), ('ņ', "n"), ('Ň', "N"), ('ň', "n"), ('ŉ', "n"), ('Ŋ', "NG"), ('ŋ', "ng"),
+    ('Ō', "O"), ('ō', "o"), ('Ŏ', "O"), ('ŏ', "o"), ('Ő', "O"), ('ő', "o"), ('Œ', "OE"),
+    ('œ', "oe"), ('Ŕ', "R"), ('ŕ', "r"), ('Ŗ', "R"), ('ŗ', "r"), ('Ř', "R"), ('ř', "r"),
+    ('Ś', "S"), ('ś', "s"), ('Ŝ', "S"), ('ŝ', "s"), ('Ş', "S"), ('ş', "s"), ('Š', "S"),
+    ('š', "s"), ('Ţ', "T"), ('ţ', "t"), ('Ť', "T"), ('ť', "t"), ('Ŧ', "T"), ('ŧ', "t"),
+    ('Ũ', "U"), ('ũ', "u"), ('Ū', "U"), ('ū', "u"), ('Ŭ', "U"), ('ŭ', "u"), ('Ů', "U"),
+    ('ů', "u"), ('Ű', "U"), ('ű', "u"), ('Ų', "U"), ('ų', "u"), ('Ŵ', "W"), ('ŵ', "w"),
+    ('Ŷ', "Y"), ('ŷ', "y"), ('Ÿ', "Y"), ('Ź', "Z"), ('ź', "z"), ('Ż', "Z"), ('ż', "z"),
+    ('Ž', "Z"), ('ž', "z"), ('ſ', "s"), ('ƀ', "b"), ('ƚ', "l"), ('Ơ', "O"), ('ơ', "o"),
+    ('Ư', "U"), ('ư', "u"), ('Ƶ', "Z"), ('ƶ', "z"), ('Ǆ', "DZ"), ('ǅ', "Dz"), ('ǆ', "dz"),
+    ('Ǉ', "LJ"), ('ǈ', "Lj"), ('ǉ', "lj"), ('Ǌ', "NJ"), ('ǋ', "Nj"), ('ǌ', "nj"), ('Ǎ', "A"),
+    ('ǎ', "a"), ('Ǐ', "I"), ('ǐ', "i"), ('Ǒ', "O"), ('ǒ', "o"), ('Ǔ', "U"), ('ǔ', "u"),
+    ('Ǖ', "U"), ('ǖ', "u"), ('Ǘ', "U"), ('ǘ', "u"), ('Ǚ', "U"), ('ǚ', "u"), ('Ǜ', "U"),
+    ('ǜ', "u"), ('Ǟ', "A"), ('ǟ', "a"), ('Ǡ', "A"), ('ǡ', "a"), ('Ǣ', "AE"), ('ǣ', "ae"),
+    ('Ǧ', "G"), ('ǧ', "g"), ('Ǩ', "K"), ('ǩ', "k"), ('Ǫ', "O"), ('ǫ', "o"), ('Ǭ', "O"),
+    ('ǭ', "o"), ('ǰ', "j"), ('Ǳ', "DZ"), ('ǲ', "Dz"), ('ǳ', "dz"), ('Ǵ', "G"), ('ǵ', "g"),
+    ('Ǹ', "N"), ('ǹ', "n"), ('Ǻ', "A"), ('ǻ', "a"), ('Ǽ', "AE"), ('ǽ', "ae"), ('Ǿ', "O"),
+    ('ǿ', "o"), ('Ȁ', "A"), ('ȁ', "a"), ('Ȃ', "A"), ('ȃ', "a"), ('Ȅ', "E"), ('ȅ', "e"),
+    ('Ȇ', "E"), ('ȇ', "e"), ('Ȉ', "I"), ('ȉ', "i"), ('Ȋ', "I"), ('ȋ', "i"), ('Ȍ', "O"),
+    ('ȍ', "o"), ('Ȏ', "O"), ('ȏ', "o"), ('Ȑ', "R"), ('ȑ', "r"), ('Ȓ', "R"), ('ȓ', "r"),
+    ('Ȕ', "U"), ('ȕ', "u"), ('Ȗ', "U"), ('ȗ', "u"), ('Ș', "S"), ('ș', "s"), ('Ț', "T"),
+    ('ț', "t"), ('Ȟ', "H"), ('ȟ', "h"), ('Ȧ', "A"), ('ȧ', "a"), ('Ȩ', "E"), ('ȩ', "e"),
+    ('Ȫ', "O"), ('ȫ', "o"), ('Ȭ', "O"), ('ȭ', "o"), ('Ȯ', "O"), ('ȯ', "o"), ('Ȱ', "O"),
+    ('ȱ', "o"), ('Ȳ', "Y"), ('ȳ', "y"), ('Ḁ', "A"), ('ḁ', "a"), ('Ḃ', "B"), ('ḃ', "b"),
+    ('Ḅ', "B"), ('ḅ', "b"), ('Ḇ', "B"), ('ḇ', "b"), ('Ḉ', "C"), ('ḉ', "c"), ('Ḋ', "D"),
+    ('ḋ', "d"), ('Ḍ', "D"), ('ḍ', "d"), ('Ḏ', "D"), ('ḏ', "d"), ('Ḑ', "D"), ('ḑ', "d"),
+    ('Ḓ', "D"), ('ḓ', "d"), ('Ḕ', "E"), ('ḕ', "e"), ('Ḗ', "E"), ('ḗ', "e"), ('Ḙ', "E"),
+    ('ḙ', "e"), ('Ḛ', "E"), ('ḛ', "e"), ('Ḝ', "E"), ('ḝ', "e"), ('Ḟ', "F"), ('ḟ', "f"),
+    ('Ḡ', "G"), ('ḡ', "g"), ('Ḣ', "H"), ('ḣ', "h"), ('Ḥ', "H"), ('ḥ', "h"), ('Ḧ', "H"),
+    ('ḧ', "h"), ('Ḩ', "H"), ('ḩ', "h"), ('Ḫ', "H"), ('ḫ', "h"), ('Ḭ', "I"), ('ḭ', "i"),
+    ('Ḯ', "I"), ('ḯ', "i"), ('Ḱ', "K"), ('ḱ', "k"), ('Ḳ', "K"), ('ḳ', "k"), ('Ḵ', "K"),
+    ('ḵ', "k"), ('Ḷ', "L"), ('ḷ', "l"), ('Ḹ', "L"), ('ḹ', "l"), ('Ḻ', "L"), ('ḻ', "l"),
+    ('Ḽ', "L"), ('ḽ', "l"), ('Ḿ', "M"), ('ḿ', "m"), ('Ṁ', "M"), ('ṁ', "m"), ('Ṃ', "M"),
+    ('ṃ', "m"), ('Ṅ', "N"), ('ṅ', "n"), ('Ṇ', "N"), ('ṇ', "n"), ('Ṉ', "N"), ('ṉ', "n"),
+    ('Ṋ', "N"), ('ṋ', "n"), ('Ṍ', "O"), ('ṍ', "o"), ('Ṏ', "O"), ('ṏ', "o"), ('Ṑ', "O"),
+    ('ṑ', "o"), ('Ṓ', "O"), ('ṓ', "o"), ('Ṕ', "P"), ('ṕ', "p"), ('Ṗ', "P"), ('ṗ', "p"),
+    ('Ṙ', "R"), ('ṙ', "r"), ('Ṛ', "R"), ('ṛ', "r"), ('Ṝ', "R"), ('ṝ', "r"), ('Ṟ', "R"),
+    ('ṟ', "r"), ('Ṡ', "S"), ('ṡ', "s"), ('Ṣ', "S"), ('ṣ', "s"), ('Ṥ', "S"), ('ṥ', "s"),
+    ('Ṧ', "S"), ('ṧ', "s"), ('Ṩ', "S"), ('ṩ', "s"), ('Ṫ', "T"), ('ṫ', "t"), ('Ṭ', "T"),
+    ('ṭ', "t"), ('Ṯ', "T"), ('ṯ', "t"), ('Ṱ', "T"), ('ṱ', "t"), ('Ṳ', "U"), ('ṳ', "u"),
+    ('Ṵ', "U"), ('ṵ', "u"), ('Ṷ', "U"), ('ṷ', "u"), ('Ṹ', "U"), ('ṹ', "u"), ('Ṻ', "U"),
+    ('ṻ', "u"), ('Ṽ', "V"), ('ṽ', "v"), ('Ṿ', "V"), ('ṿ', "v"), ('Ẁ', "W"), ('ẁ', "w"),
+    ('Ẃ', "W"), ('ẃ', "w"), ('Ẅ', "W"), ('ẅ', "w"), ('Ẇ', "W"), ('ẇ', "w"), ('Ẉ', "W"),
+    ('ẉ', "w"), ('Ẋ', "X"), ('ẋ', "x"), ('Ẍ', "X"), ('ẍ', "x"), ('Ẏ', "Y"), ('ẏ', "y"),
+    ('Ẑ', "Z"), ('ẑ', "z"), ('Ẓ', "Z"), ('ẓ', "z"), ('Ẕ', "Z"), ('ẕ', "z"), ('ẖ', "h"),
+    ('ẗ', "t"), ('ẘ', "w"), ('ẙ', "y"), ('ẛ', "s"), ('ẞ', "SS"), ('Ạ', "A"), ('ạ', "a"),
+    ('Ả', "A"), ('ả', "a"), ('Ấ', "A"), ('ấ', "a"), ('Ầ', "A"), ('ầ', "a"), ('Ẩ', "A"),
+    ('ẩ', "a"), ('Ẫ', "A"), ('ẫ', "a"), ('Ậ', "A"), ('ậ', "a"), ('Ắ', "A"), ('ắ', "a"),
+    ('Ằ', "A"), ('ằ', "a"), ('Ẳ', "A"), ('ẳ', "a"), ('Ẵ', "A"), ('ẵ', "a"), ('Ặ', "A"),
+    ('ặ', "a"), ('Ẹ', "E"), ('ẹ', "e"), ('Ẻ', "E"), ('ẻ', "e"), ('Ẽ', "E"), ('ẽ', "e"),
+    ('Ế', "E"), ('ế', "e"), ('Ề', "E"), ('ề', "e"), ('Ể', "E"), ('ể', "e"), ('Ễ', "E"),
+    ('ễ', "e"), ('Ệ', "E"), ('ệ', "e"), ('Ỉ', "I"), ('ỉ', "i"), ('Ị', "I"), ('ị', "i"),
+    ('Ọ', "O"), ('ọ', "o"), ('Ỏ', "O"), ('ỏ', "o"), ('Ố', "O"), ('ố', "o"), ('Ồ', "O"),
+    ('ồ', "o"), ('Ổ', "O"), ('ổ', "o"), ('Ỗ', "O"), ('ỗ', "o"), ('Ộ', "O"), ('ộ', "o"),
+    ('Ớ', "O"), ('ớ', "o"), ('Ờ', "O"), ('ờ', "o"), ('Ở', "O"), ('ở', "o"), ('Ỡ', "O"),
+    ('ỡ', "o"), ('Ợ', "O"), ('ợ', "o"), ('Ụ', "U"), ('ụ', "u"), ('Ủ', "U"), ('ủ', "u"),
+    ('Ứ', "U"), ('ứ', "u"), ('Ừ', "U"), ('ừ', "u"), ('Ử', "U"), ('ử', "u"), ('Ữ', "U"),
+    ('ữ', "u"), ('Ự', "U"), ('ự', "u"), ('Ỳ', "Y"), ('ỳ', "y"), ('Ỵ', "Y"), ('ỵ', "y"),
+    ('Ỷ', "Y"), ('ỷ', "y"), ('Ỹ', "Y"), ('ỹ', "y"), ('ﬀ', "ff"), ('ﬁ', "fi"), ('ﬂ', "fl"),
+    ('ﬃ', "ffi"), ('ﬄ', "ffl"), ('ﬅ', "st"), ('ﬆ', "st"), ('！', "!"), ('＂', "\""), ('＃', "#"),
+    ('＄', "$"), ('％', "%"), ('＆', "&"), ('＇', "'"), ('（', "("), ('）', ")"), ('＊', "*"),
+    ('＋', "+"), ('，', ","), ('－', "-"), ('．', "."), ('／', "/"), ('０', "0"), ('１', "1"),
+    ('２', "2"), ('３', "3"), ('４', "4"), ('５', "5"), ('６', "6"), ('７', "7"), ('８', "8"),
+    ('９', "9"), ('：', ":"), ('；', ";"), ('＜', "<"), ('＝', "="), ('＞', ">"), ('？', "?"),
+    ('＠', "@"), ('Ａ', "A"), ('Ｂ', "B"), ('Ｃ', "C"), ('Ｄ', "D"), ('Ｅ', "E"), ('Ｆ', "F"),
+    ('Ｇ', "G"), ('Ｈ', "H"), ('Ｉ', "I"), ('Ｊ', "J"), ('Ｋ', "K"), ('Ｌ', "L"), ('Ｍ', "M"),
+    ('Ｎ', "N"), ('Ｏ', "O"), ('Ｐ', "P"), ('Ｑ', "Q"), ('Ｒ', "R"), ('Ｓ', "S"), ('Ｔ', "T"),
+    ('Ｕ', "U"), ('Ｖ', "V"), ('Ｗ', "W"), ('Ｘ', "X"), ('Ｙ', "Y"), ('Ｚ', "Z"), ('［', "["),
+    ('＼', "\\"), ('］', "]"), ('＾', "^"), ('＿', "_"), ('｀', "`"), ('ａ', "a"), ('ｂ', "b"),
+    ('ｃ', "c"), ('ｄ', "d"), ('ｅ', "e"), ('ｆ', "f"), ('ｇ', "g"), ('ｈ', "h"), ('ｉ', "i"),
+    ('ｊ', "j"), ('ｋ', "k"), ('ｌ', "l"), ('ｍ', "m"), ('ｎ', "n"), ('ｏ', "o"), ('ｐ', "p"),
+    ('ｑ', "q"), ('ｒ', "r"), ('ｓ', "s"), ('ｔ', "t"), ('ｕ', "u"), ('ｖ', "v"), ('ｗ', "w"),
+    ('ｘ', "x"), ('ｙ', "y"), ('ｚ', "z"), ('｛', "{"), ('｜', "|"), ('｝', "}"), ('～', "~"),
+];
+
+/// Returns true for the combining diacritical marks U+0300..=U+036F
+fn is_combining_mark(c: char) -> bool {
+    ('\u{300}'..='\u{36f}').contains(&c)
+}
+
+/// Returns the ASCII spelling of `c`, if it is covered by the folding table
+pub fn fold_char(c: char) -> Option<&'static str> {
+    FOLDS.binary_search_by_key(&c, |&(from, _)| from).ok().map(|i| FOLDS[i].1)
+}
+
+/// Folds accented Latin characters in `text` to ASCII
+///
+/// Returns the input unchanged, without allocating, when it is already ASCII.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::fold::fold_diacritics;
+/// assert_eq!(fold_diacritics("Résumé, naïve, Straße"), "Resume, naive, Strasse");
+/// ```
+pub fn fold_diacritics(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() { return Cow::Borrowed(text); }
+
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else if let Some(folded) = fold_char(c) {
+            out.push_str(folded);
+        } else if !is_combining_mark(c) {
+            out.push(c);
+        }
+    }
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_sorted() {
+        assert!(FOLDS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_fold_diacritics() {
+        assert!(matches!(fold_diacritics("plain"), Cow::Borrowed("plain")));
+        assert_eq!(fold_diacritics("Ærøskøbing ﬁnal ｗｉｄｅ"), "AEroskobing final wide");
+        assert_eq!(fold_diacritics("cafe\u{301} łódź"), "cafe lodz");
+        assert_eq!(fold_diacritics("Ελλάδα"), "Ελλάδα");
+    }
+}
@@ -0,0 +1,375 @@
+//! # French (Snowball)
+//!
+//! The Snowball French stemmer
+//! (<https://snowballstem.org/algorithms/french/stemmer.html>). Besides R1
+//! and R2 it uses the region RV, which starts after the first vowel that is
+//! not the first letter (after the third letter if the word starts with two
+//! vowels, or with "par", "col" or "tap"). Vowels between vowels and a "u"
+//! after "q" are marked as consonants before the steps run.
+//!
+//! The implementation follows the Snowball source; each routine is named
+//! after the one it implements, and comments quote the conditions that are
+//! not obvious from the suffix tables.
+
+use crate::fold;
+use crate::snowball::{ends_with, find_suffix, regions, replace_from};
+use crate::stemmer::Stemmer;
+
+/// Vowels; the marked consonants `I`, `U` and `Y` are not among them
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'â' | 'à' | 'ë' | 'é' | 'ê' | 'è' | 'ï' | 'î' | 'ô' | 'û' | 'ù')
+}
+
+const STANDARD_SUFFIXES: &[&str] = &[
+    "ance", "iqUe", "isme", "able", "iste", "eux", "ances", "iqUes", "ismes", "ables", "istes",
+    "atrice", "ateur", "ation", "atrices", "ateurs", "ations", "logie", "logies",
+    "usion", "ution", "usions", "utions", "ence", "ences", "ement", "ements", "ité", "ités",
+    "if", "ive", "ifs", "ives", "eaux", "aux", "euse", "euses", "issement", "issements",
+    "amment", "emment", "ment", "ments",
+];
+
+const I_VERB_SUFFIXES: &[&str] = &[
+    "îmes", "ît", "îtes", "i", "ie", "ies", "ir", "ira", "irai", "iraIent", "irais", "irait",
+    "iras", "irent", "irez", "iriez", "irions", "irons", "iront", "is", "issaIent", "issais",
+    "issait", "issant", "issante", "issantes", "issants", "isse", "issent", "isses", "issez",
+    "issiez", "issions", "issons", "it",
+];
+
+/// Verb endings removed outright
+const VERB_SUFFIXES: &[&str] = &[
+    "é", "ée", "ées", "és", "èrent", "er", "era", "erai", "eraIent", "erais", "erait", "eras",
+    "erez", "eriez", "erions", "erons", "eront", "ez", "iez",
+];
+
+/// Verb endings removed along with an "e" before them
+const VERB_SUFFIXES_E: &[&str] = &[
+    "âmes", "ât", "âtes", "a", "ai", "aIent", "ais", "ait", "ant", "ante", "antes", "ants",
+    "as", "asse", "assent", "asses", "assiez", "assions",
+];
+
+const RESIDUAL_SUFFIXES: &[&str] = &["ion", "ier", "ière", "Ier", "Ière", "e", "ë"];
+
+/// A Snowball French stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::FrenchStemmer;
+/// let mut stemmer = FrenchStemmer::new();
+/// assert_eq!(stemmer.stem("continuellement"), "continuel");
+/// assert_eq!(stemmer.stem("majestueusement"), "majestu");
+/// assert_eq!(stemmer.stem("mangeaient"), "mang");
+/// ```
+#[derive(Debug, Default)]
+pub struct FrenchStemmer {
+    /// Word being stemmed
+    buffer: Vec<char>,
+    /// Start of region RV
+    pv: usize,
+    /// Start of region R1
+    p1: usize,
+    /// Start of region R2
+    p2: usize,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl FrenchStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        FrenchStemmer { buffer: Vec::new(), pv: 0, p1: 0, p2: 0, fold_diacritics: false }
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    ///
+    /// Folding loses distinctions the algorithm relies on, such as "é" in
+    /// past participles, so it is only useful for text typed without accents.
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        self.buffer.clear();
+        self.buffer.extend(word.chars().flat_map(char::to_lowercase));
+
+        self.prelude();
+        self.mark_regions();
+        if self.standard_suffix() || self.i_verb_suffix() || self.verb_suffix() {
+            match self.buffer.last() {
+                Some('Y') => *self.buffer.last_mut().unwrap() = 'i',
+                Some('ç') => *self.buffer.last_mut().unwrap() = 'c',
+                _ => {},
+            }
+        } else {
+            self.residual_suffix();
+        }
+        self.un_double();
+        self.un_accent();
+
+        self.buffer
+            .iter()
+            .map(|&c| match c {
+                'I' => 'i',
+                'U' => 'u',
+                'Y' => 'y',
+                c => c,
+            })
+            .collect()
+    }
+
+    /// Marks "u", "i" and "y" that act as consonants by uppercasing them
+    fn prelude(&mut self) {
+        let b = &mut self.buffer;
+        let vowel_at = |b: &[char], i: usize| b.get(i).is_some_and(|&c| is_vowel(c));
+        for i in 0..b.len() {
+            if vowel_at(b, i) && matches!(b.get(i + 1), Some('u' | 'i')) && vowel_at(b, i + 2) {
+                b[i + 1] = b[i + 1].to_ascii_uppercase();
+            } else if vowel_at(b, i) && b.get(i + 1) == Some(&'y') {
+                b[i + 1] = 'Y';
+            } else if b[i] == 'y' && vowel_at(b, i + 1) {
+                b[i] = 'Y';
+            } else if b[i] == 'q' && b.get(i + 1) == Some(&'u') {
+                b[i + 1] = 'U';
+            }
+        }
+    }
+
+    fn mark_regions(&mut self) {
+        let b = &self.buffer;
+        let len = b.len();
+        let starts_two_vowels = len >= 3 && is_vowel(b[0]) && is_vowel(b[1]);
+        self.pv = if starts_two_vowels || ["par", "col", "tap"].iter().any(|p| b.iter().take(3).copied().eq(p.chars())) {
+            3
+        } else {
+            (1..len).find(|&i| is_vowel(b[i])).map_or(len, |i| i + 1)
+        };
+        (self.p1, self.p2) = regions(b, is_vowel);
+    }
+
+    /// Returns true if the letter before `i` exists, is at or after `limit`
+    /// and is not a vowel
+    fn consonant_before(&self, i: usize, limit: usize) -> bool {
+        i > limit && !is_vowel(self.buffer[i - 1])
+    }
+
+    fn standard_suffix(&mut self) -> bool {
+        let Some((suffix, start)) = find_suffix(&self.buffer, STANDARD_SUFFIXES, 0) else { return false };
+        let (r1, r2, rv) = (start >= self.p1, start >= self.p2, start >= self.pv);
+        match suffix {
+            "ance" | "iqUe" | "isme" | "able" | "iste" | "eux" | "ances" | "iqUes" | "ismes" | "ables" | "istes" => {
+                if !r2 { return false; }
+                self.buffer.truncate(start);
+            },
+            "atrice" | "ateur" | "ation" | "atrices" | "ateurs" | "ations" => {
+                if !r2 { return false; }
+                self.buffer.truncate(start);
+                self.delete_ic_in_r2();
+            },
+            "logie" | "logies" => {
+                if !r2 { return false; }
+                replace_from(&mut self.buffer, start, "log");
+            },
+            "usion" | "ution" | "usions" | "utions" => {
+                if !r2 { return false; }
+                replace_from(&mut self.buffer, start, "u");
+            },
+            "ence" | "ences" => {
+                if !r2 { return false; }
+                replace_from(&mut self.buffer, start, "ent");
+            },
+            "ement" | "ements" => {
+                if !rv { return false; }
+                self.buffer.truncate(start);
+                match find_suffix(&self.buffer, &["iv", "eus", "abl", "iqU", "ièr", "Ièr"], 0) {
+                    Some(("iv", s)) if s >= self.p2 => {
+                        self.buffer.truncate(s);
+                        if ends_with(&self.buffer, "at") && s - 2 >= self.p2 { self.buffer.truncate(s - 2); }
+                    },
+                    Some(("eus", s)) if s >= self.p2 => self.buffer.truncate(s),
+                    Some(("eus", s)) if s >= self.p1 => replace_from(&mut self.buffer, s, "eux"),
+                    Some(("abl" | "iqU", s)) if s >= self.p2 => self.buffer.truncate(s),
+                    Some(("ièr" | "Ièr", s)) if s >= self.pv => replace_from(&mut self.buffer, s, "i"),
+                    _ => {},
+                }
+            },
+            "ité" | "ités" => {
+                if !r2 { return false; }
+                self.buffer.truncate(start);
+                match find_suffix(&self.buffer, &["abil", "ic", "iv"], 0) {
+                    Some(("abil", s)) if s >= self.p2 => self.buffer.truncate(s),
+                    Some(("abil", s)) => replace_from(&mut self.buffer, s, "abl"),
+                    Some(("ic", s)) if s >= self.p2 => self.buffer.truncate(s),
+                    Some(("ic", s)) => replace_from(&mut self.buffer, s, "iqU"),
+                    Some(("iv", s)) if s >= self.p2 => self.buffer.truncate(s),
+                    _ => {},
+                }
+            },
+            "if" | "ive" | "ifs" | "ives" => {
+                if !r2 { return false; }
+                self.buffer.truncate(start);
+                if ends_with(&self.buffer, "at") && start - 2 >= self.p2 {
+                    self.buffer.truncate(start - 2);
+                    self.delete_ic_in_r2();
+                }
+            },
+            "eaux" => replace_from(&mut self.buffer, start, "eau"),
+            "aux" => {
+                if !r1 { return false; }
+                replace_from(&mut self.buffer, start, "al");
+            },
+            "euse" | "euses" => {
+                if r2 {
+                    self.buffer.truncate(start);
+                } else if r1 {
+                    replace_from(&mut self.buffer, start, "eux");
+                } else {
+                    return false;
+                }
+            },
+            "issement" | "issements" => {
+                if !r1 || !self.consonant_before(start, 0) { return false; }
+                self.buffer.truncate(start);
+            },
+            // The -ment family always fails, so that the shortened word goes
+            // on to the verb suffixes ("confusément" -> "confusé" -> "confus")
+            "amment" => {
+                if rv { replace_from(&mut self.buffer, start, "ant"); }
+                return false;
+            },
+            "emment" => {
+                if rv { replace_from(&mut self.buffer, start, "ent"); }
+                return false;
+            },
+            _ => {
+                // "ment" and "ments", after a vowel in RV
+                if start > self.pv && is_vowel(self.buffer[start - 1]) { self.buffer.truncate(start); }
+                return false;
+            },
+        }
+        true
+    }
+
+    /// Removes a final "ic" if it is in R2, and changes it to "iqU" otherwise
+    fn delete_ic_in_r2(&mut self) {
+        if !ends_with(&self.buffer, "ic") { return; }
+        let start = self.buffer.len() - 2;
+        if start >= self.p2 {
+            self.buffer.truncate(start);
+        } else {
+            replace_from(&mut self.buffer, start, "iqU");
+        }
+    }
+
+    fn i_verb_suffix(&mut self) -> bool {
+        match find_suffix(&self.buffer, I_VERB_SUFFIXES, self.pv) {
+            // "if preceded by a non-vowel which is also in RV"
+            Some((_, start)) if self.consonant_before(start, self.pv) => {
+                self.buffer.truncate(start);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    fn verb_suffix(&mut self) -> bool {
+        let suffixes = [VERB_SUFFIXES, VERB_SUFFIXES_E, &["ions"]].concat();
+        let Some((suffix, start)) = find_suffix(&self.buffer, &suffixes, self.pv) else { return false };
+        if suffix == "ions" {
+            if start < self.p2 { return false; }
+            self.buffer.truncate(start);
+        } else if VERB_SUFFIXES.contains(&suffix) {
+            self.buffer.truncate(start);
+        } else {
+            self.buffer.truncate(start);
+            if ends_with(&self.buffer, "e") && start > self.pv { self.buffer.pop(); }
+        }
+        true
+    }
+
+    fn residual_suffix(&mut self) {
+        let len = self.buffer.len();
+        if len >= 2 && self.buffer[len - 1] == 's' && !matches!(self.buffer[len - 2], 'a' | 'i' | 'o' | 'u' | 'è' | 's') {
+            self.buffer.pop();
+        }
+        match find_suffix(&self.buffer, RESIDUAL_SUFFIXES, self.pv) {
+            // "if in R2 and preceded by s or t" (in RV)
+            Some(("ion", start)) if start >= self.p2 && start > self.pv && matches!(self.buffer[start - 1], 's' | 't') => {
+                self.buffer.truncate(start);
+            },
+            Some(("e", start)) => self.buffer.truncate(start),
+            Some(("ë", start)) if start >= self.pv + 2 && self.buffer[start - 2..start] == ['g', 'u'] => {
+                self.buffer.truncate(start);
+            },
+            Some(("ion" | "ë", _)) | None => {},
+            Some((_, start)) => replace_from(&mut self.buffer, start, "i"),
+        }
+    }
+
+    /// Removes the last letter of a final "enn", "onn", "ett", "ell" or "eill"
+    fn un_double(&mut self) {
+        if ["enn", "onn", "ett", "ell", "eill"].iter().any(|s| ends_with(&self.buffer, s)) {
+            self.buffer.pop();
+        }
+    }
+
+    /// Changes "é" or "è" to "e" when only non-vowels follow it
+    fn un_accent(&mut self) {
+        let consonants = self.buffer.iter().rev().take_while(|&&c| !is_vowel(c)).count();
+        if consonants == 0 || consonants == self.buffer.len() { return; }
+        let i = self.buffer.len() - consonants - 1;
+        if matches!(self.buffer[i], 'é' | 'è') { self.buffer[i] = 'e'; }
+    }
+}
+
+impl Stemmer for FrenchStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        FrenchStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_examples() {
+        let mut stemmer = FrenchStemmer::new();
+        let cases = [
+            ("continu", "continu"), ("continua", "continu"), ("continuait", "continu"),
+            ("continuant", "continu"), ("continuation", "continu"), ("continue", "continu"),
+            ("continué", "continu"), ("continuel", "continuel"), ("continuelle", "continuel"),
+            ("continuellement", "continuel"), ("continuelles", "continuel"), ("continuer", "continu"),
+            ("continuera", "continu"), ("continuerait", "continu"), ("continueront", "continu"),
+            ("continuez", "continu"), ("continuité", "continu"), ("continuons", "continuon"),
+            ("contorsions", "contors"), ("contour", "contour"), ("contournait", "contourn"),
+            ("contournant", "contourn"), ("contourne", "contourn"), ("contours", "contour"),
+            ("contractait", "contract"), ("contracté", "contract"), ("contractée", "contract"),
+            ("contracter", "contract"), ("contractés", "contract"), ("contractions", "contract"),
+            ("contradictoirement", "contradictoir"), ("contraignait", "contraign"),
+            ("contraindre", "contraindr"), ("contraint", "contraint"), ("contraintes", "contraint"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_snowball_vocabulary() {
+        // The vocabulary and expected output of the Snowball project's test
+        // data (<https://github.com/snowballstem/snowball-data>)
+        let mut stemmer = FrenchStemmer::new();
+        let words = include_str!("../test_data/french/voc.txt");
+        let stems = include_str!("../test_data/french/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+    }
+}
@@ -0,0 +1,186 @@
+//! # Stem Frequencies
+//!
+//! Counts how often each stem occurs in a text, for keyword extraction and
+//! term statistics. [`stem_frequencies`] counts a whole text at once, and
+//! [`FrequencyCounter`] can be fed a text in chunks of any size, such as
+//! blocks read from a file, with the same result.
+//!
+//! Text is tokenized like [`crate::text::stems`], so diacritic folding on
+//! the stemmer applies here too.
+
+use std::collections::HashMap;
+
+use crate::fold::fold_diacritics;
+use crate::stopwords::Stopwords;
+use crate::text::{is_word_letter, tokens_for};
+use crate::Stemmer;
+
+/// Counts the stems of `text`
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, freq::stem_frequencies};
+/// let mut stemmer = PorterStemmer::new();
+/// let counts = stem_frequencies(&mut stemmer, "Cats chase cats; a cat runs");
+/// assert_eq!(counts["cat"], 3);
+/// assert_eq!(counts["a"], 1);
+/// ```
+pub fn stem_frequencies(stemmer: &mut dyn Stemmer, text: &str) -> HashMap<String, u64> {
+    let mut counter = FrequencyCounter::new();
+    counter.feed(stemmer, text);
+    counter.finish(stemmer)
+}
+
+/// Sorts counts by descending count, then alphabetically by stem
+pub fn sorted_counts(counts: HashMap<String, u64>) -> Vec<(String, u64)> {
+    let mut counts: Vec<(String, u64)> = counts.into_iter().collect();
+    counts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Incremental stem counter over a text delivered in chunks
+///
+/// A word cut in two by a chunk boundary is held back until the next chunk
+/// (or [`FrequencyCounter::finish`]) completes it, so the counts do not
+/// depend on where the text was split.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, freq::FrequencyCounter};
+/// let mut stemmer = PorterStemmer::new();
+/// let mut counter = FrequencyCounter::new();
+/// counter.feed(&mut stemmer, "The runners were runn");
+/// counter.feed(&mut stemmer, "ing. Run!");
+/// let counts = counter.finish(&mut stemmer);
+/// assert_eq!(counts["run"], 2);
+/// assert_eq!(counts["runner"], 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FrequencyCounter<'a> {
+    counts: HashMap<String, u64>,
+    /// Trailing part of the last chunk that may continue in the next one
+    pending: String,
+    stopwords: Option<&'a Stopwords>,
+}
+
+impl<'a> FrequencyCounter<'a> {
+    /// Creates a counter that counts every word
+    pub fn new() -> Self {
+        FrequencyCounter { counts: HashMap::new(), pending: String::new(), stopwords: None }
+    }
+
+    /// Skips the given stopwords instead of counting them
+    pub fn stopwords(mut self, stopwords: &'a Stopwords) -> Self {
+        self.stopwords = Some(stopwords);
+        self
+    }
+
+    /// Counts the complete words of `chunk`
+    pub fn feed(&mut self, stemmer: &mut dyn Stemmer, chunk: &str) {
+        self.pending.push_str(chunk);
+        // Cut after the last ASCII non-letter: everything before it is made
+        // of complete words, while a word (possibly with accented letters
+        // still to be folded) may run on from the rest into the next chunk.
+        // For a stemmer of non-ASCII words, any non-letter ends a word.
+        let unicode = stemmer.unicode_words();
+        let cut = self
+            .pending
+            .char_indices()
+            .rfind(|&(_, c)| if unicode { !is_word_letter(c) } else { c.is_ascii() && !c.is_ascii_alphabetic() })
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        if cut > 0 {
+            let rest = self.pending.split_off(cut);
+            let complete = std::mem::replace(&mut self.pending, rest);
+            self.count(stemmer, &complete);
+        }
+    }
+
+    /// Counts any word held back from the last chunk and returns the counts
+    pub fn finish(mut self, stemmer: &mut dyn Stemmer) -> HashMap<String, u64> {
+        let rest = std::mem::take(&mut self.pending);
+        self.count(stemmer, &rest);
+        self.counts
+    }
+
+    /// Counts seen so far, not including a word held back from the last chunk
+    pub fn counts(&self) -> &HashMap<String, u64> {
+        &self.counts
+    }
+
+    fn count(&mut self, stemmer: &mut dyn Stemmer, text: &str) {
+        let folded = if stemmer.folds_diacritics() { fold_diacritics(text) } else { text.into() };
+        for token in tokens_for(stemmer, &folded) {
+            if self.stopwords.is_some_and(|stopwords| stopwords.contains(token.text)) { continue; }
+            *self.counts.entry(stemmer.stem(token.text)).or_insert(0) += 1;
+        }
+    }
+}
+
+/// Returns the `k` most frequent stems of `text` with their counts
+///
+/// English stopwords are excluded. Stems with equal counts are ordered
+/// alphabetically, so the result is deterministic.
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each word
+/// * `text` - Arbitrary input text
+/// * `k` - Maximum number of stems to return
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, freq::top_stems};
+/// let mut stemmer = PorterStemmer::new();
+/// let top = top_stems(&mut stemmer, "The runner runs. Running is what runners do.", 2);
+/// assert_eq!(top, [("run".to_string(), 2), ("runner".to_string(), 2)]);
+/// ```
+pub fn top_stems(stemmer: &mut dyn Stemmer, text: &str, k: usize) -> Vec<(String, u64)> {
+    top_stems_filtered(stemmer, text, k, &Stopwords::english())
+}
+
+/// Like [`top_stems`] with a custom stopword set
+pub fn top_stems_filtered(
+    stemmer: &mut dyn Stemmer,
+    text: &str,
+    k: usize,
+    stopwords: &Stopwords,
+) -> Vec<(String, u64)> {
+    let mut counter = FrequencyCounter::new().stopwords(stopwords);
+    counter.feed(stemmer, text);
+    let mut top = sorted_counts(counter.finish(stemmer));
+    top.truncate(k);
+    top
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_top_stems_excludes_stopwords() {
+        let mut stemmer = PorterStemmer::new();
+        let top = top_stems(&mut stemmer, "the the the cat and the cats", 5);
+        assert_eq!(top, [("cat".to_string(), 2)]);
+        assert!(top_stems(&mut stemmer, "cats", 0).is_empty());
+        let none = Stopwords::new();
+        assert_eq!(top_stems_filtered(&mut stemmer, "the cat the", 1, &none), [("the".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_chunking_does_not_change_counts() {
+        let text = "Connections, connected; the résumés connect. Caf\u{e9}s";
+        let mut stemmer = PorterStemmer::new().with_diacritic_folding(true);
+        let whole = stem_frequencies(&mut stemmer, text);
+        for size in 1..8 {
+            let mut counter = FrequencyCounter::new();
+            let chars: Vec<char> = text.chars().collect();
+            for chunk in chars.chunks(size) {
+                counter.feed(&mut stemmer, &chunk.iter().collect::<String>());
+            }
+            assert_eq!(counter.finish(&mut stemmer), whole, "chunk size {}", size);
+        }
+        assert_eq!(whole["connect"], 3);
+        assert_eq!(whole["resum"], 1);
+        assert_eq!(whole["cafe"], 1);
+    }
+}
@@ -0,0 +1,57 @@
+//! # Bounded Edit Distance
+//!
+//! Levenshtein distance between two stems, used by
+//! [`crate::PorterStemmer::stems_match_fuzzy`] to tolerate typos in queries.
+//! The distance is computed directly on the stemmer's byte buffers
+//! with a single reused row, and gives up as soon as it is certain to
+//! exceed the bound.
+
+/// Returns the Levenshtein distance between `a` and `b` if it is at most
+/// `max`, or None if it is larger
+///
+/// `row` is working storage; it is resized as needed and can be reused
+/// across calls so that no allocation happens once it is large enough.
+pub(crate) fn bounded_distance<T: PartialEq>(a: &[T], b: &[T], max: usize, row: &mut Vec<usize>) -> Option<usize> {
+    let (a, b) = if a.len() < b.len() { (b, a) } else { (a, b) };
+    if a.len() - b.len() > max { return None; }
+
+    // row[j] is the distance between the current prefix of a and b[..j]
+    row.clear();
+    row.extend(0..=b.len());
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        let mut row_min = row[0];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+            row_min = row_min.min(row[j + 1]);
+        }
+        if row_min > max { return None; }
+    }
+
+    let distance = row[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: &str, b: &str, max: usize) -> Option<usize> {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        bounded_distance(&a, &b, max, &mut Vec::new())
+    }
+
+    #[test]
+    fn test_bounded_distance() {
+        assert_eq!(distance("kitten", "sitting", 3), Some(3));
+        assert_eq!(distance("kitten", "sitting", 2), None);
+        assert_eq!(distance("", "abc", 3), Some(3));
+        assert_eq!(distance("flaw", "lawn", 5), Some(2));
+        assert_eq!(distance("same", "same", 0), Some(0));
+        assert_eq!(distance("a", "abcdef", 2), None);
+    }
+}
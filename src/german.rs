@@ -0,0 +1,284 @@
+//! # German (Snowball)
+//!
+//! The Snowball German stemmer
+//! (<https://snowballstem.org/algorithms/german/stemmer.html>). "ß" is
+//! written "ss", and the umlauts are folded to plain vowels once the
+//! suffixes are gone, so "Häuser" and "Haus" share the stem "haus".
+//!
+//! Two options go beyond the standard algorithm:
+//!
+//! - [`GermanStemmer::with_transliterated_umlauts`] reads "ae", "oe" and "ue"
+//!   as "ä", "ö" and "ü" first, for text typed without umlauts (the Snowball
+//!   "German2" variant)
+//! - [`GermanStemmer::with_ge_prefix_removal`] removes the "ge-" of past
+//!   participles, as the CISTEM stemmer does, so "gemacht" and "macht" share
+//!   a stem
+
+use crate::fold;
+use crate::snowball::{ends_with, find_suffix, regions};
+use crate::stemmer::Stemmer;
+
+/// Vowels; the marked consonants `U` and `Y` are not among them
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'ä' | 'ö' | 'ü')
+}
+
+/// Letters before which a final "s" is removed
+fn is_s_ending(c: char) -> bool {
+    matches!(c, 'b' | 'd' | 'f' | 'g' | 'h' | 'k' | 'l' | 'm' | 'n' | 'r' | 't')
+}
+
+/// Letters before which a final "st" is removed
+fn is_st_ending(c: char) -> bool {
+    c != 'r' && is_s_ending(c)
+}
+
+/// A Snowball German stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::GermanStemmer;
+/// let mut stemmer = GermanStemmer::new();
+/// assert_eq!(stemmer.stem("Häuser"), "haus");
+/// assert_eq!(stemmer.stem("aufeinanderfolgenden"), "aufeinanderfolg");
+/// assert_eq!(stemmer.stem("Straße"), "strass");
+/// ```
+#[derive(Debug, Default)]
+pub struct GermanStemmer {
+    /// Word being stemmed
+    buffer: Vec<char>,
+    /// Start of region R1
+    p1: usize,
+    /// Start of region R2
+    p2: usize,
+    /// Whether "ae", "oe" and "ue" are read as umlauts
+    transliterated_umlauts: bool,
+    /// Whether the "ge-" of past participles is removed
+    ge_prefix: bool,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl GermanStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        GermanStemmer { buffer: Vec::new(), p1: 0, p2: 0, transliterated_umlauts: false, ge_prefix: false, fold_diacritics: false }
+    }
+
+    /// Enables or disables reading "ae", "oe" and "ue" as "ä", "ö" and "ü"
+    ///
+    /// "ue" after "q" is left alone ("Quelle").
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::GermanStemmer;
+    /// let mut stemmer = GermanStemmer::new().with_transliterated_umlauts(true);
+    /// assert_eq!(stemmer.stem("Haeuser"), stemmer.stem("Häuser"));
+    /// ```
+    pub fn with_transliterated_umlauts(mut self, enable: bool) -> Self {
+        self.transliterated_umlauts = enable;
+        self
+    }
+
+    /// Enables or disables removing a leading "ge" from words of at least
+    /// six letters, as past participles have
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::GermanStemmer;
+    /// let mut stemmer = GermanStemmer::new().with_ge_prefix_removal(true);
+    /// assert_eq!(stemmer.stem("gearbeitet"), stemmer.stem("arbeitet"));
+    /// ```
+    pub fn with_ge_prefix_removal(mut self, enable: bool) -> Self {
+        self.ge_prefix = enable;
+        self
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    ///
+    /// Folding turns the umlauts into plain vowels before the regions are
+    /// found; it is only useful for text that mixes German with other
+    /// languages.
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        self.buffer.clear();
+        self.buffer.extend(word.chars().flat_map(char::to_lowercase));
+        if self.ge_prefix && self.buffer.len() >= 6 && self.buffer.starts_with(&['g', 'e']) {
+            self.buffer.drain(..2);
+        }
+
+        self.prelude();
+        self.mark_regions();
+        self.standard_suffix();
+
+        self.buffer
+            .iter()
+            .map(|&c| match c {
+                'Y' => 'y',
+                'U' | 'ü' => 'u',
+                'ä' => 'a',
+                'ö' => 'o',
+                c => c,
+            })
+            .collect()
+    }
+
+    /// Writes "ß" as "ss" and marks "u" and "y" between vowels as consonants
+    fn prelude(&mut self) {
+        let mut i = 0;
+        while i < self.buffer.len() {
+            if self.buffer[i] == 'ß' {
+                self.buffer.splice(i..=i, ['s', 's']);
+            }
+            i += 1;
+        }
+
+        let b = &mut self.buffer;
+        let vowel_at = |b: &[char], i: usize| b.get(i).is_some_and(|&c| is_vowel(c));
+        for i in 1..b.len() {
+            if vowel_at(b, i - 1) && matches!(b[i], 'u' | 'y') && vowel_at(b, i + 1) {
+                b[i] = b[i].to_ascii_uppercase();
+            }
+        }
+
+        if self.transliterated_umlauts {
+            let mut i = 0;
+            while i + 1 < b.len() {
+                let umlaut = match (b[i], b[i + 1]) {
+                    ('a', 'e') => Some('ä'),
+                    ('o', 'e') => Some('ö'),
+                    ('u', 'e') if i == 0 || b[i - 1] != 'q' => Some('ü'),
+                    _ => None,
+                };
+                if let Some(umlaut) = umlaut {
+                    b.splice(i..i + 2, [umlaut]);
+                }
+                i += 1;
+            }
+        }
+    }
+
+    fn mark_regions(&mut self) {
+        let len = self.buffer.len();
+        if len < 3 {
+            (self.p1, self.p2) = (len, len);
+            return;
+        }
+        (self.p1, self.p2) = regions(&self.buffer, is_vowel);
+        // R1 is adjusted to contain at least three letters before it
+        self.p1 = self.p1.max(3);
+    }
+
+    fn standard_suffix(&mut self) {
+        match find_suffix(&self.buffer, &["em", "ern", "er", "e", "en", "es", "s"], 0) {
+            Some(("s", start)) if start >= self.p1 && start > 0 && is_s_ending(self.buffer[start - 1]) => {
+                self.buffer.truncate(start);
+            },
+            Some(("s", _)) => {},
+            Some((suffix, start)) if start >= self.p1 => {
+                self.buffer.truncate(start);
+                if matches!(suffix, "e" | "en" | "es") && ends_with(&self.buffer, "niss") {
+                    self.buffer.pop();
+                }
+            },
+            _ => {},
+        }
+
+        match find_suffix(&self.buffer, &["en", "er", "est", "st"], 0) {
+            // "st" needs a valid st-ending, itself preceded by at least 3 letters
+            Some(("st", start)) if start >= self.p1 && start >= 4 && is_st_ending(self.buffer[start - 1]) => {
+                self.buffer.truncate(start);
+            },
+            Some(("st", _)) => {},
+            Some((_, start)) if start >= self.p1 => self.buffer.truncate(start),
+            _ => {},
+        }
+
+        let not_after_e = |b: &[char], i: usize| i == 0 || b[i - 1] != 'e';
+        match find_suffix(&self.buffer, &["end", "ung", "ig", "ik", "isch", "lich", "heit", "keit"], 0) {
+            Some((_, start)) if start < self.p2 => {},
+            Some(("end" | "ung", start)) => {
+                self.buffer.truncate(start);
+                if ends_with(&self.buffer, "ig") && start - 2 >= self.p2 && not_after_e(&self.buffer, start - 2) {
+                    self.buffer.truncate(start - 2);
+                }
+            },
+            Some(("ig" | "ik" | "isch", start)) if not_after_e(&self.buffer, start) => self.buffer.truncate(start),
+            Some(("ig" | "ik" | "isch", _)) => {},
+            Some(("lich" | "heit", start)) => {
+                self.buffer.truncate(start);
+                if (ends_with(&self.buffer, "er") || ends_with(&self.buffer, "en")) && start - 2 >= self.p1 {
+                    self.buffer.truncate(start - 2);
+                }
+            },
+            Some((_, start)) => {
+                // "keit"
+                self.buffer.truncate(start);
+                if let Some((_, s)) = find_suffix(&self.buffer, &["lich", "ig"], self.p2) {
+                    self.buffer.truncate(s);
+                }
+            },
+            None => {},
+        }
+    }
+}
+
+impl Stemmer for GermanStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        GermanStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_examples() {
+        let mut stemmer = GermanStemmer::new();
+        let cases = [
+            ("aufeinanderfolgenden", "aufeinanderfolg"), ("aufeinanderfolgte", "aufeinanderfolgt"),
+            ("aufeinanderschlügen", "aufeinanderschlug"), ("kategorisch", "kategor"),
+            ("kategorische", "kategor"), ("katers", "kat"), ("kathedrale", "kathedral"),
+            ("kauf", "kauf"), ("kaufen", "kauf"), ("kaufmannsstand", "kaufmannsstand"),
+            ("kenntnisse", "kenntnis"), ("häuser", "haus"), ("straße", "strass"), ("ja", "ja"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        let mut stemmer = GermanStemmer::new().with_transliterated_umlauts(true);
+        assert_eq!(stemmer.stem("Koenige"), "konig");
+        assert_eq!(stemmer.stem("Quelle"), "quell");
+        let mut stemmer = GermanStemmer::new().with_ge_prefix_removal(true);
+        assert_eq!(stemmer.stem("gemacht"), "macht");
+        assert_eq!(stemmer.stem("gelb"), "gelb");
+    }
+
+    #[test]
+    fn test_snowball_vocabulary() {
+        // The vocabulary and expected output of the Snowball project's test
+        // data (<https://github.com/snowballstem/snowball-data>)
+        let mut stemmer = GermanStemmer::new();
+        let words = include_str!("../test_data/german/voc.txt");
+        let stems = include_str!("../test_data/german/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+    }
+}
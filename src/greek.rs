@@ -0,0 +1,556 @@
+//! # Greek (Snowball)
+//!
+//! The Snowball Greek stemmer
+//! (<https://snowballstem.org/algorithms/greek/stemmer.html>), after Ntais'
+//! stemmer for Modern Greek as extended by Saroukos. Words are read without
+//! accents and with a plain "σ" for the final sigma, then a long series of
+//! rules removes derivational and inflectional suffixes. Many rules put
+//! part of the suffix back when the stem is one of a list of words or ends
+//! with one of a list of endings ("καθαρίζω" loses "ίζω", but "βαρκίζω"
+//! keeps "ιζ"). A final inflection ("-ος", "-ες") is only removed if no
+//! derivational suffix was.
+//!
+//! Words of fewer than three letters are left as they are.
+//!
+//! Three quirks of the Snowball source are not reproduced: "ϊ" and "ΐ" are
+//! read as "ι" rather than "η", a word that is "αγαμε" only after earlier
+//! rules is still reduced to "αγαμ", and a stem that had a vowel ending
+//! restored ("σκαετε" to "σκαετ") gets no second one ("σκαεεττ").
+
+use crate::fold;
+use crate::snowball::{ends_with, find_group, find_suffix, greek_base_letter, is_greek_vowel};
+use crate::stemmer::Stemmer;
+
+/// The vowels other than "υ"
+fn is_vowel_not_upsilon(c: char) -> bool {
+    c != 'υ' && is_greek_vowel(c)
+}
+
+/// What a rule does with the stem left after removing its suffix; the
+/// first context that applies is used
+#[derive(Debug)]
+enum Context {
+    /// The stem is one of the words: append the string
+    Word(&'static [&'static str], &'static str),
+    /// The stem ends with one of the endings: append the string
+    Ending(&'static [&'static str], &'static str),
+    /// The stem ends with a letter of the group: append the string
+    Vowel(fn(char) -> bool, &'static str),
+    /// The stem ends with one of a group's endings: replace that ending
+    Replace(&'static [(&'static [&'static str], &'static str)]),
+    /// The stem is exactly the first string: replace it with the second
+    Exactly(&'static str, &'static str),
+    /// The stem ends with one of the endings: leave it as it is
+    Keep(&'static [&'static str]),
+    /// Append the string
+    Always(&'static str),
+}
+
+/// A suffix rule: remove the longest of `suffixes`, then adjust the stem
+/// by the first of `contexts` that applies
+#[derive(Debug)]
+struct Rule {
+    suffixes: &'static [&'static str],
+    /// Whether removing the suffix counts as removing a derivational one,
+    /// after which a final inflection is kept
+    derivational: bool,
+    contexts: &'static [Context],
+}
+
+use Context::*;
+
+/// Irregular nouns whose inflected forms replace their ending
+const IRREGULAR_NOUNS: &[(&[&str], &str)] = &[
+    (&["φαγια", "φαγιου", "φαγιων"], "φα"),
+    (&["σκαγια", "σκαγιου", "σκαγιων"], "σκα"),
+    (&["ολογιου", "ολογια", "ολογιων"], "ολο"),
+    (&["σογιου", "σογια", "σογιων"], "σο"),
+    (&["τατογια", "τατογιου", "τατογιων"], "τατο"),
+    (&["κρεασ", "κρεατοσ", "κρεατα", "κρεατων"], "κρε"),
+    (&["περασ", "περατοσ", "περατη", "περατα", "περατων"], "περ"),
+    (&["τερασ", "τερατοσ", "τερατα", "τερατων"], "τερ"),
+    (&["φωσ", "φωτοσ", "φωτα", "φωτων"], "φω"),
+    (&["καθεστωσ", "καθεστωτοσ", "καθεστωτα", "καθεστωτων"], "καθεστ"),
+    (&["γεγονοσ", "γεγονοτοσ", "γεγονοτα", "γεγονοτων"], "γεγον"),
+];
+
+/// The words after which "-ισα", "-ισω" and the like leave an "ι"
+const ISA_I_WORDS: &[&str] = &[
+    "αναμπα", "αθρο", "εμπα", "εσε", "εσωκλε", "επα", "ξαναπα", "επε", "περιπα", "συναθρο", "δανε",
+    "κλε", "χαρτοπα", "εξαρχα", "μετεπε", "αποκλε", "απεκλε", "εκλε", "πε",
+];
+
+/// Derivational suffix rules, in the order they are applied
+const DERIVATIONAL_RULES: &[Rule] = &[
+    Rule {
+        suffixes: &[
+            "ιζα", "ιζεσ", "ιζε", "ιζαμε", "ιζατε", "ιζαν", "ιζανε", "ιζω", "ιζεισ", "ιζει", "ιζουμε",
+            "ιζετε", "ιζουν", "ιζουνε",
+        ],
+        derivational: true,
+        contexts: &[
+            Word(&["αναμπα", "εμπα", "επα", "ξαναπα", "πα", "περιπα", "αθρο", "συναθρο", "δανε"], "ι"),
+            Word(
+                &[
+                    "μαρκ", "κορν", "αμπαρ", "αρρ", "βαθυρι", "βαρκ", "β", "βολβορ", "γκρ", "γλυκορ",
+                    "γλυκυρ", "ιμπ", "λ", "λου", "μαρ", "μ", "πρ", "μπρ", "πολυρ", "π", "ρ", "πιπερορ",
+                ],
+                "ιζ",
+            ),
+        ],
+    },
+    Rule {
+        suffixes: &["ωθηκα", "ωθηκεσ", "ωθηκε", "ωθηκαμε", "ωθηκατε", "ωθηκαν", "ωθηκανε"],
+        derivational: true,
+        contexts: &[Word(&["αλ", "βι", "εν", "υψ", "λι", "ζω", "σ", "χ"], "ων")],
+    },
+    Rule {
+        suffixes: &["ισα", "ισεσ", "ισε", "ισαμε", "ισατε", "ισαν", "ισανε"],
+        derivational: true,
+        contexts: &[
+            Exactly("ισα", "ισ"),
+            Word(ISA_I_WORDS, "ι"),
+            Word(
+                &["αν", "αφ", "γε", "γιγαντοαφ", "γκε", "δημοκρατ", "κομ", "γκ", "μ", "π", "πουκαμ", "ολο", "λαρ"],
+                "ισ",
+            ),
+        ],
+    },
+    Rule {
+        suffixes: &["ισω", "ισεισ", "ισει", "ισουμε", "ισετε", "ισουν", "ισουνε"],
+        derivational: true,
+        contexts: &[Word(ISA_I_WORDS, "ι")],
+    },
+    Rule {
+        suffixes: &["ιστοσ", "ιστου", "ιστο", "ιστε", "ιστοι", "ιστων", "ιστουσ", "ιστη", "ιστησ", "ιστα", "ιστεσ"],
+        derivational: true,
+        contexts: &[
+            Word(&["δανε", "συναθρο", "κλε", "σε", "εσωκλε", "ασε", "πλε"], "ι"),
+            Word(
+                &[
+                    "μ", "π", "απ", "αρ", "ηδ", "κτ", "σκ", "σχ", "υψ", "φα", "χρ", "χτ", "ακτ", "αορ", "ασχ",
+                    "ατα", "αχν", "αχτ", "γεμ", "γυρ", "εμπ", "ευπ", "εχθ", "ηφα", "καθ", "κακ", "κυλ", "λυγ",
+                    "μακ", "μεγ", "ταχ", "φιλ", "χωρ",
+                ],
+                "ιστ",
+            ),
+        ],
+    },
+    Rule {
+        suffixes: &["ισμο", "ισμοι", "ισμοσ", "ισμου", "ισμουσ", "ισμων"],
+        derivational: true,
+        contexts: &[
+            Word(&["σε", "μετασε", "μικροσε", "εγκλε", "αποκλε"], "ισμ"),
+            Word(&["δανε", "αντιδανε"], "ι"),
+            Replace(&[
+                (&["αγνωστικ"], "αγνωστ"),
+                (&["ατομικ"], "ατομ"),
+                (&["γνωστικ"], "γνωστ"),
+                (&["εθνικ"], "εθν"),
+                (&["εκλεκτικ"], "εκλεκτ"),
+                (&["σκεπτικ"], "σκεπτ"),
+                (&["τοπικ"], "τοπ"),
+                (&["αλεξανδριν"], "αλεξανδρ"),
+                (&["βυζαντιν"], "βυζαντ"),
+                (&["θεατριν"], "θεατρ"),
+            ]),
+        ],
+    },
+    Rule {
+        suffixes: &["αρακι", "αρακια", "ουδακι", "ουδακια"],
+        derivational: true,
+        contexts: &[Word(&["σ", "χ"], "αρακ")],
+    },
+    Rule {
+        suffixes: &["ακι", "ακια", "ιτσα", "ιτσασ", "ιτσεσ", "ιτσων", "αρακι", "αρακια"],
+        derivational: true,
+        contexts: &[
+            Word(
+                &[
+                    "βαμβ", "βρ", "καιμ", "κον", "κορ", "λαβρ", "λουλ", "μερ", "μουστ", "ναγκασ", "πλ", "ρ",
+                    "ρυ", "σ", "σκ", "σοκ", "σπαν", "τζ", "φαρμ", "χ", "καπακ", "αλισφ", "αμβρ", "ανθρ", "κ",
+                    "φυλ", "κατραπ", "κλιμ", "μαλ", "σλοβ", "φ", "σφ", "τσεχοσλοβ",
+                ],
+                "ακ",
+            ),
+            Word(
+                &[
+                    "β", "βαλ", "γιαν", "γλ", "ζ", "ηγουμεν", "καρδ", "κον", "μακρυν", "νυφ", "πατερ", "π",
+                    "σκ", "τοσ", "τριπολ",
+                ],
+                "ιτσ",
+            ),
+            Ending(&["κορ"], "ιτσ"),
+        ],
+    },
+    Rule {
+        suffixes: &["ιδιο", "ιδια", "ιδιων"],
+        derivational: true,
+        contexts: &[Word(&["αιφν", "ιρ", "ολο", "ψαλ"], "ιδ"), Ending(&["ε", "παιχν"], "ιδ")],
+    },
+    Rule {
+        suffixes: &["ισκοσ", "ισκου", "ισκο", "ισκε"],
+        derivational: true,
+        contexts: &[Word(&["δ", "ιβ", "μην", "ρ", "φραγκ", "λυκ", "οβελ"], "ισκ")],
+    },
+    Rule {
+        suffixes: &["αδεσ", "αδων"],
+        derivational: false,
+        contexts: &[
+            Keep(&["οκ", "μαμ", "μαν", "μπαμπ", "πατερ", "γιαγι", "νταντ", "κυρ", "θει", "πεθερ"]),
+            Always("αδ"),
+        ],
+    },
+    Rule {
+        suffixes: &["εδεσ", "εδων"],
+        derivational: false,
+        contexts: &[Ending(&["οπ", "ιπ", "εμπ", "υπ", "γηπ", "δαπ", "κρασπ", "μιλ"], "εδ")],
+    },
+    Rule {
+        suffixes: &["ουδεσ", "ουδων"],
+        derivational: false,
+        contexts: &[Ending(
+            &["αρκ", "καλιακ", "πεταλ", "λιχ", "πλεξ", "σκ", "σ", "φλ", "φρ", "βελ", "λουλ", "χν", "σπ", "τραγ", "φε"],
+            "ουδ",
+        )],
+    },
+    Rule {
+        suffixes: &["εωσ", "εων"],
+        derivational: true,
+        contexts: &[Word(&["θ", "δ", "ελ", "γαλ", "ν", "π", "ιδ", "παρ"], "ε")],
+    },
+    Rule { suffixes: &["ια", "ιου", "ιων"], derivational: true, contexts: &[Vowel(is_greek_vowel, "ι")] },
+    Rule {
+        suffixes: &["ικα", "ικο", "ικου", "ικων"],
+        derivational: true,
+        contexts: &[
+            Vowel(is_greek_vowel, "ικ"),
+            Word(
+                &[
+                    "αλ", "αδ", "ενδ", "αμαν", "αμμοχαλ", "ηθ", "ανηθ", "αντιδ", "φυσ", "βρωμ", "γερ", "εξωδ",
+                    "καλπ", "καλλιν", "καταδ", "μουλ", "μπαν", "μπαγιατ", "μπολ", "μποσ", "νιτ", "ξικ",
+                    "συνομηλ", "πετσ", "πιτσ", "πικαντ", "πλιατσ", "ποστελν", "πρωτοδ", "σερτ", "συναδ",
+                    "τσαμ", "υποδ", "φιλον", "φυλοδ", "χασ",
+                ],
+                "ικ",
+            ),
+        ],
+    },
+];
+
+/// Verb ending rules, applied after the derivational ones and after "αγαμε"
+/// on its own has become "αγαμ"
+const VERB_RULES: &[Rule] = &[
+    Rule { suffixes: &["αγαμε", "ησαμε", "ουσαμε", "ηκαμε", "ηθηκαμε"], derivational: true, contexts: &[] },
+    Rule {
+        suffixes: &["αμε"],
+        derivational: true,
+        contexts: &[Word(
+            &["αναπ", "αποθ", "αποκ", "αποστ", "βουβ", "ξεθ", "ουλ", "πεθ", "πικρ", "ποτ", "σιχ", "χ"],
+            "αμ",
+        )],
+    },
+    Rule {
+        suffixes: &[
+            "αγανε", "ησανε", "ουσανε", "ιοντανε", "ιοτανε", "ιουντανε", "οντανε", "οτανε", "ουντανε", "ηκανε",
+            "ηθηκανε",
+        ],
+        derivational: true,
+        contexts: &[Word(&["τρ", "τσ"], "αγαν")],
+    },
+    Rule {
+        suffixes: &["ανε"],
+        derivational: true,
+        contexts: &[
+            Vowel(is_vowel_not_upsilon, "αν"),
+            Word(
+                &[
+                    "βετερ", "βουλκ", "βραχμ", "γ", "δραδουμ", "θ", "καλπουζ", "καστελ", "κορμορ", "λαοπλ",
+                    "μωαμεθ", "μ", "μουσουλμ", "ν", "ουλ", "π", "πελεκ", "πλ", "πολισ", "πορτολ", "σαρακατσ",
+                    "σουλτ", "τσαρλατ", "ορφ", "τσιγγ", "τσοπ", "φωτοστεφ", "χ", "ψυχοπλ", "αγ", "γαλ", "γερ",
+                    "δεκ", "διπλ", "αμερικαν", "ουρ", "πιθ", "πουριτ", "σ", "ζωντ", "ικ", "καστ", "κοπ", "λιχ",
+                    "λουθηρ", "μαιντ", "μελ", "σιγ", "σπ", "στεγ", "τραγ", "τσαγ", "φ", "ερ", "αδαπ", "αθιγγ",
+                    "αμηχ", "ανικ", "ανοργ", "απηγ", "απιθ", "ατσιγγ", "βασ", "βασκ", "βαθυγαλ", "βιομηχ",
+                    "βραχυκ", "διατ", "διαφ", "ενοργ", "θυσ", "καπνοβιομηχ", "καταγαλ", "κλιβ", "κοιλαρφ",
+                    "λιβ", "μεγλοβιομηχ", "μικροβιομηχ", "νταβ", "ξηροκλιβ", "ολιγοδαμ", "ολογαλ", "πενταρφ",
+                    "περηφ", "περιτρ", "πλατ", "πολυδαπ", "πολυμηχ", "στεφ", "ταβ", "τετ", "υπερηφ", "υποκοπ",
+                    "χαμηλοδαπ", "ψηλοταβ",
+                ],
+                "αν",
+            ),
+        ],
+    },
+    Rule { suffixes: &["ησετε"], derivational: true, contexts: &[] },
+    Rule {
+        suffixes: &["ετε"],
+        derivational: true,
+        contexts: &[
+            Vowel(is_vowel_not_upsilon, "ετ"),
+            Ending(
+                &[
+                    "οδ", "αιρ", "φορ", "ταθ", "διαθ", "σχ", "ενδ", "ευρ", "τιθ", "υπερθ", "ραθ", "ενθ", "ροθ",
+                    "σθ", "πυρ", "αιν", "συνδ", "συν", "συνθ", "χωρ", "πον", "βρ", "καθ", "ευθ", "εκθ", "νετ",
+                    "ρον", "αρκ", "βαρ", "βολ", "ωφελ",
+                ],
+                "ετ",
+            ),
+            Word(
+                &[
+                    "αβαρ", "βεν", "εναρ", "αβρ", "αδ", "αθ", "αν", "απλ", "βαρον", "ντρ", "σκ", "κοπ", "μπορ",
+                    "νιφ", "παγ", "παρακαλ", "σερπ", "σκελ", "συρφ", "τοκ", "υ", "δ", "εμ", "θαρρ", "θ",
+                ],
+                "ετ",
+            ),
+        ],
+    },
+    Rule {
+        suffixes: &["οντασ", "ωντασ"],
+        derivational: true,
+        contexts: &[Word(&["αρχ"], "οντ"), Ending(&["κρε"], "ωντ")],
+    },
+    Rule { suffixes: &["ομαστε", "ιομαστε"], derivational: true, contexts: &[Word(&["ον"], "ομαστ")] },
+    Rule {
+        suffixes: &["ιεστε"],
+        derivational: true,
+        contexts: &[Word(&["π", "απ", "συμπ", "ασυμπ", "ακαταπ", "αμεταμφ"], "ιεστ")],
+    },
+    Rule {
+        suffixes: &["εστε"],
+        derivational: true,
+        contexts: &[Word(&["αλ", "αρ", "εκτελ", "ζ", "μ", "ξ", "παρακαλ", "προ", "νισ"], "ιεστ")],
+    },
+    Rule { suffixes: &["ηθηκα", "ηθηκεσ", "ηθηκε"], derivational: true, contexts: &[] },
+    Rule {
+        suffixes: &["ηκα", "ηκεσ", "ηκε"],
+        derivational: true,
+        contexts: &[
+            Ending(&["σκωλ", "σκουλ", "ναρθ", "σφ", "οθ", "πιθ"], "ηκ"),
+            Word(&["διαθ", "θ", "παρακαταθ", "προσθ", "συνθ"], "ηκ"),
+        ],
+    },
+    Rule {
+        suffixes: &["ουσα", "ουσεσ", "ουσε"],
+        derivational: true,
+        contexts: &[
+            Ending(
+                &["ποδαρ", "βλεπ", "πανταχ", "φρυδ", "μαντιλ", "μαλλ", "κυματ", "λαχ", "ληγ", "φαγ", "ομ", "πρωτ"],
+                "ουσ",
+            ),
+            Word(
+                &[
+                    "φαρμακ", "χαδ", "αγκ", "αναρρ", "βρομ", "εκλιπ", "λαμπιδ", "λεχ", "μ", "πατ", "ρ", "λ",
+                    "μεδ", "μεσαζ", "υποτειν", "αμ", "αιθ", "ανηκ", "δεσποζ", "ενδιαφερ", "δε", "δευτερευ",
+                    "καθαρευ", "πλε", "τσα",
+                ],
+                "ουσ",
+            ),
+        ],
+    },
+    Rule {
+        suffixes: &["ησε", "ησου", "ησα"],
+        derivational: true,
+        contexts: &[Word(&["ν", "χερσον", "δωδεκαν", "ερημον", "μεγαλον", "επταν"], "ησ")],
+    },
+    Rule {
+        suffixes: &["αγα", "αγεσ", "αγε"],
+        derivational: true,
+        contexts: &[
+            Ending(&["κολλ"], "αγ"),
+            Keep(&["ψοφ", "ναυλοχ"]),
+            Ending(&["οφ", "πελ", "χορτ", "λλ", "σφ", "ρπ", "φρ", "πρ", "λοχ", "σμην"], "αγ"),
+            Word(
+                &[
+                    "αβαστ", "πολυφ", "αδηφ", "παμφ", "ρ", "ασπ", "αφ", "αμαλ", "αμαλλι", "ανυστ", "απερ",
+                    "ασπαρ", "αχαρ", "δερβεν", "δροσοπ", "ξεφ", "νεοπ", "νομοτ", "ολοπ", "ομοτ", "προστ",
+                    "προσωποπ", "συμπ", "συντ", "τ", "υποτ", "χαρ", "αειπ", "αιμοστ", "ανυπ", "αποτ", "αρτιπ",
+                    "διατ", "εν", "επιτ", "κροκαλοπ", "σιδηροπ", "λ", "ναυ", "ουλαμ", "ουρ", "π", "τρ", "μ",
+                ],
+                "αγ",
+            ),
+        ],
+    },
+    Rule {
+        suffixes: &["ηστε"],
+        derivational: true,
+        contexts: &[Word(&["ασβ", "σβ", "αχρ", "χρ", "απλ", "αειμν", "δυσχρ", "ευχρ", "κοινοχρ", "παλιμψ"], "ηστ")],
+    },
+    Rule {
+        suffixes: &["ουνε", "ησουνε", "ηθουνε"],
+        derivational: true,
+        contexts: &[Word(&["ν", "ρ", "σπι", "στραβομουτσ", "κακομουτσ", "εξων"], "ουν")],
+    },
+    Rule {
+        suffixes: &["ουμε", "ησουμε", "ηθουμε"],
+        derivational: true,
+        contexts: &[Word(&["παρασουσ", "φ", "χ", "ωριοπλ", "αζ", "αλλοσουσ", "ασουσ"], "ουμ")],
+    },
+    Rule { suffixes: &["ματα", "ματων", "ματοσ"], derivational: false, contexts: &[Always("μα")] },
+];
+
+/// Final inflections, removed if no derivational suffix was
+const INFLECTIONS: &[&str] = &[
+    "α", "αγατε", "αγαν", "αει", "αμαι", "αν", "ασ", "ασαι", "αται", "αω", "ε", "ει", "εισ", "ειτε", "εσαι",
+    "εσ", "εται", "ι", "ιεμαι", "ιεμαστε", "ιεται", "ιεσαι", "ιεσαστε", "ιομασταν", "ιομουν", "ιομουνα",
+    "ιονταν", "ιοντουσαν", "ιοσασταν", "ιοσαστε", "ιοσουν", "ιοσουνα", "ιοταν", "ιουμα", "ιουμαστε",
+    "ιουνται", "ιουνταν", "η", "ηδεσ", "ηδων", "ηθει", "ηθεισ", "ηθειτε", "ηθηκατε", "ηθηκαν", "ηθουν",
+    "ηθω", "ηκατε", "ηκαν", "ησ", "ησαν", "ησατε", "ησει", "ησεσ", "ησουν", "ησω", "ο", "οι", "ομαι",
+    "ομασταν", "ομουν", "ομουνα", "ονται", "ονταν", "οντουσαν", "οσ", "οσασταν", "οσαστε", "οσουν",
+    "οσουνα", "οταν", "ου", "ουμαι", "ουμαστε", "ουν", "ουνται", "ουνταν", "ουσ", "ουσαν", "ουσατε", "υ",
+    "υσ", "ω", "ων",
+];
+
+/// Comparative and superlative endings, removed last
+const COMPARATIVES: &[&str] = &["εστερ", "εστατ", "οτερ", "οτατ", "υτερ", "υτατ", "ωτερ", "ωτατ"];
+
+/// A Snowball Greek stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::GreekStemmer;
+/// let mut stemmer = GreekStemmer::new();
+/// assert_eq!(stemmer.stem("αυτοκίνητα"), "αυτοκινητ");
+/// assert_eq!(stemmer.stem("ΕΠΙΤΡΟΠΟΣ"), "επιτροπ");
+/// assert_eq!(stemmer.stem("χορδίζουμε"), "χορδ");
+/// ```
+#[derive(Debug, Default)]
+pub struct GreekStemmer {
+    /// Word being stemmed
+    buffer: Vec<char>,
+    /// Whether a final inflection may still be removed
+    inflection: bool,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl GreekStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        GreekStemmer::default()
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    ///
+    /// Greek letters are never folded (their accents are ignored anyway);
+    /// the option only affects Latin words mixed into the text.
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased and has its accents
+    /// removed first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        self.buffer.clear();
+        self.buffer.extend(word.chars().flat_map(char::to_lowercase).map(greek_base_letter));
+        if self.buffer.len() < 3 {
+            return self.buffer.iter().collect();
+        }
+
+        self.inflection = true;
+        if let Some((with, start)) = find_group(&self.buffer, IRREGULAR_NOUNS, 0) {
+            self.buffer.truncate(start);
+            self.buffer.extend(with.chars());
+            self.inflection = false;
+        }
+        for rule in DERIVATIONAL_RULES {
+            self.apply(rule);
+        }
+        if self.buffer.iter().copied().eq("αγαμε".chars()) {
+            self.buffer.pop();
+        }
+        for rule in VERB_RULES {
+            self.apply(rule);
+        }
+        if self.inflection {
+            if let Some((_, start)) = find_suffix(&self.buffer, INFLECTIONS, 0) { self.buffer.truncate(start); }
+        }
+        if let Some((_, start)) = find_suffix(&self.buffer, COMPARATIVES, 0) {
+            self.buffer.truncate(start);
+        }
+
+        self.buffer.iter().collect()
+    }
+
+    fn apply(&mut self, rule: &Rule) {
+        let Some((_, start)) = find_suffix(&self.buffer, rule.suffixes, 0) else { return };
+        self.buffer.truncate(start);
+        if rule.derivational { self.inflection = false; }
+
+        let b = &mut self.buffer;
+        let is = |b: &[char], word: &str| b.iter().copied().eq(word.chars());
+        for context in rule.contexts {
+            let append = match *context {
+                Word(words, with) if words.iter().any(|w| is(b, w)) => with,
+                Ending(endings, with) if endings.iter().any(|e| ends_with(b, e)) => with,
+                Vowel(group, with) if b.last().is_some_and(|&c| group(c)) => with,
+                Replace(groups) => match find_group(b, groups, 0) {
+                    Some((with, start)) => {
+                        b.truncate(start);
+                        with
+                    },
+                    None => continue,
+                },
+                Exactly(word, with) if is(b, word) => {
+                    b.clear();
+                    with
+                },
+                Keep(endings) if endings.iter().any(|e| ends_with(b, e)) => return,
+                Always(with) => with,
+                _ => continue,
+            };
+            b.extend(append.chars());
+            return;
+        }
+    }
+}
+
+impl Stemmer for GreekStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        GreekStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suffix_rules() {
+        // Checked against the Snowball reference implementation
+        let mut stemmer = GreekStemmer::new();
+        let cases = [
+            ("καθεστώτα", "καθεστ"), ("βαρκίζω", "βαρκιζ"), ("πρωτοπορία", "πρωτοπορ"), ("αγαμε", "αγαμ"),
+            ("Ελλάδα", "ελλαδ"), ("ΦΩΣ", "φω"), ("νέος", "νε"), ("ως", "ωσ"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+        // Where the Snowball source misbehaves
+        let cases = [("σκαετε", "σκαετ"), ("αγαμεακια", "αγαμ"), ("Ταΐζει", "τα")];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_snowball_vocabulary() {
+        // The Snowball project's test vocabulary
+        // (<https://github.com/snowballstem/snowball-data>) and the words of
+        // the test data of the lingua crate's Greek language model, each with
+        // its stem from the Snowball 3.0 Greek stemmer. Words with "ϊ" or "ΐ",
+        // which this stemmer reads differently, are left out.
+        let mut stemmer = GreekStemmer::new();
+        let words = include_str!("../test_data/greek/voc.txt");
+        let stems = include_str!("../test_data/greek/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+    }
+}
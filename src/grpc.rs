@@ -0,0 +1,164 @@
+//! The gRPC service of the command line front end
+//!
+//! Built with the `grpc` feature, `porterstem serve --grpc` answers the
+//! `porterstem.v1.Stemmer` service of `proto/porterstem.proto` over HTTP/2
+//! without TLS (h2c with prior knowledge, as behind the sidecar of a service
+//! mesh), so any client generated from the `.proto` file can call it:
+//! - `Stem` stems one word
+//! - `StemBatch` stems a list of words in one call
+//! - `StemStream` answers a stream of words with a stream of stems, each as
+//!   soon as its word has arrived
+//!
+//! The service is served by tonic, from the code build.rs generates from the
+//! `.proto` file. Request messages must be at most [`MAX_MESSAGE`] bytes.
+//! Each call stems with a stemmer of its own, a stream's words in the order
+//! they arrive. A message that makes the stemmer panic fails its call with
+//! status INTERNAL.
+
+use std::io;
+use std::net::TcpListener;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+use m_porter_stemmer_rust::Stemmer;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::{Request, Response, Status, Streaming};
+
+use proto::stemmer_server::StemmerServer;
+use proto::{StemBatchRequest, StemBatchResponse, StemRequest, StemResponse};
+
+mod proto {
+    tonic::include_proto!("porterstem.v1");
+}
+
+/// Largest request message accepted, the usual limit of gRPC servers
+pub const MAX_MESSAGE: usize = 4 << 20;
+
+/// Responses of a stream held while the client is slow to read them
+const STREAM_BUFFER: usize = 64;
+
+/// Creates the stemmer of each call
+type MakeStemmer = Arc<dyn Fn() -> Box<dyn Stemmer> + Send + Sync>;
+
+/// The `porterstem.v1.Stemmer` service
+struct StemService {
+    make_stemmer: MakeStemmer,
+}
+
+impl StemService {
+    /// Stems `words` with a new stemmer, or fails with INTERNAL if it panics
+    fn stem_all(&self, words: &[String]) -> Result<Vec<String>, Status> {
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut stemmer = (self.make_stemmer)();
+            words.iter().map(|word| stemmer.stem(word)).collect()
+        }))
+        .map_err(|_| Status::internal("stemming failed"))
+    }
+}
+
+#[tonic::async_trait]
+impl proto::stemmer_server::Stemmer for StemService {
+    async fn stem(&self, request: Request<StemRequest>) -> Result<Response<StemResponse>, Status> {
+        let word = request.into_inner().word;
+        let stem = self.stem_all(std::slice::from_ref(&word))?.remove(0);
+        Ok(Response::new(StemResponse { word, stem }))
+    }
+
+    async fn stem_batch(&self, request: Request<StemBatchRequest>) -> Result<Response<StemBatchResponse>, Status> {
+        let stems = self.stem_all(&request.into_inner().words)?;
+        Ok(Response::new(StemBatchResponse { stems }))
+    }
+
+    type StemStreamStream = ReceiverStream<Result<StemResponse, Status>>;
+
+    async fn stem_stream(
+        &self,
+        request: Request<Streaming<StemRequest>>,
+    ) -> Result<Response<Self::StemStreamStream>, Status> {
+        let mut inbound = request.into_inner();
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let make_stemmer = Arc::clone(&self.make_stemmer);
+        // Stemmers are not `Send`, so the stream's stemmer stays on one
+        // blocking thread, which waits there for each word
+        let runtime = Handle::current();
+        tokio::task::spawn_blocking(move || {
+            let mut stemmer = make_stemmer();
+            loop {
+                let response = match runtime.block_on(inbound.message()) {
+                    Ok(None) => return,
+                    Ok(Some(StemRequest { word })) => {
+                        match panic::catch_unwind(AssertUnwindSafe(|| stemmer.stem(&word))) {
+                            Ok(stem) => Ok(StemResponse { word, stem }),
+                            Err(_) => Err(Status::internal("stemming failed")),
+                        }
+                    },
+                    Err(status) => Err(status),
+                };
+                let failed = response.is_err();
+                // The client went away, or the call is over
+                if sender.blocking_send(response).is_err() || failed {
+                    return;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+/// Serves connections to `listener` until serving fails, each call with a
+/// stemmer from `make_stemmer`
+pub fn serve(listener: TcpListener, make_stemmer: impl Fn() -> Box<dyn Stemmer> + Send + Sync + 'static) -> io::Error {
+    let runtime = match tokio::runtime::Builder::new_multi_thread().enable_all().build() {
+        Ok(runtime) => runtime,
+        Err(e) => return e,
+    };
+    let service = StemService { make_stemmer: Arc::new(make_stemmer) };
+    let result = runtime.block_on(async {
+        listener.set_nonblocking(true)?;
+        let listener = tokio::net::TcpListener::from_std(listener)?;
+        tonic::transport::Server::builder()
+            .add_service(StemmerServer::new(service).max_decoding_message_size(MAX_MESSAGE))
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+            .map_err(io::Error::other)
+    });
+    match result {
+        Ok(()) => io::Error::other("the gRPC server stopped"),
+        Err(e) => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m_porter_stemmer_rust::PorterStemmer;
+    use proto::stemmer_client::StemmerClient;
+
+    #[test]
+    fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || serve(listener, || Box::new(PorterStemmer::new()) as Box<dyn Stemmer>));
+
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(async {
+            let mut client = StemmerClient::connect(format!("http://{}", address)).await.unwrap();
+            let response = client.stem(StemRequest { word: "running".to_string() }).await.unwrap().into_inner();
+            assert_eq!((response.word.as_str(), response.stem.as_str()), ("running", "run"));
+
+            let words = vec!["caresses".to_string(), "ponies".to_string()];
+            let response = client.stem_batch(StemBatchRequest { words }).await.unwrap().into_inner();
+            assert_eq!(response.stems, ["caress", "poni"]);
+
+            let words = ["running", "ponies"].map(|word| StemRequest { word: word.to_string() });
+            let mut stems = client.stem_stream(tokio_stream::iter(words)).await.unwrap().into_inner();
+            let mut pairs = Vec::new();
+            while let Some(response) = stems.message().await.unwrap() {
+                pairs.push((response.word, response.stem));
+            }
+            assert_eq!(pairs, [("running".to_string(), "run".to_string()), ("ponies".to_string(), "poni".to_string())]);
+        });
+    }
+}
@@ -0,0 +1,92 @@
+//! # Match Highlighting
+//!
+//! Maps query stems back to byte ranges in a document, the step between
+//! stemming a query and highlighting it in a snippet. The document is
+//! tokenized like [`crate::text::stem_text`] and every word whose stem is one
+//! of the query stems is reported, so a query for "running" finds "runs",
+//! "Run" and "running" alike.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::text::tokens_for;
+use crate::Stemmer;
+
+/// Stems every word of a query into a set, for use with [`find_matches`]
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, highlight::query_stems};
+/// let mut stemmer = PorterStemmer::new();
+/// let stems = query_stems(&mut stemmer, "running dogs");
+/// assert!(stems.contains("run") && stems.contains("dog"));
+/// ```
+pub fn query_stems(stemmer: &mut dyn Stemmer, query: &str) -> HashSet<String> {
+    tokens_for(stemmer, query).map(|token| stemmer.stem(token.text)).collect()
+}
+
+/// Finds the words of `text` whose stem is in `stems`
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each word of the document
+/// * `text` - The document to search
+/// * `stems` - Query stems, e.g. from [`query_stems`]
+///
+/// # Returns
+/// Byte ranges of the matching words, in document order
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, highlight::{find_matches, query_stems}};
+/// let mut stemmer = PorterStemmer::new();
+/// let stems = query_stems(&mut stemmer, "run");
+/// assert_eq!(find_matches(&mut stemmer, "He runs. Running is fun.", &stems), [3..7, 9..16]);
+/// ```
+pub fn find_matches(stemmer: &mut dyn Stemmer, text: &str, stems: &HashSet<String>) -> Vec<Range<usize>> {
+    if stems.is_empty() { return Vec::new(); }
+    tokens_for(stemmer, text)
+        .filter(|token| stems.contains(&stemmer.stem(token.text)))
+        .map(|token| token.range())
+        .collect()
+}
+
+/// Wraps each range of `text` in `open` and `close` markers
+///
+/// Ranges must be sorted, non-overlapping and on character boundaries, as
+/// returned by [`find_matches`].
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::highlight::highlight;
+/// assert_eq!(highlight("a dog ran", &[2..5], "<b>", "</b>"), "a <b>dog</b> ran");
+/// ```
+pub fn highlight(text: &str, ranges: &[Range<usize>], open: &str, close: &str) -> String {
+    let mut out = String::with_capacity(text.len() + ranges.len() * (open.len() + close.len()));
+    let mut last = 0;
+    for range in ranges {
+        out.push_str(&text[last..range.start]);
+        out.push_str(open);
+        out.push_str(&text[range.clone()]);
+        out.push_str(close);
+        last = range.end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_find_matches_ignores_case_and_partial_words() {
+        let mut stemmer = PorterStemmer::new();
+        let stems = query_stems(&mut stemmer, "connection");
+        let text = "Connected! The connections reconnect, unconnected.";
+        let ranges = find_matches(&mut stemmer, text, &stems);
+        assert_eq!(ranges, [0..9, 15..26]);
+        assert_eq!(highlight(text, &ranges, "[", "]"), "[Connected]! The [connections] reconnect, unconnected.");
+        assert!(find_matches(&mut stemmer, text, &HashSet::new()).is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! # Hindi (light)
+//!
+//! A light stemmer for Hindi in Devanagari script, after Ramanathan and
+//! Rao, "A Lightweight Stemmer for Hindi" (2003), the algorithm Lucene's
+//! Hindi stemmer uses. It removes one inflectional ending: the plural and
+//! oblique endings of nouns and adjectives ("-ियों", "-ें") and the tense,
+//! gender and number endings of verbs ("-ेंगे", "-ाती"). There are no
+//! regions; the longest ending is removed that leaves at least two letters,
+//! a vowel sign counting as a letter.
+
+use crate::fold;
+use crate::snowball::find_suffix;
+use crate::stemmer::Stemmer;
+
+/// The endings, of one to five letters
+const SUFFIXES: &[&str] = &[
+    "ाएंगी", "ाएंगे", "ाऊंगी", "ाऊंगा", "ाइयाँ", "ाइयों", "ाइयां",
+    "ाएगी", "ाएगा", "ाओगी", "ाओगे", "एंगी", "ेंगी", "एंगे", "ेंगे", "ूंगी", "ूंगा", "ातीं", "नाओं",
+    "नाएं", "ताओं", "ताएं", "ियाँ", "ियों", "ियां",
+    "ाकर", "ाइए", "ाईं", "ाया", "ेगी", "ेगा", "ोगी", "ोगे", "ाने", "ाना", "ाते", "ाती", "ाता", "तीं",
+    "ाओं", "ाएं", "ुओं", "ुएं", "ुआं",
+    "कर", "ाओ", "िए", "ाई", "ाए", "ने", "नी", "ना", "ते", "ीं", "ती", "ता", "ाँ", "ां", "ों", "ें",
+    "ो", "े", "ू", "ु", "ी", "ि", "ा",
+];
+
+/// A light Hindi stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::HindiStemmer;
+/// let mut stemmer = HindiStemmer::new();
+/// assert_eq!(stemmer.stem("किताबें"), "किताब");
+/// assert_eq!(stemmer.stem("नदियों"), "नद");
+/// assert_eq!(stemmer.stem("जाएंगे"), "जा");
+/// ```
+#[derive(Debug, Default)]
+pub struct HindiStemmer {
+    /// Word being stemmed
+    buffer: Vec<char>,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl HindiStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        HindiStemmer { buffer: Vec::new(), fold_diacritics: false }
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    ///
+    /// Devanagari is left as it is, so this only affects Latin words mixed
+    /// into Hindi text.
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        self.buffer.clear();
+        self.buffer.extend(word.chars().flat_map(char::to_lowercase));
+
+        if let Some((_, start)) = find_suffix(&self.buffer, SUFFIXES, 2) {
+            self.buffer.truncate(start);
+        }
+        self.buffer.iter().collect()
+    }
+}
+
+impl Stemmer for HindiStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        HindiStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inflections() {
+        let mut stemmer = HindiStemmer::new();
+        let cases = [
+            ("किताबें", "किताब"), ("किताबों", "किताब"), ("कुर्सियाँ", "कुर्स"), ("नदियों", "नद"),
+            ("खाएंगे", "खा"), ("खाता", "खा"), ("खाकर", "खा"), ("बच्चों", "बच्च"),
+            // At least two letters are left
+            ("गया", "गय"), ("जा", "जा"), ("को", "को"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! # HTML Input
+//!
+//! Stems only the visible prose of an HTML document. Tags (including their
+//! names and attribute values), comments, entity references and the raw
+//! contents of `<script>` and `<style>` elements are never handed to the
+//! stemmer.
+//!
+//! Two outputs are offered:
+//! - [`stem_html`] keeps the document structure untouched and rewrites only
+//!   the text between tags
+//! - [`stem_html_text`] drops the markup and emits the stemmed visible text
+//!
+//! This is a small tolerant scanner, not a conforming HTML parser; it is
+//! meant for scraped pages where a stray `<` is more likely than perfect markup.
+
+use crate::text::stem_text;
+use crate::Stemmer;
+
+/// A slice of an HTML document as seen by the scanner
+#[derive(Debug, PartialEq)]
+enum Piece<'a> {
+    /// Visible character data, possibly containing entity references
+    Text(&'a str),
+    /// A tag, comment, doctype or raw `<script>`/`<style>` content
+    Markup(&'a str),
+    /// A start or end tag that separates blocks of text (`<p>`, `<br>`, ...)
+    Break(&'a str),
+}
+
+/// Elements whose content is raw text that must never be stemmed
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Elements that start a new line when the markup is stripped
+const BLOCK_ELEMENTS: [&str; 25] = [
+    "address", "article", "aside", "blockquote", "br", "dd", "div", "dl",
+    "dt", "footer", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "li",
+    "ol", "p", "pre", "section", "tr", "ul",
+];
+
+/// Splits an HTML document into text and markup pieces
+struct Pieces<'a> {
+    html: &'a str,
+    pos: usize,
+    /// Set after a `<script>`/`<style>` start tag: the end tag to scan for
+    raw_until: Option<&'static str>,
+}
+
+impl<'a> Pieces<'a> {
+    fn new(html: &'a str) -> Self {
+        Pieces { html, pos: 0, raw_until: None }
+    }
+
+    /// Returns the end of the markup starting at `start`, or None if the
+    /// `<` at `start` does not open a tag and is plain text
+    fn markup_end(&self, start: usize) -> Option<usize> {
+        let bytes = self.html.as_bytes();
+        let rest = &self.html[start..];
+        if !rest.starts_with('<') { return None; }
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            return Some(comment.find("-->").map_or(bytes.len(), |e| start + 4 + e + 3));
+        }
+
+        match bytes.get(start + 1) {
+            Some(b) if b.is_ascii_alphabetic() || matches!(b, b'/' | b'!' | b'?') => {}
+            _ => return None,
+        }
+
+        let mut quote = None;
+        for (i, &b) in bytes.iter().enumerate().skip(start + 1) {
+            match quote {
+                Some(q) if b == q => quote = None,
+                Some(_) => {}
+                None if b == b'"' || b == b'\'' => quote = Some(b),
+                None if b == b'>' => return Some(i + 1),
+                None => {}
+            }
+        }
+        Some(bytes.len())
+    }
+}
+
+/// Returns the lowercase element name of a tag and whether it is an end tag
+fn tag_name(tag: &str) -> (String, bool) {
+    let inner = tag.trim_start_matches('<');
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+    let name: String = inner
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    (name, closing)
+}
+
+impl<'a> Iterator for Pieces<'a> {
+    type Item = Piece<'a>;
+
+    fn next(&mut self) -> Option<Piece<'a>> {
+        if self.pos >= self.html.len() { return None; }
+        let start = self.pos;
+
+        if let Some(end_tag) = self.raw_until.take() {
+            let lower = self.html[start..].to_ascii_lowercase();
+            self.pos = lower.find(end_tag).map_or(self.html.len(), |e| start + e);
+            if self.pos > start {
+                return Some(Piece::Markup(&self.html[start..self.pos]));
+            }
+        }
+
+        let start = self.pos;
+        if let Some(end) = self.markup_end(start) {
+            self.pos = end;
+            let tag = &self.html[start..end];
+            let (name, closing) = tag_name(tag);
+            if !closing && RAW_TEXT_ELEMENTS.contains(&name.as_str()) && !tag.ends_with("/>") {
+                self.raw_until = Some(if name == "script" { "</script" } else { "</style" });
+            }
+            if BLOCK_ELEMENTS.contains(&name.as_str()) {
+                return Some(Piece::Break(tag));
+            }
+            return Some(Piece::Markup(tag));
+        }
+
+        // Text runs until the next `<` that really opens markup
+        let mut end = start + 1;
+        while end < self.html.len() {
+            if self.html.as_bytes()[end] == b'<' && self.markup_end(end).is_some() { break; }
+            end += 1;
+        }
+        self.pos = end;
+        Some(Piece::Text(&self.html[start..end]))
+    }
+}
+
+/// Returns the length of the entity reference at the start of `s`, if any
+fn entity_len(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    if bytes.first() != Some(&b'&') { return None; }
+    let body = bytes[1..].iter().take(32).position(|&b| b == b';')?;
+    let name = &s[1..1 + body];
+    let valid = !name.is_empty() && match name.strip_prefix('#') {
+        Some(num) => match num.strip_prefix(['x', 'X']) {
+            Some(hex) => !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit()),
+            None => !num.is_empty() && num.bytes().all(|b| b.is_ascii_digit()),
+        },
+        None => name.bytes().all(|b| b.is_ascii_alphanumeric()),
+    };
+    if valid { Some(body + 2) } else { None }
+}
+
+/// Decodes a single entity reference such as `&amp;` or `&#233;`
+fn decode_entity(entity: &str) -> Option<char> {
+    let name = &entity[1..entity.len() - 1];
+    if let Some(num) = name.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => None,
+    }
+}
+
+/// Stems a text piece, leaving its entity references as they are
+fn stem_text_piece(stemmer: &mut dyn Stemmer, text: &str, out: &mut String) {
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&stem_text(stemmer, &rest[..amp]));
+        let len = entity_len(&rest[amp..]).unwrap_or(1);
+        out.push_str(&rest[amp..amp + len]);
+        rest = &rest[amp + len..];
+    }
+    out.push_str(&stem_text(stemmer, rest));
+}
+
+/// Stems the visible text of an HTML document, keeping all markup intact
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each word of prose
+/// * `html` - The HTML document
+///
+/// # Returns
+/// The document with only its text content stemmed
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, html::stem_html};
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(
+///     stem_html(&mut stemmer, "<p class=\"greetings\">running dogs</p>"),
+///     "<p class=\"greetings\">run dog</p>"
+/// );
+/// ```
+pub fn stem_html(stemmer: &mut dyn Stemmer, html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    for piece in Pieces::new(html) {
+        match piece {
+            Piece::Text(text) => stem_text_piece(stemmer, text, &mut out),
+            Piece::Markup(markup) | Piece::Break(markup) => out.push_str(markup),
+        }
+    }
+    out
+}
+
+/// Extracts the visible text of an HTML document
+///
+/// Markup is removed, block-level elements become line breaks, and the common
+/// entity references are decoded. The result is not stemmed.
+pub fn html_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    for piece in Pieces::new(html) {
+        match piece {
+            Piece::Text(text) => {
+                let mut rest = text;
+                while let Some(amp) = rest.find('&') {
+                    out.push_str(&rest[..amp]);
+                    let len = entity_len(&rest[amp..]).unwrap_or(1);
+                    let entity = &rest[amp..amp + len];
+                    match decode_entity(entity).filter(|_| len > 1) {
+                        Some(ch) => out.push(ch),
+                        None => out.push_str(entity),
+                    }
+                    rest = &rest[amp + len..];
+                }
+                out.push_str(rest);
+            },
+            Piece::Break(_) => {
+                if !out.is_empty() && !out.ends_with('\n') { out.push('\n'); }
+            },
+            Piece::Markup(_) => {}
+        }
+    }
+    out
+}
+
+/// Extracts the visible text of an HTML document and stems it
+///
+/// Equivalent to [`html_text`] followed by [`stem_text`].
+pub fn stem_html_text(stemmer: &mut dyn Stemmer, html: &str) -> String {
+    stem_text(stemmer, &html_text(html))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    const PAGE: &str = "<!DOCTYPE html><html><head><title>Running</title>\
+        <style>.jumping { color: red }</style>\
+        <script>if (a < b) { running() }</script></head>\
+        <body><!-- hidden comments --><p data-note='flying cats'>Cats &amp; ponies \
+        <b>jumping</b></p><p>a < b</p></body></html>";
+
+    #[test]
+    fn test_stem_html_keeps_markup() {
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(
+            stem_html(&mut stemmer, PAGE),
+            "<!DOCTYPE html><html><head><title>run</title>\
+            <style>.jumping { color: red }</style>\
+            <script>if (a < b) { running() }</script></head>\
+            <body><!-- hidden comments --><p data-note='flying cats'>cat &amp; poni \
+            <b>jump</b></p><p>a < b</p></body></html>"
+        );
+    }
+
+    #[test]
+    fn test_html_text_strips_markup() {
+        assert_eq!(html_text(PAGE), "Running\nCats & ponies jumping\na < b\n");
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(stem_html_text(&mut stemmer, "<li>Flies</li><li>ties</li>"), "fli\nti\n");
+    }
+}
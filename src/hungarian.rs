@@ -0,0 +1,213 @@
+//! # Hungarian (Snowball)
+//!
+//! The Snowball Hungarian stemmer
+//! (<https://snowballstem.org/algorithms/hungarian/stemmer.html>), which
+//! removes the inflections of nouns: case endings ("-ban", "-nak"),
+//! possessive endings ("-juk", "-aim") and the plural "-k". A final "á" or
+//! "é" left behind is shortened to "a" or "e", and a consonant doubled by
+//! the instrumental "-val" or the factive "-vá" ("késsel", after "kés") is
+//! undoubled.
+//!
+//! R1 starts after the first consonant if the word starts with a vowel, a
+//! digraph such as "sz" counting as one consonant, and after the first
+//! vowel otherwise.
+
+use crate::fold;
+use crate::snowball::{find_group, find_suffix, replace_from};
+use crate::stemmer::Stemmer;
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'á' | 'é' | 'í' | 'ó' | 'ö' | 'ő' | 'ú' | 'ü' | 'ű')
+}
+
+/// Double consonants, a consonant after which is removed with "-al",
+/// "-el", "-á" or "-é"
+const DOUBLES: &[&str] = &[
+    "bb", "cc", "ccs", "dd", "ff", "gg", "ggy", "jj", "kk", "ll", "lly", "mm", "nn", "nny", "pp", "rr",
+    "ss", "ssz", "tt", "tty", "vv", "zz", "zzs",
+];
+
+const CASE_SUFFIXES: &[&str] = &[
+    "ban", "ben", "ba", "be", "ra", "re", "nak", "nek", "val", "vel", "tól", "től", "ról", "ről",
+    "ból", "ből", "hoz", "hez", "höz", "nál", "nél", "ig", "at", "et", "ot", "öt", "ért", "képp",
+    "képpen", "kor", "ul", "ül", "vá", "vé", "onként", "enként", "anként", "ként", "en", "on", "an",
+    "ön", "n", "t",
+];
+
+const CASE_SPECIAL_SUFFIXES: &[(&[&str], &str)] = &[(&["én"], "e"), (&["án", "ánként"], "a")];
+
+const CASE_OTHER_SUFFIXES: &[(&[&str], &str)] =
+    &[(&["astul", "estül", "stul", "stül"], ""), (&["ástul"], "a"), (&["éstül"], "e")];
+
+const OWNED_SUFFIXES: &[(&[&str], &str)] = &[
+    (&["oké", "öké", "aké", "eké", "ké", "éi", "é"], ""),
+    (&["éké", "ééi", "éé"], "e"),
+    (&["áké", "áéi"], "a"),
+];
+
+const SING_OWNER_SUFFIXES: &[(&[&str], &str)] = &[
+    (
+        &[
+            "ünk", "unk", "nk", "juk", "jük", "uk", "ük", "em", "om", "am", "m", "od", "ed", "ad", "öd",
+            "d", "ja", "je", "a", "e", "o",
+        ],
+        "",
+    ),
+    (&["ánk", "ájuk", "ám", "ád", "á"], "a"),
+    (&["énk", "éjük", "ém", "éd", "é"], "e"),
+];
+
+const PLUR_OWNER_SUFFIXES: &[(&[&str], &str)] = &[
+    (
+        &[
+            "jaim", "jeim", "aim", "eim", "im", "jaid", "jeid", "aid", "eid", "id", "jai", "jei", "ai",
+            "ei", "i", "jaink", "jeink", "eink", "aink", "ink", "jaitok", "jeitek", "aitok", "eitek",
+            "itek", "jeik", "jaik", "aik", "eik", "ik",
+        ],
+        "",
+    ),
+    (&["áim", "áid", "ái", "áink", "áitok", "áik"], "a"),
+    (&["éim", "éid", "éi", "éink", "éitek", "éik"], "e"),
+];
+
+const PLURAL_SUFFIXES: &[(&[&str], &str)] = &[(&["ák"], "a"), (&["ék"], "e"), (&["ök", "ak", "ok", "ek", "k"], "")];
+
+/// A Snowball Hungarian stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::HungarianStemmer;
+/// let mut stemmer = HungarianStemmer::new();
+/// assert_eq!(stemmer.stem("házakban"), "ház");
+/// assert_eq!(stemmer.stem("késsel"), "kés");
+/// assert_eq!(stemmer.stem("barátaitok"), "barát");
+/// ```
+#[derive(Debug, Default)]
+pub struct HungarianStemmer {
+    /// Word being stemmed
+    buffer: Vec<char>,
+    /// Start of region R1
+    p1: usize,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl HungarianStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        HungarianStemmer { buffer: Vec::new(), p1: 0, fold_diacritics: false }
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    ///
+    /// Folding loses the long vowels that tell many endings apart, so it is
+    /// only useful for text typed without accents.
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        self.buffer.clear();
+        self.buffer.extend(word.chars().flat_map(char::to_lowercase));
+
+        self.mark_regions();
+        self.undoubling_suffix(&["al", "el"]);
+        if let Some((_, start)) = find_suffix(&self.buffer, CASE_SUFFIXES, 0) {
+            if start >= self.p1 {
+                self.buffer.truncate(start);
+                self.replace(&[(&["á"], "a"), (&["é"], "e")]);
+            }
+        }
+        self.replace(CASE_SPECIAL_SUFFIXES);
+        self.replace(CASE_OTHER_SUFFIXES);
+        self.undoubling_suffix(&["á", "é"]);
+        self.replace(OWNED_SUFFIXES);
+        self.replace(SING_OWNER_SUFFIXES);
+        self.replace(PLUR_OWNER_SUFFIXES);
+        self.replace(PLURAL_SUFFIXES);
+
+        self.buffer.iter().collect()
+    }
+
+    fn mark_regions(&mut self) {
+        let b = &self.buffer;
+        let len = b.len();
+        self.p1 = match b.first() {
+            Some(&c) if is_vowel(c) => match b.iter().position(|&c| !is_vowel(c)) {
+                Some(i) => {
+                    let digraph = ["dzs", "cs", "gy", "ly", "ny", "sz", "ty", "zs"]
+                        .iter()
+                        .find(|d| b[i..].iter().copied().take(d.len()).eq(d.chars()));
+                    i + digraph.map_or(1, |d| d.len())
+                },
+                None => len,
+            },
+            _ => (1..len).find(|&i| is_vowel(b[i])).map_or(len, |i| i + 1),
+        };
+    }
+
+    /// Replaces the longest suffix of `groups` in R1 with its group's
+    /// replacement
+    fn replace(&mut self, groups: &[(&[&str], &str)]) {
+        if let Some((with, start)) = find_group(&self.buffer, groups, 0) {
+            if start >= self.p1 { replace_from(&mut self.buffer, start, with); }
+        }
+    }
+
+    /// Removes one of `suffixes` in R1 if it follows a double consonant, and
+    /// undoubles the consonant ("-ssal" to "-s")
+    fn undoubling_suffix(&mut self, suffixes: &[&str]) {
+        let Some((_, start)) = find_suffix(&self.buffer, suffixes, 0) else { return };
+        if start < self.p1 || find_suffix(&self.buffer[..start], DOUBLES, 0).is_none() { return; }
+        self.buffer.truncate(start);
+        self.buffer.remove(start - 2);
+    }
+}
+
+impl Stemmer for HungarianStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        HungarianStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inflections() {
+        // Checked against the Snowball reference implementation
+        let mut stemmer = HungarianStemmer::new();
+        let cases = [
+            ("ház", "ház"), ("házak", "ház"), ("házakban", "ház"), ("házban", "ház"), ("háznak", "ház"),
+            ("házunk", "ház"), ("házaim", "ház"), ("almát", "alm"), ("almának", "alm"),
+            ("almáink", "alma"), ("kertek", "kert"), ("kertekben", "kert"), ("késsel", "kés"),
+            ("asszonnyal", "asszony"), ("barátaitok", "barát"), ("egyszer", "egyszer"), ("a", "a"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_snowball_vocabulary() {
+        // The words of the test data of the lingua crate's Hungarian
+        // language model, each with its stem from the Snowball project's C
+        // libstemmer (<https://snowballstem.org>)
+        let mut stemmer = HungarianStemmer::new();
+        let words = include_str!("../test_data/hungarian/voc.txt");
+        let stems = include_str!("../test_data/hungarian/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+    }
+}
@@ -0,0 +1,168 @@
+//! # Source-Code Identifier Stemming
+//!
+//! Splits identifiers such as `getUserNames`, `parse_http_response` or
+//! `HTTPServer2` into their words and stems each one, so source code can be
+//! indexed with the same terms as prose.
+//!
+//! An identifier is split:
+//! - at underscores (and any other character that is not a letter or digit)
+//! - at lower-to-upper case transitions (`getUser` -> `get`, `User`)
+//! - before the last capital of an acronym (`HTTPServer` -> `HTTP`, `Server`),
+//!   except for a plural `s` (`URLs` stays one word)
+//! - around runs of digits (`utf8Decoder` -> `utf`, `8`, `Decoder`)
+//!
+//! Digit runs are kept as parts of their own and are not stemmed.
+
+use crate::Stemmer;
+
+/// The kind of an identifier character, for finding split points
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CharKind {
+    Upper,
+    Lower,
+    Digit,
+    Separator,
+}
+
+fn kind(c: char) -> CharKind {
+    if c.is_uppercase() {
+        CharKind::Upper
+    } else if c.is_ascii_digit() {
+        CharKind::Digit
+    } else if c.is_alphanumeric() {
+        CharKind::Lower
+    } else {
+        CharKind::Separator
+    }
+}
+
+/// Splits an identifier into its component words
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::identifier::split_identifier;
+/// assert_eq!(split_identifier("getHTTPResponse_v2"), ["get", "HTTP", "Response", "v", "2"]);
+/// ```
+pub fn split_identifier(ident: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = ident.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (n, &(i, c)) in chars.iter().enumerate() {
+        let this = kind(c);
+        if this == CharKind::Separator {
+            if let Some(s) = start.take() { parts.push(&ident[s..i]); }
+            continue;
+        }
+
+        if let Some(s) = start {
+            let prev = kind(chars[n - 1].1);
+            let next = chars.get(n + 1).map(|&(_, c)| c);
+            let plural_s = next == Some('s')
+                && chars.get(n + 2).is_none_or(|&(_, c)| kind(c) != CharKind::Lower);
+            let boundary = match (prev, this) {
+                (CharKind::Lower, CharKind::Upper) => true,
+                (CharKind::Upper, CharKind::Upper) => next.map(kind) == Some(CharKind::Lower) && !plural_s,
+                (CharKind::Digit, CharKind::Digit) => false,
+                (CharKind::Digit, _) | (_, CharKind::Digit) => true,
+                _ => false,
+            };
+            if boundary {
+                parts.push(&ident[s..i]);
+                start = Some(i);
+            }
+        } else {
+            start = Some(i);
+        }
+    }
+
+    if let Some(s) = start { parts.push(&ident[s..]); }
+    parts
+}
+
+/// Splits an identifier and stems each of its words
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each word
+/// * `ident` - A camelCase, PascalCase, snake_case or mixed identifier
+///
+/// # Returns
+/// The stems of the identifier's words, in order
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, identifier::stem_identifier};
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(stem_identifier(&mut stemmer, "getUserNames"), ["get", "user", "name"]);
+/// ```
+pub fn stem_identifier(stemmer: &mut dyn Stemmer, ident: &str) -> Vec<String> {
+    split_identifier(ident)
+        .into_iter()
+        .map(|part| {
+            if part.starts_with(|c: char| c.is_ascii_digit()) {
+                part.to_string()
+            } else {
+                stemmer.stem(part)
+            }
+        })
+        .collect()
+}
+
+/// Stems an identifier and joins its stems with `separator`
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, identifier::stem_identifier_joined};
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(stem_identifier_joined(&mut stemmer, "parse_running_tasks", "_"), "pars_run_task");
+/// ```
+pub fn stem_identifier_joined(stemmer: &mut dyn Stemmer, ident: &str, separator: &str) -> String {
+    stem_identifier(stemmer, ident).join(separator)
+}
+
+/// Replaces every identifier in source text by its joined stems
+///
+/// Identifiers are maximal runs of letters, digits and underscores; all
+/// other characters are copied through unchanged.
+pub fn stem_identifiers_in_text(stemmer: &mut dyn Stemmer, text: &str, separator: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+    while !rest.is_empty() {
+        let other = rest.find(is_ident).unwrap_or(rest.len());
+        out.push_str(&rest[..other]);
+        rest = &rest[other..];
+        let ident = rest.find(|c: char| !is_ident(c)).unwrap_or(rest.len());
+        if ident > 0 {
+            out.push_str(&stem_identifier_joined(stemmer, &rest[..ident], separator));
+        }
+        rest = &rest[ident..];
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_split_identifier() {
+        assert_eq!(split_identifier("getUserNames"), ["get", "User", "Names"]);
+        assert_eq!(split_identifier("__parse_HTTPServer__"), ["parse", "HTTP", "Server"]);
+        assert_eq!(split_identifier("utf8Decoder"), ["utf", "8", "Decoder"]);
+        assert_eq!(split_identifier("parseURLsQuickly"), ["parse", "URLs", "Quickly"]);
+        assert!(split_identifier("__").is_empty());
+    }
+
+    #[test]
+    fn test_stem_identifiers_in_text() {
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(
+            stem_identifiers_in_text(&mut stemmer, "let userNames = getUserNames(running_tasks);", " "),
+            "let user name = get user name(run task);"
+        );
+    }
+}
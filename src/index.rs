@@ -0,0 +1,241 @@
+//! # Inverted Index
+//!
+//! A stemmed inverted index of documents that can be saved to a directory
+//! and loaded again, so that a collection is stemmed once and searched many
+//! times. Documents are named by a path (or any other string), counted by
+//! stem with a [`TfIdf`] collection and ranked against a query with
+//! [`Bm25Scorer`].
+//!
+//! The index also records the name of the stemmer it was built with, so
+//! that queries can be stemmed the same way; what the name means is up to
+//! the caller (the `porterstem` binary stores an algorithm or language).
+//!
+//! ## Format
+//! A saved index is a directory of three UTF-8 text files with one
+//! tab-separated record per line, in which tabs, line breaks and
+//! backslashes are escaped as `\t`, `\n` and `\\`:
+//! - `meta.tsv`: `format` and the format version, then `stemmer` and the
+//!   stemmer name
+//! - `documents.tsv`: the document names, in the order they were added
+//! - `postings.tsv`: each stem, sorted, followed by a `doc:count` field for
+//!   every document containing it, where `doc` is the line number of the
+//!   document in `documents.tsv`, counting from 0
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::{PorterStemmer, index::Index};
+//! let mut stemmer = PorterStemmer::new();
+//! let mut index = Index::new("porter");
+//! index.add(&mut stemmer, "pasta.txt", "A guide to cooking pasta");
+//! index.add(&mut stemmer, "shoes.txt", "Running shoes for runners who run daily");
+//!
+//! let dir = std::env::temp_dir().join(format!("index-doc-{}", std::process::id()));
+//! index.save(&dir).unwrap();
+//! let index = Index::load(&dir).unwrap();
+//! let results = index.search(&mut stemmer, "run fast");
+//! assert_eq!(results[0].0, "shoes.txt");
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::bm25::Bm25Scorer;
+use crate::tfidf::TfIdf;
+use crate::Stemmer;
+
+/// Version written to and expected in `meta.tsv`
+const FORMAT: &str = "1";
+
+/// A stemmed inverted index of named documents
+#[derive(Debug, Clone)]
+pub struct Index {
+    stemmer: String,
+    names: Vec<String>,
+    documents: TfIdf,
+}
+
+impl Index {
+    /// Creates an empty index of documents stemmed with the stemmer called
+    /// `stemmer`
+    pub fn new(stemmer: impl Into<String>) -> Self {
+        Index { stemmer: stemmer.into(), names: Vec::new(), documents: TfIdf::new() }
+    }
+
+    /// The name of the stemmer the index was built with
+    pub fn stemmer_name(&self) -> &str {
+        &self.stemmer
+    }
+
+    /// Stems and adds the document `name` with the given text, returning
+    /// its number
+    pub fn add(&mut self, stemmer: &mut dyn Stemmer, name: impl Into<String>, text: &str) -> usize {
+        self.names.push(name.into());
+        self.documents.add_document(stemmer, text)
+    }
+
+    /// Number of documents
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns true if no document has been added
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The names of the documents, by number
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Stems `query` and returns the names of the documents matching it with
+    /// their BM25 scores, best first
+    pub fn search(&self, stemmer: &mut dyn Stemmer, query: &str) -> Vec<(&str, f64)> {
+        Bm25Scorer::new(&self.documents)
+            .rank(stemmer, query)
+            .into_iter()
+            .map(|(doc, score)| (self.names[doc].as_str(), score))
+            .collect()
+    }
+
+    /// Writes the index to the directory `dir`, creating it if needed
+    pub fn save(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let create = |name: &str| File::create(dir.join(name)).map(BufWriter::new);
+
+        let mut meta = create("meta.tsv")?;
+        writeln!(meta, "format\t{}\nstemmer\t{}", FORMAT, escape(&self.stemmer))?;
+        meta.flush()?;
+
+        let mut documents = create("documents.tsv")?;
+        for name in &self.names {
+            writeln!(documents, "{}", escape(name))?;
+        }
+        documents.flush()?;
+
+        let mut postings: BTreeMap<&str, Vec<(usize, u64)>> = BTreeMap::new();
+        for doc in 0..self.documents.len() {
+            for (stem, &count) in self.documents.counts(doc) {
+                postings.entry(stem).or_default().push((doc, count));
+            }
+        }
+        let mut out = create("postings.tsv")?;
+        for (stem, docs) in postings {
+            write!(out, "{}", escape(stem))?;
+            for (doc, count) in docs {
+                write!(out, "\t{}:{}", doc, count)?;
+            }
+            writeln!(out)?;
+        }
+        out.flush()
+    }
+
+    /// Reads an index written by [`Index::save`] from the directory `dir`
+    ///
+    /// A file that is missing or not in the format is an error, naming the
+    /// file and, where there is one, the line.
+    pub fn load(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let lines = |name: &str| -> io::Result<Vec<String>> {
+            let path = dir.join(name);
+            let named = |e: io::Error| io::Error::new(e.kind(), format!("{}: {}", path.display(), e));
+            BufReader::new(File::open(&path).map_err(named)?).lines().collect::<io::Result<_>>().map_err(named)
+        };
+        let invalid = |name: &str, line: usize, what: &str| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}:{}: {}", dir.join(name).display(), line + 1, what))
+        };
+
+        let mut stemmer = None;
+        let mut format = None;
+        for (number, line) in lines("meta.tsv")?.iter().enumerate() {
+            match line.split_once('\t') {
+                Some(("format", version)) => format = Some(version.to_string()),
+                Some(("stemmer", name)) => stemmer = Some(unescape(name)),
+                _ => return Err(invalid("meta.tsv", number, "expected a key and a value")),
+            }
+        }
+        if format.as_deref() != Some(FORMAT) {
+            return Err(invalid("meta.tsv", 0, "not a version 1 index"));
+        }
+        let mut index = Index::new(stemmer.ok_or_else(|| invalid("meta.tsv", 0, "no stemmer given"))?);
+        index.names = lines("documents.tsv")?.iter().map(|line| unescape(line)).collect();
+
+        let mut counts: Vec<HashMap<String, u64>> = vec![HashMap::new(); index.names.len()];
+        for (number, line) in lines("postings.tsv")?.iter().enumerate() {
+            let mut fields = line.split('\t');
+            let stem = unescape(fields.next().unwrap_or_default());
+            for field in fields {
+                let (doc, count) = field.split_once(':').unwrap_or_default();
+                match (doc.parse::<usize>(), count.parse()) {
+                    (Ok(doc), Ok(count)) if doc < counts.len() => counts[doc].insert(stem.clone(), count),
+                    _ => return Err(invalid("postings.tsv", number, "expected doc:count postings of known documents")),
+                };
+            }
+        }
+        for counts in counts {
+            index.documents.add_counts(counts);
+        }
+        Ok(index)
+    }
+}
+
+/// Escapes the tabs, line breaks and backslashes of a field
+fn escape(field: &str) -> String {
+    field.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+/// Undoes [`escape`]
+fn unescape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_save_and_load() {
+        let mut stemmer = PorterStemmer::new();
+        let mut index = Index::new("porter\twith\\tab");
+        index.add(&mut stemmer, "cats.txt", "cats chase mice; the cat sat");
+        index.add(&mut stemmer, "odd\tname\n.txt", "dogs chase cats");
+        index.add(&mut stemmer, "empty.txt", "");
+
+        let dir = std::env::temp_dir().join(format!("porterstem-index-{}", std::process::id()));
+        index.save(&dir).unwrap();
+        let loaded = Index::load(&dir).unwrap();
+        assert_eq!(loaded.stemmer_name(), "porter\twith\\tab");
+        assert_eq!(loaded.names(), index.names());
+        for doc in 0..index.len() {
+            assert_eq!(loaded.documents.counts(doc), index.documents.counts(doc));
+        }
+        assert_eq!(loaded.search(&mut stemmer, "chasing cat"), index.search(&mut stemmer, "chasing cat"));
+        assert_eq!(loaded.search(&mut stemmer, "mouse").len(), 0);
+        assert_eq!(loaded.search(&mut stemmer, "cat")[0].0, "cats.txt");
+
+        fs::write(dir.join("postings.tsv"), "cat\t0:2\t7:1\n").unwrap();
+        let error = Index::load(&dir).unwrap_err();
+        assert!(error.to_string().ends_with("postings.tsv:1: expected doc:count postings of known documents"));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(Index::load(&dir).unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+}
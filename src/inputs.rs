@@ -0,0 +1,260 @@
+//! Input files of the command line front end
+//!
+//! A command line argument is a file, `-` for stdin, a directory (walked
+//! with `--recursive`) or a glob pattern such as `corpus/**/*.txt`, which is
+//! expanded here so that it also works quoted or on shells that do not
+//! expand it. `*` and `?` match within one path component, `[a-z]` and
+//! `[!0-9]` match one character of a set, and a `**` component matches any
+//! number of directories.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// An input file, with its path relative to the directory or glob it was
+/// found under, which is where its output goes under `--out-dir`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputFile {
+    pub path: PathBuf,
+    pub relative: PathBuf,
+}
+
+impl InputFile {
+    /// Stdin, given as `-`
+    pub fn stdin() -> Self {
+        InputFile { path: "-".into(), relative: "-".into() }
+    }
+
+    /// The stdin pseudo-file `-`
+    pub fn is_stdin(&self) -> bool {
+        self.path == Path::new("-")
+    }
+}
+
+/// Returns true if `arg` contains glob wildcards
+pub fn is_glob(arg: &str) -> bool {
+    arg.contains(['*', '?', '['])
+}
+
+/// Resolves one command line argument to the input files it names, sorted
+/// by path within a directory or glob
+///
+/// A plain file is taken as it is (even if it does not exist, so that the
+/// error is reported when it is opened), with its file name as the relative
+/// path. A directory is an error unless `recursive` is set, and so is a
+/// glob that matches nothing.
+pub fn expand(arg: &str, recursive: bool) -> io::Result<Vec<InputFile>> {
+    if arg == "-" {
+        return Ok(vec![InputFile::stdin()]);
+    }
+    if is_glob(arg) {
+        let files = expand_glob(arg)?;
+        if files.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("{}: no matching files", arg)));
+        }
+        return Ok(files);
+    }
+
+    let path = Path::new(arg);
+    if path.is_dir() {
+        if !recursive {
+            return Err(io::Error::other(format!("{}: is a directory (use --recursive)", arg)));
+        }
+        let mut files = Vec::new();
+        walk(path, path, None, &mut files)?;
+        return Ok(files);
+    }
+    let relative = path.file_name().map_or_else(|| path.into(), PathBuf::from);
+    Ok(vec![InputFile { path: path.into(), relative }])
+}
+
+/// What `--watch` compares to notice a change: each input file with its
+/// modification time and size, or None where it could not be read
+pub type Snapshot = Vec<(PathBuf, Option<(SystemTime, u64)>)>;
+
+/// Takes a [`Snapshot`] of the files the arguments name, leaving out those
+/// in or under `skip` (the output file or directory), so that writing the
+/// outputs does not count as a change
+pub fn snapshot(args: &[String], recursive: bool, skip: &[&Path]) -> Snapshot {
+    let mut files = Vec::new();
+    for arg in args {
+        match expand(arg, recursive) {
+            Ok(found) => files.extend(found.into_iter().map(|file| file.path)),
+            Err(_) => files.push(PathBuf::from(arg)),
+        }
+    }
+    files.retain(|path| !skip.iter().any(|skip| path.starts_with(skip)));
+    files
+        .into_iter()
+        .map(|path| {
+            let stamp = fs::metadata(&path).and_then(|meta| Ok((meta.modified()?, meta.len()))).ok();
+            (path, stamp)
+        })
+        .collect()
+}
+
+/// Expands a glob pattern, walking from its leading components without
+/// wildcards
+fn expand_glob(pattern: &str) -> io::Result<Vec<InputFile>> {
+    let parts: Vec<&str> = pattern.split('/').collect();
+    let literal = parts.iter().take_while(|part| !is_glob(part)).count();
+    let base = match parts[..literal].join("/") {
+        base if base.is_empty() && literal > 0 => "/".to_string(),
+        base if base.is_empty() => ".".to_string(),
+        base => base,
+    };
+    let rest: Vec<&str> = parts[literal..].iter().copied().filter(|part| !part.is_empty()).collect();
+    let depth = if rest.contains(&"**") { None } else { Some(rest.len()) };
+
+    let base = Path::new(&base);
+    let mut files = Vec::new();
+    if base.is_dir() {
+        walk(base, base, depth, &mut files)?;
+    }
+    files.retain(|file| {
+        let names: Vec<String> = file.relative.iter().map(|name| name.to_string_lossy().into_owned()).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        match_path(&rest, &names)
+    });
+    Ok(files)
+}
+
+/// Collects the files under `dir`, down to `depth` levels if given, with
+/// their paths relative to `root`
+fn walk(root: &Path, dir: &Path, depth: Option<usize>, files: &mut Vec<InputFile>) -> io::Result<()> {
+    if depth == Some(0) {
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(dir)
+        .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", dir.display(), e)))?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            walk(root, &path, depth.map(|d| d - 1), files)?;
+        } else if path.is_file() {
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            let path = if root == Path::new(".") { relative.clone() } else { path };
+            files.push(InputFile { path, relative });
+        }
+    }
+    Ok(())
+}
+
+/// Matches path components against pattern components, `**` matching any
+/// number of them
+fn match_path(patterns: &[&str], names: &[&str]) -> bool {
+    match patterns.split_first() {
+        None => names.is_empty(),
+        Some((&"**", rest)) => (0..=names.len()).any(|i| match_path(rest, &names[i..])),
+        Some((pattern, rest)) => {
+            !names.is_empty() && match_name(pattern, names[0]) && match_path(rest, &names[1..])
+        },
+    }
+}
+
+/// Matches one path component against a pattern of literal characters,
+/// `*`, `?` and `[...]` sets; wildcards do not match a leading "." of a
+/// hidden file, as in the shell
+pub fn match_name(pattern: &str, name: &str) -> bool {
+    if name.starts_with('.') && !pattern.starts_with('.') {
+        return false;
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    match_chars(&pattern, &name)
+}
+
+fn match_chars(pattern: &[char], name: &[char]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') => (0..=name.len()).any(|i| match_chars(&pattern[1..], &name[i..])),
+        Some('?') => !name.is_empty() && match_chars(&pattern[1..], &name[1..]),
+        Some('[') => match (name.first(), match_set(&pattern[1..], name.first().copied())) {
+            (Some(_), Some((true, rest))) => match_chars(rest, &name[1..]),
+            _ => false,
+        },
+        Some(&c) => name.first() == Some(&c) && match_chars(&pattern[1..], &name[1..]),
+    }
+}
+
+/// Matches `c` against the set after a `[`, returning whether it matched
+/// and the pattern after the closing `]`, or None if the set is not closed
+fn match_set(pattern: &[char], c: Option<char>) -> Option<(bool, &[char])> {
+    let c = c?;
+    let (negated, mut i) = match pattern.first() {
+        Some('!') | Some('^') => (true, 1),
+        _ => (false, 0),
+    };
+    let mut matched = false;
+    let mut first = true;
+    while i < pattern.len() && (first || pattern[i] != ']') {
+        if i + 2 < pattern.len() && pattern[i + 1] == '-' && pattern[i + 2] != ']' {
+            matched |= (pattern[i]..=pattern[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            matched |= pattern[i] == c;
+            i += 1;
+        }
+        first = false;
+    }
+    (i < pattern.len()).then(|| (matched != negated, &pattern[i + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_name() {
+        assert!(match_name("*.txt", "notes.txt"));
+        assert!(!match_name("*.txt", "notes.md"));
+        assert!(!match_name("*.txt", ".hidden.txt"));
+        assert!(match_name("ch??.md", "ch01.md"));
+        assert!(match_name("[a-c]*", "beta"));
+        assert!(!match_name("[!a-c]*", "beta"));
+        assert!(match_name("[]x]", "]"));
+        assert!(!match_name("[ab", "a"));
+        assert!(match_path(&["**", "*.txt"], &["a.txt"]));
+        assert!(match_path(&["**", "*.txt"], &["x", "y", "a.txt"]));
+        assert!(!match_path(&["*", "*.txt"], &["a.txt"]));
+    }
+
+    #[test]
+    fn test_expand() {
+        let root = std::env::temp_dir().join(format!("porterstem-inputs-{}", std::process::id()));
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        for file in ["one.txt", "a/two.txt", "a/b/three.txt", "a/skip.md"] {
+            fs::write(root.join(file), "x").unwrap();
+        }
+        let arg = root.to_str().unwrap();
+        let relative = |files: Vec<InputFile>| -> Vec<String> {
+            files.iter().map(|f| f.relative.to_string_lossy().replace('\\', "/")).collect()
+        };
+
+        assert_eq!(relative(expand(arg, true).unwrap()), ["a/b/three.txt", "a/skip.md", "a/two.txt", "one.txt"]);
+        assert!(expand(arg, false).is_err());
+        let files = expand(&format!("{}/**/*.txt", arg), false).unwrap();
+        assert_eq!(relative(files), ["a/b/three.txt", "a/two.txt", "one.txt"]);
+        let files = expand(&format!("{}/*/*.txt", arg), false).unwrap();
+        assert_eq!(files[0].path, root.join("a/two.txt"));
+        assert_eq!(relative(files), ["a/two.txt"]);
+        assert!(expand(&format!("{}/*.rs", arg), false).is_err());
+        assert_eq!(relative(expand(&format!("{}/a/two.txt", arg), false).unwrap()), ["two.txt"]);
+
+        let args = [arg.to_string()];
+        let out_dir = root.join("a/b");
+        let skip = [out_dir.as_path()];
+        let before = snapshot(&args, true, &skip);
+        assert_eq!(before.len(), 3);
+        assert!(before.iter().all(|(_, stamp)| stamp.is_some()));
+        fs::write(root.join("a/b/three.txt"), "changed").unwrap();
+        assert_eq!(snapshot(&args, true, &skip), before);
+        fs::write(root.join("one.txt"), "changed").unwrap();
+        assert_ne!(snapshot(&args, true, &skip), before);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
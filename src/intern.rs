@@ -0,0 +1,124 @@
+//! # Stem Interning
+//!
+//! An index holding millions of postings sees the same few thousand stems
+//! over and over. [`StemInterner`] keeps one `Arc<str>` for each distinct
+//! stem and numbers them from 0, so postings can hold a `u32` symbol, or a
+//! shared `Arc<str>`, instead of a `String` of their own. With
+//! [`crate::corpus::stem_corpus_interned`] a whole corpus is stemmed into
+//! symbols, each distinct token stemmed once.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::{intern::StemInterner, PorterStemmer};
+//! let mut stemmer = PorterStemmer::new();
+//! let mut interner = StemInterner::new();
+//! let running = interner.stem(&mut stemmer, "running");
+//! assert_eq!(interner.stem(&mut stemmer, "runs"), running);
+//! assert_eq!(interner.resolve(running), Some("run"));
+//! assert_eq!(interner.len(), 1);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::Stemmer;
+
+/// A pool of stems, each held once and numbered in the order first seen
+#[derive(Debug, Clone, Default)]
+pub struct StemInterner {
+    /// The symbol of each stem
+    symbols: HashMap<Arc<str>, u32>,
+    /// Each stem, at its symbol
+    stems: Vec<Arc<str>>,
+}
+
+impl StemInterner {
+    /// Creates an empty pool
+    pub fn new() -> Self {
+        StemInterner { symbols: HashMap::new(), stems: Vec::new() }
+    }
+
+    /// The symbol of `stem`, adding it to the pool if it is new
+    ///
+    /// # Panics
+    /// If `stem` is new and the pool already has a stem for every `u32`.
+    pub fn intern(&mut self, stem: &str) -> u32 {
+        if let Some(&symbol) = self.symbols.get(stem) {
+            return symbol;
+        }
+        let symbol = u32::try_from(self.stems.len()).expect("a symbol for every u32 already");
+        let stem: Arc<str> = Arc::from(stem);
+        self.stems.push(Arc::clone(&stem));
+        self.symbols.insert(stem, symbol);
+        symbol
+    }
+
+    /// The symbol of the stem of `word`
+    pub fn stem(&mut self, stemmer: &mut dyn Stemmer, word: &str) -> u32 {
+        let stem = stemmer.stem(word);
+        self.intern(&stem)
+    }
+
+    /// The pool's `Arc<str>` for `stem`, adding it if it is new
+    pub fn intern_arc(&mut self, stem: &str) -> Arc<str> {
+        let symbol = self.intern(stem);
+        Arc::clone(&self.stems[symbol as usize])
+    }
+
+    /// The symbol of `stem`, if the pool holds it
+    pub fn symbol(&self, stem: &str) -> Option<u32> {
+        self.symbols.get(stem).copied()
+    }
+
+    /// The stem of `symbol`
+    pub fn resolve(&self, symbol: u32) -> Option<&str> {
+        self.stems.get(symbol as usize).map(|stem| &**stem)
+    }
+
+    /// The pool's `Arc<str>` for the stem of `symbol`
+    pub fn resolve_arc(&self, symbol: u32) -> Option<Arc<str>> {
+        self.stems.get(symbol as usize).cloned()
+    }
+
+    /// The number of distinct stems
+    pub fn len(&self) -> usize {
+        self.stems.len()
+    }
+
+    /// Returns true if the pool holds no stems
+    pub fn is_empty(&self) -> bool {
+        self.stems.is_empty()
+    }
+
+    /// Each stem, at its symbol
+    pub fn stems(&self) -> &[Arc<str>] {
+        &self.stems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_interner() {
+        let mut stemmer = PorterStemmer::new();
+        let mut interner = StemInterner::new();
+        assert!(interner.is_empty());
+        let ids: Vec<u32> = ["connection", "connected", "ponies", "Connects"]
+            .iter()
+            .map(|word| interner.stem(&mut stemmer, word))
+            .collect();
+        assert_eq!(ids, [0, 0, 1, 0]);
+        assert_eq!((interner.symbol("poni"), interner.symbol("pony")), (Some(1), None));
+        assert_eq!((interner.resolve(1), interner.resolve(2)), (Some("poni"), None));
+
+        // Every holder of a stem shares the pool's one allocation
+        let connect = interner.intern_arc("connect");
+        assert!(Arc::ptr_eq(&connect, &interner.resolve_arc(0).unwrap()));
+        assert_eq!(Arc::strong_count(&connect), 3);
+        assert_eq!(interner.intern("run"), 2);
+        assert_eq!(interner.stems().len(), 3);
+    }
+}
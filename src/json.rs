@@ -0,0 +1,400 @@
+//! # JSON / JSONL Field Stemming
+//!
+//! Stems selected string fields of JSON records in place and passes the rest
+//! of each record through byte for byte, which is the shape of most
+//! search-ingestion pipelines.
+//!
+//! Fields are selected with dotted paths such as `title` or `body.text`.
+//! Arrays are transparent: `tags` selects every string in a `tags` array and
+//! `authors.name` selects the `name` of every object in an `authors` array.
+//! When a path selects an object or array, every string value inside it is
+//! stemmed; object keys are never stemmed.
+//!
+//! Inside a selected string, escape sequences (`\n`, `\u00e9`, ...) are kept
+//! as they are and only the letters between them are stemmed.
+//!
+//! [`stem_tokens_jsonl`] goes the other way, describing plain text as JSON
+//! lines of `{"token":"running","stem":"run","offset":123}`, so that
+//! downstream tools can line stems up with the original text without
+//! tokenizing it again.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::text::{read_chunks, stem_text, token_stems};
+use crate::Stemmer;
+
+/// A syntax error in a JSON record
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonError {
+    /// Byte offset of the error within the record
+    pub offset: usize,
+    /// What was expected at that offset
+    pub message: &'static str,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON at byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// Walks one JSON value, copying it to `out` and stemming selected strings
+struct Rewriter<'a, 's> {
+    stemmer: &'s mut dyn Stemmer,
+    json: &'a str,
+    pos: usize,
+    fields: &'a [Vec<&'a str>],
+    path: Vec<String>,
+    out: String,
+}
+
+impl<'a, 's> Rewriter<'a, 's> {
+    fn error(&self, message: &'static str) -> JsonError {
+        JsonError { offset: self.pos, message }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.json.as_bytes().get(self.pos).copied()
+    }
+
+    /// Copies whitespace through to the output
+    fn skip_whitespace(&mut self) {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) { self.pos += 1; }
+        self.out.push_str(&self.json[start..self.pos]);
+    }
+
+    /// Copies one expected byte through to the output
+    fn expect(&mut self, b: u8, message: &'static str) -> Result<(), JsonError> {
+        if self.peek() != Some(b) { return Err(self.error(message)); }
+        self.out.push(b as char);
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Returns true if the current path is, or is inside, a selected field
+    fn is_selected(&self) -> bool {
+        self.fields.iter().any(|field| {
+            field.len() <= self.path.len() && field.iter().zip(&self.path).all(|(a, b)| a == b)
+        })
+    }
+
+    /// Scans a string literal, returning the raw contents between the quotes
+    fn scan_string(&mut self) -> Result<&'a str, JsonError> {
+        if self.peek() != Some(b'"') { return Err(self.error("expected string")); }
+        let start = self.pos + 1;
+        let bytes = self.json.as_bytes();
+        let mut i = start;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' => {
+                    self.pos = i + 1;
+                    return Ok(&self.json[start..i]);
+                },
+                b'\\' => i += 2,
+                _ => i += 1,
+            }
+        }
+        self.pos = bytes.len();
+        Err(self.error("unterminated string"))
+    }
+
+    /// Copies a string value, stemming it if the current path is selected
+    fn string_value(&mut self) -> Result<(), JsonError> {
+        let raw = self.scan_string()?;
+        self.out.push('"');
+        if self.is_selected() {
+            let mut rest = raw;
+            while let Some(backslash) = rest.find('\\') {
+                self.out.push_str(&stem_text(self.stemmer, &rest[..backslash]));
+                let len = if rest[backslash + 1..].starts_with('u') { 6 } else { 2 };
+                let mut end = (backslash + len).min(rest.len());
+                while !rest.is_char_boundary(end) { end += 1; }
+                self.out.push_str(&rest[backslash..end]);
+                rest = &rest[end..];
+            }
+            self.out.push_str(&stem_text(self.stemmer, rest));
+        } else {
+            self.out.push_str(raw);
+        }
+        self.out.push('"');
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string_value(),
+            Some(_) => self.literal(),
+            None => Err(self.error("expected value")),
+        }
+    }
+
+    /// Copies a number, `true`, `false` or `null`
+    fn literal(&mut self) -> Result<(), JsonError> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b) if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'+' | b'.')) {
+            self.pos += 1;
+        }
+        let literal = &self.json[start..self.pos];
+        let is_number = literal.starts_with(|c: char| c == '-' || c.is_ascii_digit())
+            && literal.bytes().all(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E'))
+            && literal.parse::<f64>().is_ok();
+        if !(is_number || matches!(literal, "true" | "false" | "null")) {
+            self.pos = start;
+            return Err(self.error("expected value"));
+        }
+        self.out.push_str(literal);
+        Ok(())
+    }
+
+    fn object(&mut self) -> Result<(), JsonError> {
+        self.expect(b'{', "expected '{'")?;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') { return self.expect(b'}', "expected '}'"); }
+        loop {
+            self.skip_whitespace();
+            let key = self.scan_string()?;
+            self.out.push('"');
+            self.out.push_str(key);
+            self.out.push('"');
+            self.skip_whitespace();
+            self.expect(b':', "expected ':'")?;
+            self.path.push(unescape_key(key));
+            self.value()?;
+            self.path.pop();
+            self.skip_whitespace();
+            if self.peek() == Some(b',') {
+                self.expect(b',', "expected ','")?;
+            } else {
+                return self.expect(b'}', "expected ',' or '}'");
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<(), JsonError> {
+        self.expect(b'[', "expected '['")?;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') { return self.expect(b']', "expected ']'"); }
+        loop {
+            self.value()?;
+            self.skip_whitespace();
+            if self.peek() == Some(b',') {
+                self.expect(b',', "expected ','")?;
+            } else {
+                return self.expect(b']', "expected ',' or ']'");
+            }
+        }
+    }
+}
+
+/// Decodes the escapes in an object key so it can be compared with a path
+fn unescape_key(raw: &str) -> String {
+    if !raw.contains('\\') { return raw.to_string(); }
+    let mut key = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            key.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => key.push('\n'),
+            Some('t') => key.push('\t'),
+            Some('r') => key.push('\r'),
+            Some('b') => key.push('\u{8}'),
+            Some('f') => key.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let decoded = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                key.push(decoded.unwrap_or(char::REPLACEMENT_CHARACTER));
+            },
+            Some(other) => key.push(other),
+            None => {}
+        }
+    }
+    key
+}
+
+/// Splits dotted field selectors such as `body.text` into their path segments
+pub fn parse_fields<'a>(fields: &[&'a str]) -> Vec<Vec<&'a str>> {
+    fields.iter().map(|field| field.split('.').collect()).collect()
+}
+
+/// Stems the selected fields of a single JSON document
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each word in the selected fields
+/// * `json` - One JSON value, typically an object
+/// * `fields` - Dotted paths of the fields to stem, e.g. `["title", "body.text"]`
+///
+/// # Returns
+/// The document with the selected strings stemmed, or the first syntax error
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, json::stem_json_fields};
+/// let mut stemmer = PorterStemmer::new();
+/// let record = r#"{"id": "running-1", "body": {"text": "Running dogs"}}"#;
+/// assert_eq!(
+///     stem_json_fields(&mut stemmer, record, &["body.text"]).unwrap(),
+///     r#"{"id": "running-1", "body": {"text": "run dog"}}"#
+/// );
+/// ```
+pub fn stem_json_fields(
+    stemmer: &mut dyn Stemmer,
+    json: &str,
+    fields: &[&str],
+) -> Result<String, JsonError> {
+    let fields = parse_fields(fields);
+    let mut rewriter = Rewriter {
+        stemmer,
+        json,
+        pos: 0,
+        fields: &fields,
+        path: Vec::new(),
+        out: String::with_capacity(json.len()),
+    };
+    rewriter.value()?;
+    rewriter.skip_whitespace();
+    if rewriter.pos != json.len() {
+        return Err(rewriter.error("unexpected trailing characters"));
+    }
+    Ok(rewriter.out)
+}
+
+/// Stems the selected fields of every record in newline-delimited JSON
+///
+/// Blank lines are copied through. A malformed record stops processing with
+/// an `InvalidData` error naming its line number.
+pub fn stem_jsonl<R: BufRead, W: Write>(
+    stemmer: &mut dyn Stemmer,
+    reader: R,
+    mut writer: W,
+    fields: &[&str],
+) -> io::Result<()> {
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            writeln!(writer, "{}", line)?;
+            continue;
+        }
+        let stemmed = stem_json_fields(stemmer, &line, fields).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", number + 1, e))
+        })?;
+        writeln!(writer, "{}", stemmed)?;
+    }
+    writer.flush()
+}
+
+/// Appends `s` to `out` as a JSON string literal
+pub fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Writes a JSON line for every word of the text read from `reader`: the
+/// word as it appears, its stem and the byte offset where it starts
+///
+/// The text is read a piece at a time (see [`read_chunks`]), and offsets
+/// count from the start of the whole input.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, json::stem_tokens_jsonl};
+/// let mut out = Vec::new();
+/// stem_tokens_jsonl(&mut PorterStemmer::new(), "Ponies ran\nrunning".as_bytes(), &mut out).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), concat!(
+///     "{\"token\":\"Ponies\",\"stem\":\"poni\",\"offset\":0}\n",
+///     "{\"token\":\"ran\",\"stem\":\"ran\",\"offset\":7}\n",
+///     "{\"token\":\"running\",\"stem\":\"run\",\"offset\":11}\n",
+/// ));
+/// ```
+pub fn stem_tokens_jsonl<R: BufRead, W: Write>(
+    stemmer: &mut dyn Stemmer,
+    reader: R,
+    mut writer: W,
+) -> io::Result<()> {
+    let mut record = String::new();
+    let mut offset = 0;
+    read_chunks(reader, |chunk| {
+        for (token, stem) in token_stems(stemmer, chunk) {
+            record.clear();
+            record.push_str("{\"token\":");
+            write_json_string(&mut record, token.text);
+            record.push_str(",\"stem\":");
+            write_json_string(&mut record, &stem);
+            record.push_str(&format!(",\"offset\":{}}}", offset + token.start));
+            writeln!(writer, "{}", record)?;
+        }
+        offset += chunk.len();
+        Ok(())
+    })?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_only_selected_paths_are_stemmed() {
+        let mut stemmer = PorterStemmer::new();
+        let record = r#"{ "title":"Running \"fast\"\nponies", "running": "running",
+            "tags": ["flies", 3, null], "authors": [{"name": "Caresses", "bio": "caresses"}],
+            "ponies": {"cats": ["ties"]}, "n": -1.5e3, "ok": true }"#;
+        let fields = ["title", "tags", "authors.name", "ponies"];
+        assert_eq!(
+            stem_json_fields(&mut stemmer, record, &fields).unwrap(),
+            r#"{ "title":"run \"fast\"\nponi", "running": "running",
+            "tags": ["fli", 3, null], "authors": [{"name": "caress", "bio": "caresses"}],
+            "ponies": {"cats": ["ti"]}, "n": -1.5e3, "ok": true }"#
+        );
+    }
+
+    #[test]
+    fn test_jsonl_reports_line_of_bad_record() {
+        let mut stemmer = PorterStemmer::new();
+        let mut out = Vec::new();
+        stem_jsonl(&mut stemmer, "{\"a\":\"cats\"}\n\n".as_bytes(), &mut out, &["a"]).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "{\"a\":\"cat\"}\n\n");
+
+        let err = stem_jsonl(&mut stemmer, "{}\n{\"a\" 1}\n".as_bytes(), Vec::new(), &["a"]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2:"));
+        assert!(stem_json_fields(&mut stemmer, "[1,]", &[]).is_err());
+        assert!(stem_json_fields(&mut stemmer, r#"{"a":"\é"}"#, &["a"]).is_ok());
+    }
+
+    #[test]
+    fn test_token_offsets_are_those_of_the_input() {
+        let mut stemmer = PorterStemmer::new().with_diacritic_folding(true);
+        let mut out = Vec::new();
+        stem_tokens_jsonl(&mut stemmer, "«Résumés»\n\"Naïve\"".as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out, concat!(
+            "{\"token\":\"Résumés\",\"stem\":\"resum\",\"offset\":2}\n",
+            "{\"token\":\"Naïve\",\"stem\":\"naiv\",\"offset\":15}\n",
+        ));
+        let mut quoted = String::new();
+        write_json_string(&mut quoted, "a\"b\\\u{1}");
+        assert_eq!(quoted, r#""a\"b\\\u0001""#);
+    }
+}
@@ -0,0 +1,377 @@
+//! # Lemmatizer
+//!
+//! Reduces English words to their dictionary forms ("better" to "good",
+//! "ran" to "run", "geese" to "goose") instead of truncated stems, in the
+//! way of WordNet's morphy: an irregular form is looked up in exception
+//! lists, and otherwise inflectional endings are detached by rule ("-ies"
+//! to "-y", "-ing" to "" or "-e") and each candidate is kept only if the
+//! [`Lexicon`] has it for that part of speech. Unlike morphy, a doubled
+//! final consonant left by "-ing", "-ed", "-er" or "-est" is undoubled
+//! ("running" to "run") and "-ier" and "-iest" become "-y" ("happier" to
+//! "happy"), which WordNet covers with exceptions.
+//!
+//! The [`Lemmatizer`] implements [`Stemmer`], so a pipeline can switch
+//! between stemming and lemmatization. Words are lemmatized without a part
+//! of speech: an irregular form wins over a regular reading, and otherwise
+//! the shortest lemma of any part of speech is chosen; a word with no lemma
+//! is returned lowercased as it is.
+//!
+//! The built-in exceptions cover the common irregular nouns, verbs and
+//! adjectives; the lexicon is loaded from a word list or from the index and
+//! exception files of a WordNet database ([`Lemmatizer::load_wordnet`]).
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::stemmer::Stemmer;
+
+/// A part of speech as WordNet has them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartOfSpeech {
+    Noun,
+    Verb,
+    Adjective,
+    Adverb,
+}
+
+impl PartOfSpeech {
+    /// Every part of speech, in the order ties between lemmas are resolved
+    pub const ALL: &'static [PartOfSpeech] =
+        &[PartOfSpeech::Noun, PartOfSpeech::Verb, PartOfSpeech::Adjective, PartOfSpeech::Adverb];
+
+    /// The name WordNet's files use ("noun", "verb", "adj", "adv")
+    pub fn wordnet_name(self) -> &'static str {
+        match self {
+            PartOfSpeech::Noun => "noun",
+            PartOfSpeech::Verb => "verb",
+            PartOfSpeech::Adjective => "adj",
+            PartOfSpeech::Adverb => "adv",
+        }
+    }
+
+    /// Endings detached by rule, with what replaces them
+    fn rules(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            PartOfSpeech::Noun => &[
+                ("s", ""), ("ses", "s"), ("xes", "x"), ("zes", "z"), ("ches", "ch"), ("shes", "sh"), ("men", "man"),
+                ("ies", "y"),
+            ],
+            PartOfSpeech::Verb => {
+                &[("s", ""), ("ies", "y"), ("es", "e"), ("es", ""), ("ed", "e"), ("ed", ""), ("ing", "e"), ("ing", "")]
+            },
+            PartOfSpeech::Adjective => {
+                &[("er", ""), ("est", ""), ("er", "e"), ("est", "e"), ("ier", "y"), ("iest", "y")]
+            },
+            PartOfSpeech::Adverb => &[],
+        }
+    }
+
+    /// A bit for the part of speech in a [`Lexicon`] entry
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Irregular nouns
+const NOUN_EXCEPTIONS: &[(&str, &str)] = &[
+    ("children", "child"), ("men", "man"), ("women", "woman"), ("people", "person"), ("mice", "mouse"),
+    ("lice", "louse"), ("feet", "foot"), ("teeth", "tooth"), ("geese", "goose"), ("oxen", "ox"),
+    ("dice", "die"), ("leaves", "leaf"), ("lives", "life"), ("knives", "knife"), ("wives", "wife"),
+    ("wolves", "wolf"), ("halves", "half"), ("selves", "self"), ("shelves", "shelf"), ("thieves", "thief"),
+    ("loaves", "loaf"), ("calves", "calf"), ("data", "datum"), ("criteria", "criterion"),
+    ("phenomena", "phenomenon"), ("analyses", "analysis"), ("crises", "crisis"), ("theses", "thesis"),
+    ("indices", "index"), ("matrices", "matrix"), ("vertices", "vertex"), ("cacti", "cactus"),
+    ("fungi", "fungus"), ("nuclei", "nucleus"), ("radii", "radius"), ("stimuli", "stimulus"),
+    ("alumni", "alumnus"), ("bacteria", "bacterium"), ("media", "medium"), ("curricula", "curriculum"),
+];
+
+/// Irregular verb forms
+const VERB_EXCEPTIONS: &[(&str, &str)] = &[
+    ("am", "be"), ("is", "be"), ("are", "be"), ("was", "be"), ("were", "be"), ("been", "be"), ("being", "be"),
+    ("has", "have"), ("had", "have"), ("having", "have"), ("does", "do"), ("did", "do"), ("done", "do"),
+    ("went", "go"), ("gone", "go"), ("goes", "go"), ("ran", "run"), ("saw", "see"), ("seen", "see"),
+    ("took", "take"), ("taken", "take"), ("ate", "eat"), ("eaten", "eat"), ("gave", "give"), ("given", "give"),
+    ("got", "get"), ("gotten", "get"), ("made", "make"), ("said", "say"), ("knew", "know"), ("known", "know"),
+    ("thought", "think"), ("came", "come"), ("became", "become"), ("began", "begin"), ("begun", "begin"),
+    ("brought", "bring"), ("bought", "buy"), ("caught", "catch"), ("taught", "teach"), ("felt", "feel"),
+    ("found", "find"), ("left", "leave"), ("lost", "lose"), ("met", "meet"), ("paid", "pay"), ("sat", "sit"),
+    ("sold", "sell"), ("sent", "send"), ("spoke", "speak"), ("spoken", "speak"), ("stood", "stand"),
+    ("told", "tell"), ("understood", "understand"), ("wrote", "write"), ("written", "write"),
+    ("drove", "drive"), ("driven", "drive"), ("flew", "fly"), ("flown", "fly"), ("grew", "grow"),
+    ("grown", "grow"), ("held", "hold"), ("kept", "keep"), ("led", "lead"), ("meant", "mean"),
+    ("rose", "rise"), ("risen", "rise"), ("sang", "sing"), ("sung", "sing"), ("slept", "sleep"),
+    ("spent", "spend"), ("stole", "steal"), ("stolen", "steal"), ("swam", "swim"), ("swum", "swim"),
+    ("threw", "throw"), ("thrown", "throw"), ("woke", "wake"), ("woken", "wake"), ("wore", "wear"),
+    ("worn", "wear"), ("won", "win"), ("broke", "break"), ("broken", "break"), ("chose", "choose"),
+    ("chosen", "choose"), ("drew", "draw"), ("drawn", "draw"), ("drank", "drink"), ("drunk", "drink"),
+    ("fell", "fall"), ("fallen", "fall"), ("forgot", "forget"), ("forgotten", "forget"), ("froze", "freeze"),
+    ("frozen", "freeze"), ("hid", "hide"), ("hidden", "hide"), ("rode", "ride"), ("ridden", "ride"),
+    ("shook", "shake"), ("shaken", "shake"), ("built", "build"), ("heard", "hear"), ("sought", "seek"),
+    ("fought", "fight"), ("taught", "teach"), ("lay", "lie"), ("lain", "lie"), ("dealt", "deal"),
+    ("dug", "dig"), ("fed", "feed"), ("fled", "flee"), ("hung", "hang"), ("lent", "lend"), ("bled", "bleed"),
+    ("bent", "bend"), ("bit", "bite"), ("bitten", "bite"), ("blew", "blow"), ("blown", "blow"),
+];
+
+/// Irregular comparatives and superlatives
+const ADJECTIVE_EXCEPTIONS: &[(&str, &str)] = &[
+    ("better", "good"), ("best", "good"), ("worse", "bad"), ("worst", "bad"), ("further", "far"),
+    ("farther", "far"), ("furthest", "far"), ("farthest", "far"), ("less", "little"), ("least", "little"),
+    ("more", "much"), ("most", "much"), ("elder", "old"), ("eldest", "old"),
+];
+
+/// Irregular adverb forms
+const ADVERB_EXCEPTIONS: &[(&str, &str)] =
+    &[("better", "well"), ("best", "well"), ("worse", "badly"), ("worst", "badly")];
+
+/// The words a [`Lemmatizer`] accepts as lemmas, each for one or more parts
+/// of speech
+#[derive(Debug, Clone, Default)]
+pub struct Lexicon {
+    /// Lowercase lemmas, with a bit per part of speech
+    words: HashMap<String, u8>,
+}
+
+impl Lexicon {
+    /// Creates an empty lexicon
+    pub fn new() -> Self {
+        Lexicon { words: HashMap::new() }
+    }
+
+    /// Creates a lexicon of words that are lemmas for every part of speech
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut lexicon = Lexicon::new();
+        for word in words { lexicon.insert(word.as_ref(), None); }
+        lexicon
+    }
+
+    /// Parses a word list, the first field of each line being a lemma;
+    /// lines that start with white space or `#` are skipped
+    ///
+    /// This reads WordNet's `index.noun` and the like too, whose license
+    /// lines start with spaces and whose lemmas write spaces as `_`.
+    pub fn parse(&mut self, list: &str, pos: Option<PartOfSpeech>) {
+        for line in list.lines().filter(|line| !line.starts_with([' ', '\t', '#'])) {
+            if let Some(word) = line.split_whitespace().next() {
+                self.insert(word, pos);
+            }
+        }
+    }
+
+    /// Loads a word list file in the format accepted by [`Lexicon::parse`],
+    /// its words being lemmas for every part of speech
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut lexicon = Lexicon::new();
+        lexicon.parse(&fs::read_to_string(path)?, None);
+        Ok(lexicon)
+    }
+
+    /// Adds a lemma for one part of speech, or for all of them
+    pub fn insert(&mut self, word: &str, pos: Option<PartOfSpeech>) {
+        let bits = pos.map_or(u8::MAX, PartOfSpeech::bit);
+        *self.words.entry(word.to_lowercase()).or_default() |= bits;
+    }
+
+    /// Returns true if `word` is a lemma for `pos`
+    pub fn contains(&self, word: &str, pos: PartOfSpeech) -> bool {
+        self.words.get(word).is_some_and(|bits| bits & pos.bit() != 0)
+    }
+
+    /// Number of lemmas in the lexicon
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns true if the lexicon has no lemmas
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+/// A dictionary-based English lemmatizer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::lemmatizer::{Lemmatizer, Lexicon};
+/// let mut lemmatizer = Lemmatizer::new(Lexicon::from_words(["dog", "run", "happy"]));
+/// assert_eq!(lemmatizer.lemmatize("Better"), "good");
+/// assert_eq!(lemmatizer.lemmatize("ran"), "run");
+/// assert_eq!(lemmatizer.lemmatize("dogs"), "dog");
+/// assert_eq!(lemmatizer.lemmatize("running"), "run");
+/// assert_eq!(lemmatizer.lemmatize("happiest"), "happy");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Lemmatizer {
+    lexicon: Lexicon,
+    /// The lemmas of irregular forms, with their parts of speech
+    exceptions: HashMap<String, Vec<(PartOfSpeech, String)>>,
+}
+
+impl Lemmatizer {
+    /// Creates a lemmatizer with the built-in exceptions and `lexicon`
+    pub fn new(lexicon: Lexicon) -> Self {
+        let mut lemmatizer = Lemmatizer { lexicon, exceptions: HashMap::new() };
+        let lists = [
+            (PartOfSpeech::Noun, NOUN_EXCEPTIONS),
+            (PartOfSpeech::Verb, VERB_EXCEPTIONS),
+            (PartOfSpeech::Adjective, ADJECTIVE_EXCEPTIONS),
+            (PartOfSpeech::Adverb, ADVERB_EXCEPTIONS),
+        ];
+        for (pos, list) in lists {
+            for &(form, lemma) in list { lemmatizer.add_exception(form, lemma, pos); }
+        }
+        lemmatizer
+    }
+
+    /// Creates a lemmatizer from a WordNet database directory, reading the
+    /// `index.*` files into the lexicon and the `*.exc` exception files
+    /// along with the built-in exceptions
+    pub fn load_wordnet<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let mut lemmatizer = Lemmatizer::new(Lexicon::new());
+        for &pos in PartOfSpeech::ALL {
+            let index = fs::read_to_string(dir.join(format!("index.{}", pos.wordnet_name())))?;
+            lemmatizer.lexicon.parse(&index, Some(pos));
+            let exceptions = fs::read_to_string(dir.join(format!("{}.exc", pos.wordnet_name())))?;
+            lemmatizer.parse_exceptions(&exceptions, pos);
+        }
+        Ok(lemmatizer)
+    }
+
+    /// Adds exceptions in WordNet's `.exc` format: an inflected form and
+    /// its lemmas on each line ("geese goose")
+    pub fn parse_exceptions(&mut self, list: &str, pos: PartOfSpeech) {
+        for line in list.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(form) = fields.next() else { continue };
+            for lemma in fields { self.add_exception(form, lemma, pos); }
+        }
+    }
+
+    /// Adds one irregular form and its lemma
+    pub fn add_exception(&mut self, form: &str, lemma: &str, pos: PartOfSpeech) {
+        let lemmas = self.exceptions.entry(form.to_lowercase()).or_default();
+        let entry = (pos, lemma.to_lowercase());
+        if !lemmas.contains(&entry) { lemmas.push(entry); }
+    }
+
+    /// The lexicon lemmas are checked against
+    pub fn lexicon(&self) -> &Lexicon {
+        &self.lexicon
+    }
+
+    /// Returns the lemmas of `word` as `pos`, shortest first: the lemmas
+    /// listed for it as an irregular form, or else those of the word
+    /// itself and its rule-based candidates that the lexicon has
+    pub fn lemmas(&self, word: &str, pos: PartOfSpeech) -> Vec<String> {
+        let word = word.to_lowercase();
+        let mut lemmas: Vec<String> = self
+            .exceptions
+            .get(&word)
+            .into_iter()
+            .flatten()
+            .filter(|(p, _)| *p == pos)
+            .map(|(_, lemma)| lemma.clone())
+            .collect();
+        if lemmas.is_empty() {
+            let mut candidates = vec![word.clone()];
+            for &(ending, with) in pos.rules() {
+                let Some(stem) = word.strip_suffix(ending) else { continue };
+                candidates.push(format!("{}{}", stem, with));
+                if with.is_empty() && ending.len() > 1 && ending != "es" {
+                    if let Some(undoubled) = undouble(stem) { candidates.push(undoubled.to_string()); }
+                }
+            }
+            let mut seen = HashSet::new();
+            lemmas = candidates
+                .into_iter()
+                .filter(|c| !c.is_empty() && self.lexicon.contains(c, pos) && seen.insert(c.clone()))
+                .collect();
+        }
+        lemmas.sort_by_key(String::len);
+        lemmas
+    }
+
+    /// Returns the lemma of `word` as `pos`, or `word` lowercased if it has
+    /// none
+    pub fn lemmatize_as(&self, word: &str, pos: PartOfSpeech) -> String {
+        self.lemmas(word, pos).into_iter().next().unwrap_or_else(|| word.to_lowercase())
+    }
+
+    /// Returns the lemma of `word` for whichever part of speech gives one,
+    /// or `word` lowercased if none does
+    pub fn lemmatize(&self, word: &str) -> String {
+        let lower = word.to_lowercase();
+        let irregular = self.exceptions.get(&lower).into_iter().flatten().min_by_key(|(_, lemma)| lemma.len());
+        if let Some((_, lemma)) = irregular {
+            return lemma.clone();
+        }
+        PartOfSpeech::ALL
+            .iter()
+            .flat_map(|&pos| self.lemmas(&lower, pos))
+            .min_by_key(String::len)
+            .unwrap_or(lower)
+    }
+}
+
+/// `stem` without its final letter if it ends with a doubled consonant
+/// other than "l", "s" or "z" ("runn", "bigg")
+fn undouble(stem: &str) -> Option<&str> {
+    let b = stem.as_bytes();
+    let n = b.len();
+    (n >= 3 && b[n - 1] == b[n - 2] && !b"aeioulsz".contains(&b[n - 1]) && b[n - 1].is_ascii_alphabetic())
+        .then(|| &stem[..n - 1])
+}
+
+impl Stemmer for Lemmatizer {
+    fn stem(&mut self, word: &str) -> String {
+        self.lemmatize(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lemmatize() {
+        let lexicon = Lexicon::from_words([
+            "dog", "run", "glass", "glasses", "fly", "box", "church", "leave", "bus", "walk", "happy", "big",
+            "hop", "bless",
+        ]);
+        let lemmatizer = Lemmatizer::new(lexicon);
+        let cases = [
+            ("better", "good"), ("ran", "run"), ("children", "child"), ("Geese", "goose"), ("dogs", "dog"),
+            ("glasses", "glass"), ("flies", "fly"), ("boxes", "box"), ("churches", "church"), ("leaves", "leaf"),
+            ("buses", "bus"), ("running", "run"), ("walked", "walk"), ("happier", "happy"), ("biggest", "big"),
+            ("hopping", "hop"), ("blessed", "bless"), ("xyzzy", "xyzzy"), ("Dog", "dog"),
+        ];
+        for (word, lemma) in cases {
+            assert_eq!(lemmatizer.lemmatize(word), lemma, "{}", word);
+        }
+        assert_eq!(lemmatizer.lemmatize_as("leaves", PartOfSpeech::Verb), "leave");
+        assert_eq!(lemmatizer.lemmatize_as("better", PartOfSpeech::Adverb), "well");
+        assert_eq!(lemmatizer.lemmas("glasses", PartOfSpeech::Noun), ["glass", "glasses"]);
+    }
+
+    #[test]
+    fn test_wordnet_files() {
+        let mut lexicon = Lexicon::new();
+        let index = "  1 This software and database is being provided\nabacus n 1 0 1 0 01\nfree_will n 1\n";
+        lexicon.parse(index, Some(PartOfSpeech::Noun));
+        assert_eq!(lexicon.len(), 2);
+        assert!(lexicon.contains("abacus", PartOfSpeech::Noun));
+        assert!(!lexicon.contains("abacus", PartOfSpeech::Verb));
+
+        let mut lemmatizer = Lemmatizer::new(lexicon);
+        lemmatizer.parse_exceptions("abaci abacus\n", PartOfSpeech::Noun);
+        let mut stemmer: Box<dyn Stemmer> = Box::new(lemmatizer);
+        assert_eq!(stemmer.stem("abaci"), "abacus");
+        assert!(stemmer.same_stem("abacus", "abaci"));
+    }
+}
@@ -0,0 +1,1784 @@
+//! # Porter Stemmer Implementation in Rust
+//! 
+//! This is a Rust implementation of the Porter Stemming algorithm, based on the original
+//! work by Martin Porter (1980). The algorithm reduces English words to their word stem,
+//! base, or root form through a series of systematic steps.
+//! 
+//! specifically based on cannonical ansi c version by Martin Porter 
+//! at https://tartarus.org/martin/PorterStemmer/c.txt
+//! 
+//! ## Algorithm Overview
+//! The Porter Stemmer follows five steps to reduce words to their stems:
+//! 1. Handles plurals and past participles
+//! 2. Handles various suffixes
+//! 3. Deals with -ic-, -full, -ness etc.
+//! 4. Handles -ant, -ence, etc.
+//! 5. Removes final -e and changes -ll to -l in specific contexts
+//! 
+//! ## Reference
+//! Porter, M.F., "An algorithm for suffix stripping", Program, Vol. 14,
+//! No. 3, pp 130-137, July 1980.
+//! 
+//! ## Conformance
+//! The tests stem every word of Martin Porter's test vocabulary (`voc.txt`,
+//! 23,531 words, in `test_data/porter`) and require exactly the stems of his
+//! `output.txt`.
+//! 
+//! ## Usage Example
+//! ```rust
+//! # use m_porter_stemmer_rust::PorterStemmer;
+//! let mut stemmer = PorterStemmer::new();
+//! assert_eq!(stemmer.stem("running"), "run");
+//! assert_eq!(stemmer.stem("capabilities"), "capabl");
+//! ```
+//! 
+//! ## Implementation Notes
+//! - This implementation operates on lowercase ASCII characters only; what
+//!   is done with other words is set with [`PorterStemmer::with_non_ascii`]
+//!   (stemmed by default, or transliterated, kept or rejected)
+//! - Input should be pre-processed to remove non-alphabetic characters
+//! - The algorithm never increases word length
+//! - Words of length 1 or 2 are not stemmed
+//! 
+//! ## Safety and Performance
+//! - Memory safe: works on the word in a bounds-checked byte buffer, held in
+//!   the stemmer up to 32 bytes and spilling to the heap beyond, not raw C buffers
+//! - No unsafe blocks, except in the optional `mmap`, `ffi` and `plugin` modules
+//! - No external dependencies by default. The optional features that bring
+//!   some in are `heapless`, `arrow`, `compress`, `serde`, `sqlite` and
+//!   `async` for the library, and `serve`, `jsonrpc`, `websocket`, `grpc` and
+//!   `config` for the `porterstem` binary; the `[features]` table of
+//!   Cargo.toml says which crates each one uses
+//! - Maintains O(n) time complexity where n is word length
+//! - Everything but the allocation-free `fixed` module needs the default `std`
+//!   feature; without it the crate is `no_std`, for embedded targets
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+pub mod analyzer;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "std")]
+pub mod bm25;
+#[cfg(feature = "compress")]
+pub mod compress;
+#[cfg(feature = "std")]
+pub mod concordance;
+#[cfg(feature = "std")]
+pub mod corpus;
+#[cfg(feature = "std")]
+pub mod csv;
+#[cfg(feature = "detect")]
+pub mod detect;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
+pub mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fixed;
+#[cfg(feature = "std")]
+pub mod fold;
+#[cfg(feature = "std")]
+pub mod french;
+#[cfg(feature = "std")]
+pub mod freq;
+#[cfg(feature = "std")]
+mod fuzzy;
+#[cfg(feature = "std")]
+pub mod german;
+#[cfg(feature = "std")]
+pub mod greek;
+#[cfg(feature = "std")]
+pub mod highlight;
+#[cfg(feature = "std")]
+pub mod hindi;
+#[cfg(feature = "std")]
+pub mod html;
+#[cfg(feature = "std")]
+pub mod hungarian;
+#[cfg(feature = "std")]
+pub mod identifier;
+#[cfg(feature = "std")]
+pub mod index;
+#[cfg(feature = "std")]
+pub mod intern;
+#[cfg(feature = "std")]
+pub mod json;
+#[cfg(feature = "std")]
+pub mod lancaster;
+#[cfg(feature = "std")]
+pub mod lemmatizer;
+#[cfg(feature = "std")]
+pub mod lucene;
+#[cfg(feature = "std")]
+pub mod markdown;
+#[cfg(all(feature = "mmap", unix))]
+pub mod mmap;
+#[cfg(feature = "std")]
+pub mod ngram;
+#[cfg(feature = "std")]
+pub mod pipeline;
+#[cfg(feature = "plugin")]
+pub mod plugin;
+#[cfg(feature = "std")]
+pub mod porter2;
+#[cfg(feature = "std")]
+pub mod portuguese;
+#[cfg(feature = "std")]
+pub mod romanian;
+#[cfg(feature = "std")]
+pub mod russian;
+#[cfg(feature = "std")]
+pub mod sbl;
+#[cfg(feature = "std")]
+pub mod scandinavian;
+#[cfg(feature = "std")]
+pub mod sentence;
+#[cfg(feature = "serde")]
+pub mod serde_stem;
+#[cfg(feature = "std")]
+mod snowball;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod stem_key;
+#[cfg(feature = "std")]
+pub mod stem_map;
+#[cfg(feature = "std")]
+pub mod stem_set;
+#[cfg(feature = "std")]
+pub mod stemmer;
+#[cfg(feature = "std")]
+pub mod stopwords;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod text;
+#[cfg(feature = "std")]
+pub mod tfidf;
+#[cfg(feature = "std")]
+pub mod token;
+#[cfg(feature = "std")]
+pub mod turkish;
+#[cfg(feature = "std")]
+pub mod uea_lite;
+#[cfg(feature = "std")]
+mod word_buffer;
+
+#[cfg(feature = "std")]
+pub use error::StemError;
+#[cfg(feature = "std")]
+pub use french::FrenchStemmer;
+#[cfg(feature = "std")]
+pub use german::GermanStemmer;
+#[cfg(feature = "std")]
+pub use greek::GreekStemmer;
+#[cfg(feature = "std")]
+pub use hindi::HindiStemmer;
+#[cfg(feature = "std")]
+pub use hungarian::HungarianStemmer;
+#[cfg(feature = "std")]
+pub use lancaster::LancasterStemmer;
+#[cfg(feature = "std")]
+pub use lucene::LuceneStemmer;
+#[cfg(feature = "std")]
+pub use porter2::Porter2Stemmer;
+#[cfg(feature = "std")]
+pub use portuguese::PortugueseStemmer;
+#[cfg(feature = "std")]
+pub use romanian::RomanianStemmer;
+#[cfg(feature = "std")]
+pub use russian::RussianStemmer;
+#[cfg(feature = "std")]
+pub use scandinavian::ScandinavianStemmer;
+#[cfg(feature = "std")]
+pub use stemmer::{stem_with_language, Algorithm, Language, Stemmer};
+#[cfg(feature = "std")]
+pub use turkish::TurkishStemmer;
+#[cfg(feature = "std")]
+pub use uea_lite::UeaLiteStemmer;
+#[cfg(feature = "std")]
+use word_buffer::WordBuffer;
+
+// https://tartarus.org/martin/PorterStemmer/c.txt
+// /* This is the Porter stemming algorithm, coded up in ANSI C by the
+//    author. It may be be regarded as canonical, in that it follows the
+//    algorithm presented in
+
+//    Porter, 1980, An algorithm for suffix stripping, Program, Vol. 14,
+//    no. 3, pp 130-137,
+
+//    only differing from it at the points marked --DEPARTURE-- below.
+
+//    See also http://www.tartarus.org/~martin/PorterStemmer
+
+//    The algorithm as described in the paper could be exactly replicated
+//    by adjusting the points of DEPARTURE, but this is barely necessary,
+//    because (a) the points of DEPARTURE are definitely improvements, and
+//    (b) no encoding of the Porter stemmer I have seen is anything like
+//    as exact as this version, even with the points of DEPARTURE!
+
+//    You can compile it on Unix with 'gcc -O3 -o stem stem.c' after which
+//    'stem' takes a list of inputs and sends the stemmed equivalent to
+//    stdout.
+
+//    The algorithm as encoded here is particularly fast.
+
+//    Release 1: was many years ago
+//    Release 2: 11 Apr 2013
+//        fixes a bug noted by Matt Patenaude <matt@mattpatenaude.com>,
+
+//        case 'o': if (ends("\03" "ion") && (b[j] == 's' || b[j] == 't')) break;
+//            ==>
+//        case 'o': if (ends("\03" "ion") && j >= k0 && (b[j] == 's' || b[j] == 't')) break;
+
+//        to avoid accessing b[k0-1] when the word in b is "ion".
+//    Release 3: 25 Mar 2014
+//        fixes a similar bug noted by Klemens Baum <klemensbaum@gmail.com>,
+//        that if step1ab leaves a one letter result (ied -> i, aing -> a etc),
+//        step2 and step4 access the byte before the first letter. So we skip
+//        steps after step1ab unless k > k0.
+// */
+
+// #include <string.h>  /* for memmove */
+
+// #define TRUE 1
+// #define FALSE 0
+
+// /* The main part of the stemming algorithm starts here. b is a buffer
+//    holding a word to be stemmed. The letters are in b[k0], b[k0+1] ...
+//    ending at b[k]. In fact k0 = 0 in this demo program. k is readjusted
+//    downwards as the stemming progresses. Zero termination is not in fact
+//    used in the algorithm.
+
+//    Note that only lower case sequences are stemmed. Forcing to lower case
+//    should be done before stem(...) is called.
+// */
+
+// static char * b;       /* buffer for word to be stemmed */
+// static int k,k0,j;     /* j is a general offset into the string */
+
+// /* cons(i) is TRUE <=> b[i] is a consonant. */
+
+// static int cons(int i)
+// {  switch (b[i])
+//    {  case 'a': case 'e': case 'i': case 'o': case 'u': return FALSE;
+//       case 'y': return (i==k0) ? TRUE : !cons(i-1);
+//       default: return TRUE;
+//    }
+// }
+
+// /* m() measures the number of consonant sequences between k0 and j. if c is
+//    a consonant sequence and v a vowel sequence, and <..> indicates arbitrary
+//    presence,
+
+//       <c><v>       gives 0
+//       <c>vc<v>     gives 1
+//       <c>vcvc<v>   gives 2
+//       <c>vcvcvc<v> gives 3
+//       ....
+// */
+
+// static int m()
+// {  int n = 0;
+//    int i = k0;
+//    while(TRUE)
+//    {  if (i > j) return n;
+//       if (! cons(i)) break; i++;
+//    }
+//    i++;
+//    while(TRUE)
+//    {  while(TRUE)
+//       {  if (i > j) return n;
+//             if (cons(i)) break;
+//             i++;
+//       }
+//       i++;
+//       n++;
+//       while(TRUE)
+//       {  if (i > j) return n;
+//          if (! cons(i)) break;
+//          i++;
+//       }
+//       i++;
+//    }
+// }
+
+// /* vowelinstem() is TRUE <=> k0,...j contains a vowel */
+
+// static int vowelinstem()
+// {  int i; for (i = k0; i <= j; i++) if (! cons(i)) return TRUE;
+//    return FALSE;
+// }
+
+// /* doublec(j) is TRUE <=> j,(j-1) contain a double consonant. */
+
+// static int doublec(int j)
+// {  if (j < k0+1) return FALSE;
+//    if (b[j] != b[j-1]) return FALSE;
+//    return cons(j);
+// }
+
+// /* cvc(i) is TRUE <=> i-2,i-1,i has the form consonant - vowel - consonant
+//    and also if the second c is not w,x or y. this is used when trying to
+//    restore an e at the end of a short word. e.g.
+
+//       cav(e), lov(e), hop(e), crim(e), but
+//       snow, box, tray.
+
+// */
+
+// static int cvc(int i)
+// {  if (i < k0+2 || !cons(i) || cons(i-1) || !cons(i-2)) return FALSE;
+//    {  int ch = b[i];
+//       if (ch == 'w' || ch == 'x' || ch == 'y') return FALSE;
+//    }
+//    return TRUE;
+// }
+
+// /* ends(s) is TRUE <=> k0,...k ends with the string s. */
+
+// static int ends(char * s)
+// {  int length = s[0];
+//    if (s[length] != b[k]) return FALSE; /* tiny speed-up */
+//    if (length > k-k0+1) return FALSE;
+//    if (memcmp(b+k-length+1,s+1,length) != 0) return FALSE;
+//    j = k-length;
+//    return TRUE;
+// }
+
+// /* setto(s) sets (j+1),...k to the characters in the string s, readjusting
+//    k. */
+
+// static void setto(char * s)
+// {  int length = s[0];
+//    memmove(b+j+1,s+1,length);
+//    k = j+length;
+// }
+
+// /* r(s) is used further down. */
+
+// static void r(char * s) { if (m() > 0) setto(s); }
+
+// /* step1ab() gets rid of plurals and -ed or -ing. e.g.
+
+//        caresses  ->  caress
+//        ponies    ->  poni
+//        ties      ->  ti
+//        caress    ->  caress
+//        cats      ->  cat
+
+//        feed      ->  feed
+//        agreed    ->  agree
+//        disabled  ->  disable
+
+//        matting   ->  mat
+//        mating    ->  mate
+//        meeting   ->  meet
+//        milling   ->  mill
+//        messing   ->  mess
+
+//        meetings  ->  meet
+
+// */
+
+// static void step1ab()
+// {  if (b[k] == 's')
+//    {  if (ends("\04" "sses")) k -= 2; else
+//       if (ends("\03" "ies")) setto("\01" "i"); else
+//       if (b[k-1] != 's') k--;
+//    }
+//    if (ends("\03" "eed")) { if (m() > 0) k--; } else
+//    if ((ends("\02" "ed") || ends("\03" "ing")) && vowelinstem())
+//    {  k = j;
+//       if (ends("\02" "at")) setto("\03" "ate"); else
+//       if (ends("\02" "bl")) setto("\03" "ble"); else
+//       if (ends("\02" "iz")) setto("\03" "ize"); else
+//       if (doublec(k))
+//       {  k--;
+//          {  int ch = b[k];
+//             if (ch == 'l' || ch == 's' || ch == 'z') k++;
+//          }
+//       }
+//       else if (m() == 1 && cvc(k)) setto("\01" "e");
+//    }
+// }
+
+// /* step1c() turns terminal y to i when there is another vowel in the stem. */
+
+// static void step1c() { if (ends("\01" "y") && vowelinstem()) b[k] = 'i'; }
+
+
+// /* step2() maps double suffices to single ones. so -ization ( = -ize plus
+//    -ation) maps to -ize etc. note that the string before the suffix must give
+//    m() > 0. */
+
+// static void step2() { switch (b[k-1])
+// {
+//     case 'a': if (ends("\07" "ational")) { r("\03" "ate"); break; }
+//               if (ends("\06" "tional")) { r("\04" "tion"); break; }
+//               break;
+//     case 'c': if (ends("\04" "enci")) { r("\04" "ence"); break; }
+//               if (ends("\04" "anci")) { r("\04" "ance"); break; }
+//               break;
+//     case 'e': if (ends("\04" "izer")) { r("\03" "ize"); break; }
+//               break;
+//     case 'l': if (ends("\03" "bli")) { r("\03" "ble"); break; } /*-DEPARTURE-*/
+
+//  /* To match the published algorithm, replace this line with
+//     case 'l': if (ends("\04" "abli")) { r("\04" "able"); break; } */
+
+//               if (ends("\04" "alli")) { r("\02" "al"); break; }
+//               if (ends("\05" "entli")) { r("\03" "ent"); break; }
+//               if (ends("\03" "eli")) { r("\01" "e"); break; }
+//               if (ends("\05" "ousli")) { r("\03" "ous"); break; }
+//               break;
+//     case 'o': if (ends("\07" "ization")) { r("\03" "ize"); break; }
+//               if (ends("\05" "ation")) { r("\03" "ate"); break; }
+//               if (ends("\04" "ator")) { r("\03" "ate"); break; }
+//               break;
+//     case 's': if (ends("\05" "alism")) { r("\02" "al"); break; }
+//               if (ends("\07" "iveness")) { r("\03" "ive"); break; }
+//               if (ends("\07" "fulness")) { r("\03" "ful"); break; }
+//               if (ends("\07" "ousness")) { r("\03" "ous"); break; }
+//               break;
+//     case 't': if (ends("\05" "aliti")) { r("\02" "al"); break; }
+//               if (ends("\05" "iviti")) { r("\03" "ive"); break; }
+//               if (ends("\06" "biliti")) { r("\03" "ble"); break; }
+//               break;
+//     case 'g': if (ends("\04" "logi")) { r("\03" "log"); break; } /*-DEPARTURE-*/
+
+//  /* To match the published algorithm, delete this line */
+
+// } }
+
+// /* step3() deals with -ic-, -full, -ness etc. similar strategy to step2. */
+
+// static void step3() { switch (b[k])
+// {
+//     case 'e': if (ends("\05" "icate")) { r("\02" "ic"); break; }
+//               if (ends("\05" "ative")) { r("\00" ""); break; }
+//               if (ends("\05" "alize")) { r("\02" "al"); break; }
+//               break;
+//     case 'i': if (ends("\05" "iciti")) { r("\02" "ic"); break; }
+//               break;
+//     case 'l': if (ends("\04" "ical")) { r("\02" "ic"); break; }
+//               if (ends("\03" "ful")) { r("\00" ""); break; }
+//               break;
+//     case 's': if (ends("\04" "ness")) { r("\00" ""); break; }
+//               break;
+// } }
+
+// /* step4() takes off -ant, -ence etc., in context <c>vcvc<v>. */
+
+// static void step4()
+// {  switch (b[k-1])
+//     {  case 'a': if (ends("\02" "al")) break; return;
+//        case 'c': if (ends("\04" "ance")) break;
+//                  if (ends("\04" "ence")) break; return;
+//        case 'e': if (ends("\02" "er")) break; return;
+//        case 'i': if (ends("\02" "ic")) break; return;
+//        case 'l': if (ends("\04" "able")) break;
+//                  if (ends("\04" "ible")) break; return;
+//        case 'n': if (ends("\03" "ant")) break;
+//                  if (ends("\05" "ement")) break;
+//                  if (ends("\04" "ment")) break;
+//                  if (ends("\03" "ent")) break; return;
+//        case 'o': if (ends("\03" "ion") && j >= k0 && (b[j] == 's' || b[j] == 't')) break;
+//                  if (ends("\02" "ou")) break; return;
+//                  /* takes care of -ous */
+//        case 's': if (ends("\03" "ism")) break; return;
+//        case 't': if (ends("\03" "ate")) break;
+//                  if (ends("\03" "iti")) break; return;
+//        case 'u': if (ends("\03" "ous")) break; return;
+//        case 'v': if (ends("\03" "ive")) break; return;
+//        case 'z': if (ends("\03" "ize")) break; return;
+//        default: return;
+//     }
+//     if (m() > 1) k = j;
+// }
+
+// /* step5() removes a final -e if m() > 1, and changes -ll to -l if
+//    m() > 1. */
+
+// static void step5()
+// {  j = k;
+//    if (b[k] == 'e')
+//    {  int a = m();
+//       if (a > 1 || a == 1 && !cvc(k-1)) k--;
+//    }
+//    if (b[k] == 'l' && doublec(k) && m() > 1) k--;
+// }
+
+// /* In stem(p,i,j), p is a char pointer, and the string to be stemmed is from
+//    p[i] to p[j] inclusive. Typically i is zero and j is the offset to the last
+//    character of a string, (p[j+1] == '\0'). The stemmer adjusts the
+//    characters p[i] ... p[j] and returns the new end-point of the string, k.
+//    Stemming never increases word length, so i <= k <= j. To turn the stemmer
+//    into a module, declare 'stem' as extern, and delete the remainder of this
+//    file.
+// */
+
+// int stem(char * p, int i, int j)
+// {  b = p; k = j; k0 = i; /* copy the parameters into statics */
+//    if (k <= k0+1) return k; /*-DEPARTURE-*/
+
+//    /* With this line, strings of length 1 or 2 don't go through the
+//       stemming process, although no mention is made of this in the
+//       published algorithm. Remove the line to match the published
+//       algorithm. */
+
+//    step1ab();
+//    if (k > k0) {
+//        step1c(); step2(); step3(); step4(); step5();
+//    }
+//    return k;
+// }
+
+// /*--------------------stemmer definition ends here------------------------*/
+
+// #include <stdio.h>
+// #include <stdlib.h>      /* for malloc, free */
+// #include <ctype.h>       /* for isupper, islower, tolower */
+
+// static char * s;         /* a char * (=string) pointer; passed into b above */
+
+// #define INC 50           /* size units in which s is increased */
+// static int i_max = INC;  /* maximum offset in s */
+
+// void increase_s()
+// {  i_max += INC;
+//    {  char * new_s = (char *) malloc(i_max+1);
+//       { int i; for (i = 0; i < i_max; i++) new_s[i] = s[i]; } /* copy across */
+//       free(s); s = new_s;
+//    }
+// }
+
+// #define LETTER(ch) (isupper(ch) || islower(ch))
+
+// static void stemfile(FILE * f)
+// {  while(TRUE)
+//    {  int ch = getc(f);
+//       if (ch == EOF) return;
+//       if (LETTER(ch))
+//       {  int i = 0;
+//          while(TRUE)
+//          {  if (i == i_max) increase_s();
+
+//             ch = tolower(ch); /* forces lower case */
+
+//             s[i] = ch; i++;
+//             ch = getc(f);
+//             if (!LETTER(ch)) { ungetc(ch,f); break; }
+//          }
+//          s[stem(s,0,i-1)+1] = 0;
+//          /* the previous line calls the stemmer and uses its result to
+//             zero-terminate the string in s */
+//          printf("%s",s);
+//       }
+//       else putchar(ch);
+//    }
+// }
+
+// int main(int argc, char * argv[])
+// {  int i;
+//    s = (char *) malloc(i_max+1);
+//    for (i = 1; i < argc; i++)
+//    {  FILE * f = fopen(argv[i],"r");
+//       if (f == 0) { fprintf(stderr,"File %s not found\n",argv[i]); exit(1); }
+//       stemfile(f);
+//    }
+//    free(s);
+//    return 0;
+// }
+
+
+
+/// The byte standing for a non-ASCII letter in the stemmer's buffer
+#[cfg(feature = "std")]
+const NON_ASCII: u8 = 0x80;
+
+/// The letter at a position of the buffer: the byte itself if it is ASCII,
+/// else the letter `wide` holds there
+#[cfg(feature = "std")]
+fn unit(byte: u8, wide: char) -> char {
+    if byte.is_ascii() { char::from(byte) } else { wide }
+}
+
+/// An ending and what replaces it
+#[cfg(feature = "std")]
+type Rule = (&'static str, &'static str);
+
+/// A step's rules for each byte: those a word can match when it is the
+/// last letter (step 3) or the one before (steps 2 and 4), in the order the
+/// C program tries them
+#[cfg(feature = "std")]
+type Dispatch = [&'static [Rule]; 128];
+
+#[cfg(feature = "std")]
+const fn dispatch(groups: &[(u8, &'static [Rule])]) -> Dispatch {
+    let mut table: Dispatch = [&[]; 128];
+    let mut i = 0;
+    while i < groups.len() {
+        table[groups[i].0 as usize] = groups[i].1;
+        i += 1;
+    }
+    table
+}
+
+/// The rules for `byte` in `table`, none for a non-ASCII letter
+#[cfg(feature = "std")]
+fn rules(table: &Dispatch, byte: u8) -> &'static [Rule] {
+    table.get(usize::from(byte)).copied().unwrap_or_default()
+}
+
+/// Step 2, by the letter before the last: double suffixes to single ones
+#[cfg(feature = "std")]
+static STEP2: Dispatch = dispatch(&[
+    (b'a', &[("ational", "ate"), ("tional", "tion")]),
+    (b'c', &[("enci", "ence"), ("anci", "ance")]),
+    (b'e', &[("izer", "ize")]),
+    (b'l', &[("bli", "ble"), ("alli", "al"), ("entli", "ent"), ("eli", "e"), ("ousli", "ous")]),
+    (b'o', &[("ization", "ize"), ("ation", "ate"), ("ator", "ate")]),
+    (b's', &[("alism", "al"), ("iveness", "ive"), ("fulness", "ful"), ("ousness", "ous")]),
+    (b't', &[("aliti", "al"), ("iviti", "ive"), ("biliti", "ble")]),
+    (b'g', &[("logi", "log")]),
+]);
+
+/// Step 3, by the last letter: -ic-, -full, -ness etc.
+#[cfg(feature = "std")]
+static STEP3: Dispatch = dispatch(&[
+    (b'e', &[("icate", "ic"), ("ative", ""), ("alize", "al")]),
+    (b'i', &[("iciti", "ic")]),
+    (b'l', &[("ical", "ic"), ("ful", "")]),
+    (b's', &[("ness", "")]),
+]);
+
+/// Step 4, by the letter before the last: the endings removed, all but
+/// -ion, which step 4 checks itself
+#[cfg(feature = "std")]
+static STEP4: Dispatch = dispatch(&[
+    (b'a', &[("al", "")]),
+    (b'c', &[("ance", ""), ("ence", "")]),
+    (b'e', &[("er", "")]),
+    (b'i', &[("ic", "")]),
+    (b'l', &[("able", ""), ("ible", "")]),
+    (b'n', &[("ant", ""), ("ement", ""), ("ment", ""), ("ent", "")]),
+    (b's', &[("ism", "")]),
+    (b't', &[("ate", ""), ("iti", "")]),
+    (b'u', &[("ous", "")]),
+    (b'v', &[("ive", "")]),
+    (b'z', &[("ize", "")]),
+]);
+
+#[cfg(feature = "std")]
+/// Porter Stemmer struct that maintains the state during stemming operations
+#[derive(Debug)]
+pub struct PorterStemmer {
+    /// Buffer holding the word being processed, one byte per letter: the
+    /// letter itself if it is ASCII, or [`NON_ASCII`] or the byte after it
+    buffer: WordBuffer,
+    /// The letters of a word that is not all ASCII, by position in the
+    /// buffer, and empty for an ASCII word
+    wide: Vec<char>,
+    /// Second buffer holding one stem while another is computed
+    scratch: WordBuffer,
+    /// The letters of the stem in `scratch`, as `wide` holds them
+    scratch_wide: Vec<char>,
+    /// Working row for edit distances between stems
+    distance_row: Vec<usize>,
+    /// Current end position in buffer
+    k: usize,
+    /// Start position in buffer (typically 0)
+    k0: usize,
+    /// General offset used in various operations
+    j: usize,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+    /// What is done with words that are not all ASCII
+    non_ascii: fold::NonAsciiPolicy,
+    /// Whether words are stemmed as Lucene's PorterStemFilter stems them:
+    /// as given, without lowercasing, and one UTF-16 unit at a time
+    lucene: bool,
+    /// Steps recorded by [`PorterStemmer::explain`], while it runs
+    trace: Option<Vec<explain::Step>>,
+    /// While tracing, the end of the stem before the first ending matched in
+    /// the current step
+    matched: Option<usize>,
+}
+
+#[cfg(feature = "std")]
+impl Default for PorterStemmer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl PorterStemmer {
+    /// Creates a new Porter Stemmer instance
+    /// 
+    /// # Returns
+    /// A new `PorterStemmer` with empty buffer and initialized indices
+    pub fn new() -> Self {
+        PorterStemmer {
+            buffer: WordBuffer::new(),
+            wide: Vec::new(),
+            scratch: WordBuffer::new(),
+            scratch_wide: Vec::new(),
+            distance_row: Vec::new(),
+            k: 0,
+            k0: 0,
+            j: 0,
+            fold_diacritics: false,
+            non_ascii: fold::NonAsciiPolicy::Stem,
+            lucene: false,
+            trace: None,
+            matched: None,
+        }
+    }
+
+    /// Enables or disables the diacritic folding pre-pass
+    ///
+    /// When enabled, words (and, in the text pipeline, whole texts) are run
+    /// through [`fold::fold_diacritics`] first, so "résumé" stems like "resume".
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::PorterStemmer;
+    /// let mut stemmer = PorterStemmer::new().with_diacritic_folding(true);
+    /// assert_eq!(stemmer.stem("résumés"), "resum");
+    /// ```
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns true if the diacritic folding pre-pass is enabled
+    pub fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics || self.non_ascii == fold::NonAsciiPolicy::Transliterate
+    }
+
+    /// Sets what is done with words that are not all ASCII (by default they
+    /// are stemmed, see [`fold::NonAsciiPolicy`])
+    pub fn with_non_ascii(mut self, policy: fold::NonAsciiPolicy) -> Self {
+        self.non_ascii = policy;
+        self
+    }
+
+    /// Returns the policy for words that are not all ASCII
+    pub fn non_ascii_policy(&self) -> fold::NonAsciiPolicy {
+        self.non_ascii
+    }
+
+    /// Stems `word` as [`PorterStemmer::stem`] does, but fails if it is
+    /// empty or, with [`fold::NonAsciiPolicy::Reject`], not all ASCII
+    pub fn try_stem(&mut self, word: &str) -> Result<String, StemError> {
+        if word.is_empty() {
+            return Err(StemError::EmptyInput);
+        }
+        if self.non_ascii == fold::NonAsciiPolicy::Reject {
+            StemError::check_ascii(word)?;
+        }
+        Ok(self.stem(word))
+    }
+
+    /// Stems `word` into `out` and returns the stem, without allocating
+    /// once the stemmer's buffers have grown to the length of the words
+    ///
+    /// For indexers that keep their terms in their own buffers, such as an
+    /// arena. It fails as [`PorterStemmer::try_stem`] does, and if the stem
+    /// is longer than `out`; the stem is never longer than the word, so a
+    /// buffer as long as the longest word is enough. Folding diacritics
+    /// still allocates for a word that is not all ASCII.
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::{PorterStemmer, StemError};
+    /// let mut stemmer = PorterStemmer::new();
+    /// let mut term = [0; 16];
+    /// assert_eq!(stemmer.stem_to_slice("Connections", &mut term).unwrap(), "connect");
+    /// let error = stemmer.stem_to_slice("Connections", &mut term[..4]).unwrap_err();
+    /// assert!(matches!(error, StemError::BufferTooSmall { needed: 7, capacity: 4 }));
+    /// ```
+    pub fn stem_to_slice<'o>(&mut self, word: &str, out: &'o mut [u8]) -> Result<&'o str, StemError> {
+        if word.is_empty() {
+            return Err(StemError::EmptyInput);
+        }
+        if self.non_ascii == fold::NonAsciiPolicy::Reject {
+            StemError::check_ascii(word)?;
+        }
+        let len = self.stem_into_buffer(word);
+        let stem = &self.buffer[..len];
+        let needed = if self.wide.is_empty() { len } else { self.chars(0, stem).map(char::len_utf8).sum() };
+        if needed > out.len() {
+            return Err(StemError::BufferTooSmall { needed, capacity: out.len() });
+        }
+        if self.wide.is_empty() {
+            out[..len].copy_from_slice(stem);
+        } else {
+            let mut at = 0;
+            for c in self.chars(0, stem) {
+                at += c.encode_utf8(&mut out[at..]).len();
+            }
+        }
+        // Only whole characters were written
+        Ok(std::str::from_utf8(&out[..needed]).unwrap_or_default())
+    }
+
+    /// Determines if a character at position i is a consonant
+    /// 
+    /// # Arguments
+    /// * `i` - Index in the buffer to check
+    /// 
+    /// # Returns
+    /// * `true` if the character is a consonant
+    /// * `false` if the character is a vowel
+    /// 
+    /// # Notes
+    /// - A consonant is defined as any letter other than A, E, I, O, or U
+    /// - Y is considered a consonant when:
+    ///   1. It's the first letter (k0)
+    ///   2. The previous letter is a consonan
+    ///
+    /// Returns true if the character at position i is a consonant
+    fn is_consonant(&self, i: usize) -> bool {
+        match self.buffer[i] {
+            b'a' | b'e' | b'i' | b'o' | b'u' => false,
+            b'y' => if i == self.k0 {
+                true
+            } else {
+                !self.is_consonant(i - 1)
+            },
+            _ => true,
+        }
+    }
+
+    /// Measures the number of consonant sequences between k0 and j
+    /// 
+    /// # Returns
+    /// The number of consonant-vowel sequences (measure)
+    /// 
+    /// # Examples
+    /// - TR.A gives measure 1
+    /// - TRE.A gives measure 1
+    /// - Y gives measure 0
+    /// - BY gives measure 1
+    /// 
+    /// Where '.' indicates the current position
+    fn measure(&self) -> usize {
+        let mut n = 0;
+        let mut i = self.k0;
+        let end = self.stem_end();
+        
+        loop {
+            if i >= end { return n; }
+            if !self.is_consonant(i) { break; }
+            i += 1;
+        }
+        
+        i += 1;
+        
+        loop {
+            loop {
+                if i >= end { return n; }
+                if self.is_consonant(i) { break; }
+                i += 1;
+            }
+            
+            i += 1;
+            n += 1;
+            
+            loop {
+                if i >= end { return n; }
+                if !self.is_consonant(i) { break; }
+                i += 1;
+            }
+            
+            i += 1;
+        }
+    }
+
+    /// Returns true if k0,...j contains a vowel
+    fn vowel_in_stem(&self) -> bool {
+        (self.k0..self.stem_end()).any(|i| !self.is_consonant(i))
+    }
+
+    /// One past j, the end of the stem before the ending last matched
+    ///
+    /// When the ending is the whole word, j is one before k0: -1 in the C
+    /// program, and here `usize::MAX`, so this is 0 and the stem is empty.
+    fn stem_end(&self) -> usize {
+        self.j.wrapping_add(1)
+    }
+
+    /// Returns true if j,(j-1) contain a double consonant
+    fn double_consonant(&self, j: usize) -> bool {
+        if j < self.k0 + 1 { return false; }
+        if self.buffer[j] != self.buffer[j-1] { return false; }
+        self.is_consonant(j)
+    }
+
+    /// Returns true if i-2,i-1,i has the form consonant-vowel-consonant
+    /// and also if the second c is not w,x or y
+    fn cvc(&self, i: usize) -> bool {
+        if i < self.k0 + 2 
+            || !self.is_consonant(i)
+            || self.is_consonant(i-1)
+            || !self.is_consonant(i-2) {
+            return false;
+        }
+        
+        !matches!(self.buffer[i], b'w' | b'x' | b'y')
+    }
+
+    /// Returns true if the word ends with the given string, compared as
+    /// bytes in place: the steps call this for every ending they try
+    fn ends_with(&mut self, s: &str) -> bool {
+        let length = s.len();
+        // k >= k0 whenever a word is being stemmed, so this cannot wrap
+        if length > self.k + 1 - self.k0 { return false; }
+        
+        if &self.buffer[(self.k + 1 - length)..=self.k] != s.as_bytes() { return false; }
+        
+        self.j = (self.k + 1 - length).wrapping_sub(1);
+        if self.trace.is_some() && self.matched.is_none() {
+            self.matched = Some(self.j);
+        }
+        true
+    }
+
+    /// Sets (j+1),...k to the characters in the string s, copied in place
+    fn set_to(&mut self, s: &str) {
+        let start = self.stem_end();
+        self.buffer[start..start + s.len()].copy_from_slice(s.as_bytes());
+        self.k = self.j.wrapping_add(s.len());
+    }
+
+    /// Main stemming function that processes a word through all steps
+    /// 
+    /// # Arguments
+    /// * `word` - Input word to be stemmed
+    /// 
+    /// # Returns
+    /// The stemmed word as a String
+    /// 
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::PorterStemmer;
+    /// let mut stemmer = PorterStemmer::new();
+    /// assert_eq!(stemmer.stem("running"), "run");
+    /// ```
+    /// 
+    /// # Process
+    /// 1. Converts input to lowercase
+    /// 2. Applies steps 1a through 5 in sequence
+    /// 3. Returns the stemmed result
+    pub fn stem(&mut self, word: &str) -> String {
+        let len = self.stem_into_buffer(word);
+        self.text(0, &self.buffer[..len])
+    }
+
+    /// The text of `bytes`, found from `start` in the buffer, with each
+    /// non-ASCII letter taken back from `wide`
+    fn text(&self, start: usize, bytes: &[u8]) -> String {
+        if self.wide.is_empty() {
+            return std::str::from_utf8(bytes).unwrap_or_default().to_string();
+        }
+        let mut text = String::with_capacity(self.chars(start, bytes).map(char::len_utf8).sum());
+        text.extend(self.chars(start, bytes));
+        text
+    }
+
+    /// The characters of `bytes`, found from `start` in the buffer: the
+    /// letters, the low surrogate of each pair left out for Lucene
+    fn chars<'a>(&'a self, start: usize, bytes: &'a [u8]) -> impl Iterator<Item = char> + 'a {
+        let letter = move |i: usize| unit(bytes[i], self.wide.get(start + i).copied().unwrap_or_default());
+        (0..bytes.len()).filter(move |&i| !(self.lucene && i > 0 && letter(i - 1).len_utf16() == 2)).map(letter)
+    }
+
+    /// Fills the buffer with one byte for each letter in `wide`
+    ///
+    /// Non-ASCII letters are consonants to the algorithm, and only ever
+    /// compared with the letter before them, by `double_consonant`: so each
+    /// is [`NON_ASCII`] or the byte after it, the same byte as the letter
+    /// before if that is the same letter and a different one otherwise.
+    fn fill_from_wide(&mut self) {
+        self.buffer.clear();
+        for (i, &c) in self.wide.iter().enumerate() {
+            let byte = match i.checked_sub(1).map(|p| (self.wide[p], self.buffer[p])) {
+                _ if c.is_ascii() => c as u8,
+                Some((before, byte)) if !before.is_ascii() => if before == c { byte } else { byte ^ 1 },
+                _ => NON_ASCII,
+            };
+            self.buffer.push(byte);
+        }
+    }
+
+    /// The letter at position i of the buffer
+    fn unit(&self, i: usize) -> char {
+        unit(self.buffer[i], self.wide.get(i).copied().unwrap_or_default())
+    }
+
+    /// The letter at position i of the stem kept in `scratch`
+    fn scratch_unit(&self, i: usize) -> char {
+        unit(self.scratch[i], self.scratch_wide.get(i).copied().unwrap_or_default())
+    }
+
+    /// Keeps the stem just computed in `scratch`, to compute another
+    fn swap_buffers(&mut self) {
+        std::mem::swap(&mut self.buffer, &mut self.scratch);
+        std::mem::swap(&mut self.wide, &mut self.scratch_wide);
+    }
+
+    /// Stems `word`, recording each step of the algorithm that changes it
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::PorterStemmer;
+    /// let explanation = PorterStemmer::new().explain("hopeful");
+    /// assert_eq!(explanation.stem, "hope");
+    /// assert_eq!(explanation.steps[0].name, "3");
+    /// assert_eq!((explanation.steps[0].removed.as_str(), explanation.steps[0].measure), ("ful", 1));
+    /// ```
+    pub fn explain(&mut self, word: &str) -> explain::Explanation {
+        self.trace = Some(Vec::new());
+        let stem = self.stem(word);
+        explain::Explanation { word: word.to_string(), steps: self.trace.take().unwrap_or_default(), stem }
+    }
+
+    /// The word as it stands, if the steps are being traced
+    fn snapshot(&mut self) -> Option<Vec<u8>> {
+        self.matched = None;
+        self.trace.as_ref().map(|_| self.buffer[self.k0..=self.k].to_vec())
+    }
+
+    /// Records the step `name` if it changed the word from `before`
+    ///
+    /// The replaced ending is the one the step matched, or for step 5, which
+    /// matches none, what differs between the word before and after.
+    fn record(&mut self, name: &'static str, before: Option<Vec<u8>>) {
+        let Some(before) = before else { return };
+        let after = &self.buffer[self.k0..=self.k];
+        if before[..] == *after { return; }
+        let common = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+        let kept = self.matched.take().map_or(common, |j| j.wrapping_add(1).saturating_sub(self.k0).min(common));
+        let step = explain::Step {
+            name,
+            before: self.text(self.k0, &before),
+            after: self.text(self.k0, after),
+            removed: self.text(self.k0 + kept, &before[kept..]),
+            added: self.text(self.k0 + kept, &after[kept..]),
+            measure: 0,
+        };
+        let saved = self.j;
+        let measure = if kept == 0 { 0 } else { self.j = self.k0 + kept - 1; self.measure() };
+        self.j = saved;
+        if let Some(trace) = &mut self.trace {
+            trace.push(explain::Step { measure, ..step });
+        }
+    }
+
+    /// Runs one step of the algorithm, recording it if tracing
+    fn run_step(&mut self, name: &'static str, step: fn(&mut Self)) {
+        let before = self.snapshot();
+        step(self);
+        self.record(name, before);
+    }
+
+    /// Runs the algorithm on `word`, leaving the stem in `buffer[..len]`
+    ///
+    /// # Returns
+    /// The length of the stem in letters, or in UTF-16 units for Lucene
+    fn stem_into_buffer(&mut self, given: &str) -> usize {
+        let word = if self.folds_diacritics() {
+            fold::fold_diacritics(given)
+        } else {
+            std::borrow::Cow::Borrowed(given)
+        };
+
+        // Convert to lowercase into the reused buffer
+        self.buffer.clear();
+        self.wide.clear();
+        if word.is_ascii() {
+            if self.lucene {
+                self.buffer.extend_from_slice(word.as_bytes());
+            } else {
+                self.buffer.extend(word.bytes().map(|b| b.to_ascii_lowercase()));
+            }
+        } else {
+            if self.lucene {
+                lucene::push_units(&mut self.wide, &word);
+            } else if self.non_ascii != fold::NonAsciiPolicy::Stem {
+                self.wide.extend(given.chars());
+                self.fill_from_wide();
+                return self.buffer.len();
+            } else {
+                self.wide.extend(word.chars().flat_map(char::to_lowercase));
+            }
+            self.fill_from_wide();
+        }
+        if self.buffer.is_empty() { return 0; }
+        self.k = self.buffer.len() - 1;
+        self.k0 = 0;
+
+        if self.k <= self.k0 + 1 {
+            return self.buffer.len();
+        }
+
+        self.step1ab();
+        if self.k > self.k0 {
+            self.run_step("1c", Self::step1c);
+            self.run_step("2", Self::step2);
+            self.run_step("3", Self::step3);
+            self.run_step("4", Self::step4);
+            self.run_step("5", Self::step5);
+        }
+
+        self.k + 1
+    }
+
+    /// Step 1ab handles plurals and past participles
+    /// 
+    /// # Transformations
+    /// - SSES -> SS (caresses -> caress)
+    /// - IES  -> I  (ponies -> poni)
+    /// - SS   -> SS (caress -> caress)
+    /// - S    ->    (cats -> cat)
+    /// 
+    /// And then:
+    /// - (m>0) EED -> EE     (agreed -> agree)
+    /// - (*v*) ED  ->        (plastered -> plaster)
+    /// - (*v*) ING ->        (motoring -> motor)
+    fn step1ab(&mut self) {
+        let before = self.snapshot();
+        if self.buffer[self.k] == b's' {
+            if self.ends_with("sses") {
+                self.k -= 2;
+            } else if self.ends_with("ies") {
+                self.set_to("i");
+            } else if self.k > self.k0 && self.buffer[self.k - 1] != b's' {
+                self.k -= 1;
+            }
+        }
+        self.record("1a", before);
+        let before = self.snapshot();
+
+        if self.ends_with("eed") {
+            if self.measure() > 0 {
+                self.k -= 1;
+            }
+        } else if (self.ends_with("ed") || self.ends_with("ing")) && self.vowel_in_stem() {
+            self.k = self.j;
+
+            if self.ends_with("at") {
+                self.set_to("ate");
+            } else if self.ends_with("bl") {
+                self.set_to("ble");
+            } else if self.ends_with("iz") {
+                self.set_to("ize");
+            } else if self.double_consonant(self.k) {
+                self.k -= 1;
+                let ch = self.buffer[self.k];
+                if ch == b'l' || ch == b's' || ch == b'z' {
+                    self.k += 1;
+                }
+            } else if self.measure() == 1 && self.cvc(self.k) {
+                self.set_to("e");
+            }
+        }
+        self.record("1b", before);
+    }
+
+    /// Step 1c turns terminal y to i when there is another vowel in the stem
+    /// 
+    /// # Examples
+    /// - happy -> happi
+    /// - sky -> sky (unchanged)
+    fn step1c(&mut self) {
+        if self.ends_with("y") && self.vowel_in_stem() {
+            self.buffer[self.k] = b'i';
+        }
+    }
+
+    /// Step 2 maps double suffices to single ones when measure > 0
+    /// 
+    /// # Examples
+    /// - ATIONAL -> ATE (relational -> relate)
+    /// - TIONAL  -> TION (conditional -> condition)
+    /// - ENCI    -> ENCE (valenci -> valence)
+    fn step2(&mut self) {
+        if self.k <= self.k0 { return; }
+        self.replace_first(rules(&STEP2, self.buffer[self.k - 1]));
+    }
+
+    /// Step 3 deals with -ic-, -full, -ness etc.
+    /// 
+    /// # Examples
+    /// - ICATE -> IC (triplicate -> triplic)
+    /// - ATIVE ->    (formative -> form)
+    /// - ALIZE -> AL (formalize -> formal)
+    fn step3(&mut self) {
+        self.replace_first(rules(&STEP3, self.buffer[self.k]));
+    }
+
+    /// Step 4 removes suffixes when measure > 1
+    /// 
+    /// # Examples
+    /// - AL    ->  (revival -> reviv)
+    /// - ANCE  ->  (allowance -> allow)
+    /// - ENCE  ->  (inference -> infer)
+    fn step4(&mut self) {
+        if self.k <= self.k0 { return; }
+
+        let matched = match self.buffer[self.k - 1] {
+            b'o' => {
+                (self.ends_with("ion") && self.stem_end() > self.k0 &&
+                    (self.buffer[self.j] == b's' || self.buffer[self.j] == b't'))
+                    || self.ends_with("ou")
+            },
+            byte => rules(&STEP4, byte).iter().any(|&(ending, _)| self.ends_with(ending)),
+        };
+        if !matched { return; }
+        if self.measure() > 1 {
+            self.k = self.j;
+        }
+    }
+
+    /// Step 5 removes final -e if measure > 1, and changes -ll to -l if measure > 1
+    /// 
+    /// # Examples
+    /// - E     ->  (probate -> probat, rate -> rate)
+    /// - L     ->  (controll -> control)
+    fn step5(&mut self) {
+        self.j = self.k;
+        if self.buffer[self.k] == b'e' {
+            let a = self.measure();
+            // A measure of 1 leaves at least two letters before the e
+            if a > 1 || (a == 1 && !self.k.checked_sub(1).is_some_and(|i| self.cvc(i))) {
+                self.k -= 1;
+            }
+        }
+        if self.buffer[self.k] == b'l' && self.double_consonant(self.k) && self.measure() > 1 {
+            self.k -= 1;
+        }
+    }
+
+    /// Helper function for step2 and step3
+    /// replaces current suffix with new_suffix if the stem has measure > 0
+    fn replace_suffix_if_stem_measured(&mut self, s: &str) {
+        if self.measure() > 0 {
+            self.set_to(s);
+        }
+    }
+
+    /// Replaces the first of `rules` whose ending the word has, if the stem
+    /// before it has a measure above 0
+    fn replace_first(&mut self, rules: &[Rule]) {
+        if let Some(&(_, to)) = rules.iter().find(|&&(ending, _)| self.ends_with(ending)) {
+            self.replace_suffix_if_stem_measured(to);
+        }
+    }
+}
+
+// Test with bash: Cargo Test
+#[cfg(feature = "std")]
+impl Stemmer for PorterStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        PorterStemmer::stem(self, word)
+    }
+
+    fn try_stem(&mut self, word: &str) -> Result<String, StemError> {
+        PorterStemmer::try_stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        PorterStemmer::folds_diacritics(self)
+    }
+
+    fn non_ascii_policy(&self) -> fold::NonAsciiPolicy {
+        self.non_ascii
+    }
+
+    /// Returns true if `a` and `b` have the same stem
+    ///
+    /// Both stems are compared in the stemmer's buffers, without building
+    /// a String for either of them; for ASCII words, with one memcmp.
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::{PorterStemmer, Stemmer};
+    /// let mut stemmer = PorterStemmer::new();
+    /// assert!(stemmer.same_stem("Connected", "connections"));
+    /// assert!(!stemmer.same_stem("running", "runner"));
+    /// ```
+    fn same_stem(&mut self, a: &str, b: &str) -> bool {
+        let len_a = self.stem_into_buffer(a);
+        self.swap_buffers();
+        let len_b = self.stem_into_buffer(b);
+        if self.wide.is_empty() && self.scratch_wide.is_empty() {
+            return self.scratch[..len_a] == self.buffer[..len_b];
+        }
+        len_a == len_b && (0..len_a).all(|i| self.scratch_unit(i) == self.unit(i))
+    }
+
+    /// Returns true if the stems of `a` and `b` are at most `max_dist` edits
+    /// (insertions, deletions or substitutions) apart
+    ///
+    /// Tolerates typos in queries: "runnning" and "running" both stem to a
+    /// form one edit away from the other. The distance is computed on the
+    /// stemmer's buffers, so no allocation happens once they have grown to
+    /// the length of the words.
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::{PorterStemmer, Stemmer};
+    /// let mut stemmer = PorterStemmer::new();
+    /// assert!(stemmer.stems_match_fuzzy("runnning", "runs", 1));
+    /// assert!(!stemmer.stems_match_fuzzy("runnning", "runs", 0));
+    /// assert!(stemmer.stems_match_fuzzy("conecting", "connection", 1));
+    /// ```
+    fn stems_match_fuzzy(&mut self, a: &str, b: &str, max_dist: usize) -> bool {
+        let len_a = self.stem_into_buffer(a);
+        self.swap_buffers();
+        let len_b = self.stem_into_buffer(b);
+        let row = &mut self.distance_row;
+        if self.wide.is_empty() && self.scratch_wide.is_empty() {
+            return fuzzy::bounded_distance(&self.scratch[..len_a], &self.buffer[..len_b], max_dist, row).is_some();
+        }
+        let stem_a: Vec<char> = (0..len_a).map(|i| self.scratch_unit(i)).collect();
+        let stem_b: Vec<char> = (0..len_b).map(|i| self.unit(i)).collect();
+        fuzzy::bounded_distance(&stem_a, &stem_b, max_dist, &mut self.distance_row).is_some()
+    }
+}
+
+/// Asserts that `stemmer` stems each line of `words` to the same line of
+/// `stems`, and that the two have as many lines
+///
+/// Stops at the first word stemmed differently, with its line number.
+#[cfg(all(test, feature = "std"))]
+pub(crate) fn assert_vocabulary(stemmer: &mut dyn Stemmer, words: &str, stems: &str) {
+    let (count, expected) = (words.lines().count(), stems.lines().count());
+    assert_eq!(count, expected, "{} words, but {} stems", count, expected);
+    assert!(count > 0, "no words");
+    for (line, (word, stem)) in words.lines().zip(stems.lines()).enumerate() {
+        let got = stemmer.stem(word);
+        assert!(got == stem, "line {}: {:?} stems to {:?}, expected {:?}", line + 1, word, got, stem);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_stemming() {
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(stemmer.stem("caresses"), "caress");
+        assert_eq!(stemmer.stem("ponies"), "poni");
+        assert_eq!(stemmer.stem("ties"), "ti");
+        assert_eq!(stemmer.stem("caress"), "caress");
+        assert_eq!(stemmer.stem("cats"), "cat");
+    }
+
+    #[test]
+    fn test_complex_stemming() {
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(stemmer.stem("troubled"), "troubl");
+        assert_eq!(stemmer.stem("troubles"), "troubl");
+        assert_eq!(stemmer.stem("troubling"), "troubl");
+        assert_eq!(stemmer.stem("capability"), "capabl");
+        assert_eq!(stemmer.stem("marketing"), "market");
+    }
+
+    #[test]
+    fn test_diacritic_folding() {
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(stemmer.stem("naïve"), "naïv");
+        let mut stemmer = PorterStemmer::new().with_diacritic_folding(true);
+        assert_eq!(stemmer.stem("naïve"), "naiv");
+        assert_eq!(stemmer.stem("Cafés"), "cafe");
+        assert_eq!(stemmer.stem("\u{301}"), "");
+    }
+
+    #[test]
+    fn test_non_ascii_policy() {
+        use fold::NonAsciiPolicy;
+
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(stemmer.stem("İcing"), "i\u{307}c");
+        let mut stemmer = PorterStemmer::new().with_non_ascii(NonAsciiPolicy::Transliterate);
+        assert!(stemmer.folds_diacritics());
+        assert_eq!(stemmer.stem("Résumés"), "resum");
+        assert_eq!(stemmer.stem("İcing"), "ic");
+        assert_eq!(stemmer.stem("Книгами"), "Книгами");
+        let mut stemmer = PorterStemmer::new().with_non_ascii(NonAsciiPolicy::Verbatim);
+        assert_eq!(stemmer.stem("İcing"), "İcing");
+        assert_eq!(stemmer.stem("Connections"), "connect");
+        assert!(stemmer.same_stem("Cafés", "Cafés"));
+        let mut stemmer = PorterStemmer::new().with_non_ascii(NonAsciiPolicy::Reject);
+        assert_eq!(stemmer.stem("naïve"), "naïve");
+        let error = stemmer.try_stem("naïve").unwrap_err();
+        assert!(matches!(error, StemError::NonAsciiLetter { character: 'ï', position: 2 }));
+        assert_eq!(stemmer.try_stem("hopping").unwrap(), "hop");
+        assert!(matches!(stemmer.try_stem(""), Err(StemError::EmptyInput)));
+        assert!(matches!(PorterStemmer::new().try_stem(""), Err(StemError::EmptyInput)));
+        assert_eq!(PorterStemmer::new().try_stem("Cafés").unwrap(), "café");
+    }
+
+    #[test]
+    fn test_stem_to_slice() {
+        let mut stemmer = PorterStemmer::new();
+        let mut term = [0; 8];
+        assert_eq!(stemmer.stem_to_slice("Cafés", &mut term).unwrap(), "café");
+        assert_eq!(stemmer.stem_to_slice("ponies", &mut term).unwrap(), "poni");
+        let error = stemmer.stem_to_slice("Cafés", &mut term[..4]).unwrap_err();
+        assert!(matches!(error, StemError::BufferTooSmall { needed: 5, capacity: 4 }));
+        assert!(matches!(stemmer.stem_to_slice("", &mut term), Err(StemError::EmptyInput)));
+        let mut stemmer = PorterStemmer::new().with_non_ascii(fold::NonAsciiPolicy::Reject);
+        assert!(matches!(stemmer.stem_to_slice("naïve", &mut term), Err(StemError::NonAsciiLetter { .. })));
+        let mut lucene = PorterStemmer::new();
+        lucene.lucene = true;
+        assert_eq!(lucene.stem_to_slice("a𝔵𝔵ing", &mut [0; 16]).unwrap(), lucene.stem("a𝔵𝔵ing"));
+    }
+
+    #[test]
+    fn test_same_stem() {
+        let mut stemmer = PorterStemmer::new();
+        assert!(stemmer.same_stem("troubled", "TROUBLING"));
+        assert!(stemmer.same_stem("", ""));
+        assert!(!stemmer.same_stem("cats", "cat's"));
+        assert_eq!(stemmer.stem_key("Ponies").as_str(), "poni");
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut stemmer = PorterStemmer::new();
+        let explanation = stemmer.explain("hopping");
+        assert_eq!(explanation.to_string(), "hopping -> hop\n  1b: hopping -> hop (-ping -> -, m=1)\n");
+        assert_eq!(stemmer.explain("conflated").steps[0].to_string(), "1b: conflated -> conflate (-ed -> -e, m=2)");
+        assert!(stemmer.explain("cat").steps.is_empty());
+        assert_eq!(stemmer.stem("hopping"), "hop");
+    }
+
+    #[test]
+    fn test_porter_vocabulary() {
+        // The vocabulary and expected output on Martin Porter's site
+        // (<https://tartarus.org/martin/PorterStemmer/>)
+        let mut stemmer = PorterStemmer::new();
+        let words = include_str!("../test_data/porter/voc.txt");
+        let stems = include_str!("../test_data/porter/output.txt");
+        assert_vocabulary(&mut stemmer, words, stems);
+    }
+
+    #[test]
+    #[should_panic(expected = "2 words, but 1 stems")]
+    fn test_assert_vocabulary_lengths() {
+        // A truncated output file fails, rather than checking fewer words
+        assert_vocabulary(&mut PorterStemmer::new(), "cats\nponies\n", "cat\n");
+    }
+
+    #[test]
+    fn test_nltk_modes() {
+        // Each of NLTK's PorterStemmer modes over one wordlist, as
+        // test_data/nltk/generate.py writes them: the stems match
+        // MARTIN_EXTENSIONS on every row, ORIGINAL_ALGORITHM but where the
+        // C program departs from the paper, and NLTK_EXTENSIONS, where its
+        // stem is known (not "-"), but for its four-letter "ies" and "ied" to "ie"
+        let mut stemmer = PorterStemmer::new();
+        let mut lines = include_str!("../test_data/nltk/porter_modes.tsv").lines().filter(|l| !l.starts_with('#'));
+        let modes: Vec<&str> = lines.next().unwrap().split('\t').skip(1).collect();
+        assert_eq!(modes, ["ORIGINAL_ALGORITHM", "MARTIN_EXTENSIONS", "NLTK_EXTENSIONS"]);
+        let (mut rows, mut known) = (0, [0; 3]);
+        let mut mismatches: [Vec<&str>; 3] = Default::default();
+        for line in lines {
+            let fields: Vec<&str> = line.split('\t').collect();
+            assert_eq!(fields.len(), 4, "porter_modes.tsv: {:?}", line);
+            let stem = stemmer.stem(fields[0]);
+            for (mode, expected) in fields[1..].iter().enumerate() {
+                if *expected == "-" {
+                    continue;
+                }
+                known[mode] += 1;
+                if stem != *expected {
+                    mismatches[mode].push(fields[0]);
+                }
+            }
+            rows += 1;
+        }
+        assert_eq!(rows, 23543);
+        assert_eq!(known, [rows, rows, 23]);
+        let original = [
+            "apology", "as", "assemblies", "assembly", "ay", "corruptibly", "dissembly", "dumbly", "es", "ey",
+            "forcibly", "horribly", "humbly", "ignobly", "infallibly", "is", "nimbly", "ns", "possibly", "rs", "s",
+            "sensibly", "terribly", "ts", "us", "uy", "visibly",
+        ];
+        assert_eq!(mismatches[0], original, "ORIGINAL_ALGORITHM");
+        assert!(mismatches[1].is_empty(), "MARTIN_EXTENSIONS: {:?}", mismatches[1]);
+        assert_eq!(mismatches[2], ["died", "dies"], "NLTK_EXTENSIONS");
+    }
+
+    /// Endings covering every rule, added to the test vocabulary's words to
+    /// compare with the C program on words it does not list
+    const DIFFERENTIAL_ENDINGS: [&str; 47] = [
+        "", "s", "es", "ies", "sses", "ed", "eed", "ing", "y", "e", "ll", "ly", "er", "ness", "ful", "fulness",
+        "ational", "tional", "enci", "anci", "izer", "bli", "alli", "entli", "eli", "ousli", "ization", "ation",
+        "ator", "alism", "iveness", "ousness", "aliti", "iviti", "biliti", "logi", "icate", "ative", "alize",
+        "iciti", "ical", "ance", "ible", "ement", "ion", "ism", "ous",
+    ];
+
+    #[test]
+    fn test_against_reference_c() {
+        // test_data/stem.c is Martin Porter's program, as quoted above from
+        // <https://tartarus.org/martin/PorterStemmer/c.txt>; $CC, or cc,
+        // compiles it, and without a C compiler the test is skipped
+        let source = concat!(env!("CARGO_MANIFEST_DIR"), "/test_data/stem.c");
+        let dir = std::env::temp_dir().join(format!("porterstem-reference-c-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let program = dir.join("stem");
+        let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+        let compile = std::process::Command::new(&compiler).args(["-O2", "-w", "-o"]).arg(&program).arg(source).status();
+        let status = match compile {
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                eprintln!("skipping test_against_reference_c: no C compiler {:?}", compiler);
+                std::fs::remove_dir_all(&dir).unwrap();
+                return;
+            },
+            status => status,
+        };
+        assert!(status.unwrap().success(), "compiling {}", source);
+
+        let vocabulary = include_str!("../test_data/porter/voc.txt");
+        let words: Vec<String> = vocabulary
+            .lines()
+            .flat_map(|word| DIFFERENTIAL_ENDINGS.iter().map(move |ending| format!("{}{}", word, ending)))
+            .collect();
+        let word_file = dir.join("words.txt");
+        std::fs::write(&word_file, words.join("\n") + "\n").unwrap();
+        let output = std::process::Command::new(&program).arg(&word_file).output().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(output.status.success());
+
+        let expected = String::from_utf8(output.stdout).unwrap();
+        let mut stemmer = PorterStemmer::new();
+        let divergences: Vec<String> = words
+            .iter()
+            .zip(expected.lines())
+            .filter_map(|(word, stem)| {
+                let ours = stemmer.stem(word);
+                (ours != stem).then(|| format!("{:?}: C {:?}, Rust {:?}", word, stem, ours))
+            })
+            .collect();
+        assert_eq!(expected.lines().count(), words.len());
+        assert!(
+            divergences.is_empty(),
+            "{} of {} words diverge:\n{}",
+            divergences.len(),
+            words.len(),
+            divergences[..divergences.len().min(50)].join("\n")
+        );
+    }
+
+    #[test]
+    fn test_degenerate_words() {
+        // Stems of Porter's C program; words this short or made of endings
+        // reach the index arithmetic at its bounds
+        let cases = [
+            ("", ""), ("s", "s"), ("''", "''"), ("123", "123"), ("ss", "ss"), ("sss", "sss"), ("ied", "i"),
+            ("aing", "a"), ("ion", "ion"), ("eed", "eed"), ("ing", "ing"), ("ed", "ed"), ("ies", "i"),
+            ("y", "y"), ("yyy", "yyi"), ("aed", "a"), ("ees", "ee"), ("ate", "at"), ("ious", "iou"),
+            ("sses", "ss"), ("iing", "i"), ("lll", "lll"), ("eeed", "eeed"),
+        ];
+        let mut stemmer = PorterStemmer::new();
+        let mut lucene = LuceneStemmer::new();
+        let mut buffer = [0; 8];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{:?}", word);
+            assert_eq!(stemmer.explain(word).stem, stem, "{:?}", word);
+            assert_eq!(lucene.stem(word), stem, "{:?}", word);
+            assert_eq!(fixed::stem_into(word, &mut buffer), Ok(stem), "{:?}", word);
+        }
+        for word in ["\u{301}", "İ", "ǅ", "\u{10400}s", "ΐed", "ﬃing"] {
+            stemmer.stem(word);
+            lucene.stem(word);
+        }
+    }
+
+    /// Counts the allocations of each thread, so tests running in parallel
+    /// do not see each other's
+    struct CountingAlloc;
+
+    thread_local! {
+        static THREAD_ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl std::alloc::GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            THREAD_ALLOCATIONS.with(|n| n.set(n.get() + 1));
+            unsafe { std::alloc::System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            unsafe { std::alloc::System.dealloc(ptr, layout) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+            THREAD_ALLOCATIONS.with(|n| n.set(n.get() + 1));
+            unsafe { std::alloc::System.realloc(ptr, layout, new_size) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+    /// The number of allocations, reallocations included, `f` makes on this
+    /// thread
+    fn allocations<T>(f: impl FnOnce() -> T) -> usize {
+        let before = THREAD_ALLOCATIONS.with(|n| n.get());
+        std::hint::black_box(f());
+        THREAD_ALLOCATIONS.with(|n| n.get()) - before
+    }
+
+    /// The most allocations stemming each word may make: one for the
+    /// returned `String`, since the endings are compared and written as
+    /// bytes in place, in a buffer held inline for these short words
+    const STEM_ALLOCATIONS: &[(&str, usize)] =
+        &[("a", 1), ("is", 1), ("cats", 1), ("hopeful", 1), ("running", 1), ("generalizations", 1)];
+
+    #[test]
+    fn test_steady_state_allocations() {
+        let mut stemmer = PorterStemmer::new();
+        stemmer.same_stem("counterrevolutionaries", "counterrevolutionaries");
+        for &(word, most) in STEM_ALLOCATIONS {
+            let made = allocations(|| stemmer.stem(word));
+            assert!(made <= most, "stem({:?}) made {} allocations, at most {} expected", word, made, most);
+            // Comparing stems builds no String
+            let made = allocations(|| stemmer.same_stem(word, word));
+            assert_eq!(made, 0, "same_stem({:?}) made {} allocations", word, made);
+            let made = allocations(|| stemmer.stem_to_slice(word, &mut [0; 32]).map(str::len));
+            assert_eq!(made, 0, "stem_to_slice({:?}) made {} allocations", word, made);
+        }
+
+        // The helpers each step calls compare and copy endings in place
+        stemmer.buffer.clear();
+        stemmer.buffer.extend_from_slice(b"relational");
+        (stemmer.k0, stemmer.k) = (0, 9);
+        assert_eq!(allocations(|| stemmer.ends_with("ational") && !stemmer.ends_with("tional!")), 0);
+        assert_eq!(allocations(|| stemmer.set_to("ate")), 0);
+        assert_eq!(&stemmer.buffer[..=stemmer.k], b"relate");
+        // A word that is not all ASCII allocates only its stem too
+        stemmer.stem("Cafés");
+        assert_eq!(allocations(|| stemmer.stem("Cafés")), 1);
+
+        // Even a new stemmer holds words this short without allocating
+        let made = allocations(|| PorterStemmer::new().stem_to_slice("generalizations", &mut [0; 32]).map(str::len));
+        assert_eq!(made, 0);
+        assert_eq!(allocations(|| PorterStemmer::new().same_stem("Connected", "connections")), 0);
+        assert_eq!(allocations(|| PorterStemmer::new().stem("running")), 1);
+
+        let mut buffer = [0; 32];
+        assert_eq!(allocations(|| fixed::stem_into("generalizations", &mut buffer).map(str::len)), 0);
+        let mut word = *b"Running";
+        assert_eq!(allocations(|| fixed::stem_in_place(&mut word)), 0);
+    }
+
+    #[test]
+    fn test_steady_state_allocations_per_token() {
+        // What each pipeline worker runs on its pieces: one String for each
+        // stem, and the output growing
+        let text = "Running ponies were hopping over the generalizations.\n".repeat(1000);
+        let tokens = text.split_whitespace().count();
+        let mut stemmer = PorterStemmer::new();
+        let options = text::TextOptions::new();
+        text::stem_text_with(&mut stemmer, &text, &options);
+        let made = allocations(|| text::stem_text_with(&mut stemmer, &text, &options));
+        assert!(made <= tokens + 32, "{} allocations for {} tokens", made, tokens);
+    }
+
+    #[test]
+    fn test_dispatch_tables() {
+        // Each ending is filed under the letter its step looks it up by
+        for (table, from_end) in [(&STEP2, 2), (&STEP3, 1), (&STEP4, 2)] {
+            for (byte, rules) in table.iter().enumerate() {
+                for (ending, _) in rules.iter() {
+                    assert_eq!(usize::from(ending.as_bytes()[ending.len() - from_end]), byte, "{}", ending);
+                }
+            }
+        }
+        assert_eq!(STEP2.iter().map(|rules| rules.len()).sum::<usize>(), 21);
+        assert!(rules(&STEP3, NON_ASCII).is_empty());
+    }
+
+    #[test]
+    fn test_non_ascii_letters() {
+        // Non-ASCII letters are consonants, doubled only by the same letter
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(stemmer.stem("aßßed"), "aß");
+        assert_eq!(stemmer.stem("aßçed"), "aßç");
+        assert_eq!(stemmer.stem("Çafés"), "çafé");
+        assert!(stemmer.same_stem("cafés", "Café"));
+        assert!(!stemmer.same_stem("cafés", "cafe"));
+        assert!(stemmer.stems_match_fuzzy("cafés", "cafe", 1));
+        assert_eq!(stemmer.explain("naïvely").steps[0].before, "naïvely");
+    }
+
+    #[test]
+    fn test_not_idempotent() {
+        // Stemming a stem may shorten it again, as with Porter's C program,
+        // so stems are only compared with stems made from the words
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(stemmer.stem("agreed"), "agre");
+        assert_eq!(stemmer.stem("agre"), "agr");
+        assert_eq!(stemmer.stem("confederal"), "confeder");
+        assert_eq!(stemmer.stem("confeder"), "confed");
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_shorter_and_lowercase(word in "[a-zA-Z]{0,24}") {
+            let stem = PorterStemmer::new().stem(&word);
+            proptest::prop_assert!(stem.len() <= word.len());
+            proptest::prop_assert!(stem.bytes().all(|b| b.is_ascii_lowercase()), "{:?}", stem);
+            let mut buffer = [0; 24];
+            proptest::prop_assert_eq!(fixed::stem_into(&word, &mut buffer), Ok(stem.as_str()));
+        }
+
+        #[test]
+        fn prop_short_and_consonant_words(word in "[a-zA-Z]{0,2}|[bcdfghjklmnpqrstvwxzBCDFGHJKLMNPQRSTVWXZ]{0,16}") {
+            let stem = PorterStemmer::new().stem(&word);
+            if word.len() <= 2 {
+                proptest::prop_assert_eq!(stem, word.to_ascii_lowercase());
+            } else {
+                proptest::prop_assert!(stem.len() <= word.len() && !stem.is_empty());
+            }
+        }
+
+        #[test]
+        fn prop_restemming_reaches_a_fixpoint(word in "[a-z]{0,24}") {
+            let mut stemmer = PorterStemmer::new();
+            let mut stem = stemmer.stem(&word);
+            for _ in 0..=word.len() {
+                let again = stemmer.stem(&stem);
+                if again == stem {
+                    return Ok(());
+                }
+                proptest::prop_assert!(again.len() <= stem.len(), "{:?} -> {:?}", stem, again);
+                stem = again;
+            }
+            proptest::prop_assert!(false, "{:?} keeps changing", word);
+        }
+
+        #[test]
+        fn prop_no_panics(word in proptest::prelude::any::<String>()) {
+            for algorithm in Algorithm::ALL {
+                algorithm.stemmer(false).stem(&word);
+            }
+            PorterStemmer::new().with_diacritic_folding(true).stem(&word);
+            fixed::stem_into(&word, &mut [0; 16]).ok();
+        }
+    }
+}
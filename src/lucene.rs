@@ -0,0 +1,147 @@
+//! # Lucene Compatibility
+//!
+//! [`LuceneStemmer`] stems words exactly as Lucene's `PorterStemFilter`
+//! does (and so Elasticsearch's and OpenSearch's `porter_stem` token
+//! filter, and Solr's `PorterStemFilterFactory`), so that an index built
+//! here matches queries analyzed by them, and the other way around. It is
+//! checked against the test vectors of Lucene's `TestPorterStemFilter`,
+//! which are Martin Porter's own: `voc.txt` and `output.txt`.
+//!
+//! Lucene's `PorterStemmer` class is a Java port of the same C program
+//! [`PorterStemmer`] follows, and on lowercase words the two agree. Where
+//! they differ is in what they take a word to be:
+//!
+//! - Lucene does not lowercase: an analyzer puts a `LowerCaseFilter` before
+//!   the stemmer. An uppercase letter is a consonant to it, which it never
+//!   changes, so "Running" stems to "Run" and "CATS" to "CATS", where
+//!   [`PorterStemmer`] gives "run" and "cat" for both spellings. Lowercase
+//!   the words first for the stems an `english` analyzer would index.
+//! - Lucene works on Java's UTF-16 `char`s, so a character outside the
+//!   Basic Multilingual Plane (a mathematical letter such as "𝔵", or an
+//!   emoji) is two consonants to it, never doubled with its neighbour:
+//!   "a𝔵𝔵ing" stems to "a𝔵𝔵", where [`PorterStemmer`] sees two equal
+//!   consonants and gives "a𝔵". Other letters are one unit in both.
+//! - Nothing else: a word the first step cuts to one letter is left there by
+//!   both, whether the later steps run and find nothing (Lucene) or are
+//!   skipped (C), and words of one or two letters are left alone.
+//!
+//! Tokens that Lucene's `KeywordMarkerFilter` protects are never passed to
+//! the stemmer; leaving such words out is up to the caller here too.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::{LuceneStemmer, PorterStemmer};
+//! let mut lucene = LuceneStemmer::new();
+//! assert_eq!(lucene.stem("connections"), "connect");
+//! assert_eq!(lucene.stem("Running"), "Run");
+//! assert_eq!(PorterStemmer::new().stem("Running"), "run");
+//! ```
+
+use crate::stemmer::Stemmer;
+use crate::PorterStemmer;
+
+/// Stands for the low surrogate of a character outside the BMP, which
+/// follows the character itself in the stemmer's buffer
+const LOW_SURROGATE: char = '\u{FFFF}';
+
+/// Pushes the UTF-16 units of `word` onto `buffer`, a character outside the
+/// BMP as itself followed by [`LOW_SURROGATE`]
+///
+/// Both are consonants and no two adjacent units of a pair are equal, as
+/// in Java, and no rule ever removes one half of a pair, since every ending
+/// and every letter the rules drop is ASCII.
+pub(crate) fn push_units(buffer: &mut Vec<char>, word: &str) {
+    for c in word.chars() {
+        buffer.push(c);
+        if c.len_utf16() == 2 {
+            buffer.push(LOW_SURROGATE);
+        }
+    }
+}
+
+/// The Porter stemmer as Lucene's `PorterStemFilter` runs it
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::LuceneStemmer;
+/// let mut stemmer = LuceneStemmer::new();
+/// assert_eq!(stemmer.stem("generalizations"), "gener");
+/// assert_eq!(stemmer.stem("CATS"), "CATS");
+/// ```
+#[derive(Debug)]
+pub struct LuceneStemmer {
+    porter: PorterStemmer,
+}
+
+impl Default for LuceneStemmer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LuceneStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        let mut porter = PorterStemmer::new();
+        porter.lucene = true;
+        LuceneStemmer { porter }
+    }
+
+    /// Enables or disables folding of accented Latin letters before
+    /// stemming, as an `asciifolding` filter before the stemmer would
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.porter = self.porter.with_diacritic_folding(fold);
+        self
+    }
+
+    /// Returns the stem of `word`, stemmed as given
+    pub fn stem(&mut self, word: &str) -> String {
+        self.porter.stem(word)
+    }
+}
+
+impl Stemmer for LuceneStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        LuceneStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.porter.folds_diacritics()
+    }
+
+    fn same_stem(&mut self, a: &str, b: &str) -> bool {
+        self.porter.same_stem(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lucene_vocabulary() {
+        let mut stemmer = LuceneStemmer::new();
+        let words = include_str!("../test_data/porter/voc.txt");
+        let stems = include_str!("../test_data/porter/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+    }
+
+    #[test]
+    fn test_divergences() {
+        let mut lucene = LuceneStemmer::new();
+        let mut porter = PorterStemmer::new();
+        // Case: Lucene expects lowercase input and keeps capitals
+        assert_eq!((lucene.stem("Running"), porter.stem("Running")), ("Run".into(), "run".into()));
+        assert_eq!((lucene.stem("CATS"), porter.stem("CATS")), ("CATS".into(), "cat".into()));
+        assert_eq!(lucene.stem("running"), porter.stem("running"));
+        // Characters outside the BMP are two units, never a double consonant
+        assert_eq!((lucene.stem("a𝔵𝔵ing"), porter.stem("a𝔵𝔵ing")), ("a𝔵𝔵".into(), "a𝔵".into()));
+        assert_eq!(lucene.stem("dogs🐕"), "dogs🐕");
+        assert_eq!(lucene.stem("🐕🐕s"), "🐕🐕");
+        // Other letters, and one-letter stems, are the same
+        assert_eq!(lucene.stem("cafés"), porter.stem("cafés"));
+        assert_eq!(lucene.stem("aed"), porter.stem("aed"));
+        assert!(lucene.same_stem("connected", "connections"));
+        assert!(!lucene.same_stem("Connected", "connections"));
+    }
+}
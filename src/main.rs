@@ -1,898 +1,1992 @@
-//! # Porter Stemmer Implementation in Rust
-//! 
-//! This is a Rust implementation of the Porter Stemming algorithm, based on the original
-//! work by Martin Porter (1980). The algorithm reduces English words to their word stem,
-//! base, or root form through a series of systematic steps.
-//! 
-//! specifically based on cannonical ansi c version by Martin Porter 
-//! at https://tartarus.org/martin/PorterStemmer/c.txt
-//! 
-//! ## Algorithm Overview
-//! The Porter Stemmer follows five steps to reduce words to their stems:
-//! 1. Handles plurals and past participles
-//! 2. Handles various suffixes
-//! 3. Deals with -ic-, -full, -ness etc.
-//! 4. Handles -ant, -ence, etc.
-//! 5. Removes final -e and changes -ll to -l in specific contexts
-//! 
-//! ## Reference
-//! Porter, M.F., "An algorithm for suffix stripping", Program, Vol. 14,
-//! No. 3, pp 130-137, July 1980.
-//! 
-//! ## Usage Example
-//! ```rust
-//! let mut stemmer = PorterStemmer::new();
-//! assert_eq!(stemmer.stem("running"), "run");
-//! assert_eq!(stemmer.stem("capabilities"), "capabl");
-//! ```
-//! 
-//! ## Implementation Notes
-//! - This implementation operates on lowercase ASCII characters only
-//! - Input should be pre-processed to remove non-alphabetic characters
-//! - The algorithm never increases word length
-//! - Words of length 1 or 2 are not stemmed
-//! 
-//! ## Safety and Performance
-//! - Memory safe: Uses Rust's Vec<char> instead of raw character buffers
-//! - No unsafe blocks
-//! - No external dependencies
-//! - Maintains O(n) time complexity where n is word length
-
-// https://tartarus.org/martin/PorterStemmer/c.txt
-// /* This is the Porter stemming algorithm, coded up in ANSI C by the
-//    author. It may be be regarded as canonical, in that it follows the
-//    algorithm presented in
-
-//    Porter, 1980, An algorithm for suffix stripping, Program, Vol. 14,
-//    no. 3, pp 130-137,
-
-//    only differing from it at the points marked --DEPARTURE-- below.
-
-//    See also http://www.tartarus.org/~martin/PorterStemmer
-
-//    The algorithm as described in the paper could be exactly replicated
-//    by adjusting the points of DEPARTURE, but this is barely necessary,
-//    because (a) the points of DEPARTURE are definitely improvements, and
-//    (b) no encoding of the Porter stemmer I have seen is anything like
-//    as exact as this version, even with the points of DEPARTURE!
-
-//    You can compile it on Unix with 'gcc -O3 -o stem stem.c' after which
-//    'stem' takes a list of inputs and sends the stemmed equivalent to
-//    stdout.
-
-//    The algorithm as encoded here is particularly fast.
-
-//    Release 1: was many years ago
-//    Release 2: 11 Apr 2013
-//        fixes a bug noted by Matt Patenaude <matt@mattpatenaude.com>,
-
-//        case 'o': if (ends("\03" "ion") && (b[j] == 's' || b[j] == 't')) break;
-//            ==>
-//        case 'o': if (ends("\03" "ion") && j >= k0 && (b[j] == 's' || b[j] == 't')) break;
-
-//        to avoid accessing b[k0-1] when the word in b is "ion".
-//    Release 3: 25 Mar 2014
-//        fixes a similar bug noted by Klemens Baum <klemensbaum@gmail.com>,
-//        that if step1ab leaves a one letter result (ied -> i, aing -> a etc),
-//        step2 and step4 access the byte before the first letter. So we skip
-//        steps after step1ab unless k > k0.
-// */
-
-// #include <string.h>  /* for memmove */
-
-// #define TRUE 1
-// #define FALSE 0
-
-// /* The main part of the stemming algorithm starts here. b is a buffer
-//    holding a word to be stemmed. The letters are in b[k0], b[k0+1] ...
-//    ending at b[k]. In fact k0 = 0 in this demo program. k is readjusted
-//    downwards as the stemming progresses. Zero termination is not in fact
-//    used in the algorithm.
-
-//    Note that only lower case sequences are stemmed. Forcing to lower case
-//    should be done before stem(...) is called.
-// */
-
-// static char * b;       /* buffer for word to be stemmed */
-// static int k,k0,j;     /* j is a general offset into the string */
-
-// /* cons(i) is TRUE <=> b[i] is a consonant. */
-
-// static int cons(int i)
-// {  switch (b[i])
-//    {  case 'a': case 'e': case 'i': case 'o': case 'u': return FALSE;
-//       case 'y': return (i==k0) ? TRUE : !cons(i-1);
-//       default: return TRUE;
-//    }
-// }
-
-// /* m() measures the number of consonant sequences between k0 and j. if c is
-//    a consonant sequence and v a vowel sequence, and <..> indicates arbitrary
-//    presence,
-
-//       <c><v>       gives 0
-//       <c>vc<v>     gives 1
-//       <c>vcvc<v>   gives 2
-//       <c>vcvcvc<v> gives 3
-//       ....
-// */
-
-// static int m()
-// {  int n = 0;
-//    int i = k0;
-//    while(TRUE)
-//    {  if (i > j) return n;
-//       if (! cons(i)) break; i++;
-//    }
-//    i++;
-//    while(TRUE)
-//    {  while(TRUE)
-//       {  if (i > j) return n;
-//             if (cons(i)) break;
-//             i++;
-//       }
-//       i++;
-//       n++;
-//       while(TRUE)
-//       {  if (i > j) return n;
-//          if (! cons(i)) break;
-//          i++;
-//       }
-//       i++;
-//    }
-// }
-
-// /* vowelinstem() is TRUE <=> k0,...j contains a vowel */
-
-// static int vowelinstem()
-// {  int i; for (i = k0; i <= j; i++) if (! cons(i)) return TRUE;
-//    return FALSE;
-// }
-
-// /* doublec(j) is TRUE <=> j,(j-1) contain a double consonant. */
-
-// static int doublec(int j)
-// {  if (j < k0+1) return FALSE;
-//    if (b[j] != b[j-1]) return FALSE;
-//    return cons(j);
-// }
-
-// /* cvc(i) is TRUE <=> i-2,i-1,i has the form consonant - vowel - consonant
-//    and also if the second c is not w,x or y. this is used when trying to
-//    restore an e at the end of a short word. e.g.
-
-//       cav(e), lov(e), hop(e), crim(e), but
-//       snow, box, tray.
-
-// */
-
-// static int cvc(int i)
-// {  if (i < k0+2 || !cons(i) || cons(i-1) || !cons(i-2)) return FALSE;
-//    {  int ch = b[i];
-//       if (ch == 'w' || ch == 'x' || ch == 'y') return FALSE;
-//    }
-//    return TRUE;
-// }
-
-// /* ends(s) is TRUE <=> k0,...k ends with the string s. */
-
-// static int ends(char * s)
-// {  int length = s[0];
-//    if (s[length] != b[k]) return FALSE; /* tiny speed-up */
-//    if (length > k-k0+1) return FALSE;
-//    if (memcmp(b+k-length+1,s+1,length) != 0) return FALSE;
-//    j = k-length;
-//    return TRUE;
-// }
-
-// /* setto(s) sets (j+1),...k to the characters in the string s, readjusting
-//    k. */
-
-// static void setto(char * s)
-// {  int length = s[0];
-//    memmove(b+j+1,s+1,length);
-//    k = j+length;
-// }
-
-// /* r(s) is used further down. */
-
-// static void r(char * s) { if (m() > 0) setto(s); }
-
-// /* step1ab() gets rid of plurals and -ed or -ing. e.g.
-
-//        caresses  ->  caress
-//        ponies    ->  poni
-//        ties      ->  ti
-//        caress    ->  caress
-//        cats      ->  cat
-
-//        feed      ->  feed
-//        agreed    ->  agree
-//        disabled  ->  disable
-
-//        matting   ->  mat
-//        mating    ->  mate
-//        meeting   ->  meet
-//        milling   ->  mill
-//        messing   ->  mess
-
-//        meetings  ->  meet
-
-// */
-
-// static void step1ab()
-// {  if (b[k] == 's')
-//    {  if (ends("\04" "sses")) k -= 2; else
-//       if (ends("\03" "ies")) setto("\01" "i"); else
-//       if (b[k-1] != 's') k--;
-//    }
-//    if (ends("\03" "eed")) { if (m() > 0) k--; } else
-//    if ((ends("\02" "ed") || ends("\03" "ing")) && vowelinstem())
-//    {  k = j;
-//       if (ends("\02" "at")) setto("\03" "ate"); else
-//       if (ends("\02" "bl")) setto("\03" "ble"); else
-//       if (ends("\02" "iz")) setto("\03" "ize"); else
-//       if (doublec(k))
-//       {  k--;
-//          {  int ch = b[k];
-//             if (ch == 'l' || ch == 's' || ch == 'z') k++;
-//          }
-//       }
-//       else if (m() == 1 && cvc(k)) setto("\01" "e");
-//    }
-// }
-
-// /* step1c() turns terminal y to i when there is another vowel in the stem. */
-
-// static void step1c() { if (ends("\01" "y") && vowelinstem()) b[k] = 'i'; }
-
-
-// /* step2() maps double suffices to single ones. so -ization ( = -ize plus
-//    -ation) maps to -ize etc. note that the string before the suffix must give
-//    m() > 0. */
-
-// static void step2() { switch (b[k-1])
-// {
-//     case 'a': if (ends("\07" "ational")) { r("\03" "ate"); break; }
-//               if (ends("\06" "tional")) { r("\04" "tion"); break; }
-//               break;
-//     case 'c': if (ends("\04" "enci")) { r("\04" "ence"); break; }
-//               if (ends("\04" "anci")) { r("\04" "ance"); break; }
-//               break;
-//     case 'e': if (ends("\04" "izer")) { r("\03" "ize"); break; }
-//               break;
-//     case 'l': if (ends("\03" "bli")) { r("\03" "ble"); break; } /*-DEPARTURE-*/
-
-//  /* To match the published algorithm, replace this line with
-//     case 'l': if (ends("\04" "abli")) { r("\04" "able"); break; } */
-
-//               if (ends("\04" "alli")) { r("\02" "al"); break; }
-//               if (ends("\05" "entli")) { r("\03" "ent"); break; }
-//               if (ends("\03" "eli")) { r("\01" "e"); break; }
-//               if (ends("\05" "ousli")) { r("\03" "ous"); break; }
-//               break;
-//     case 'o': if (ends("\07" "ization")) { r("\03" "ize"); break; }
-//               if (ends("\05" "ation")) { r("\03" "ate"); break; }
-//               if (ends("\04" "ator")) { r("\03" "ate"); break; }
-//               break;
-//     case 's': if (ends("\05" "alism")) { r("\02" "al"); break; }
-//               if (ends("\07" "iveness")) { r("\03" "ive"); break; }
-//               if (ends("\07" "fulness")) { r("\03" "ful"); break; }
-//               if (ends("\07" "ousness")) { r("\03" "ous"); break; }
-//               break;
-//     case 't': if (ends("\05" "aliti")) { r("\02" "al"); break; }
-//               if (ends("\05" "iviti")) { r("\03" "ive"); break; }
-//               if (ends("\06" "biliti")) { r("\03" "ble"); break; }
-//               break;
-//     case 'g': if (ends("\04" "logi")) { r("\03" "log"); break; } /*-DEPARTURE-*/
-
-//  /* To match the published algorithm, delete this line */
-
-// } }
-
-// /* step3() deals with -ic-, -full, -ness etc. similar strategy to step2. */
-
-// static void step3() { switch (b[k])
-// {
-//     case 'e': if (ends("\05" "icate")) { r("\02" "ic"); break; }
-//               if (ends("\05" "ative")) { r("\00" ""); break; }
-//               if (ends("\05" "alize")) { r("\02" "al"); break; }
-//               break;
-//     case 'i': if (ends("\05" "iciti")) { r("\02" "ic"); break; }
-//               break;
-//     case 'l': if (ends("\04" "ical")) { r("\02" "ic"); break; }
-//               if (ends("\03" "ful")) { r("\00" ""); break; }
-//               break;
-//     case 's': if (ends("\04" "ness")) { r("\00" ""); break; }
-//               break;
-// } }
-
-// /* step4() takes off -ant, -ence etc., in context <c>vcvc<v>. */
-
-// static void step4()
-// {  switch (b[k-1])
-//     {  case 'a': if (ends("\02" "al")) break; return;
-//        case 'c': if (ends("\04" "ance")) break;
-//                  if (ends("\04" "ence")) break; return;
-//        case 'e': if (ends("\02" "er")) break; return;
-//        case 'i': if (ends("\02" "ic")) break; return;
-//        case 'l': if (ends("\04" "able")) break;
-//                  if (ends("\04" "ible")) break; return;
-//        case 'n': if (ends("\03" "ant")) break;
-//                  if (ends("\05" "ement")) break;
-//                  if (ends("\04" "ment")) break;
-//                  if (ends("\03" "ent")) break; return;
-//        case 'o': if (ends("\03" "ion") && j >= k0 && (b[j] == 's' || b[j] == 't')) break;
-//                  if (ends("\02" "ou")) break; return;
-//                  /* takes care of -ous */
-//        case 's': if (ends("\03" "ism")) break; return;
-//        case 't': if (ends("\03" "ate")) break;
-//                  if (ends("\03" "iti")) break; return;
-//        case 'u': if (ends("\03" "ous")) break; return;
-//        case 'v': if (ends("\03" "ive")) break; return;
-//        case 'z': if (ends("\03" "ize")) break; return;
-//        default: return;
-//     }
-//     if (m() > 1) k = j;
-// }
-
-// /* step5() removes a final -e if m() > 1, and changes -ll to -l if
-//    m() > 1. */
-
-// static void step5()
-// {  j = k;
-//    if (b[k] == 'e')
-//    {  int a = m();
-//       if (a > 1 || a == 1 && !cvc(k-1)) k--;
-//    }
-//    if (b[k] == 'l' && doublec(k) && m() > 1) k--;
-// }
-
-// /* In stem(p,i,j), p is a char pointer, and the string to be stemmed is from
-//    p[i] to p[j] inclusive. Typically i is zero and j is the offset to the last
-//    character of a string, (p[j+1] == '\0'). The stemmer adjusts the
-//    characters p[i] ... p[j] and returns the new end-point of the string, k.
-//    Stemming never increases word length, so i <= k <= j. To turn the stemmer
-//    into a module, declare 'stem' as extern, and delete the remainder of this
-//    file.
-// */
-
-// int stem(char * p, int i, int j)
-// {  b = p; k = j; k0 = i; /* copy the parameters into statics */
-//    if (k <= k0+1) return k; /*-DEPARTURE-*/
-
-//    /* With this line, strings of length 1 or 2 don't go through the
-//       stemming process, although no mention is made of this in the
-//       published algorithm. Remove the line to match the published
-//       algorithm. */
-
-//    step1ab();
-//    if (k > k0) {
-//        step1c(); step2(); step3(); step4(); step5();
-//    }
-//    return k;
-// }
-
-// /*--------------------stemmer definition ends here------------------------*/
-
-// #include <stdio.h>
-// #include <stdlib.h>      /* for malloc, free */
-// #include <ctype.h>       /* for isupper, islower, tolower */
-
-// static char * s;         /* a char * (=string) pointer; passed into b above */
-
-// #define INC 50           /* size units in which s is increased */
-// static int i_max = INC;  /* maximum offset in s */
-
-// void increase_s()
-// {  i_max += INC;
-//    {  char * new_s = (char *) malloc(i_max+1);
-//       { int i; for (i = 0; i < i_max; i++) new_s[i] = s[i]; } /* copy across */
-//       free(s); s = new_s;
-//    }
-// }
-
-// #define LETTER(ch) (isupper(ch) || islower(ch))
-
-// static void stemfile(FILE * f)
-// {  while(TRUE)
-//    {  int ch = getc(f);
-//       if (ch == EOF) return;
-//       if (LETTER(ch))
-//       {  int i = 0;
-//          while(TRUE)
-//          {  if (i == i_max) increase_s();
-
-//             ch = tolower(ch); /* forces lower case */
-
-//             s[i] = ch; i++;
-//             ch = getc(f);
-//             if (!LETTER(ch)) { ungetc(ch,f); break; }
-//          }
-//          s[stem(s,0,i-1)+1] = 0;
-//          /* the previous line calls the stemmer and uses its result to
-//             zero-terminate the string in s */
-//          printf("%s",s);
-//       }
-//       else putchar(ch);
-//    }
-// }
-
-// int main(int argc, char * argv[])
-// {  int i;
-//    s = (char *) malloc(i_max+1);
-//    for (i = 1; i < argc; i++)
-//    {  FILE * f = fopen(argv[i],"r");
-//       if (f == 0) { fprintf(stderr,"File %s not found\n",argv[i]); exit(1); }
-//       stemfile(f);
-//    }
-//    free(s);
-//    return 0;
-// }
-
-
-
-/// Porter Stemmer struct that maintains the state during stemming operations
-#[derive(Debug)]
-pub struct PorterStemmer {
-    /// Buffer holding the word being processed
-    buffer: Vec<char>,
-    /// Current end position in buffer
-    k: usize,
-    /// Start position in buffer (typically 0)
-    k0: usize,
-    /// General offset used in various operations
-    j: usize,
-}
-
-
-impl PorterStemmer {
-    /// Creates a new Porter Stemmer instance
-    /// 
-    /// # Returns
-    /// A new `PorterStemmer` with empty buffer and initialized indices
-    pub fn new() -> Self {
-        PorterStemmer {
-            buffer: Vec::new(),
-            k: 0,
-            k0: 0,
-            j: 0,
-        }
-    }
-
-    /// Determines if a character at position i is a consonant
-    /// 
-    /// # Arguments
-    /// * `i` - Index in the buffer to check
-    /// 
-    /// # Returns
-    /// * `true` if the character is a consonant
-    /// * `false` if the character is a vowel
-    /// 
-    /// # Notes
-    /// - A consonant is defined as any letter other than A, E, I, O, or U
-    /// - Y is considered a consonant when:
-    ///   1. It's the first letter (k0)
-    ///   2. The previous letter is a consonan
-    ///
-    /// Returns true if the character at position i is a consonant
-    fn is_consonant(&self, i: usize) -> bool {
-        match self.buffer[i] {
-            'a' | 'e' | 'i' | 'o' | 'u' => false,
-            'y' => if i == self.k0 {
-                true
-            } else {
-                !self.is_consonant(i - 1)
+//! Command line front end for the Porter stemmer
+//!
+//! Input is read from the files named on the command line, one after the
+//! other, or from stdin if there are none (or a file is `-`); output goes to
+//! stdout, or to the file given with `-o`/`--output`. `-r`/`--recursive`
+//! takes in every file under a directory argument, and glob patterns such
+//! as `'corpus/**/*.txt'` are expanded even when quoted; `--out-dir out/`
+//! then writes each file's output to the same relative path under `out/`.
+//! With the `mmap` feature, `--mmap` maps each plain-text input file into
+//! memory and stems it a chunk at a time instead, one file after the
+//! other, so that multi-gigabyte files take little memory.
+//! Several files are stemmed in parallel, on as many threads as there are
+//! CPUs or `-j`/`--jobs N`, and their output still comes in input order; so
+//! is a single large plain-text input, or stdin, in pieces (see the
+//! `pipeline` module of the library). A file that cannot be
+//! read is reported and skipped, and the exit status is then 1; invalid
+//! arguments exit with status 2, and input rejected with `--long-tokens
+//! fail` stops the run with status 3.
+//! Plain text goes through in pieces ending at line breaks (see
+//! `text::read_chunks`), so memory use does not grow with the input; HTML,
+//! Markdown and `--kwic` input is read whole.
+//!
+//! Usage:
+//! - `porterstem < doc.txt` (or `porterstem doc.txt`) works as a filter like
+//!   the reference C program: every run of letters is lowercased and
+//!   stemmed, and everything else is copied through; `--text` selects this
+//!   mode explicitly. `--stopwords builtin` (or `--stopwords words.txt`) drops
+//!   stopwords first, here and with `--freq` and `--unique`; `--digits split|skip|verbatim|strip` chooses how
+//!   tokens such as "2023" or "covid19" are handled; `--symbols drop` removes
+//!   emoji and other symbols and `--symbol-placeholder "<sym>"` replaces them;
+//!   `--min-length 4` leaves shorter words unstemmed (the default is 3) and
+//!   `--max-length 64` passes longer tokens through, or drops them with
+//!   `--long-tokens drop`, or stops at the first with `--long-tokens fail`
+//! - `--algorithm porter2` stems with Porter2 (Snowball English) instead of
+//!   the original Porter algorithm, in every mode; `--algorithm lancaster`
+//!   selects the more aggressive Lancaster (Paice/Husk) stemmer and
+//!   `--algorithm uea-lite` one whose stems are readable English words;
+//!   `--algorithm lucene` gives the stems of Lucene's and Elasticsearch's
+//!   Porter filter, which keeps the case of the input
+//! - `--lang fr|de|pt|ru|sv|no|da|hu|ro|tr|el` stems French, German,
+//!   Portuguese, Russian, Swedish, Norwegian, Danish, Hungarian, Romanian,
+//!   Turkish or Greek text with the Snowball stemmer for that language
+//!   instead, and `--lang hi` Hindi text with a light stemmer (`--lang en`
+//!   is the default); `--list-algorithms` lists the algorithms and languages
+//! - `find docs -name '*.md' -print0 | porterstem -0` (or `--null`) takes
+//!   NUL-terminated input and stems each record whole, as one word, with
+//!   any spaces or newlines in it, writing NUL-terminated stems for
+//!   `xargs -0`
+//! - `porterstem --in-place --backup .bak corpus/*.txt` (or `-i`) replaces
+//!   each file with its output, keeping the old file as `corpus/a.txt.bak`;
+//!   the output is written to a temporary file that is then renamed over
+//!   the input, so a file is never left half written
+//! - `porterstem --watch -r docs/ --out-dir stemmed/` runs again whenever
+//!   an input file changes, is added or is removed (the files are looked at
+//!   twice a second), for build pipelines; it works with every mode, such
+//!   as `--freq -o table.tsv`, and stops only when interrupted
+//! - `porterstem serve --port 8080`, built with the `serve` feature, answers
+//!   `POST /stem` (`{"word": "running"}`), `POST /stem-batch` (a JSON array
+//!   of words) and `POST /stem-text` (`{"text": "..."}`) with JSON, for
+//!   services in other languages; it listens on 127.0.0.1 unless `--host
+//!   0.0.0.0` says otherwise. Built with the `websocket` feature, `GET /ws`
+//!   opens a WebSocket that answers each message with its stems at once, for
+//!   search-as-you-type. Built with the `grpc`
+//!   feature, `serve --grpc` answers the gRPC service of
+//!   `proto/porterstem.proto` instead
+//! - `porterstem daemon` answers each line of stdin with a line on stdout:
+//!   the stems of plain text, or, built with the `jsonrpc` feature, a
+//!   JSON-RPC 2.0 response to a request for the methods of `serve`
+//!   (`{"jsonrpc":"2.0","id":1,"method":"stem","params":{"word":"running"}}`);
+//!   `--socket /tmp/stem.sock` serves every connection to a Unix domain
+//!   socket the same way
+//! - `porterstem -r corpus/ --out-dir stemmed/` reports the files done,
+//!   megabytes read, MB/s and time left on stderr once a run over files
+//!   takes more than a second, if stderr is a terminal; `--progress` reports
+//!   anyway (a line every 5 seconds in a log), and `-q`/`--quiet` never does
+//! - `porterstem index --out idx/ docs/` stems every file under `docs/` into
+//!   a search index saved in `idx/`, and `porterstem search idx/ "running
+//!   fast"` stems the query the same way and prints a `score\tpath` line
+//!   for each of the best matching files (BM25 ranking; `--top N`, 10 by
+//!   default)
+//! - `porterstem bench corpus.txt` stems the file (or, with no file, 8 MB
+//!   of synthetic text) over and over for a second with the stemmer and
+//!   text options of the other flags, and prints words and megabytes per
+//!   second and, built with the `bench-alloc` feature, heap allocations
+//!   per word
+//! - `porterstem --check voc.txt output.txt` stems each word of a
+//!   vocabulary file, one per line, and compares the stems with an
+//!   expected-output file, such as those of Martin Porter's site; each
+//!   mismatch is printed with its line number, and the exit status is 1 if
+//!   there are any
+//! - `porterstem verify --golden stems.tsv corpus.txt` stems the corpus
+//!   again and compares it with a `word\tstem` mapping saved before, such as
+//!   one from `--tsv --unique`, to show what an upgrade would change in an
+//!   index: each word whose stem changed and each word the mapping lacks is
+//!   printed, and the exit status is 1 if any stem changed
+//! - `porterstem --trace generalizations hopping` prints each step of the
+//!   Porter algorithm that changes each word: the rule's ending, its
+//!   replacement, the measure of the stem and the word after the step; with
+//!   no words, every word of the input is traced
+//! - `--preserve-case` gives each stem the case of its word in text mode
+//!   ("Running quickly" becomes "Run quickli" rather than "run quickli")
+//! - `--fold` folds accented Latin letters to ASCII before stemming, in
+//!   every mode ("résumé" is stemmed as "resume")
+//! - `porterstem --identifiers < main.rs` splits source-code
+//!   identifiers (`getUserNames` -> `get user name`) and stems each word;
+//!   `--join _` rejoins the stems with another separator
+//! - `porterstem --html < page.html` stems the visible text of an
+//!   HTML document on stdin, keeping the markup
+//! - `porterstem --html-text < page.html` emits only the stemmed
+//!   visible text
+//! - `porterstem --markdown < README.md` stems Markdown prose,
+//!   leaving code blocks, inline code and link targets untouched
+//! - `porterstem --csv --columns 2,5 < data.csv` stems the given
+//!   (1-based) columns of CSV input; `--delimiter tab` reads TSV and
+//!   `--no-header` stems the first row too
+//! - `porterstem --json < doc.txt` prints a JSON line for every word, such
+//!   as `{"token":"Running","stem":"run","offset":123}`, giving its byte
+//!   offset in the input (the file's, with several files)
+//! - `porterstem --tsv < doc.txt` prints a `word\tstem` line for every word,
+//!   lowercased, and `--tsv --unique` one for each distinct word
+//! - `porterstem --changed-only --unique < vocabulary.txt` prints a
+//!   `word -> stem` line for each word whose stem differs from it, skipping
+//!   the words stemming leaves alone, to see what the stemmer does to a new
+//!   vocabulary
+//! - `porterstem --unique < corpus.txt` prints each distinct stem of all the
+//!   input once, sorted, for building vocabulary files
+//! - `porterstem --stats < corpus.txt` summarizes the input: tokens, unique
+//!   words and stems, words per stem, average stem length and the stems
+//!   merging the most distinct words (the top 10, or `--top N`)
+//! - `porterstem --jsonl --fields title,body.text < docs.jsonl`
+//!   stems the given fields of each JSON record, passing the rest through
+//! - `porterstem --kwic run < corpus.txt` prints a concordance of
+//!   every word stemming like "run", with `--window 30` characters of
+//!   context on each side
+//! - `porterstem --freq < corpus.txt` prints a `count\tstem` line for each
+//!   stem of all the input, most frequent first (like `sort | uniq -c |
+//!   sort -rn`); `--top 20` prints only the 20 most frequent
+//! - `porterstem --sqlite stems.db docs/*.txt`, built with the `sqlite`
+//!   feature, writes every token of the files with its offset and stem, the
+//!   stem counts and the counts per document to an SQLite database, to query
+//!   with SQL (`SELECT * FROM stem_frequencies LIMIT 20`)
+//! - `porterstem --compressed auto corpus.txt.gz -o stems.txt.zst`, built
+//!   with the `compress` feature, reads gzip and zstd input, telling it from
+//!   plain text by its first bytes, and compresses output files named `.gz`
+//!   or `.zst`; `--compressed gzip` (or `zstd`) decompresses every input and
+//!   compresses every output, stdout included
+//! - Built with the `config` feature, `porterstem --config porterstem.toml`
+//!   reads settings from a file
+//!   (`algorithm = "porter2"`, `format = "tsv"`, `fold = true`), as does a
+//!   `porterstem.toml` in the current directory or one above it unless
+//!   `--no-config` is given; flags on the command line win over the file
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use inputs::InputFile;
+use progress::{Counted, Reporter, Totals};
+
+mod bench;
+mod config;
+mod daemon;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod inputs;
+mod progress;
+#[cfg(feature = "serve")]
+mod serve;
+#[cfg(not(feature = "compress"))]
+mod uncompressed;
+#[cfg(feature = "websocket")]
+mod websocket;
+
+#[cfg(feature = "compress")]
+use m_porter_stemmer_rust::compress::{self, Compression, Encoder};
+#[cfg(not(feature = "compress"))]
+use uncompressed::{self as compress, Compression, Encoder};
+use m_porter_stemmer_rust::csv::{self, CsvOptions};
+use m_porter_stemmer_rust::pipeline::Pipeline;
+use m_porter_stemmer_rust::stats::CorpusStats;
+use m_porter_stemmer_rust::stopwords::Stopwords;
+use m_porter_stemmer_rust::text::TextOptions;
+use m_porter_stemmer_rust::token::{DigitPolicy, LongTokenPolicy, SymbolPolicy};
+use m_porter_stemmer_rust::freq::{sorted_counts, FrequencyCounter};
+use m_porter_stemmer_rust::index::Index;
+#[cfg(feature = "sqlite")]
+use m_porter_stemmer_rust::sqlite::StemDatabase;
+use m_porter_stemmer_rust::{
+    concordance, html, identifier, json, markdown, text, Algorithm, Language, PorterStemmer, StemError, Stemmer,
+};
+
+/// What the binary should do, as selected by the command line flags
+#[derive(Debug, PartialEq)]
+enum Mode {
+    /// Stem plain text, line by line
+    Text,
+    /// Split and stem the identifiers of source code on stdin
+    Identifiers,
+    /// Stem the prose of HTML on stdin, keeping the document structure
+    Html,
+    /// Stem the visible text of HTML on stdin, dropping the markup
+    HtmlText,
+    /// Stem the prose of Markdown on stdin, skipping code and links
+    Markdown,
+    /// Stem selected columns of CSV on stdin
+    Csv,
+    /// Stem selected fields of newline-delimited JSON on stdin
+    Jsonl,
+    /// Describe every word of plain text as a JSON line of token, stem and
+    /// offset
+    JsonTokens,
+    /// Print keyword-in-context lines for a word on stdin
+    Concordance,
+    /// Print a table of stem counts for text on stdin
+    Freq,
+    /// Print a `word\tstem` line for every word of plain text
+    Tsv,
+    /// Print each distinct stem of plain text once, sorted
+    Unique,
+    /// Print the algorithms and languages to choose from
+    ListAlgorithms,
+    /// Print a summary of the words and stems of plain text
+    Stats,
+    /// Print the steps of the Porter algorithm for each word
+    Trace,
+    /// Stem NUL-delimited words, as from `find -print0`
+    Null,
+    /// Compare the stems of a vocabulary with an expected-output file
+    Check,
+    /// Compare the stems of a corpus with a saved `word\tstem` mapping
+    Verify,
+    /// Measure the speed and allocations of stemming a corpus
+    Bench,
+    /// Print a `word -> stem` line for every word that stemming changes
+    Changed,
+    /// Build a search index of the input files
+    Index,
+    /// Rank the documents of a search index against a query
+    Search,
+    /// Write the tokens and stems of the input files to an SQLite database
+    Sqlite,
+    /// Answer stemming requests over HTTP
+    Serve,
+    /// Answer stemming requests line by line on stdio or a Unix socket
+    Daemon,
+}
+
+/// How gzip and zstd apply to the inputs and outputs, from `--compressed`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Compressed {
+    /// Read and write files as they are
+    Off,
+    /// Decompress inputs that start with a gzip or zstd magic number and
+    /// compress output files named `.gz` or `.zst`
+    Auto,
+    /// Decompress every input and compress every output, stdout included
+    Always(Compression),
+}
+
+impl Compressed {
+    /// The compression of the output file `path`, or of stdout if None
+    fn output(self, path: Option<&Path>) -> Option<Compression> {
+        match self {
+            Compressed::Off => None,
+            Compressed::Auto => path.and_then(Compression::from_path),
+            Compressed::Always(compression) => Some(compression),
+        }
+    }
+}
+
+/// Parsed command line
+#[derive(Debug, PartialEq)]
+struct Options {
+    mode: Mode,
+    /// Input files, read in order; stdin if empty
+    files: Vec<String>,
+    /// Words to trace in trace mode, instead of those of the input
+    words: Vec<String>,
+    /// File to write to instead of stdout
+    output: Option<String>,
+    /// Walk directory arguments
+    recursive: bool,
+    /// Directory to write one output file per input to, mirroring the input
+    /// tree
+    out_dir: Option<String>,
+    /// Number of worker threads for several input files
+    jobs: usize,
+    /// Decompression of the inputs and compression of the outputs
+    compressed: Compressed,
+    /// Read input files through a memory map, for text mode
+    mmap: bool,
+    /// Run again whenever an input file changes
+    watch: bool,
+    /// Report progress on stderr even when it is not a terminal or the input
+    /// is stdin
+    progress: bool,
+    /// Report neither progress nor changes seen by `--watch`
+    quiet: bool,
+    /// Replace each input file with its output
+    in_place: bool,
+    /// Suffix of the copy of each file kept by `--in-place`
+    backup: Option<String>,
+    /// SQLite database file to write the tokens and stems to
+    sqlite: Option<String>,
+    /// Saved `word\tstem` mapping for `verify` to compare with
+    golden: Option<String>,
+    /// Address and port to listen on with `serve`
+    host: String,
+    port: u16,
+    /// Whether `serve` answers gRPC instead of JSON over HTTP/1.1
+    grpc: bool,
+    /// Unix domain socket for `daemon` to listen on instead of stdio
+    socket: Option<String>,
+    /// 0-based columns to stem in CSV mode
+    columns: Vec<usize>,
+    /// Field separator in CSV mode
+    delimiter: u8,
+    /// Whether the first CSV row is a header to copy unchanged
+    has_header: bool,
+    /// Dotted field paths to stem in JSONL mode
+    fields: Vec<String>,
+    /// `builtin` or the path of a stopword list, for text mode
+    stopwords: Option<String>,
+    /// Language of the text
+    lang: Language,
+    /// Stemming algorithm for English text
+    algorithm: Algorithm,
+    /// Fold diacritics to ASCII before stemming
+    fold: bool,
+    /// Give stems the case of their words, for text mode
+    preserve_case: bool,
+    /// Handling of tokens with digits, for text mode
+    digits: Option<DigitPolicy>,
+    /// Drop symbols (`Some(true)`) or keep them (`Some(false)`), for text mode
+    drop_symbols: Option<bool>,
+    /// Text replacing each symbol, for text mode
+    symbol_placeholder: Option<String>,
+    /// Shortest word to stem, for text mode
+    min_length: Option<usize>,
+    /// Longest token to stem, for text mode
+    max_length: Option<usize>,
+    /// What to do with tokens over `max_length`, for text mode
+    long_tokens: Option<LongTokenPolicy>,
+    /// Separator between the stems of an identifier
+    join: String,
+    /// Word to look up in concordance mode
+    keyword: String,
+    /// Characters of context on each side in concordance mode
+    window: usize,
+    /// Print each distinct stem (or, in TSV mode, line) once
+    unique: bool,
+    /// Number of most frequent stems to print in frequency mode, or of
+    /// conflation classes in statistics mode
+    top: Option<usize>,
+}
+
+/// Parses a `--columns` list of 1-based column numbers such as `2,5`
+fn parse_columns(list: &str) -> Result<Vec<usize>, String> {
+    list.split(',')
+        .map(|n| match n.trim().parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n - 1),
+            _ => Err(format!("invalid column number: {:?}", n)),
+        })
+        .collect()
+}
+
+/// Parses a `--delimiter` value: a single ASCII character or `tab`
+fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value {
+        "tab" | "\\t" | "\t" => Ok(b'\t'),
+        _ if value.len() == 1 && value.is_ascii() => Ok(value.as_bytes()[0]),
+        _ => Err(format!("invalid delimiter: {:?}", value)),
+    }
+}
+
+/// Parses the command line arguments (excluding the program name)
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut options = Options {
+        mode: Mode::Text,
+        files: Vec::new(),
+        words: Vec::new(),
+        output: None,
+        recursive: false,
+        out_dir: None,
+        jobs: thread::available_parallelism().map_or(1, usize::from),
+        compressed: Compressed::Off,
+        mmap: false,
+        watch: false,
+        progress: false,
+        quiet: false,
+        in_place: false,
+        backup: None,
+        sqlite: None,
+        golden: None,
+        host: "127.0.0.1".to_string(),
+        port: 8080,
+        grpc: false,
+        socket: None,
+        columns: Vec::new(),
+        delimiter: b',',
+        has_header: true,
+        fields: Vec::new(),
+        stopwords: None,
+        lang: Language::English,
+        algorithm: Algorithm::Porter,
+        fold: false,
+        preserve_case: false,
+        digits: None,
+        drop_symbols: None,
+        symbol_placeholder: None,
+        min_length: None,
+        max_length: None,
+        long_tokens: None,
+        join: " ".to_string(),
+        keyword: String::new(),
+        window: 30,
+        unique: false,
+        top: None,
+    };
+
+    let mut args = args.into_iter().peekable();
+    if let Some(command) = args.next_if(|arg| config::SUBCOMMANDS.contains(&arg.as_str())) {
+        options.mode = match command.as_str() {
+            "bench" => Mode::Bench,
+            "verify" => Mode::Verify,
+            "index" => Mode::Index,
+            "serve" => Mode::Serve,
+            "daemon" => Mode::Daemon,
+            _ => Mode::Search,
+        };
+    }
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("{} requires a value", flag));
+        match arg.as_str() {
+            "--text" => options.mode = Mode::Text,
+            "--stopwords" => options.stopwords = Some(value("--stopwords")?),
+            "--identifiers" => options.mode = Mode::Identifiers,
+            "--join" => options.join = value("--join")?,
+            "--algorithm" => options.algorithm = value("--algorithm")?.parse()?,
+            "--lang" => options.lang = value("--lang")?.parse()?,
+            "--list-algorithms" => options.mode = Mode::ListAlgorithms,
+            "--trace" => options.mode = Mode::Trace,
+            "-0" | "--null" => options.mode = Mode::Null,
+            "--check" => options.mode = Mode::Check,
+            "--golden" => options.golden = Some(value("--golden")?),
+            "--watch" => options.watch = true,
+            "--progress" => options.progress = true,
+            "-q" | "--quiet" => options.quiet = true,
+            "-i" | "--in-place" => options.in_place = true,
+            "--backup" => options.backup = Some(value("--backup")?),
+            "--fold" => options.fold = true,
+            "--preserve-case" => options.preserve_case = true,
+            "--digits" => options.digits = Some(value("--digits")?.parse()?),
+            "--symbols" => {
+                options.drop_symbols = Some(match value("--symbols")?.as_str() {
+                    "drop" => true,
+                    "preserve" => false,
+                    other => return Err(format!("unknown symbol policy: {:?} (expected preserve or drop)", other)),
+                });
             },
-            _ => true,
-        }
-    }
-
-    /// Measures the number of consonant sequences between k0 and j
-    /// 
-    /// # Returns
-    /// The number of consonant-vowel sequences (measure)
-    /// 
-    /// # Examples
-    /// - TR.A gives measure 1
-    /// - TRE.A gives measure 1
-    /// - Y gives measure 0
-    /// - BY gives measure 1
-    /// 
-    /// Where '.' indicates the current position
-    fn measure(&self) -> usize {
-        let mut n = 0;
-        let mut i = self.k0;
-        
-        loop {
-            if i > self.j { return n; }
-            if !self.is_consonant(i) { break; }
-            i += 1;
-        }
-        
-        i += 1;
-        
-        loop {
-            loop {
-                if i > self.j { return n; }
-                if self.is_consonant(i) { break; }
-                i += 1;
-            }
-            
-            i += 1;
-            n += 1;
-            
-            loop {
-                if i > self.j { return n; }
-                if !self.is_consonant(i) { break; }
-                i += 1;
-            }
-            
-            i += 1;
+            "--symbol-placeholder" => options.symbol_placeholder = Some(value("--symbol-placeholder")?),
+            "--min-length" | "--max-length" => {
+                let length = value(&arg)?;
+                let length = Some(length.parse().map_err(|_| format!("invalid {} length: {:?}", arg, length))?);
+                if arg == "--min-length" {
+                    options.min_length = length;
+                } else {
+                    options.max_length = length;
+                }
+            },
+            "--long-tokens" => options.long_tokens = Some(value("--long-tokens")?.parse()?),
+            "--html" => options.mode = Mode::Html,
+            "--html-text" => options.mode = Mode::HtmlText,
+            "--markdown" => options.mode = Mode::Markdown,
+            "--csv" => options.mode = Mode::Csv,
+            "--columns" => options.columns = parse_columns(&value("--columns")?)?,
+            "--delimiter" => options.delimiter = parse_delimiter(&value("--delimiter")?)?,
+            "--no-header" => options.has_header = false,
+            "--jsonl" => options.mode = Mode::Jsonl,
+            "--json" => options.mode = Mode::JsonTokens,
+            "--fields" => {
+                options.fields = value("--fields")?.split(',').map(|f| f.trim().to_string()).collect();
+            },
+            "--kwic" => {
+                options.mode = Mode::Concordance;
+                options.keyword = value("--kwic")?;
+            },
+            "--freq" => options.mode = Mode::Freq,
+            "--tsv" => options.mode = Mode::Tsv,
+            "--changed-only" => options.mode = Mode::Changed,
+            "--stats" => options.mode = Mode::Stats,
+            "--sqlite" if cfg!(feature = "sqlite") => {
+                options.mode = Mode::Sqlite;
+                options.sqlite = Some(value("--sqlite")?);
+            },
+            "--sqlite" => return Err("--sqlite requires porterstem built with the sqlite feature".to_string()),
+            "--unique" => options.unique = true,
+            "--top" => {
+                let top = value("--top")?;
+                options.top = Some(top.parse().map_err(|_| format!("invalid --top count: {:?}", top))?);
+            },
+            "--window" => {
+                let window = value("--window")?;
+                options.window = window.parse().map_err(|_| format!("invalid window: {:?}", window))?;
+            },
+            "-o" | "--output" | "--out" => options.output = Some(value("--output")?),
+            "--host" => options.host = value("--host")?,
+            "--port" => {
+                let port = value("--port")?;
+                options.port = port.parse().map_err(|_| format!("invalid port: {:?}", port))?;
+            },
+            "--grpc" if cfg!(feature = "grpc") => options.grpc = true,
+            "--grpc" => return Err("--grpc requires porterstem built with the grpc feature".to_string()),
+            "--socket" if cfg!(unix) => options.socket = Some(value("--socket")?),
+            "--socket" => return Err("--socket requires Unix domain sockets".to_string()),
+            "-r" | "--recursive" => options.recursive = true,
+            "--out-dir" => options.out_dir = Some(value("--out-dir")?),
+            "-j" | "--jobs" => {
+                let jobs = value("--jobs")?;
+                options.jobs = match jobs.parse() {
+                    Ok(jobs) if jobs > 0 => jobs,
+                    _ => return Err(format!("invalid number of jobs: {:?}", jobs)),
+                };
+            },
+            "--compressed" if cfg!(feature = "compress") => {
+                options.compressed = match value("--compressed")?.as_str() {
+                    "auto" => Compressed::Auto,
+                    "none" => Compressed::Off,
+                    other => Compressed::Always(other.parse()?),
+                };
+            },
+            "--compressed" => return Err("--compressed requires porterstem built with the compress feature".to_string()),
+            "--mmap" if cfg!(all(feature = "mmap", unix)) => options.mmap = true,
+            "--mmap" => return Err("--mmap requires porterstem built with the mmap feature, on Unix".to_string()),
+            "-" => options.files.push(arg),
+            _ if arg.starts_with('-') => return Err(format!("unknown argument: {}", arg)),
+            _ => options.files.push(arg),
         }
     }
 
-    /// Returns true if k0,...j contains a vowel
-    fn vowel_in_stem(&self) -> bool {
-        (self.k0..=self.j).any(|i| !self.is_consonant(i))
+    if options.out_dir.is_some() && options.output.is_some() {
+        return Err("--out-dir cannot be combined with --output".to_string());
+    }
+    if options.out_dir.is_some() && (options.files.is_empty() || options.files.iter().any(|f| f == "-")) {
+        return Err("--out-dir requires input files".to_string());
+    }
+    if options.top.is_some() && !matches!(options.mode, Mode::Freq | Mode::Stats | Mode::Search) {
+        return Err("--top is only supported with --freq, --stats and search".to_string());
+    }
+    if options.unique && options.mode == Mode::Text {
+        options.mode = Mode::Unique;
+    }
+    if options.unique && !matches!(options.mode, Mode::Tsv | Mode::Unique | Mode::Changed) {
+        return Err("--unique is only supported with plain text, --tsv or --changed-only".to_string());
+    }
+    if options.mode == Mode::Csv && options.columns.is_empty() {
+        return Err("--csv requires --columns".to_string());
+    }
+    if options.mode == Mode::Jsonl && options.fields.is_empty() {
+        return Err("--jsonl requires --fields".to_string());
+    }
+    if options.stopwords.is_some() && !matches!(options.mode, Mode::Text | Mode::Freq | Mode::Unique) {
+        return Err("--stopwords is only supported with --text, --freq and --unique".to_string());
+    }
+    if options.preserve_case && options.mode != Mode::Text {
+        return Err("--preserve-case is only supported with --text".to_string());
+    }
+    if options.digits.is_some() && options.mode != Mode::Text {
+        return Err("--digits is only supported with --text".to_string());
     }
+    if (options.drop_symbols.is_some() || options.symbol_placeholder.is_some()) && options.mode != Mode::Text {
+        return Err("--symbols and --symbol-placeholder are only supported with --text".to_string());
+    }
+    if (options.min_length.is_some() || options.max_length.is_some()) && options.mode != Mode::Text {
+        return Err("--min-length and --max-length are only supported with --text".to_string());
+    }
+    if options.long_tokens.is_some() && options.max_length.is_none() {
+        return Err("--long-tokens requires --max-length".to_string());
+    }
+    if options.algorithm != Algorithm::Porter && options.lang != Language::English {
+        return Err("--algorithm only applies to English text".to_string());
+    }
+    if options.mode == Mode::Trace {
+        if options.algorithm != Algorithm::Porter || options.lang != Language::English {
+            return Err("--trace only traces the Porter algorithm".to_string());
+        }
+        if options.out_dir.is_some() || options.recursive {
+            return Err("--trace reads its words from the arguments or stdin".to_string());
+        }
+        options.words = std::mem::take(&mut options.files);
+    }
+    if options.mode == Mode::Check && (options.files.len() != 2 || options.out_dir.is_some() || options.recursive) {
+        return Err("--check requires a vocabulary file and an expected-output file".to_string());
+    }
+    if (options.mode == Mode::Verify) != options.golden.is_some() {
+        return Err("verify requires --golden FILE, which only verify takes".to_string());
+    }
+    if options.mode == Mode::Verify && (options.out_dir.is_some() || options.watch) {
+        return Err("verify writes one report, without --out-dir or --watch".to_string());
+    }
+    if options.mode == Mode::Bench && (options.files.len() > 1 || options.out_dir.is_some() || options.recursive) {
+        return Err("bench takes at most one corpus file".to_string());
+    }
+    if options.mode == Mode::Index {
+        if options.output.is_none() || options.files.is_empty() || options.files.iter().any(|f| f == "-") {
+            return Err("index requires --out DIR and input files or directories".to_string());
+        }
+        options.recursive = true;
+    }
+    if options.mode == Mode::Search {
+        if options.files.len() < 2 {
+            return Err("search requires an index directory and a query".to_string());
+        }
+        options.words = options.files.split_off(1);
+    }
+    if options.mode == Mode::Serve && (!options.files.is_empty() || options.output.is_some() || options.watch) {
+        return Err("serve takes no input or output files".to_string());
+    }
+    if options.mode == Mode::Daemon && (!options.files.is_empty() || options.output.is_some() || options.watch) {
+        return Err("daemon takes no input or output files".to_string());
+    }
+    if options.grpc && options.mode != Mode::Serve {
+        return Err("--grpc is only supported with serve".to_string());
+    }
+    if options.mode == Mode::Serve && !options.grpc && !cfg!(feature = "serve") {
+        return Err("serve requires porterstem built with the serve feature, or --grpc and the grpc feature".to_string());
+    }
+    if options.socket.is_some() && options.mode != Mode::Daemon {
+        return Err("--socket is only supported with daemon".to_string());
+    }
+    if options.mode == Mode::Sqlite && (options.output.is_some() || options.out_dir.is_some() || options.mmap) {
+        return Err("--sqlite writes the database file only, without --output, --out-dir or --mmap".to_string());
+    }
+    if options.backup.is_some() && !options.in_place {
+        return Err("--backup requires --in-place".to_string());
+    }
+    if options.backup.as_deref().is_some_and(|suffix| suffix.is_empty() || suffix.contains(['/', '\\'])) {
+        return Err("--backup takes a file name suffix such as .bak".to_string());
+    }
+    if options.in_place {
+        if options.files.is_empty() || options.files.iter().any(|f| f == "-") {
+            return Err("--in-place requires input files".to_string());
+        }
+        if options.output.is_some() || options.out_dir.is_some() || options.mmap || options.watch {
+            return Err("--in-place cannot be combined with --output, --out-dir, --mmap or --watch".to_string());
+        }
+        let whole_input =
+            matches!(options.mode, Mode::Freq | Mode::Stats | Mode::Unique | Mode::Check | Mode::Verify | Mode::Bench);
+        if whole_input || matches!(options.mode, Mode::Trace | Mode::Sqlite) {
+            return Err("--in-place needs a mode with one output per input file".to_string());
+        }
+    }
+    if options.watch && (options.files.is_empty() || options.files.iter().any(|f| f == "-")) {
+        return Err("--watch requires input files".to_string());
+    }
+    if options.mmap && (options.mode != Mode::Text || options.compressed != Compressed::Off) {
+        return Err("--mmap is only supported with --text, on uncompressed input".to_string());
+    }
+    if options.mmap && options.long_tokens == Some(LongTokenPolicy::Reject) {
+        return Err("--long-tokens fail cannot be combined with --mmap".to_string());
+    }
+    if options.progress && options.quiet {
+        return Err("--progress cannot be combined with --quiet".to_string());
+    }
+    if options.drop_symbols.is_some() && options.symbol_placeholder.is_some() {
+        return Err("--symbols cannot be combined with --symbol-placeholder".to_string());
+    }
+    Ok(options)
+}
 
-    /// Returns true if j,(j-1) contain a double consonant
-    fn double_consonant(&self, j: usize) -> bool {
-        if j < self.k0 + 1 { return false; }
-        if self.buffer[j] != self.buffer[j-1] { return false; }
-        self.is_consonant(j)
+/// Resolves the input arguments to files, reporting those that name no
+/// file on stderr; the returned flag is false if that happened
+fn collect_inputs(args: &[String], recursive: bool) -> (Vec<InputFile>, bool) {
+    let mut files = Vec::new();
+    let mut all_found = true;
+    for arg in args {
+        match inputs::expand(arg, recursive) {
+            Ok(found) => files.extend(found),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                all_found = false;
+            },
+        }
     }
+    (files, all_found)
+}
 
-    /// Returns true if i-2,i-1,i has the form consonant-vowel-consonant
-    /// and also if the second c is not w,x or y
-    fn cvc(&self, i: usize) -> bool {
-        if i < self.k0 + 2 
-            || !self.is_consonant(i)
-            || self.is_consonant(i-1)
-            || !self.is_consonant(i-2) {
-            return false;
+/// An input reader whose errors name the file, since a corrupt
+/// stream only shows while it is read
+struct Named<R> {
+    inner: R,
+    name: String,
+}
+
+impl<R: Read> Read for Named<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", self.name, e)))
+    }
+}
+
+/// Opens an input file, or stdin for `-`, decompressing it as `compressed`
+/// says
+///
+/// The bytes read are counted before decompression, for progress reports.
+fn open_file(file: &InputFile, compressed: Compressed) -> io::Result<Box<dyn Read>> {
+    let (input, name): (Box<dyn Read>, String) = if file.is_stdin() {
+        (Box::new(Counted::new(io::stdin())), "stdin".to_string())
+    } else {
+        let path = &file.path;
+        let opened = File::open(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+        (Box::new(Counted::new(opened)), path.display().to_string())
+    };
+    let inner = match compressed {
+        Compressed::Off => return Ok(input),
+        Compressed::Auto => compress::detect_reader(BufReader::new(input))
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", name, e)))?,
+        Compressed::Always(compression) => compression.decoder(BufReader::new(input))
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", name, e)))?,
+    };
+    Ok(Box::new(Named { inner, name }))
+}
+
+/// Opens the input files as one stream
+///
+/// A file that cannot be opened is reported on stderr and skipped; the
+/// returned flag is false if that happened.
+fn open_input(files: &[InputFile], compressed: Compressed) -> (Box<dyn BufRead>, bool) {
+    if let [file] = files {
+        if file.is_stdin() && compressed == Compressed::Off {
+            return (Box::new(BufReader::new(Counted::new(io::stdin().lock()))), true);
         }
-        
-        match self.buffer[i] {
-            'w' | 'x' | 'y' => false,
-            _ => true,
+    }
+    let mut input: Box<dyn Read> = Box::new(io::empty());
+    let mut all_opened = true;
+    for file in files {
+        match open_file(file, compressed) {
+            Ok(file) => input = Box::new(input.chain(file)),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                all_opened = false;
+            },
         }
     }
+    (Box::new(BufReader::new(input)), all_opened)
+}
 
-    /// Returns true if the word ends with the given string
-    fn ends_with(&mut self, s: &str) -> bool {
-        let length = s.len();
-        if length > self.k - self.k0 + 1 { return false; }
-        
-        let end = &self.buffer[(self.k + 1 - length)..=self.k];
-        let s_chars: Vec<char> = s.chars().collect();
-        
-        if end != &s_chars[..] { return false; }
-        
-        self.j = self.k - length;
-        true
+/// Smallest input file stemmed on several threads by the pipeline
+const PIPELINE_MIN_BYTES: u64 = 1 << 20;
+
+/// Opens the input for the parallel pipeline if it is a single plain-text
+/// input that is worth it: stdin, or a file of at least
+/// [`PIPELINE_MIN_BYTES`], with more than one job and no decompression
+fn pipeline_input(options: &Options, files: &[InputFile]) -> Option<Box<dyn Read + Send>> {
+    let [file] = files else { return None };
+    if options.mode != Mode::Text || options.jobs < 2 || options.compressed != Compressed::Off {
+        return None;
+    }
+    if file.is_stdin() {
+        return Some(Box::new(Counted::new(io::stdin())));
+    }
+    // A file that cannot be opened is reported by the usual path
+    let opened = File::open(&file.path).ok().filter(|f| f.metadata().is_ok_and(|m| m.len() >= PIPELINE_MIN_BYTES))?;
+    Some(Box::new(Named { inner: Counted::new(opened), name: file.path.display().to_string() }))
+}
+
+/// Creates the `--output` file, or returns stdout if there is none,
+/// compressing as `compressed` says
+fn open_output(path: Option<&str>, compressed: Compressed) -> io::Result<Encoder<Box<dyn Write>>> {
+    let out: Box<dyn Write> = match path {
+        Some(path) => {
+            let file = File::create(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path, e)))?;
+            Box::new(BufWriter::new(file))
+        },
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    Encoder::new(out, compressed.output(path.map(Path::new)))
+}
+
+/// Reads all of the input into a String
+fn read_all(mut input: impl BufRead) -> io::Result<String> {
+    let mut text = String::new();
+    input.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Counts the stems of the input a piece at a time, without reading it all
+/// at once, skipping stopwords
+fn count_stems(
+    stemmer: &mut dyn Stemmer,
+    input: impl Read,
+    stopwords: Option<&Stopwords>,
+) -> io::Result<Vec<(String, u64)>> {
+    let mut counter = FrequencyCounter::new();
+    if let Some(stopwords) = stopwords {
+        counter = counter.stopwords(stopwords);
     }
+    text::read_chunks(input, |chunk| {
+        counter.feed(stemmer, chunk);
+        Ok(())
+    })?;
+    Ok(sorted_counts(counter.finish(stemmer)))
+}
 
-    /// Sets (j+1),...k to the characters in the string s
-    fn set_to(&mut self, s: &str) {
-        let s_chars: Vec<char> = s.chars().collect();
-        let length = s_chars.len();
-        
-        for (i, &ch) in s_chars.iter().enumerate() {
-            self.buffer[self.j + 1 + i] = ch;
-        }
-        
-        self.k = self.j + length;
-    }
-
-    /// Main stemming function that processes a word through all steps
-    /// 
-    /// # Arguments
-    /// * `word` - Input word to be stemmed
-    /// 
-    /// # Returns
-    /// The stemmed word as a String
-    /// 
-    /// # Examples
-    /// ```
-    /// let mut stemmer = PorterStemmer::new();
-    /// assert_eq!(stemmer.stem("running"), "run");
-    /// ```
-    /// 
-    /// # Process
-    /// 1. Converts input to lowercase
-    /// 2. Applies steps 1a through 5 in sequence
-    /// 3. Returns the stemmed result
-    pub fn stem(&mut self, word: &str) -> String {
-        if word.is_empty() { return String::new(); }
-        
-        // Convert to lowercase and store in buffer
-        self.buffer = word.to_lowercase().chars().collect();
-        self.k = self.buffer.len() - 1;
-        self.k0 = 0;
-        
-        if self.k <= self.k0 + 1 { 
-            return self.buffer.iter().collect(); 
-        }
-
-        self.step1ab();
-        if self.k > self.k0 {
-            self.step1c();
-            self.step2();
-            self.step3();
-            self.step4();
-            self.step5();
-        }
-
-        self.buffer[0..=self.k].iter().collect()
-    }
-    
-    /// Step 1ab handles plurals and past participles
-    /// 
-    /// # Transformations
-    /// - SSES -> SS (caresses -> caress)
-    /// - IES  -> I  (ponies -> poni)
-    /// - SS   -> SS (caress -> caress)
-    /// - S    ->    (cats -> cat)
-    /// 
-    /// And then:
-    /// - (m>0) EED -> EE     (agreed -> agree)
-    /// - (*v*) ED  ->        (plastered -> plaster)
-    /// - (*v*) ING ->        (motoring -> motor)
-    fn step1ab(&mut self) {
-        if self.buffer[self.k] == 's' {
-            if self.ends_with("sses") {
-                self.k -= 2;
-            } else if self.ends_with("ies") {
-                self.set_to("i");
-            } else if self.buffer[self.k - 1] != 's' {
-                self.k -= 1;
+/// Writes a lowercased `word\tstem` line for every word of the input, or
+/// only for the first occurrence of each word if `unique` is set
+fn write_word_stems(stemmer: &mut dyn Stemmer, input: impl Read, out: &mut dyn Write, unique: bool) -> io::Result<()> {
+    let mut seen = HashSet::new();
+    text::read_chunks(input, |chunk| {
+        for (token, stem) in text::token_stems(stemmer, chunk) {
+            let word = token.text.to_lowercase();
+            if !unique || seen.insert(word.clone()) {
+                writeln!(out, "{}\t{}", word, stem)?;
             }
         }
+        Ok(())
+    })
+}
 
-        if self.ends_with("eed") {
-            if self.measure() > 0 {
-                self.k -= 1;
-            }
-        } else if (self.ends_with("ed") || self.ends_with("ing")) && self.vowel_in_stem() {
-            self.k = self.j;
-
-            if self.ends_with("at") {
-                self.set_to("ate");
-            } else if self.ends_with("bl") {
-                self.set_to("ble");
-            } else if self.ends_with("iz") {
-                self.set_to("ize");
-            } else if self.double_consonant(self.k) {
-                self.k -= 1;
-                let ch = self.buffer[self.k];
-                if ch == 'l' || ch == 's' || ch == 'z' {
-                    self.k += 1;
-                }
-            } else if self.measure() == 1 && self.cvc(self.k) {
-                self.set_to("e");
+/// Writes a lowercased `word -> stem` line for every word of the input whose
+/// stem is not the word itself, or only for the first occurrence of each
+/// such word if `unique` is set
+fn write_changed_stems(
+    stemmer: &mut dyn Stemmer,
+    input: impl Read,
+    out: &mut dyn Write,
+    unique: bool,
+) -> io::Result<()> {
+    let mut seen = HashSet::new();
+    text::read_chunks(input, |chunk| {
+        for (token, stem) in text::token_stems(stemmer, chunk) {
+            let word = token.text.to_lowercase();
+            if stem != word && (!unique || seen.insert(word.clone())) {
+                writeln!(out, "{} -> {}", word, stem)?;
             }
         }
+        Ok(())
+    })
+}
+
+/// Stems NUL-terminated records, each one as a single word however odd its
+/// content, writing each stem followed by a NUL; a last record missing its
+/// NUL gets one
+fn stem_records(stemmer: &mut dyn Stemmer, mut input: impl BufRead, out: &mut dyn Write) -> io::Result<()> {
+    let mut record = Vec::new();
+    while input.read_until(b'\0', &mut record)? > 0 {
+        if record.last() == Some(&b'\0') {
+            record.pop();
+        }
+        out.write_all(stemmer.stem(&String::from_utf8_lossy(&record)).as_bytes())?;
+        out.write_all(b"\0")?;
+        record.clear();
     }
+    Ok(())
+}
 
-    /// Step 1c turns terminal y to i when there is another vowel in the stem
-    /// 
-    /// # Examples
-    /// - happy -> happi
-    /// - sky -> sky (unchanged)
-    fn step1c(&mut self) {
-        if self.ends_with("y") && self.vowel_in_stem() {
-            self.buffer[self.k] = 'i';
+/// Stems each word of `vocabulary`, one per line, and compares the stem with
+/// the same line of `expected`, writing a line for each mismatch and a
+/// summary; returns the number of mismatches
+///
+/// A difference in the number of lines counts as one more mismatch.
+fn check_stems(
+    stemmer: &mut dyn Stemmer,
+    (vocabulary, vocabulary_name): (impl BufRead, &str),
+    (expected, expected_name): (impl BufRead, &str),
+    out: &mut dyn Write,
+) -> io::Result<usize> {
+    let named = |name: &str, e: io::Error| io::Error::new(e.kind(), format!("{}: {}", name, e));
+    let mut expected = expected.lines();
+    let (mut words, mut mismatches) = (0, 0);
+    for (number, word) in vocabulary.lines().enumerate() {
+        let word = word.map_err(|e| named(vocabulary_name, e))?;
+        let Some(wanted) = expected.next().transpose().map_err(|e| named(expected_name, e))? else {
+            writeln!(out, "{}: ends at line {}, before {}", expected_name, number, vocabulary_name)?;
+            mismatches += 1;
+            break;
+        };
+        let (word, wanted) = (word.trim(), wanted.trim());
+        let stem = stemmer.stem(word);
+        if stem != wanted {
+            writeln!(out, "{}:{}: {:?} -> {:?}, expected {:?}", vocabulary_name, number + 1, word, stem, wanted)?;
+            mismatches += 1;
         }
+        words += 1;
+    }
+    if expected.next().is_some() {
+        writeln!(out, "{}: has more lines than {}", expected_name, vocabulary_name)?;
+        mismatches += 1;
     }
+    writeln!(out, "{} words checked, {} mismatched", words, mismatches)?;
+    Ok(mismatches)
+}
 
-    /// Step 2 maps double suffices to single ones when measure > 0
-    /// 
-    /// # Examples
-    /// - ATIONAL -> ATE (relational -> relate)
-    /// - TIONAL  -> TION (conditional -> condition)
-    /// - ENCI    -> ENCE (valenci -> valence)
-    fn step2(&mut self) {
-        if self.k <= self.k0 { return; }
-        
-        match self.buffer[self.k - 1] {
-            'a' => {
-                if self.ends_with("ational") { self.replace_suffix_if_stem_measured("ate"); }
-                else if self.ends_with("tional") { self.replace_suffix_if_stem_measured("tion"); }
-            },
-            'c' => {
-                if self.ends_with("enci") { self.replace_suffix_if_stem_measured("ence"); }
-                else if self.ends_with("anci") { self.replace_suffix_if_stem_measured("ance"); }
-            },
-            'e' => {
-                if self.ends_with("izer") { self.replace_suffix_if_stem_measured("ize"); }
-            },
-            'l' => {
-                if self.ends_with("bli") { self.replace_suffix_if_stem_measured("ble"); }
-                else if self.ends_with("alli") { self.replace_suffix_if_stem_measured("al"); }
-                else if self.ends_with("entli") { self.replace_suffix_if_stem_measured("ent"); }
-                else if self.ends_with("eli") { self.replace_suffix_if_stem_measured("e"); }
-                else if self.ends_with("ousli") { self.replace_suffix_if_stem_measured("ous"); }
-            },
-            'o' => {
-                if self.ends_with("ization") { self.replace_suffix_if_stem_measured("ize"); }
-                else if self.ends_with("ation") { self.replace_suffix_if_stem_measured("ate"); }
-                else if self.ends_with("ator") { self.replace_suffix_if_stem_measured("ate"); }
-            },
-            's' => {
-                if self.ends_with("alism") { self.replace_suffix_if_stem_measured("al"); }
-                else if self.ends_with("iveness") { self.replace_suffix_if_stem_measured("ive"); }
-                else if self.ends_with("fulness") { self.replace_suffix_if_stem_measured("ful"); }
-                else if self.ends_with("ousness") { self.replace_suffix_if_stem_measured("ous"); }
-            },
-            't' => {
-                if self.ends_with("aliti") { self.replace_suffix_if_stem_measured("al"); }
-                else if self.ends_with("iviti") { self.replace_suffix_if_stem_measured("ive"); }
-                else if self.ends_with("biliti") { self.replace_suffix_if_stem_measured("ble"); }
-            },
-            'g' => {
-                if self.ends_with("logi") { self.replace_suffix_if_stem_measured("log"); }
-            },
-            _ => {}
-        }
-    }
-
-    /// Step 3 deals with -ic-, -full, -ness etc.
-    /// 
-    /// # Examples
-    /// - ICATE -> IC (triplicate -> triplic)
-    /// - ATIVE ->    (formative -> form)
-    /// - ALIZE -> AL (formalize -> formal)
-    fn step3(&mut self) {
-        match self.buffer[self.k] {
-            'e' => {
-                if self.ends_with("icate") { self.replace_suffix_if_stem_measured("ic"); }
-                else if self.ends_with("ative") { self.replace_suffix_if_stem_measured(""); }
-                else if self.ends_with("alize") { self.replace_suffix_if_stem_measured("al"); }
-            },
-            'i' => {
-                if self.ends_with("iciti") { self.replace_suffix_if_stem_measured("ic"); }
-            },
-            'l' => {
-                if self.ends_with("ical") { self.replace_suffix_if_stem_measured("ic"); }
-                else if self.ends_with("ful") { self.replace_suffix_if_stem_measured(""); }
+/// Compares the stem of each distinct word of `input` with the saved
+/// `word\tstem` mapping `golden` and writes a line for each word whose stem
+/// changed or that the mapping lacks, sorted by word, then a summary
+///
+/// Returns the number of changed stems.
+fn verify_stems(
+    stemmer: &mut dyn Stemmer,
+    (golden, golden_name): (impl BufRead, &str),
+    input: impl Read,
+    out: &mut dyn Write,
+) -> io::Result<usize> {
+    let mut saved = BTreeMap::new();
+    for (number, line) in golden.lines().enumerate() {
+        let line = line.map_err(|e| io::Error::new(e.kind(), format!("{}: {}", golden_name, e)))?;
+        if line.is_empty() {
+            continue;
+        }
+        let Some((word, stem)) = line.split_once('\t') else {
+            let message = format!("{}:{}: expected a word<TAB>stem line", golden_name, number + 1);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        };
+        saved.insert(word.to_string(), stem.to_string());
+    }
+
+    let mut stems = BTreeMap::new();
+    text::read_chunks(input, |chunk| {
+        for (token, stem) in text::token_stems(stemmer, chunk) {
+            stems.entry(token.text.to_lowercase()).or_insert(stem);
+        }
+        Ok(())
+    })?;
+    let (mut changed, mut added) = (0, 0);
+    for (word, stem) in &stems {
+        match saved.get(word) {
+            Some(old) if old == stem => {},
+            Some(old) => {
+                writeln!(out, "changed: {:?} -> {:?}, was {:?}", word, stem, old)?;
+                changed += 1;
             },
-            's' => {
-                if self.ends_with("ness") { self.replace_suffix_if_stem_measured(""); }
+            None => {
+                writeln!(out, "added: {:?} -> {:?}", word, stem)?;
+                added += 1;
             },
-            _ => {}
         }
     }
+    writeln!(out, "{} words verified against {}: {} changed, {} added", stems.len(), golden_name, changed, added)?;
+    Ok(changed)
+}
 
-    /// Step 4 removes suffixes when measure > 1
-    /// 
-    /// # Examples
-    /// - AL    ->  (revival -> reviv)
-    /// - ANCE  ->  (allowance -> allow)
-    /// - ENCE  ->  (inference -> infer)
-    fn step4(&mut self) {
-        if self.k <= self.k0 { return; }
+/// Lists the `--algorithm` and `--lang` choices
+fn list_algorithms(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "English algorithms (--algorithm NAME):")?;
+    for &algorithm in Algorithm::ALL {
+        let default = if algorithm == Algorithm::default() { " (default)" } else { "" };
+        writeln!(out, "  {:<10} {}{}", algorithm.name(), algorithm.description(), default)?;
+    }
+    writeln!(out, "Languages (--lang CODE or NAME):")?;
+    for &language in Language::ALL {
+        let note = match language {
+            Language::English => " (default; stemmed with the --algorithm)",
+            Language::Hindi => " (light stemmer)",
+            _ => " (Snowball)",
+        };
+        writeln!(out, "  {}  {}{}", language.code(), language.name(), note)?;
+    }
+    Ok(())
+}
 
-        match self.buffer[self.k - 1] {
-            'a' => {
-                if self.ends_with("al") {}
-                else { return; }
-            },
-            'c' => {
-                if self.ends_with("ance") {}
-                else if self.ends_with("ence") {}
-                else { return; }
-            },
-            'e' => {
-                if self.ends_with("er") {}
-                else { return; }
-            },
-            'i' => {
-                if self.ends_with("ic") {}
-                else { return; }
-            },
-            'l' => {
-                if self.ends_with("able") {}
-                else if self.ends_with("ible") {}
-                else { return; }
-            },
-            'n' => {
-                if self.ends_with("ant") {}
-                else if self.ends_with("ement") {}
-                else if self.ends_with("ment") {}
-                else if self.ends_with("ent") {}
-                else { return; }
-            },
-            'o' => {
-                if self.ends_with("ion") && self.j >= self.k0 && 
-                   (self.buffer[self.j] == 's' || self.buffer[self.j] == 't') {}
-                else if self.ends_with("ou") {}
-                else { return; }
-            },
-            's' => {
-                if self.ends_with("ism") {}
-                else { return; }
-            },
-            't' => {
-                if self.ends_with("ate") {}
-                else if self.ends_with("iti") {}
-                else { return; }
+/// Prints the steps of the Porter algorithm for each of `words`
+fn trace_words<'a>(fold: bool, words: impl IntoIterator<Item = &'a str>, out: &mut dyn Write) -> io::Result<()> {
+    let mut stemmer = PorterStemmer::new().with_diacritic_folding(fold);
+    for word in words {
+        write!(out, "{}", stemmer.explain(word))?;
+    }
+    Ok(())
+}
+
+/// Loads the stopword set named by a `--stopwords` value
+fn load_stopwords(spec: &str) -> io::Result<Stopwords> {
+    if spec == "builtin" {
+        Ok(Stopwords::english())
+    } else {
+        Stopwords::load(spec).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", spec, e)))
+    }
+}
+
+/// The text mode options given on the command line
+fn text_options<'a>(options: &'a Options, stopwords: Option<&'a Stopwords>) -> TextOptions<'a> {
+    let symbols = match (&options.symbol_placeholder, options.drop_symbols) {
+        (Some(placeholder), _) => SymbolPolicy::Placeholder(placeholder),
+        (None, Some(true)) => SymbolPolicy::Drop,
+        (None, _) => SymbolPolicy::Preserve,
+    };
+    let text_options = TextOptions::new()
+        .digits(options.digits.unwrap_or_default())
+        .symbols(symbols)
+        .preserve_case(options.preserve_case)
+        .min_length(options.min_length.unwrap_or(text::DEFAULT_MIN_LENGTH));
+    let text_options = match options.max_length {
+        Some(length) => text_options.max_length(length, options.long_tokens.unwrap_or_default()),
+        None => text_options,
+    };
+    match stopwords {
+        Some(stopwords) => text_options.stopwords(stopwords),
+        None => text_options,
+    }
+}
+
+/// Runs the selected mode over one input stream
+fn process(
+    options: &Options,
+    stemmer: &mut dyn Stemmer,
+    stopwords: Option<&Stopwords>,
+    input: Box<dyn BufRead>,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    match options.mode {
+        Mode::Csv => {
+            let csv_options = CsvOptions {
+                delimiter: options.delimiter,
+                has_header: options.has_header,
+            };
+            csv::stem_csv_with(stemmer, input, &mut *out, &options.columns, &csv_options)?;
+        },
+        Mode::Jsonl => {
+            let fields: Vec<&str> = options.fields.iter().map(String::as_str).collect();
+            json::stem_jsonl(stemmer, input, &mut *out, &fields)?;
+        },
+        Mode::JsonTokens => json::stem_tokens_jsonl(stemmer, input, &mut *out)?,
+        Mode::Text => text::try_stem_stream(stemmer, input, &mut *out, &text_options(options, stopwords))?,
+        Mode::Identifiers => text::read_chunks(input, |chunk| {
+            out.write_all(identifier::stem_identifiers_in_text(stemmer, chunk, &options.join).as_bytes())
+        })?,
+        Mode::Html => out.write_all(html::stem_html(stemmer, &read_all(input)?).as_bytes())?,
+        Mode::HtmlText => out.write_all(html::stem_html_text(stemmer, &read_all(input)?).as_bytes())?,
+        Mode::Markdown => out.write_all(markdown::stem_markdown(stemmer, &read_all(input)?).as_bytes())?,
+        Mode::Concordance => {
+            let input = read_all(input)?;
+            for line in concordance::concordance(stemmer, &input, &options.keyword, options.window) {
+                writeln!(out, "{}", line.format(options.window))?;
+            }
+        },
+        Mode::Stats => {
+            let mut stats = CorpusStats::new();
+            text::read_chunks(input, |chunk| {
+                stats.feed(stemmer, chunk);
+                Ok(())
+            })?;
+            write!(out, "{}", stats.report(options.top.unwrap_or(10)))?;
+        },
+        Mode::ListAlgorithms => list_algorithms(out)?,
+        Mode::Trace => text::read_chunks(input, |chunk| {
+            trace_words(options.fold, text::tokens_for(stemmer, chunk).map(|token| token.text), out)
+        })?,
+        Mode::Null => stem_records(stemmer, input, out)?,
+        Mode::Check => unreachable!("--check opens its own files"),
+        Mode::Verify => unreachable!("verify opens its own files"),
+        Mode::Bench => unreachable!("bench reads its corpus whole"),
+        Mode::Index | Mode::Search | Mode::Sqlite => unreachable!("index, search and --sqlite read their own files"),
+        Mode::Serve | Mode::Daemon => unreachable!("serve and daemon read requests"),
+        Mode::Tsv => write_word_stems(stemmer, input, out, options.unique)?,
+        Mode::Changed => write_changed_stems(stemmer, input, out, options.unique)?,
+        Mode::Unique => {
+            let mut stems = BTreeSet::new();
+            text::read_chunks(input, |chunk| {
+                for (token, stem) in text::token_stems(stemmer, chunk) {
+                    if !stopwords.is_some_and(|stopwords| stopwords.contains(token.text)) {
+                        stems.insert(stem);
+                    }
+                }
+                Ok(())
+            })?;
+            for stem in stems {
+                writeln!(out, "{}", stem)?;
+            }
+        },
+        Mode::Freq => {
+            let counts = count_stems(stemmer, input, stopwords)?;
+            for (stem, count) in counts.iter().take(options.top.unwrap_or(usize::MAX)) {
+                writeln!(out, "{}\t{}", count, stem)?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Creates the stemmer selected by `--lang`, `--algorithm` and `--fold`
+fn make_stemmer(options: &Options) -> Box<dyn Stemmer> {
+    match options.lang {
+        Language::English => options.algorithm.stemmer(options.fold),
+        language => language.stemmer(options.fold),
+    }
+}
+
+/// Stems each input file on its own on up to `jobs` worker threads, one
+/// stemmer per worker, handing the outputs to `write` in input order
+///
+/// A file that cannot be opened or read is reported on stderr and skipped;
+/// the returned flag is false if that happened. An error from `write` stops
+/// the workers and is returned.
+fn run_files(
+    options: &Options,
+    stopwords: Option<&Stopwords>,
+    files: &[InputFile],
+    jobs: usize,
+    mut write: impl FnMut(&InputFile, &[u8]) -> io::Result<()>,
+) -> io::Result<bool> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, files.len().max(1)) {
+            let (sender, next) = (sender.clone(), &next);
+            scope.spawn(move || {
+                let mut stemmer = make_stemmer(options);
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(file) = files.get(index) else { break };
+                    let output = open_file(file, options.compressed).and_then(|input| {
+                        let mut output = Vec::new();
+                        process(options, &mut stemmer, stopwords, Box::new(BufReader::new(input)), &mut output)?;
+                        Ok(output)
+                    });
+                    // The receiver is gone once writing has failed
+                    if sender.send((index, output)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        // Outputs arrive in any order; hold each until those before it are
+        // written
+        let mut pending = BTreeMap::new();
+        let mut next_write = 0;
+        let mut all_read = true;
+        for (index, output) in receiver {
+            pending.insert(index, output);
+            while let Some(output) = pending.remove(&next_write) {
+                match output {
+                    Ok(output) => write(&files[next_write], &output)?,
+                    Err(e) if rejection(&e).is_some() => return Err(e),
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        all_read = false;
+                    },
+                }
+                next_write += 1;
+            }
+        }
+        Ok(all_read)
+    })
+}
+
+/// Writes the output for `file` with `write` to its path under `out_dir`,
+/// creating the directories it goes in
+fn write_out_file(
+    options: &Options,
+    out_dir: &str,
+    file: &InputFile,
+    write: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> io::Result<()> {
+    let target = Path::new(out_dir).join(&file.relative);
+    let create = || {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = Encoder::new(BufWriter::new(File::create(&target)?), options.compressed.output(Some(&target)))?;
+        write(&mut out)?;
+        out.finish().map(drop)
+    };
+    create().map_err(|e| io::Error::new(e.kind(), format!("{}: {}", target.display(), e)))
+}
+
+/// Replaces the file at `path` with `output`, keeping the old content under
+/// `path` with the `--backup` suffix if there is one
+///
+/// The output goes to a temporary file next to `path` first, with the same
+/// permissions, and is renamed over it, so that the file has either all its
+/// old content or all its new content whatever happens.
+fn replace_file(options: &Options, path: &Path, output: &[u8]) -> io::Result<()> {
+    let named = |e: io::Error| io::Error::new(e.kind(), format!("{}: {}", path.display(), e));
+    let name = path.file_name().ok_or_else(|| named(io::Error::other("not a file")))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".porterstem-{}", process::id()));
+    let temp = path.with_file_name(temp_name);
+
+    let write = || {
+        let permissions = fs::metadata(path)?.permissions();
+        let mut out = Encoder::new(BufWriter::new(File::create(&temp)?), options.compressed.output(Some(path)))?;
+        out.write_all(output)?;
+        out.finish()?.into_inner().map_err(io::IntoInnerError::into_error)?.sync_all()?;
+        fs::set_permissions(&temp, permissions)?;
+        if let Some(suffix) = &options.backup {
+            let mut backup = path.as_os_str().to_owned();
+            backup.push(suffix);
+            match fs::remove_file(&backup) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {},
+            }
+            if fs::hard_link(path, &backup).is_err() {
+                fs::copy(path, &backup)?;
+            }
+        }
+        fs::rename(&temp, path)
+    };
+    write().map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        named(e)
+    })
+}
+
+/// The stemmer of the command line as recorded in a search index: the
+/// algorithm name for English, the language code otherwise, and `+fold`
+/// with `--fold`
+fn stemmer_label(options: &Options) -> String {
+    let name = match options.lang {
+        Language::English => options.algorithm.name(),
+        language => language.code(),
+    };
+    format!("{}{}", name, if options.fold { "+fold" } else { "" })
+}
+
+/// Creates the stemmer named by [`stemmer_label`]
+fn stemmer_for_label(label: &str) -> Result<Box<dyn Stemmer>, String> {
+    let (name, fold) = match label.strip_suffix("+fold") {
+        Some(name) => (name, true),
+        None => (label, false),
+    };
+    match (name.parse::<Algorithm>(), name.parse::<Language>()) {
+        (Ok(algorithm), _) => Ok(algorithm.stemmer(fold)),
+        (_, Ok(language)) => Ok(language.stemmer(fold)),
+        _ => Err(format!("unknown stemmer in index: {:?}", label)),
+    }
+}
+
+/// Stems the input files into a search index saved under `--out` (`index`)
+///
+/// A file that cannot be read is reported and left out.
+fn build_index(options: &Options) -> io::Result<bool> {
+    let (files, mut all_read) = collect_inputs(&options.files, true);
+    let mut stemmer = make_stemmer(options);
+    let mut index = Index::new(stemmer_label(options));
+    for file in &files {
+        match open_file(file, options.compressed).and_then(|input| read_all(BufReader::new(input))) {
+            Ok(text) => {
+                index.add(&mut *stemmer, file.path.to_string_lossy(), &text);
             },
-            'u' => {
-                if self.ends_with("ous") {}
-                else { return; }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                all_read = false;
             },
-            'v' => {
-                if self.ends_with("ive") {}
-                else { return; }
+        }
+    }
+    let dir = options.output.as_deref().unwrap_or_default();
+    index.save(dir).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", dir, e)))?;
+    Ok(all_read)
+}
+
+/// Stems the input files, or stdin, into the SQLite database `--sqlite`,
+/// each file being a document
+///
+/// A file that cannot be read is reported and left out, along with the
+/// rest of a file that turns out not to be UTF-8.
+#[cfg(feature = "sqlite")]
+fn write_database(options: &Options) -> io::Result<bool> {
+    let (files, mut all_read) = if options.files.is_empty() {
+        (vec![InputFile::stdin()], true)
+    } else {
+        collect_inputs(&options.files, options.recursive)
+    };
+    let mut stemmer = make_stemmer(options);
+    let mut db = StemDatabase::new()?;
+    for file in &files {
+        let added = open_file(file, options.compressed)
+            .and_then(|input| db.add_document(&mut *stemmer, &file.path.to_string_lossy(), input));
+        if let Err(e) = added {
+            eprintln!("error: {}", e);
+            all_read = false;
+        }
+    }
+    let path = options.sqlite.as_deref().unwrap_or_default();
+    db.save(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path, e)))?;
+    Ok(all_read)
+}
+
+/// Writes a `score\tpath` line for each of the `top` documents of the index
+/// in `dir` that best match `query` (`search`)
+fn search_index(dir: &str, query: &str, top: usize, out: &mut dyn Write) -> io::Result<()> {
+    let index = Index::load(dir)?;
+    let mut stemmer = stemmer_for_label(index.stemmer_name()).map_err(io::Error::other)?;
+    for (name, score) in index.search(&mut *stemmer, query).into_iter().take(top) {
+        writeln!(out, "{:.3}\t{}", score, name)?;
+    }
+    Ok(())
+}
+
+/// Stems plain-text input files one after the other through a memory map
+/// (`--mmap`), writing the stems of each chunk before reading the next, so
+/// that memory use stays flat however large the files
+///
+/// Returns false if a file could not be mapped; the others are still
+/// processed.
+#[cfg(all(feature = "mmap", unix))]
+fn run_mapped(options: &Options, stopwords: Option<&Stopwords>, files: &[InputFile]) -> io::Result<bool> {
+    use m_porter_stemmer_rust::mmap::{self, Mmap};
+
+    let mut stemmer = make_stemmer(options);
+    let text_options = text_options(options, stopwords);
+    let mut out = match options.out_dir {
+        Some(_) => None,
+        None => Some(open_output(options.output.as_deref(), options.compressed)?),
+    };
+    let mut all_read = true;
+    for file in files {
+        if file.is_stdin() {
+            if let Some(out) = &mut out {
+                text::stem_stream(&mut stemmer, io::stdin().lock(), out, &text_options)?;
+            }
+            continue;
+        }
+        let map = match Mmap::open(&file.path) {
+            Ok(map) => map,
+            Err(e) => {
+                eprintln!("error: {}: {}", file.path.display(), e);
+                all_read = false;
+                continue;
             },
-            'z' => {
-                if self.ends_with("ize") {}
-                else { return; }
+        };
+        let mut stem = |out: &mut dyn Write| {
+            mmap::stem_mapped(&mut stemmer, &map, out, &text_options)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file.path.display(), e)))
+        };
+        match (&mut out, &options.out_dir) {
+            (Some(out), _) => stem(out)?,
+            (None, Some(out_dir)) => write_out_file(options, out_dir, file, stem)?,
+            (None, None) => unreachable!("output is stdout, --output or --out-dir"),
+        }
+        progress::file_done(map.len() as u64);
+    }
+    if let Some(out) = out {
+        out.finish()?;
+    }
+    Ok(all_read)
+}
+
+/// Runs the selected mode over the input, writing to stdout, the `--output`
+/// file or one file per input under `--out-dir`
+///
+/// Several input files are stemmed in parallel (`--jobs`), except with
+/// `--freq`, `--unique` and `--stats`, which take them as one text; each
+/// file then goes through the mode on its own, so a CSV header is kept for
+/// every file.
+///
+/// Returns false if an input file could not be found or read; the others
+/// are still processed.
+fn run(options: &Options) -> io::Result<bool> {
+    if options.mode == Mode::ListAlgorithms {
+        let mut out = open_output(options.output.as_deref(), options.compressed)?;
+        list_algorithms(&mut out)?;
+        out.finish()?;
+        return Ok(true);
+    }
+    if options.mode == Mode::Trace && !options.words.is_empty() {
+        let mut out = open_output(options.output.as_deref(), options.compressed)?;
+        trace_words(options.fold, options.words.iter().map(String::as_str), &mut out)?;
+        out.finish()?;
+        return Ok(true);
+    }
+    if options.mode == Mode::Bench {
+        let stopwords = options.stopwords.as_deref().map(load_stopwords).transpose()?;
+        let (corpus, name) = match options.files.first() {
+            Some(name) => {
+                let file = InputFile { path: name.into(), relative: name.into() };
+                let corpus = read_all(BufReader::new(open_file(&file, options.compressed)?))
+                    .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", name, e)))?;
+                (corpus, name.as_str())
             },
-            _ => { return; }
+            None => (bench::synthetic_corpus(bench::SYNTHETIC_SIZE), "synthetic"),
+        };
+        let mut out = open_output(options.output.as_deref(), options.compressed)?;
+        let stemmer_name = match options.lang {
+            Language::English => options.algorithm.name(),
+            language => language.name(),
+        };
+        writeln!(out, "stemmer:     {}{}", stemmer_name, if options.fold { " (folding diacritics)" } else { "" })?;
+        let text_options = text_options(options, stopwords.as_ref());
+        bench::bench(&mut *make_stemmer(options), (&corpus, name), &text_options, &mut out)?;
+        out.finish()?;
+        return Ok(true);
+    }
+    if options.mode == Mode::Index {
+        return build_index(options);
+    }
+    #[cfg(feature = "sqlite")]
+    if options.mode == Mode::Sqlite {
+        return write_database(options);
+    }
+    if options.mode == Mode::Serve {
+        let address = (options.host.as_str(), options.port);
+        let listener = TcpListener::bind(address)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}:{}: {}", options.host, options.port, e)))?;
+        if !options.quiet {
+            match options.grpc {
+                true => eprintln!("porterstem: listening for gRPC (h2c) on {}", listener.local_addr()?),
+                false => eprintln!("porterstem: listening on http://{}", listener.local_addr()?),
+            }
         }
-        if self.measure() > 1 {
-            self.k = self.j;
+        #[cfg(feature = "grpc")]
+        if options.grpc {
+            let (lang, algorithm, fold) = (options.lang, options.algorithm, options.fold);
+            return Err(grpc::serve(listener, move || match lang {
+                Language::English => algorithm.stemmer(fold),
+                language => language.stemmer(fold),
+            }));
+        }
+        #[cfg(feature = "serve")]
+        return Err(serve::serve(listener, || make_stemmer(options)));
+        #[cfg(not(feature = "serve"))]
+        unreachable!("parse_args requires the serve feature for serve without --grpc");
+    }
+    if options.mode == Mode::Daemon {
+        #[cfg(unix)]
+        if let Some(path) = &options.socket {
+            return Err(daemon::listen(path, || make_stemmer(options)));
+        }
+        daemon::serve_lines(&|| make_stemmer(options), io::stdin().lock(), io::stdout().lock())?;
+        return Ok(true);
+    }
+    if options.mode == Mode::Search {
+        let mut out = open_output(options.output.as_deref(), options.compressed)?;
+        search_index(&options.files[0], &options.words.join(" "), options.top.unwrap_or(10), &mut out)?;
+        out.finish()?;
+        return Ok(true);
+    }
+    if options.mode == Mode::Check {
+        let open = |index: usize| -> io::Result<(BufReader<Box<dyn Read>>, &str)> {
+            let name = options.files[index].as_str();
+            let file = InputFile { path: name.into(), relative: name.into() };
+            Ok((BufReader::new(open_file(&file, options.compressed)?), name))
+        };
+        let mut out = open_output(options.output.as_deref(), options.compressed)?;
+        let mismatches = check_stems(&mut *make_stemmer(options), open(0)?, open(1)?, &mut out)?;
+        out.finish()?;
+        return Ok(mismatches == 0);
+    }
+    if let Some(golden_name) = options.golden.as_deref() {
+        let golden = InputFile { path: golden_name.into(), relative: golden_name.into() };
+        let golden = BufReader::new(open_file(&golden, options.compressed)?);
+        let (files, all_found) = if options.files.is_empty() {
+            (vec![InputFile::stdin()], true)
+        } else {
+            collect_inputs(&options.files, options.recursive)
+        };
+        let (input, all_opened) = open_input(&files, options.compressed);
+        let mut out = open_output(options.output.as_deref(), options.compressed)?;
+        let changed = verify_stems(&mut *make_stemmer(options), (golden, golden_name), input, &mut out)?;
+        out.finish()?;
+        return Ok(changed == 0 && all_found && all_opened);
+    }
+    let stopwords = options.stopwords.as_deref().map(load_stopwords).transpose()?;
+    let (mut files, all_found) = if options.files.is_empty() {
+        (vec![InputFile::stdin()], true)
+    } else {
+        collect_inputs(&options.files, options.recursive)
+    };
+    // Earlier outputs under an --out-dir inside an input directory are not
+    // inputs
+    if let Some(out_dir) = &options.out_dir {
+        files.retain(|file| !file.path.starts_with(out_dir));
+    }
+
+    #[cfg(all(feature = "mmap", unix))]
+    if options.mmap {
+        return Ok(run_mapped(options, stopwords.as_ref(), &files)? && all_found);
+    }
+
+    if options.in_place {
+        let all_read = run_files(options, stopwords.as_ref(), &files, options.jobs, |file, output| {
+            replace_file(options, &file.path, output)
+        })?;
+        return Ok(all_found && all_read);
+    }
+
+    if let Some(out_dir) = &options.out_dir {
+        let all_read = run_files(options, stopwords.as_ref(), &files, options.jobs, |file, output| {
+            write_out_file(options, out_dir, file, |out| out.write_all(output))
+        })?;
+        return Ok(all_found && all_read);
+    }
+
+    let mut out = open_output(options.output.as_deref(), options.compressed)?;
+    let whole_input = matches!(options.mode, Mode::Freq | Mode::Stats) || options.unique;
+    let all_read = if files.len() > 1 && options.jobs > 1 && !whole_input {
+        run_files(options, stopwords.as_ref(), &files, options.jobs, |_, output| out.write_all(output))?
+    } else if let Some(input) = pipeline_input(options, &files) {
+        let text_options = text_options(options, stopwords.as_ref());
+        Pipeline::new().workers(options.jobs).try_run(|| make_stemmer(options), input, &mut out, &text_options)?;
+        true
+    } else {
+        // One stream, stemmed as it is read
+        let (input, all_opened) = open_input(&files, options.compressed);
+        process(options, &mut make_stemmer(options), stopwords.as_ref(), input, &mut out)?;
+        all_opened
+    };
+    out.finish()?;
+    Ok(all_found && all_read)
+}
+
+/// The [`StemError`] of input the stemmer rejected (`--long-tokens fail`),
+/// if that is what `e` is
+fn rejection(e: &io::Error) -> Option<&StemError> {
+    e.get_ref()?.downcast_ref::<StemError>().filter(|e| !matches!(e, StemError::IoError(_)))
+}
+
+/// Starts reporting progress on stderr if `--progress` asks for it, or if
+/// stderr is a terminal and the input is files rather than stdin, unless
+/// `--quiet` was given or the mode reads its input all at once
+fn start_progress(options: &Options) -> Option<Reporter> {
+    let reads_inputs =
+        !matches!(options.mode, Mode::ListAlgorithms | Mode::Trace | Mode::Bench | Mode::Search | Mode::Serve | Mode::Daemon);
+    let stdin = options.files.is_empty() || options.files.iter().any(|f| f == "-");
+    if options.quiet || !reads_inputs || !(options.progress || io::stderr().is_terminal() && !stdin) {
+        return None;
+    }
+    let mut totals = Totals { files: 0, bytes: if stdin { None } else { Some(0) } };
+    for arg in &options.files {
+        for file in inputs::expand(arg, options.recursive).unwrap_or_default() {
+            totals.files += 1;
+            let len = fs::metadata(&file.path).map_or(0, |metadata| metadata.len());
+            totals.bytes = totals.bytes.map(|bytes| bytes + len);
         }
     }
+    totals.files = totals.files.max(1);
+    Some(Reporter::start(totals))
+}
 
-    /// Step 5 removes final -e if measure > 1, and changes -ll to -l if measure > 1
-    /// 
-    /// # Examples
-    /// - E     ->  (probate -> probat, rate -> rate)
-    /// - L     ->  (controll -> control)
-    fn step5(&mut self) {
-        self.j = self.k;
-        if self.buffer[self.k] == 'e' {
-            let a = self.measure();
-            if a > 1 || (a == 1 && !self.cvc(self.k - 1)) {
-                self.k -= 1;
+/// How often `--watch` looks at the input files
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Runs once, and again whenever an input file is changed, added or
+/// removed, until writing the output fails with a broken pipe
+///
+/// Other errors are reported and the watching goes on, since the next
+/// change may well fix them.
+fn watch(options: &Options) -> io::Error {
+    let mut skip = Vec::new();
+    skip.extend(options.out_dir.as_deref().map(Path::new));
+    skip.extend(options.output.as_deref().map(Path::new));
+    let mut last = None;
+    loop {
+        let snapshot = inputs::snapshot(&options.files, options.recursive, &skip);
+        if last.as_ref() != Some(&snapshot) {
+            if last.is_some() && !options.quiet {
+                eprintln!("porterstem: input changed, stemming again");
             }
+            let reporter = start_progress(options);
+            let result = run(options);
+            drop(reporter);
+            match result {
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return e,
+                Err(e) => eprintln!("error: {}", e),
+                Ok(_) => {},
+            }
+            // A change made while stemming differs from this snapshot, so it
+            // is picked up next time around
+            last = Some(snapshot);
         }
-        if self.buffer[self.k] == 'l' && self.double_consonant(self.k) && self.measure() > 1 {
-            self.k -= 1;
-        }
+        thread::sleep(WATCH_INTERVAL);
     }
+}
 
-    /// Helper function for step2 and step3
-    /// replaces current suffix with new_suffix if the stem has measure > 0
-    fn replace_suffix_if_stem_measured(&mut self, s: &str) {
-        if self.measure() > 0 {
-            self.set_to(s);
+fn main() {
+    let options = match config::with_config(std::env::args().skip(1).collect()).and_then(parse_args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
         }
+    };
+
+    let result = if options.watch {
+        Err(watch(&options))
+    } else {
+        let _reporter = start_progress(&options);
+        run(&options)
+    };
+    match result {
+        Ok(true) => {},
+        Ok(false) => process::exit(1),
+        // The reader of a pipeline (`porterstem doc.txt | head`) went away
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {},
+        Err(e) if rejection(&e).is_some() => {
+            eprintln!("error: {}", e);
+            process::exit(3);
+        },
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        },
     }
 }
 
-// Test with bash: Cargo Test
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(args(&[])).unwrap().mode, Mode::Text);
+        assert_eq!(parse_args(args(&["--html"])).unwrap().mode, Mode::Html);
+        assert_eq!(parse_args(args(&["--html-text"])).unwrap().mode, Mode::HtmlText);
+        assert_eq!(parse_args(args(&["--markdown"])).unwrap().mode, Mode::Markdown);
+        assert_eq!(parse_args(args(&["--freq"])).unwrap().mode, Mode::Freq);
+        assert_eq!(parse_args(args(&["--freq", "--top", "5"])).unwrap().top, Some(5));
+        assert!(parse_args(args(&["--freq", "--top", "many"])).is_err());
+        assert!(parse_args(args(&["--tsv", "--top", "5"])).is_err());
+        assert_eq!(parse_args(args(&["--stats", "--top", "3"])).unwrap().mode, Mode::Stats);
+        assert_eq!(parse_args(args(&["--json"])).unwrap().mode, Mode::JsonTokens);
+        assert!(parse_args(args(&["--tsv", "--unique"])).unwrap().unique);
+        assert_eq!(parse_args(args(&["--unique"])).unwrap().mode, Mode::Unique);
+        assert!(parse_args(args(&["--html", "--unique"])).is_err());
+        assert!(parse_args(args(&["--nope"])).is_err());
+        assert!(parse_args(args(&["--text", "--fold"])).unwrap().fold);
+        assert!(parse_args(args(&["--preserve-case"])).unwrap().preserve_case);
+        assert!(parse_args(args(&["--html", "--preserve-case"])).is_err());
+        assert_eq!(parse_args(args(&[])).unwrap().algorithm, Algorithm::Porter);
+        assert_eq!(parse_args(args(&["--algorithm", "porter2"])).unwrap().algorithm, Algorithm::Porter2);
+        assert!(parse_args(args(&["--algorithm", "lovins"])).is_err());
+        assert_eq!(parse_args(args(&["--text", "--lang", "de"])).unwrap().lang, Language::German);
+        assert_eq!(parse_args(args(&["--lang", "swedish"])).unwrap().lang, Language::Swedish);
+        assert!(parse_args(args(&["--lang", "xx"])).is_err());
+        assert!(parse_args(args(&["--lang", "fr", "--algorithm", "porter2"])).is_err());
+
+        let options = parse_args(args(&["--identifiers", "--join", "_"])).unwrap();
+        assert_eq!(options.mode, Mode::Identifiers);
+        assert_eq!(options.join, "_");
+
+        let options = parse_args(args(&["a.txt", "--fold", "-", "b.txt", "-o", "out.txt"])).unwrap();
+        assert_eq!(options.files, vec!["a.txt", "-", "b.txt"]);
+        assert_eq!(options.output.as_deref(), Some("out.txt"));
+        assert!(parse_args(args(&["a.txt", "-o"])).is_err());
+        let options = parse_args(args(&["-r", "corpus/", "--out-dir", "out"])).unwrap();
+        assert!(options.recursive);
+        assert_eq!(options.out_dir.as_deref(), Some("out"));
+        assert!(parse_args(args(&["--out-dir", "out"])).is_err());
+        assert_eq!(parse_args(args(&["-j", "4"])).unwrap().jobs, 4);
+        assert!(parse_args(args(&["--jobs", "0"])).is_err());
+        assert!(parse_args(args(&["a.txt", "--out-dir", "out", "-o", "x"])).is_err());
+        let compressed = parse_args(args(&["--compressed", "auto"])).map(|options| options.compressed);
+        assert_eq!(compressed.ok(), cfg!(feature = "compress").then_some(Compressed::Auto));
+        #[cfg(feature = "compress")]
+        assert_eq!(parse_args(args(&["--compressed", "zstd"])).unwrap().compressed, Compressed::Always(Compression::Zstd));
+        assert!(parse_args(args(&["--compressed", "lzma"])).is_err());
+        assert!(parse_args(args(&["--watch", "a.txt"])).unwrap().watch);
+        assert!(parse_args(args(&["--watch"])).is_err());
+        assert!(parse_args(args(&["-q", "a.txt"])).unwrap().quiet);
+        assert!(parse_args(args(&["--progress", "--quiet"])).is_err());
+        assert!(start_progress(&parse_args(args(&["--quiet", "a.txt"])).unwrap()).is_none());
+        assert!(start_progress(&parse_args(args(&["--list-algorithms", "--progress"])).unwrap()).is_none());
+        assert_eq!(parse_args(args(&["--mmap", "big.txt"])).is_ok(), cfg!(all(feature = "mmap", unix)));
+        assert!(parse_args(args(&["--mmap", "--html", "big.txt"])).is_err());
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn test_compressed_files() {
+        let dir = std::env::temp_dir().join(format!("porterstem-compressed-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("a.txt.gz");
+        let mut encoder = Encoder::new(Vec::new(), Some(Compression::Gzip)).unwrap();
+        encoder.write_all(b"running ponies\n").unwrap();
+        fs::write(&path, encoder.finish().unwrap()).unwrap();
+        let file = InputFile { relative: path.clone(), path };
+
+        let mut text = String::new();
+        open_file(&file, Compressed::Auto).unwrap().read_to_string(&mut text).unwrap();
+        assert_eq!(text, "running ponies\n");
+        let error = open_file(&file, Compressed::Always(Compression::Zstd)).unwrap().read_to_string(&mut text);
+        assert!(error.unwrap_err().to_string().starts_with(&file.path.display().to_string()));
+        assert_eq!(Compressed::Auto.output(Some(Path::new("out.zst"))), Some(Compression::Zstd));
+        assert_eq!(Compressed::Auto.output(None), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_files_in_order() {
+        let dir = std::env::temp_dir().join(format!("porterstem-jobs-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut files = Vec::new();
+        for i in 0..20 {
+            let path = dir.join(format!("{}.txt", i));
+            fs::write(&path, format!("{} ponies\n", "running ".repeat(i * 100))).unwrap();
+            files.push(InputFile { relative: path.clone(), path });
+        }
+        files.insert(3, InputFile { path: dir.join("missing.txt"), relative: "missing.txt".into() });
+
+        let options = parse_args(Vec::new()).unwrap();
+        let mut outputs = Vec::new();
+        let all_read = run_files(&options, None, &files, 4, |file, output| {
+            outputs.push((file.path.clone(), String::from_utf8(output.to_vec()).unwrap()));
+            Ok(())
+        });
+        assert!(!all_read.unwrap());
+        let expected: Vec<_> = files.iter().filter(|f| f.path.exists()).map(|f| f.path.clone()).collect();
+        assert_eq!(outputs.iter().map(|(path, _)| path.clone()).collect::<Vec<_>>(), expected);
+        assert_eq!(outputs[0].1, " poni\n");
+
+        // A rejected file stops the run instead of being skipped
+        let options = parse_args(args(&["--max-length", "6", "--long-tokens", "fail"])).unwrap();
+        let error = run_files(&options, None, &files, 4, |_, _| Ok(())).unwrap_err();
+        assert!(matches!(rejection(&error), Some(StemError::TokenTooLong { length: 7, max: 6 })));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_word_stems() {
+        let mut stemmer = Algorithm::Porter.stemmer(false);
+        let input = "Running dogs\nrunning DOGS, caresses";
+        let mut out = Vec::new();
+        write_word_stems(&mut stemmer, input.as_bytes(), &mut out, true).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "running\trun\ndogs\tdog\ncaresses\tcaress\n");
+    }
+
+    #[test]
+    fn test_write_changed_stems() {
+        assert_eq!(parse_args(args(&["--changed-only", "--unique"])).unwrap().mode, Mode::Changed);
+        let mut stemmer = Algorithm::Porter.stemmer(false);
+        let input = "Running dogs\nrun DOGS, sky caresses";
+        let mut out = Vec::new();
+        write_changed_stems(&mut stemmer, input.as_bytes(), &mut out, true).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "running -> run\ndogs -> dog\ncaresses -> caress\n");
+        let mut out = Vec::new();
+        write_changed_stems(&mut stemmer, input.as_bytes(), &mut out, false).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 4);
+    }
+
+    #[test]
+    fn test_list_algorithms() {
+        assert_eq!(parse_args(args(&["--list-algorithms"])).unwrap().mode, Mode::ListAlgorithms);
+        let mut out = Vec::new();
+        list_algorithms(&mut out).unwrap();
+        let listing = String::from_utf8(out).unwrap();
+        assert!(listing.contains("\n  porter2    Porter2, the Snowball English algorithm\n"));
+        assert!(listing.contains("\n  de  german (Snowball)\n"));
+    }
+
     #[test]
-    fn test_basic_stemming() {
+    fn test_stem_records() {
+        assert_eq!(parse_args(args(&["-0"])).unwrap().mode, Mode::Null);
         let mut stemmer = PorterStemmer::new();
-        assert_eq!(stemmer.stem("caresses"), "caress");
-        assert_eq!(stemmer.stem("ponies"), "poni");
-        assert_eq!(stemmer.stem("ties"), "ti");
-        assert_eq!(stemmer.stem("caress"), "caress");
-        assert_eq!(stemmer.stem("cats"), "cat");
+        let mut out = Vec::new();
+        stem_records(&mut stemmer, &b"Running\0ponies\ncats\0\0caresses"[..], &mut out).unwrap();
+        assert_eq!(out, b"run\0ponies\ncat\0\0caress\0");
     }
 
     #[test]
-    fn test_complex_stemming() {
+    fn test_check_stems() {
+        assert_eq!(parse_args(args(&["--check", "voc.txt", "output.txt"])).unwrap().mode, Mode::Check);
+        assert!(parse_args(args(&["--check", "voc.txt"])).is_err());
         let mut stemmer = PorterStemmer::new();
-        assert_eq!(stemmer.stem("troubled"), "troubl");
-        assert_eq!(stemmer.stem("troubles"), "troubl");
-        assert_eq!(stemmer.stem("troubling"), "troubl");
-        assert_eq!(stemmer.stem("capability"), "capabl");
-        assert_eq!(stemmer.stem("marketing"), "market");
+        let mut out = Vec::new();
+        let vocabulary = (&b"caresses\nponies\nrunning\n"[..], "voc.txt");
+        let mismatches = check_stems(&mut stemmer, vocabulary, (&b"caress\npony\nrun\n"[..], "output.txt"), &mut out);
+        assert_eq!(mismatches.unwrap(), 1);
+        let report = "voc.txt:2: \"ponies\" -> \"poni\", expected \"pony\"\n3 words checked, 1 mismatched\n";
+        assert_eq!(String::from_utf8(out).unwrap(), report);
+
+        let mut out = Vec::new();
+        let mismatches = check_stems(&mut stemmer, vocabulary, (&b"caress\n"[..], "output.txt"), &mut out).unwrap();
+        assert_eq!(mismatches, 1);
+        assert!(String::from_utf8(out).unwrap().starts_with("output.txt: ends at line 1, before voc.txt\n"));
     }
-}
 
-fn main() {
-    let mut stemmer = PorterStemmer::new();
-    let stemmed = stemmer.stem("running");
-    println!("Stemmed word: {}", stemmed); // Outputs: "run"
-}
\ No newline at end of file
+    #[test]
+    fn test_verify_stems() {
+        let options = parse_args(args(&["verify", "--golden", "stems.tsv", "corpus.txt"])).unwrap();
+        assert_eq!((options.mode, options.golden.as_deref()), (Mode::Verify, Some("stems.tsv")));
+        assert!(parse_args(args(&["verify", "corpus.txt"])).is_err());
+        assert!(parse_args(args(&["--golden", "stems.tsv", "corpus.txt"])).is_err());
+
+        // A config file's settings go after the subcommand, not before it
+        #[cfg(feature = "config")]
+        {
+            let directory = std::env::temp_dir().join(format!("porterstem-verify-{}", std::process::id()));
+            std::fs::create_dir_all(&directory).unwrap();
+            let config = directory.join("porterstem.toml");
+            std::fs::write(&config, "algorithm = \"porter2\"\n").unwrap();
+            let given = args(&["verify", "--config", &config.to_string_lossy(), "--golden", "stems.tsv", "corpus.txt"]);
+            let options = config::with_config(given).and_then(parse_args).unwrap();
+            assert_eq!((options.mode, options.algorithm), (Mode::Verify, Algorithm::Porter2));
+            std::fs::remove_dir_all(&directory).unwrap();
+        }
+
+        let golden = (&b"running\trun\nponies\tpony\ncats\tcat\n"[..], "stems.tsv");
+        let mut out = Vec::new();
+        let corpus = &b"Running ponies, cats and dogs\n"[..];
+        assert_eq!(verify_stems(&mut PorterStemmer::new(), golden, corpus, &mut out).unwrap(), 1);
+        let report = "added: \"and\" -> \"and\"\nadded: \"dogs\" -> \"dog\"\n\
+                      changed: \"ponies\" -> \"poni\", was \"pony\"\n\
+                      5 words verified against stems.tsv: 1 changed, 2 added\n";
+        assert_eq!(String::from_utf8(out).unwrap(), report);
+
+        let error = verify_stems(&mut PorterStemmer::new(), (&b"run\n"[..], "stems.tsv"), &b""[..], &mut Vec::new());
+        assert_eq!(error.unwrap_err().to_string(), "stems.tsv:1: expected a word<TAB>stem line");
+    }
+
+    #[test]
+    fn test_parse_bench_args() {
+        let options = parse_args(args(&["bench", "corpus.txt", "--algorithm", "porter2"])).unwrap();
+        assert_eq!((options.mode, options.algorithm), (Mode::Bench, Algorithm::Porter2));
+        assert_eq!(options.files, ["corpus.txt"]);
+        assert_eq!(parse_args(args(&["bench"])).unwrap().mode, Mode::Bench);
+        assert_eq!(parse_args(args(&["corpus.txt", "bench"])).unwrap().mode, Mode::Text);
+        assert!(parse_args(args(&["bench", "a.txt", "b.txt"])).is_err());
+    }
+
+    #[test]
+    fn test_in_place() {
+        assert!(parse_args(args(&["--in-place"])).is_err());
+        assert!(parse_args(args(&["--backup", ".bak", "a.txt"])).is_err());
+        assert!(parse_args(args(&["-i", "--freq", "a.txt"])).is_err());
+        assert!(parse_args(args(&["-i", "a.txt", "-o", "b.txt"])).is_err());
+
+        let dir = std::env::temp_dir().join(format!("porterstem-in-place-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("a.txt");
+        fs::write(&path, "Running ponies\n").unwrap();
+        let arg = path.to_str().unwrap();
+        assert!(run(&parse_args(args(&["-i", "--backup", ".bak", arg])).unwrap()).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "run poni\n");
+        assert_eq!(fs::read_to_string(dir.join("a.txt.bak")).unwrap(), "Running ponies\n");
+        assert!(run(&parse_args(args(&["--in-place", "--preserve-case", arg])).unwrap()).unwrap());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_index_and_search() {
+        assert!(parse_args(args(&["index", "docs"])).is_err());
+        assert!(parse_args(args(&["search", "idx"])).is_err());
+        let options = parse_args(args(&["search", "idx", "running", "fast", "--top", "3"])).unwrap();
+        assert_eq!((options.mode, options.files), (Mode::Search, args(&["idx"])));
+        assert_eq!(options.words, ["running", "fast"]);
+
+        let dir = std::env::temp_dir().join(format!("porterstem-index-cli-{}", process::id()));
+        fs::create_dir_all(dir.join("docs/sub")).unwrap();
+        fs::write(dir.join("docs/pasta.txt"), "Cooking pasta").unwrap();
+        fs::write(dir.join("docs/sub/shoes.txt"), "Running shoes for runners").unwrap();
+        let (docs, idx) = (dir.join("docs"), dir.join("idx"));
+        let (idx_arg, docs_arg) = (idx.to_str().unwrap(), docs.to_str().unwrap());
+        assert!(run(&parse_args(args(&["index", "--lang", "de", "--out", idx_arg, docs_arg])).unwrap()).unwrap());
+        assert_eq!(stemmer_for_label(Index::load(&idx).unwrap().stemmer_name()).unwrap().stem("laufen"), "lauf");
+
+        assert!(run(&parse_args(args(&["index", "--fold", "--out", idx_arg, docs_arg])).unwrap()).unwrap());
+        let mut out = Vec::new();
+        search_index(idx_arg, "run fast", 10, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), 1);
+        assert!(out.ends_with(&format!("\t{}\n", docs.join("sub/shoes.txt").display())));
+        assert!(stemmer_for_label("porter2+fold").is_ok() && stemmer_for_label("lovins").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite() {
+        assert!(parse_args(args(&["--sqlite"])).is_err());
+        assert!(parse_args(args(&["--sqlite", "s.db", "-o", "out.txt"])).is_err());
+        let options = parse_args(args(&["--sqlite", "s.db", "a.txt"])).unwrap();
+        assert_eq!((options.mode, options.sqlite.as_deref()), (Mode::Sqlite, Some("s.db")));
+
+        let dir = std::env::temp_dir().join(format!("porterstem-sqlite-cli-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.txt"), "Running ponies").unwrap();
+        let (db, text) = (dir.join("s.db"), dir.join("a.txt"));
+        let (missing, db_arg) = (dir.join("missing.txt"), db.to_str().unwrap());
+        let options = parse_args(args(&["--sqlite", db_arg, text.to_str().unwrap(), missing.to_str().unwrap()]));
+        assert!(!run(&options.unwrap()).unwrap());
+        let file = fs::read(&db).unwrap();
+        assert!(file.starts_with(b"SQLite format 3\0") && file.len() % 4096 == 0);
+        assert!(file.windows(4).any(|w| w == b"poni"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_daemon_args() {
+        let options = parse_args(args(&["daemon", "--socket", "/tmp/stem.sock", "--lang", "de"])).unwrap();
+        assert_eq!((options.mode, options.socket.as_deref()), (Mode::Daemon, Some("/tmp/stem.sock")));
+        assert!(parse_args(args(&["--socket", "/tmp/stem.sock"])).is_err());
+        assert!(parse_args(args(&["daemon", "words.txt"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_serve_args() {
+        let options = parse_args(args(&["serve", "--port", "9000", "--algorithm", "porter2"]));
+        let options = options.map(|options| (options.mode, options.host, options.port));
+        assert_eq!(options.ok(), cfg!(feature = "serve").then(|| (Mode::Serve, "127.0.0.1".to_string(), 9000)));
+        assert!(parse_args(args(&["serve", "--port", "http"])).is_err());
+        assert!(parse_args(args(&["serve", "doc.txt"])).is_err());
+        let grpc = parse_args(args(&["serve", "--grpc"])).map(|options| options.grpc);
+        assert_eq!(grpc.ok(), cfg!(feature = "grpc").then_some(true));
+        assert!(parse_args(args(&["daemon", "--grpc"])).is_err());
+    }
+
+    #[test]
+    fn test_trace() {
+        let options = parse_args(args(&["--trace", "hopping", "cats"])).unwrap();
+        assert_eq!((options.mode, options.words, options.files.len()), (Mode::Trace, args(&["hopping", "cats"]), 0));
+        assert!(parse_args(args(&["--trace", "--algorithm", "porter2", "cats"])).is_err());
+
+        let mut out = Vec::new();
+        trace_words(false, ["cats"], &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "cats -> cat\n  1a: cats -> cat (-s -> -, m=1)\n");
+    }
+
+    #[test]
+    fn test_parse_stopwords_args() {
+        let options = parse_args(args(&["--text", "--stopwords", "builtin"])).unwrap();
+        assert_eq!(options.mode, Mode::Text);
+        assert_eq!(options.stopwords.as_deref(), Some("builtin"));
+        assert!(parse_args(args(&["--html", "--stopwords", "builtin"])).is_err());
+        assert!(parse_args(args(&["--freq", "--stopwords", "builtin"])).is_ok());
+        assert!(parse_args(args(&["--unique", "--stopwords", "stop.txt"])).is_ok());
+        assert!(parse_args(args(&["--text", "--stopwords"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_digits_args() {
+        let options = parse_args(args(&["--text", "--digits", "strip"])).unwrap();
+        assert_eq!(options.digits, Some(DigitPolicy::StripDigits));
+        assert!(parse_args(args(&["--text", "--digits", "round"])).is_err());
+        assert!(parse_args(args(&["--markdown", "--digits", "skip"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_length_args() {
+        let options = parse_args(args(&["--min-length", "4", "--max-length", "64", "--long-tokens", "drop"])).unwrap();
+        assert_eq!((options.min_length, options.max_length), (Some(4), Some(64)));
+        let given = text_options(&options, None);
+        assert_eq!((given.min_length, given.long_tokens), (4, LongTokenPolicy::Skip));
+        assert_eq!(text_options(&parse_args(args(&[])).unwrap(), None).max_length, None);
+        assert!(parse_args(args(&["--max-length", "many"])).is_err());
+        assert!(parse_args(args(&["--long-tokens", "drop"])).is_err());
+        let options = parse_args(args(&["--max-length", "8", "--long-tokens", "fail"])).unwrap();
+        assert_eq!(options.long_tokens, Some(LongTokenPolicy::Reject));
+        assert!(parse_args(args(&["--tsv", "--min-length", "2"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_symbols_args() {
+        assert_eq!(parse_args(args(&["--text", "--symbols", "drop"])).unwrap().drop_symbols, Some(true));
+        let options = parse_args(args(&["--text", "--symbol-placeholder", "<sym>"])).unwrap();
+        assert_eq!(options.symbol_placeholder.as_deref(), Some("<sym>"));
+        assert!(parse_args(args(&["--text", "--symbols", "mangle"])).is_err());
+        assert!(parse_args(args(&["--html", "--symbols", "drop"])).is_err());
+        assert!(parse_args(args(&["--text", "--symbols", "drop", "--symbol-placeholder", "_"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_args() {
+        let options = parse_args(args(&["--csv", "--columns", "2,5", "--delimiter", "tab"])).unwrap();
+        assert_eq!(options.mode, Mode::Csv);
+        assert_eq!(options.columns, vec![1, 4]);
+        assert_eq!(options.delimiter, b'\t');
+        assert!(options.has_header);
+        assert!(parse_args(args(&["--csv"])).is_err());
+        assert!(parse_args(args(&["--csv", "--columns", "0"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_jsonl_args() {
+        let options = parse_args(args(&["--jsonl", "--fields", "title, body.text"])).unwrap();
+        assert_eq!(options.mode, Mode::Jsonl);
+        assert_eq!(options.fields, vec!["title", "body.text"]);
+        assert!(parse_args(args(&["--jsonl"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_kwic_args() {
+        let options = parse_args(args(&["--kwic", "running", "--window", "12"])).unwrap();
+        assert_eq!(options.mode, Mode::Concordance);
+        assert_eq!(options.keyword, "running");
+        assert_eq!(options.window, 12);
+        assert!(parse_args(args(&["--kwic", "run", "--window", "wide"])).is_err());
+    }
+}
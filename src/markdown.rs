@@ -0,0 +1,229 @@
+//! # Markdown Input
+//!
+//! Stems the prose of a Markdown document while passing through, byte for
+//! byte, the parts where words are really identifiers or addresses:
+//! - fenced code blocks (```` ``` ```` or `~~~`)
+//! - inline code spans
+//! - link and image targets (`[text](target "title")`) and reference labels
+//! - link reference definitions (`[label]: https://...`)
+//! - autolinks (`<https://...>`) and bare `http://`, `https://` and `www.` URLs
+//!
+//! Link text and image alt text are prose and are stemmed. Indented code
+//! blocks are not recognized, as they cannot be told apart from indented list
+//! continuations without a full parser.
+
+use crate::text::stem_text;
+use crate::Stemmer;
+
+/// An open fenced code block: the fence character and its length
+struct Fence {
+    ch: u8,
+    len: usize,
+}
+
+/// Returns the fence opened or closed by `line`, if it is a fence line
+fn fence_of(line: &str) -> Option<(Fence, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 { return None; }
+    let rest = &line[indent..];
+    let ch = *rest.as_bytes().first()?;
+    if ch != b'`' && ch != b'~' { return None; }
+    let len = rest.bytes().take_while(|&b| b == ch).count();
+    if len < 3 { return None; }
+    let info = &rest[len..];
+    if ch == b'`' && info.contains('`') { return None; }
+    Some((Fence { ch, len }, info))
+}
+
+/// Returns true if `line` is a link reference definition such as `[id]: url`
+fn is_reference_definition(line: &str) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 || !trimmed.starts_with('[') { return false; }
+    match trimmed.find("]:") {
+        Some(end) => end > 1 && !trimmed[1..end].contains(['[', ']']),
+        None => false,
+    }
+}
+
+/// Returns the end of the bracketed or parenthesized group opening at `start`
+fn group_end(bytes: &[u8], start: usize, open: u8, close: u8) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b if b == open => depth += 1,
+            b if b == close => {
+                depth -= 1;
+                if depth == 0 { return Some(i + 1); }
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns the end of the code span whose opening backtick run starts at `start`
+fn code_span_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let run = bytes[start..].iter().take_while(|&&b| b == b'`').count();
+    let mut i = start + run;
+    while i < bytes.len() {
+        if bytes[i] == b'`' {
+            let close = bytes[i..].iter().take_while(|&&b| b == b'`').count();
+            if close == run { return Some(i + close); }
+            i += close;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Returns the end of an autolink such as `<https://example.com>`
+fn autolink_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let inner_len = bytes[start + 1..]
+        .iter()
+        .position(|&b| b == b'>' || b == b'<' || b.is_ascii_whitespace())?;
+    let end = start + 1 + inner_len;
+    if bytes[end] != b'>' { return None; }
+    let inner = &bytes[start + 1..end];
+    let is_uri = inner.windows(3).any(|w| w == b"://") || inner.starts_with(b"mailto:");
+    let is_email = inner.contains(&b'@');
+    if is_uri || is_email { Some(end + 1) } else { None }
+}
+
+/// Returns the end of a bare URL starting at `start`
+fn bare_url_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let rest = &bytes[start..];
+    if !(rest.starts_with(b"http://") || rest.starts_with(b"https://") || rest.starts_with(b"www.")) {
+        return None;
+    }
+    if start > 0 && bytes[start - 1].is_ascii_alphanumeric() { return None; }
+    let mut end = start + rest.iter().position(|&b| b.is_ascii_whitespace() || b == b'<').unwrap_or(rest.len());
+    while end > start && matches!(bytes[end - 1], b'.' | b',' | b';' | b':' | b'!' | b'?' | b')' | b'*' | b'_') {
+        end -= 1;
+    }
+    Some(end)
+}
+
+/// Stems the prose of one line outside code blocks
+fn stem_inline(stemmer: &mut dyn Stemmer, line: &str, out: &mut String) {
+    let bytes = line.as_bytes();
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let verbatim_end = match bytes[i] {
+            b'`' => code_span_end(bytes, i),
+            b'(' if i > 0 && bytes[i - 1] == b']' => group_end(bytes, i, b'(', b')'),
+            b'[' if i > 0 && bytes[i - 1] == b']' => group_end(bytes, i, b'[', b']'),
+            b'<' => autolink_end(bytes, i),
+            b'h' | b'w' => bare_url_end(bytes, i),
+            b'\\' => {
+                i += 2;
+                continue;
+            },
+            _ => None,
+        };
+
+        match verbatim_end {
+            Some(end) => {
+                out.push_str(&stem_text(stemmer, &line[text_start..i]));
+                out.push_str(&line[i..end]);
+                i = end;
+                text_start = end;
+            },
+            None if bytes[i] == b'`' => {
+                // An unmatched backtick run is literal text
+                i += bytes[i..].iter().take_while(|&&b| b == b'`').count();
+            },
+            None => i += 1,
+        }
+    }
+
+    if text_start < line.len() {
+        out.push_str(&stem_text(stemmer, &line[text_start..]));
+    }
+}
+
+/// Stems the prose of a Markdown document, leaving code and links untouched
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each word of prose
+/// * `markdown` - The Markdown document
+///
+/// # Returns
+/// The document with only its prose stemmed
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, markdown::stem_markdown};
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(
+///     stem_markdown(&mut stemmer, "Running `getRunningTasks()` is [documented](https://example.com/running)"),
+///     "run `getRunningTasks()` is [document](https://example.com/running)"
+/// );
+/// ```
+pub fn stem_markdown(stemmer: &mut dyn Stemmer, markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut fence: Option<Fence> = None;
+
+    for line in markdown.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        if let Some(open) = &fence {
+            if let Some((close, info)) = fence_of(content) {
+                if close.ch == open.ch && close.len >= open.len && info.trim().is_empty() {
+                    fence = None;
+                }
+            }
+            out.push_str(line);
+            continue;
+        }
+
+        if let Some((open, _)) = fence_of(content) {
+            fence = Some(open);
+            out.push_str(line);
+        } else if is_reference_definition(content) {
+            out.push_str(line);
+        } else {
+            stem_inline(stemmer, line, &mut out);
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_code_blocks_pass_through() {
+        let mut stemmer = PorterStemmer::new();
+        let doc = "# Getting started\n\n```rust\nlet running = stemmer.stem(\"running\");\n```\n\
+            Then keep running.\n~~~~\nloops ```\n~~~~\n";
+        assert_eq!(
+            stem_markdown(&mut stemmer, doc),
+            "# get start\n\n```rust\nlet running = stemmer.stem(\"running\");\n```\n\
+            then keep run.\n~~~~\nloops ```\n~~~~\n"
+        );
+    }
+
+    #[test]
+    fn test_links_and_urls_pass_through() {
+        let mut stemmer = PorterStemmer::new();
+        let doc = "See ![running dogs](img/running_dogs.png), [the docs][running-docs], \
+            <https://example.com/jumping> or https://example.com/flying.\n\
+            [running-docs]: https://example.com/docs \"Running Docs\"\n";
+        assert_eq!(
+            stem_markdown(&mut stemmer, doc),
+            "see ![run dog](img/running_dogs.png), [the doc][running-docs], \
+            <https://example.com/jumping> or https://example.com/flying.\n\
+            [running-docs]: https://example.com/docs \"Running Docs\"\n"
+        );
+        assert_eq!(stem_markdown(&mut stemmer, "a `` b"), "a `` b");
+    }
+}
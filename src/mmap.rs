@@ -0,0 +1,187 @@
+//! # Memory-Mapped Input
+//!
+//! Stems a file of any size without reading it into memory: [`Mmap`] maps
+//! the file read-only, and [`stem_mapped`] walks the mapped bytes in chunks
+//! that end at a line break, stemming and writing each one before looking
+//! at the next. Memory use stays at about one chunk of output whatever the
+//! size of the file, and the kernel pages the file in as it is read. Only
+//! available with the `mmap` feature, on Unix.
+//!
+//! A mapped file must not be truncated while it is mapped; the process is
+//! killed (`SIGBUS`) if it reads past the new end.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::mmap::{self, Mmap};
+//! # use m_porter_stemmer_rust::text::TextOptions;
+//! # use m_porter_stemmer_rust::PorterStemmer;
+//! let path = std::env::temp_dir().join(format!("mmap-doc-{}.txt", std::process::id()));
+//! std::fs::write(&path, "Running ponies\ncaresses\n").unwrap();
+//!
+//! let map = Mmap::open(&path).unwrap();
+//! let mut output = Vec::new();
+//! mmap::stem_mapped(&mut PorterStemmer::new(), &map, &mut output, &TextOptions::new()).unwrap();
+//! assert_eq!(output, b"run poni\ncaress\n");
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use std::ffi::c_void;
+use std::fs::File;
+use std::io::{self, Write};
+use std::ops::Deref;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+use std::ptr;
+
+use crate::stemmer::Stemmer;
+use crate::text::{stem_text_with, TextOptions};
+
+/// Bytes of input stemmed at a time by [`stem_mapped`]
+pub const CHUNK_SIZE: usize = 1 << 20;
+
+const PROT_READ: i32 = 1;
+const MAP_PRIVATE: i32 = 2;
+const MADV_SEQUENTIAL: i32 = 2;
+
+extern "C" {
+    fn mmap(addr: *mut c_void, len: usize, prot: i32, flags: i32, fd: i32, offset: i64) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> i32;
+    fn madvise(addr: *mut c_void, len: usize, advice: i32) -> i32;
+}
+
+/// A file mapped read-only into memory, dereferencing to its bytes
+#[derive(Debug)]
+pub struct Mmap {
+    ptr: *mut c_void,
+    len: usize,
+}
+
+// The mapping is read-only and owned by this value alone
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    /// Maps the file at `path`, which may be empty
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file too large to map"))?;
+        if len == 0 {
+            // mmap rejects a zero length
+            return Ok(Mmap { ptr: ptr::null_mut(), len });
+        }
+        // SAFETY: a fresh private read-only mapping of an open file, which
+        // may be closed once it is mapped
+        let ptr = unsafe { mmap(ptr::null_mut(), len, PROT_READ, MAP_PRIVATE, file.as_raw_fd(), 0) };
+        if ptr as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `ptr` and `len` are the mapping just made; the advice only
+        // tunes read-ahead, so its failure does not matter
+        unsafe { madvise(ptr, len, MADV_SEQUENTIAL) };
+        Ok(Mmap { ptr, len })
+    }
+}
+
+impl Deref for Mmap {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+        // SAFETY: the mapping is `len` readable bytes and lives as long as
+        // `self`
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: unmaps the mapping made in `open`, which no slice
+            // outlives
+            unsafe { munmap(self.ptr, self.len) };
+        }
+    }
+}
+
+/// Splits `bytes` into pieces of about `size` bytes, each ending just after
+/// a line break, or after a space or tab if a line runs on for another
+/// `size` bytes, so that no word or UTF-8 sequence is split
+pub fn chunks(bytes: &[u8], size: usize) -> impl Iterator<Item = &[u8]> {
+    let size = size.max(1);
+    let mut rest = bytes;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let window = &rest[size.min(rest.len())..];
+        let end = match window.iter().take(size).position(|&b| b == b'\n') {
+            Some(i) => i + 1,
+            None => window.iter().position(|&b| matches!(b, b'\n' | b' ' | b'\t')).map_or(window.len(), |i| i + 1),
+        };
+        let (chunk, tail) = rest.split_at(rest.len() - window.len() + end);
+        rest = tail;
+        Some(chunk)
+    })
+}
+
+/// Stems the words of text held in memory, as `text::stem_text_with` would
+/// line by line, a [`CHUNK_SIZE`] chunk at a time
+///
+/// Input that is not UTF-8 is an `InvalidData` error, like reading it
+/// through `BufRead::read_line`; the chunks before it have been written by
+/// then.
+pub fn stem_mapped(
+    stemmer: &mut dyn Stemmer,
+    bytes: &[u8],
+    mut output: impl Write,
+    options: &TextOptions,
+) -> io::Result<()> {
+    for chunk in chunks(bytes, CHUNK_SIZE) {
+        let text = std::str::from_utf8(chunk).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        output.write_all(stem_text_with(stemmer, text, options).as_bytes())?;
+    }
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_chunks() {
+        let text = b"one two\nthree four five\nsix";
+        let pieces: Vec<&[u8]> = chunks(text, 4).collect();
+        assert_eq!(pieces, [&b"one two\n"[..], b"three ", b"four ", b"five\n", b"six"]);
+        assert_eq!(chunks(b"", 4).count(), 0);
+        let accented = "naïveté ".repeat(3);
+        let pieces: Vec<&[u8]> = chunks(accented.as_bytes(), 2).collect();
+        assert_eq!(pieces, ["naïveté ".as_bytes(); 3]);
+    }
+
+    #[test]
+    fn test_stem_mapped() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("porterstem-mmap-{}.txt", std::process::id()));
+        let text = "Caresses, ponies!\n\trunning--\n".repeat(100_000);
+        std::fs::write(&path, &text).unwrap();
+        let map = Mmap::open(&path).unwrap();
+        assert_eq!(&map[..], text.as_bytes());
+
+        let mut output = Vec::new();
+        stem_mapped(&mut PorterStemmer::new(), &map, &mut output, &TextOptions::new()).unwrap();
+        assert_eq!(output, "caress, poni!\n\trun--\n".repeat(100_000).as_bytes());
+        drop(map);
+
+        std::fs::write(&path, b"").unwrap();
+        assert!(Mmap::open(&path).unwrap().is_empty());
+        std::fs::write(&path, b"ok \xFF").unwrap();
+        let map = Mmap::open(&path).unwrap();
+        let error = stem_mapped(&mut PorterStemmer::new(), &map, io::sink(), &TextOptions::new());
+        assert_eq!(error.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+//! # Stemmed N-grams
+//!
+//! Builds n-grams (bigrams, trigrams, ...) over the stems of a text, for
+//! phrase indexing and feature extraction. Each n-gram is `n` consecutive
+//! stems joined with a separator, so "machine learning models" gives the
+//! bigrams "machin learn" and "learn model".
+//!
+//! [`stem_phrase`] stems a whole phrase instead, keeping the separators it
+//! was written with, for matching multi-word queries against phrase indexes.
+
+use crate::text::{stem_text, stems};
+use crate::Stemmer;
+
+/// Joins every window of `n` consecutive items with `separator`
+///
+/// Returns nothing if `n` is 0 or larger than the number of items.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::ngram::ngrams;
+/// assert_eq!(ngrams(&["a", "b", "c"], 2, "_"), ["a_b", "b_c"]);
+/// ```
+pub fn ngrams<S: AsRef<str>>(items: &[S], n: usize, separator: &str) -> Vec<String> {
+    if n == 0 { return Vec::new(); }
+    items
+        .windows(n)
+        .map(|window| {
+            let parts: Vec<&str> = window.iter().map(AsRef::as_ref).collect();
+            parts.join(separator)
+        })
+        .collect()
+}
+
+/// Returns the stemmed n-grams of `text`, joined with a single space
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each word
+/// * `text` - Arbitrary input text
+/// * `n` - Number of stems per n-gram (2 for bigrams, 3 for trigrams)
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, ngram::stem_ngrams};
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(stem_ngrams(&mut stemmer, "machine learning models", 2), ["machin learn", "learn model"]);
+/// ```
+pub fn stem_ngrams(stemmer: &mut dyn Stemmer, text: &str, n: usize) -> Vec<String> {
+    stem_ngrams_with(stemmer, text, n, " ")
+}
+
+/// Returns the stemmed n-grams of `text`, joined with `separator`
+pub fn stem_ngrams_with(stemmer: &mut dyn Stemmer, text: &str, n: usize, separator: &str) -> Vec<String> {
+    ngrams(&stems(stemmer, text), n, separator)
+}
+
+/// Stems each word of a phrase, keeping the original separators
+///
+/// Whitespace around the phrase is trimmed; everything between its words
+/// (spaces, hyphens, slashes) is kept as written, so "state-of-the-art
+/// models" gives "state-of-the-art model", not "state of the art model".
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, ngram::stem_phrase};
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(stem_phrase(&mut stemmer, "machine learning models"), "machin learn model");
+/// assert_eq!(stem_phrase(&mut stemmer, " Client/server  Networking\n"), "client/server  network");
+/// ```
+pub fn stem_phrase(stemmer: &mut dyn Stemmer, phrase: &str) -> String {
+    stem_text(stemmer, phrase.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_stem_ngrams() {
+        let mut stemmer = PorterStemmer::new();
+        let text = "Running dogs chase flying cats.";
+        assert_eq!(stem_ngrams_with(&mut stemmer, text, 3, "_"), ["run_dog_chase", "dog_chase_fly", "chase_fly_cat"]);
+        assert_eq!(stem_ngrams(&mut stemmer, text, 1).len(), 5);
+        assert!(stem_ngrams(&mut stemmer, text, 0).is_empty());
+        assert!(stem_ngrams(&mut stemmer, "lonely", 2).is_empty());
+    }
+
+    #[test]
+    fn test_stem_phrase_keeps_separators() {
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(stem_phrase(&mut stemmer, "state-of-the-art models"), "state-of-the-art model");
+        assert_eq!(stem_phrase(&mut stemmer, "COVID-19 vaccines"), "covid-19 vaccin");
+        assert_eq!(stem_phrase(&mut stemmer, "   "), "");
+    }
+}
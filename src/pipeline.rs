@@ -0,0 +1,253 @@
+//! # Parallel Pipeline
+//!
+//! Stems one large input on several threads while keeping the output in
+//! input order: a reader thread cuts the input into pieces that end at line
+//! breaks (see [`crate::text::read_chunks`]), a pool of workers stems them,
+//! each with a stemmer of its own, and the calling thread writes the stemmed
+//! pieces as soon as all those before them are written. The output is the
+//! same as [`crate::text::stem_stream`] gives for the input, and
+//! [`Pipeline::try_run`] fails as [`crate::text::try_stem_stream`] does.
+//!
+//! The channels between the stages are bounded, and the reader may be at
+//! most [`Pipeline::queue`] pieces ahead of the writer, so memory use stays
+//! at that many pieces of about 64 KiB whatever the size of the input,
+//! even while one slow piece holds up the ones after it, and a slow
+//! output slows the reader down instead of filling memory.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::pipeline::Pipeline;
+//! # use m_porter_stemmer_rust::text::TextOptions;
+//! # use m_porter_stemmer_rust::PorterStemmer;
+//! let input = "Running ponies\ncaresses\n".repeat(10_000);
+//! let mut output = Vec::new();
+//! Pipeline::new()
+//!     .workers(4)
+//!     .run(PorterStemmer::new, input.as_bytes(), &mut output, &TextOptions::new())
+//!     .unwrap();
+//! assert_eq!(output, "run poni\ncaress\n".repeat(10_000).as_bytes());
+//! ```
+
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::panic;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+use crate::error::StemError;
+use crate::stemmer::Stemmer;
+use crate::text::{read_chunks, stem_text_with, try_stem_text_with, TextOptions};
+
+/// Pieces in flight per worker by default
+const QUEUE_PER_WORKER: usize = 4;
+
+/// The number of workers and pieces in flight of a pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pipeline {
+    workers: usize,
+    queue: usize,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pipeline {
+    /// A pipeline with a worker per CPU
+    pub fn new() -> Self {
+        let workers = thread::available_parallelism().map_or(1, usize::from);
+        Pipeline { workers, queue: workers * QUEUE_PER_WORKER }
+    }
+
+    /// Sets the number of stemming threads (at least 1)
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self.queue = self.workers * QUEUE_PER_WORKER;
+        self
+    }
+
+    /// Sets how many pieces may be read but not yet written (at least the
+    /// number of workers)
+    pub fn queue(mut self, pieces: usize) -> Self {
+        self.queue = pieces;
+        self
+    }
+
+    /// Stems the text read from `input` with the given options, writing the
+    /// output to `output` in input order
+    ///
+    /// `make_stemmer` is called once on each worker thread. An error
+    /// reading stops the pipeline after the pieces before it are written; an
+    /// error writing stops it at once. A panic in a worker is passed on.
+    pub fn run<S: Stemmer>(
+        &self,
+        make_stemmer: impl Fn() -> S + Sync,
+        input: impl Read + Send,
+        output: impl Write,
+        options: &TextOptions,
+    ) -> io::Result<()> {
+        Ok(self.run_checked(make_stemmer, input, output, options, false)?)
+    }
+
+    /// Stems the text read from `input` as [`Pipeline::run`] does, failing
+    /// as [`crate::text::try_stem_text_with`] does
+    ///
+    /// The pieces before the one that fails are written, and error
+    /// positions are counted from the start of the input.
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::{pipeline::Pipeline, token::LongTokenPolicy, PorterStemmer, StemError};
+    /// # use m_porter_stemmer_rust::text::TextOptions;
+    /// let input = format!("{}see {}\n", "cats\n".repeat(100_000), "x".repeat(100));
+    /// let options = TextOptions::new().max_length(64, LongTokenPolicy::Reject);
+    /// let mut output = Vec::new();
+    /// let error = Pipeline::new().try_run(PorterStemmer::new, input.as_bytes(), &mut output, &options);
+    /// assert!(matches!(error, Err(StemError::TokenTooLong { length: 100, max: 64 })));
+    /// ```
+    pub fn try_run<S: Stemmer>(
+        &self,
+        make_stemmer: impl Fn() -> S + Sync,
+        input: impl Read + Send,
+        output: impl Write,
+        options: &TextOptions,
+    ) -> Result<(), StemError> {
+        self.run_checked(make_stemmer, input, output, options, true)
+    }
+
+    /// The body of [`Pipeline::run`] and, when `strict`, [`Pipeline::try_run`]
+    fn run_checked<S: Stemmer>(
+        &self,
+        make_stemmer: impl Fn() -> S + Sync,
+        input: impl Read + Send,
+        mut output: impl Write,
+        options: &TextOptions,
+        strict: bool,
+    ) -> Result<(), StemError> {
+        let window = self.queue.max(self.workers);
+        let (work, pieces) = mpsc::sync_channel::<(usize, usize, String)>(window);
+        let pieces = Mutex::new(pieces);
+        let (done, stemmed) = mpsc::sync_channel::<(usize, Result<String, StemError>)>(window);
+        // The reader takes a credit for each piece and the writer gives it
+        // back once the piece is written
+        let (give, take) = mpsc::sync_channel(window);
+        for _ in 0..window {
+            give.send(()).expect("the channel has room for every credit");
+        }
+
+        thread::scope(|scope| {
+            let reader = scope.spawn(move || {
+                let stopped = || io::Error::new(io::ErrorKind::BrokenPipe, "the pipeline stopped");
+                let (mut index, mut offset) = (0, 0);
+                read_chunks(input, |chunk| {
+                    take.recv().map_err(|_| stopped())?;
+                    work.send((index, offset, chunk.to_string())).map_err(|_| stopped())?;
+                    index += 1;
+                    offset += chunk.len();
+                    Ok(())
+                })
+            });
+            for _ in 0..self.workers {
+                let (done, pieces, make_stemmer) = (done.clone(), &pieces, &make_stemmer);
+                scope.spawn(move || {
+                    let mut stemmer = make_stemmer();
+                    loop {
+                        let piece = pieces.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                        let Ok((index, offset, text)) = piece else { break };
+                        let stemmed = match strict {
+                            true => try_stem_text_with(&mut stemmer, &text, options).map_err(|e| e.offset(offset)),
+                            false => Ok(stem_text_with(&mut stemmer, &text, options)),
+                        };
+                        // The writer is gone once writing has failed
+                        if done.send((index, stemmed)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(done);
+
+            let mut write = || -> Result<(), StemError> {
+                let mut pending = BTreeMap::new();
+                let mut next = 0;
+                for (index, text) in &stemmed {
+                    pending.insert(index, text);
+                    while let Some(text) = pending.remove(&next) {
+                        output.write_all(text?.as_bytes())?;
+                        next += 1;
+                        // The reader may have stopped
+                        let _ = give.send(());
+                    }
+                }
+                Ok(output.flush()?)
+            };
+            let written = write();
+            // Unblock the reader and the workers if writing failed
+            drop((stemmed, give));
+            let read = reader.join().unwrap_or_else(|e| panic::resume_unwind(e));
+            written.and(read.map_err(StemError::from))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::stem_stream;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_matches_stem_stream() {
+        let text = (0..20_000).map(|i| format!("{} connections, hopping ponies\n", i)).collect::<String>();
+        let mut expected = Vec::new();
+        stem_stream(&mut PorterStemmer::new(), text.as_bytes(), &mut expected, &TextOptions::new()).unwrap();
+        for pipeline in [Pipeline::new(), Pipeline::new().workers(1), Pipeline::new().workers(3).queue(1)] {
+            let mut output = Vec::new();
+            pipeline.run(PorterStemmer::new, text.as_bytes(), &mut output, &TextOptions::new()).unwrap();
+            assert!(output == expected, "{:?}", pipeline);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        /// A writer that fails once it has taken `room` bytes
+        struct Full(usize);
+
+        impl Write for Full {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if self.0 < buf.len() {
+                    return Err(io::Error::new(io::ErrorKind::StorageFull, "full"));
+                }
+                self.0 -= buf.len();
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let text = "caresses ponies\n".repeat(100_000);
+        let pipeline = Pipeline::new().workers(2);
+        let error = pipeline.run(PorterStemmer::new, text.as_bytes(), Full(1000), &TextOptions::new());
+        assert_eq!(error.unwrap_err().kind(), io::ErrorKind::StorageFull);
+
+        let mut output = Vec::new();
+        let invalid = [b"cats\n".repeat(20_000), vec![0xff]].concat();
+        let error = Pipeline::new().run(PorterStemmer::new, &invalid[..], &mut output, &TextOptions::new());
+        assert_eq!(error.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(output, b"cat\n".repeat(20_000));
+
+        let mut output = Vec::new();
+        let rejecting = || PorterStemmer::new().with_non_ascii(crate::fold::NonAsciiPolicy::Reject);
+        let input = [b"cats\n".repeat(20_000), "café\n".into()].concat();
+        let error = Pipeline::new().try_run(rejecting, &input[..], &mut output, &TextOptions::new());
+        assert!(matches!(error, Err(StemError::NonAsciiLetter { character: 'é', position: 100_003 })));
+        // Only the pieces before the failing one are written
+        assert!(output.len() < 80_000 && b"cat\n".repeat(20_000).starts_with(&output));
+        let error = Pipeline::new().try_run(rejecting, &invalid[..], io::sink(), &TextOptions::new());
+        assert!(matches!(error, Err(StemError::IoError(e)) if e.kind() == io::ErrorKind::InvalidData));
+    }
+}
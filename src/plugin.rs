@@ -0,0 +1,334 @@
+//! # Stemmer Plugins
+//!
+//! A stable C ABI for loading stemmers from shared libraries at run time,
+//! so an application can pick up another version of this crate, or a
+//! library of stemmers written elsewhere, without being rebuilt. Rust has
+//! no stable ABI of its own, so a plugin and its host share nothing but the
+//! `#[repr(C)]` table [`StemmerPlugin`] and plain pointers and sizes; each
+//! side may be built with its own compiler and its own copy of this crate.
+//!
+//! A plugin exports one function, `porterstem_plugin`, that returns its
+//! table. The shared library of the workspace's `porterstem-ffi` package,
+//! which enables the `plugin` feature, is such a plugin, offering every
+//! [`crate::Algorithm`] by name and every [`crate::Language`] by code. A
+//! host opens a plugin with [`Plugin::load`] (on Unix) and makes stemmers
+//! from it by name; a [`PluginStemmer`] is a [`Stemmer`] like any other:
+//!
+//! ```no_run
+//! # use m_porter_stemmer_rust::{plugin::Plugin, Stemmer};
+//! let plugin = Plugin::load("libporterstem_ffi.so").unwrap();
+//! let mut stemmer = plugin.stemmer("porter2").unwrap();
+//! assert_eq!(stemmer.stem("generously"), "generous");
+//! ```
+//!
+//! A table whose `abi_version` is not [`PLUGIN_ABI_VERSION`] is refused.
+//! Changes to the table are made as a new version alongside the old, never
+//! to the fields of an existing one. Only available with the `plugin`
+//! feature.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::{plugin::Plugin, Stemmer};
+//! let plugin = Plugin::builtin();
+//! assert!(plugin.names().any(|name| name == "de"));
+//! let mut stemmer = plugin.stemmer("de").unwrap();
+//! assert_eq!(stemmer.stem("häuser"), "haus");
+//! assert!(plugin.stemmer("klingon").is_none());
+//! ```
+
+use std::ffi::{c_char, c_void, CStr};
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::stemmer::{Algorithm, Language, Stemmer};
+
+/// The version of [`StemmerPlugin`] this crate makes and accepts
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// The name of the function a plugin exports, which returns its table
+pub const PLUGIN_SYMBOL: &str = "porterstem_plugin";
+
+/// The table of functions a plugin offers
+///
+/// All strings are NUL-terminated UTF-8 and live as long as the plugin is
+/// loaded.
+#[repr(C)]
+#[derive(Debug)]
+pub struct StemmerPlugin {
+    /// [`PLUGIN_ABI_VERSION`] of the plugin; always the first field
+    pub abi_version: u32,
+    /// Version of the plugin, such as "0.1.0"
+    pub version: *const c_char,
+    /// Names of the stemmers that `create` makes, separated by spaces
+    pub names: *const c_char,
+    /// Makes the stemmer named by the `len` bytes at `name`, or returns null
+    /// for an unknown name
+    pub create: unsafe extern "C" fn(name: *const u8, len: usize) -> *mut c_void,
+    /// Stems the `len` bytes at `word` and returns the stem, whose length
+    /// goes to `stem_len`, valid until the stemmer's next call; or returns
+    /// null if stemming failed
+    pub stem:
+        unsafe extern "C" fn(stemmer: *mut c_void, word: *const u8, len: usize, stem_len: *mut usize) -> *const u8,
+    /// Frees a stemmer from `create`
+    pub free: unsafe extern "C" fn(stemmer: *mut c_void),
+}
+
+// The table only points to static strings and functions
+unsafe impl Sync for StemmerPlugin {}
+
+/// The stemmer names [`PLUGIN`] offers: the algorithms, then the languages
+const NAMES: &str = "porter porter2 lancaster uea-lite lucene fr de pt ru sv no da hu ro tr el hi\0";
+
+/// This crate's table
+static PLUGIN: StemmerPlugin = StemmerPlugin {
+    abi_version: PLUGIN_ABI_VERSION,
+    version: concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char,
+    names: NAMES.as_ptr() as *const c_char,
+    create: plugin_create,
+    stem: plugin_stem,
+    free: plugin_free,
+};
+
+/// A stemmer made by [`PLUGIN`], with the buffer its stems are returned in
+struct Handle {
+    stemmer: Box<dyn Stemmer>,
+    stem: String,
+}
+
+/// Returns this crate's plugin table, for hosts that load it with `dlopen`
+#[no_mangle]
+pub extern "C" fn porterstem_plugin() -> *const StemmerPlugin {
+    &PLUGIN
+}
+
+unsafe extern "C" fn plugin_create(name: *const u8, len: usize) -> *mut c_void {
+    if name.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: the host passes `len` readable bytes
+    let name = unsafe { std::slice::from_raw_parts(name, len) };
+    let Ok(name) = std::str::from_utf8(name) else { return ptr::null_mut() };
+    let stemmer = match (name.parse::<Algorithm>(), name.parse::<Language>()) {
+        (Ok(algorithm), _) => algorithm.stemmer(false),
+        (_, Ok(language)) => language.stemmer(false),
+        _ => return ptr::null_mut(),
+    };
+    Box::into_raw(Box::new(Handle { stemmer, stem: String::new() })) as *mut c_void
+}
+
+unsafe extern "C" fn plugin_stem(stemmer: *mut c_void, word: *const u8, len: usize, stem_len: *mut usize) -> *const u8 {
+    if stemmer.is_null() || word.is_null() || stem_len.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: the host passes a stemmer from `plugin_create` that no one
+    // else is using and `len` readable bytes
+    let (handle, word) = unsafe { (&mut *(stemmer as *mut Handle), std::slice::from_raw_parts(word, len)) };
+    let Ok(word) = std::str::from_utf8(word) else { return ptr::null() };
+    // A panic must not unwind into the host
+    match panic::catch_unwind(AssertUnwindSafe(|| handle.stemmer.stem(word))) {
+        Ok(stem) => {
+            handle.stem = stem;
+            // SAFETY: the host passes a writable size
+            unsafe { *stem_len = handle.stem.len() };
+            handle.stem.as_ptr()
+        },
+        Err(_) => ptr::null(),
+    }
+}
+
+unsafe extern "C" fn plugin_free(stemmer: *mut c_void) {
+    if !stemmer.is_null() {
+        // SAFETY: the stemmer came from Box::into_raw in `plugin_create`
+        drop(unsafe { Box::from_raw(stemmer as *mut Handle) });
+    }
+}
+
+#[cfg(unix)]
+const RTLD_NOW: i32 = 2;
+
+#[cfg(unix)]
+#[cfg_attr(target_os = "linux", link(name = "dl"))]
+extern "C" {
+    fn dlopen(filename: *const c_char, flags: i32) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    fn dlclose(handle: *mut c_void) -> i32;
+    fn dlerror() -> *const c_char;
+}
+
+/// The message of the last `dlopen` or `dlsym` error
+#[cfg(unix)]
+fn dl_error() -> String {
+    // SAFETY: dlerror returns null or a NUL-terminated message
+    let message = unsafe { dlerror() };
+    if message.is_null() {
+        return "unknown error".to_string();
+    }
+    // SAFETY: checked for null above
+    unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned()
+}
+
+/// A loaded plugin, which stays loaded as long as this value and the
+/// stemmers made from it live
+#[derive(Debug)]
+pub struct Plugin {
+    table: *const StemmerPlugin,
+    /// The `dlopen` handle, or null for [`Plugin::builtin`]
+    library: *mut c_void,
+}
+
+impl Plugin {
+    /// This crate's own stemmers, through the plugin interface
+    pub fn builtin() -> Plugin {
+        Plugin { table: &PLUGIN, library: ptr::null_mut() }
+    }
+
+    /// Opens the shared library at `path` and takes its plugin table
+    #[cfg(unix)]
+    pub fn load(path: impl AsRef<std::path::Path>) -> io::Result<Plugin> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = path.as_ref();
+        let fail = |message: String| io::Error::other(format!("{}: {}", path.display(), message));
+        let name = std::ffi::CString::new(path.as_os_str().as_bytes()).map_err(|e| fail(e.to_string()))?;
+        // SAFETY: loading a library runs its initializers, which a plugin is
+        // trusted to have
+        let library = unsafe { dlopen(name.as_ptr(), RTLD_NOW) };
+        if library.is_null() {
+            return Err(fail(dl_error()));
+        }
+        // The handle is closed if anything below fails
+        let mut plugin = Plugin { table: ptr::null(), library };
+        let symbol = std::ffi::CString::new(PLUGIN_SYMBOL).expect("no NUL in the symbol");
+        // SAFETY: a symbol lookup in the library just opened
+        let function = unsafe { dlsym(library, symbol.as_ptr()) };
+        if function.is_null() {
+            return Err(fail(dl_error()));
+        }
+        // SAFETY: a plugin exports `porterstem_plugin` with this signature
+        let function: extern "C" fn() -> *const StemmerPlugin = unsafe { std::mem::transmute(function) };
+        let table = function();
+        if table.is_null() {
+            return Err(fail("the plugin returned no table".to_string()));
+        }
+        // SAFETY: every version of the table starts with its version
+        let version = unsafe { (*table).abi_version };
+        if version != PLUGIN_ABI_VERSION {
+            return Err(fail(format!("plugin ABI version {}, expected {}", version, PLUGIN_ABI_VERSION)));
+        }
+        plugin.table = table;
+        Ok(plugin)
+    }
+
+    fn table(&self) -> &StemmerPlugin {
+        // SAFETY: the table lives as long as the library, which `self` keeps
+        // loaded
+        unsafe { &*self.table }
+    }
+
+    /// The string at `s`, from the plugin
+    fn string(&self, s: *const c_char) -> &str {
+        if s.is_null() {
+            return "";
+        }
+        // SAFETY: the plugin's strings are NUL-terminated and live as long
+        // as the library
+        unsafe { CStr::from_ptr(s) }.to_str().unwrap_or_default()
+    }
+
+    /// The plugin's version
+    pub fn version(&self) -> &str {
+        self.string(self.table().version)
+    }
+
+    /// The names of the stemmers the plugin offers
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.string(self.table().names).split_whitespace()
+    }
+
+    /// Makes the stemmer called `name`, or returns None if the plugin has
+    /// none by that name
+    pub fn stemmer(&self, name: &str) -> Option<PluginStemmer<'_>> {
+        // SAFETY: `name` is `len` readable bytes
+        let handle = unsafe { (self.table().create)(name.as_ptr(), name.len()) };
+        (!handle.is_null()).then_some(PluginStemmer { plugin: self, handle })
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if !self.library.is_null() {
+            // SAFETY: no stemmer outlives the plugin, and the table is not
+            // used after this
+            unsafe { dlclose(self.library) };
+        }
+    }
+}
+
+/// A stemmer made by a plugin
+#[derive(Debug)]
+pub struct PluginStemmer<'p> {
+    plugin: &'p Plugin,
+    handle: *mut c_void,
+}
+
+impl Stemmer for PluginStemmer<'_> {
+    /// Returns the plugin's stem of `word`, or `word` itself if the plugin
+    /// failed to stem it
+    fn stem(&mut self, word: &str) -> String {
+        let mut len = 0;
+        // SAFETY: the handle came from this plugin's `create` and is used by
+        // this value alone
+        let stem = unsafe { (self.plugin.table().stem)(self.handle, word.as_ptr(), word.len(), &mut len) };
+        if stem.is_null() {
+            return word.to_string();
+        }
+        // SAFETY: the plugin returned `len` bytes, valid until the next call
+        String::from_utf8_lossy(unsafe { std::slice::from_raw_parts(stem, len) }).into_owned()
+    }
+}
+
+impl Drop for PluginStemmer<'_> {
+    fn drop(&mut self) {
+        // SAFETY: the handle came from this plugin's `create` and is freed
+        // once
+        unsafe { (self.plugin.table().free)(self.handle) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin() {
+        let plugin = Plugin::builtin();
+        assert_eq!(plugin.version(), env!("CARGO_PKG_VERSION"));
+        let algorithms = Algorithm::ALL.iter().map(|a| a.name());
+        let languages = Language::ALL.iter().filter(|&&l| l != Language::English).map(|l| l.code());
+        assert!(plugin.names().eq(algorithms.chain(languages)));
+        for name in plugin.names() {
+            assert!(plugin.stemmer(name).is_some(), "{}", name);
+        }
+
+        let mut stemmer = plugin.stemmer("porter").unwrap();
+        assert_eq!(stemmer.stem("Connections"), "connect");
+        assert!(stemmer.same_stem("hopping", "hops"));
+        let mut french = plugin.stemmer("french").unwrap();
+        assert_eq!(french.stem("continuellement"), "continuel");
+        assert!(plugin.stemmer("").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_errors() {
+        let error = Plugin::load("/nonexistent/libplugin.so").unwrap_err();
+        assert!(error.to_string().starts_with("/nonexistent/libplugin.so: "), "{}", error);
+        // A library that is not a plugin
+        let libc = ["/lib/x86_64-linux-gnu/libc.so.6", "/usr/lib/libSystem.B.dylib"];
+        if let Some(path) = libc.iter().find(|path| std::path::Path::new(path).exists()) {
+            assert!(Plugin::load(path).unwrap_err().to_string().contains(PLUGIN_SYMBOL));
+        }
+    }
+}
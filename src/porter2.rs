@@ -0,0 +1,321 @@
+//! # Porter2 (Snowball English)
+//!
+//! Martin Porter's revised English stemmer, as published by the Snowball
+//! project (<https://snowballstem.org/algorithms/english/stemmer.html>).
+//! It fixes a number of quirks of the original algorithm:
+//!
+//! - R1 and R2 regions replace the `m() > n` measure, with special cases for
+//!   the prefixes "gener", "commun" and "arsen" ("generously" -> "generous"
+//!   instead of "gener")
+//! - `-ly` adverbs are handled ("fairly" -> "fair")
+//! - a small table of exceptional forms ("skies" -> "sky", "news" -> "news")
+//! - "y" is a consonant after a vowel ("saying" -> "say")
+//!
+//! The implementation follows the Snowball source step by step; the routine
+//! names match the steps of the published description.
+
+use crate::fold;
+use crate::stemmer::Stemmer;
+
+/// Words stemmed by table lookup before the algorithm runs
+const EXCEPTIONS: &[(&str, &str)] = &[
+    ("andes", "andes"), ("atlas", "atlas"), ("bias", "bias"), ("cosmos", "cosmos"),
+    ("dying", "die"), ("early", "earli"), ("gently", "gentl"), ("howe", "howe"),
+    ("idly", "idl"), ("lying", "lie"), ("news", "news"), ("only", "onli"),
+    ("singly", "singl"), ("skies", "sky"), ("skis", "ski"), ("sky", "sky"),
+    ("tying", "tie"), ("ugly", "ugli"),
+];
+
+/// Words left alone after step 1a
+const INVARIANT_AFTER_1A: &[&str] = &[
+    "canning", "earring", "exceed", "herring", "inning", "outing", "proceed", "succeed",
+];
+
+/// Prefixes whose end marks R1, instead of the usual vowel/consonant rule
+const R1_PREFIXES: &[&str] = &["gener", "commun", "arsen"];
+
+/// Letters that `li` may follow for it to be removed in step 2
+const VALID_LI: &[char] = &['c', 'd', 'e', 'g', 'h', 'k', 'm', 'n', 'r', 't'];
+
+/// Vowels; a `Y` (a "y" acting as a consonant) is not one
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+/// A Porter2 (Snowball English) stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::Porter2Stemmer;
+/// let mut stemmer = Porter2Stemmer::new();
+/// assert_eq!(stemmer.stem("generously"), "generous");
+/// assert_eq!(stemmer.stem("fairly"), "fair");
+/// assert_eq!(stemmer.stem("running"), "run");
+/// ```
+#[derive(Debug, Default)]
+pub struct Porter2Stemmer {
+    /// Word being stemmed; consonant "y"s are held as `Y`
+    buffer: Vec<char>,
+    /// Start of region R1
+    p1: usize,
+    /// Start of region R2
+    p2: usize,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl Porter2Stemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        Porter2Stemmer { buffer: Vec::new(), p1: 0, p2: 0, fold_diacritics: false }
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        let word = word.to_lowercase();
+
+        if let Ok(i) = EXCEPTIONS.binary_search_by(|&(w, _)| w.cmp(&word)) {
+            return EXCEPTIONS[i].1.to_string();
+        }
+        if word.chars().count() < 3 { return word; }
+
+        self.buffer.clear();
+        self.buffer.extend(word.chars());
+        self.prelude();
+        self.mark_regions();
+        self.step_1a();
+
+        let whole: String = self.buffer.iter().collect();
+        if !INVARIANT_AFTER_1A.contains(&whole.as_str()) {
+            self.step_1b();
+            self.step_1c();
+            self.step_2();
+            self.step_3();
+            self.step_4();
+            self.step_5();
+        }
+
+        self.buffer.iter().map(|&c| if c == 'Y' { 'y' } else { c }).collect()
+    }
+
+    fn ends_with(&self, suffix: &str) -> bool {
+        let n = suffix.chars().count();
+        n <= self.buffer.len() && self.buffer[self.buffer.len() - n..].iter().copied().eq(suffix.chars())
+    }
+
+    /// The longest of `suffixes` the word ends with, and where it starts
+    fn longest_suffix<'s>(&self, suffixes: &[&'s str]) -> Option<(&'s str, usize)> {
+        suffixes
+            .iter()
+            .filter(|s| self.ends_with(s))
+            .max_by_key(|s| s.len())
+            .map(|&s| (s, self.buffer.len() - s.chars().count()))
+    }
+
+    /// Replaces everything from `start` on with `with`
+    fn replace_from(&mut self, start: usize, with: &str) {
+        self.buffer.truncate(start);
+        self.buffer.extend(with.chars());
+    }
+
+    /// Returns true if the letter before `i` exists and is one of `letters`
+    fn preceded_by(&self, i: usize, letters: &[char]) -> bool {
+        i > 0 && letters.contains(&self.buffer[i - 1])
+    }
+
+    /// Returns true if the word up to `end` ends in a short syllable
+    fn ends_short_syllable(&self, end: usize) -> bool {
+        let b = &self.buffer;
+        if end >= 3 {
+            !is_vowel(b[end - 1]) && !matches!(b[end - 1], 'w' | 'x' | 'Y') && is_vowel(b[end - 2]) && !is_vowel(b[end - 3])
+        } else {
+            end == 2 && !is_vowel(b[1]) && is_vowel(b[0])
+        }
+    }
+
+    /// Drops a leading apostrophe and marks consonant "y"s as `Y`
+    fn prelude(&mut self) {
+        if self.buffer[0] == '\'' { self.buffer.remove(0); }
+        if self.buffer.first() == Some(&'y') { self.buffer[0] = 'Y'; }
+        for i in 1..self.buffer.len() {
+            if self.buffer[i] == 'y' && is_vowel(self.buffer[i - 1]) { self.buffer[i] = 'Y'; }
+        }
+    }
+
+    /// Sets R1 and R2: each starts after the first non-vowel following a
+    /// vowel in the region before it
+    fn mark_regions(&mut self) {
+        let len = self.buffer.len();
+        self.p1 = len;
+        self.p2 = len;
+        let after_syllable = |from: usize| {
+            let vowel = (from..len).find(|&i| is_vowel(self.buffer[i]))?;
+            (vowel + 1..len).find(|&i| !is_vowel(self.buffer[i])).map(|i| i + 1)
+        };
+
+        let prefix = R1_PREFIXES.iter().find(|p| self.buffer.iter().copied().take(p.len()).eq(p.chars()));
+        let Some(p1) = prefix.map(|p| p.len()).or_else(|| after_syllable(0)) else { return };
+        self.p1 = p1;
+        if let Some(p2) = after_syllable(p1) { self.p2 = p2; }
+    }
+
+    /// Step 1a: apostrophes and plurals ("ponies" -> "poni", "gaps" -> "gap")
+    fn step_1a(&mut self) {
+        if let Some((_, start)) = self.longest_suffix(&["'", "'s", "'s'"]) {
+            self.buffer.truncate(start);
+        }
+        match self.longest_suffix(&["sses", "ied", "ies", "s", "us", "ss"]) {
+            Some(("sses", start)) => self.replace_from(start, "ss"),
+            Some(("ied" | "ies", start)) => self.replace_from(start, if start >= 2 { "i" } else { "ie" }),
+            // Delete if a vowel occurs before the letter preceding the s
+            Some(("s", start)) if start >= 1 && self.buffer[..start - 1].iter().any(|&c| is_vowel(c)) => {
+                self.buffer.truncate(start);
+            },
+            _ => {}
+        }
+    }
+
+    /// Step 1b: past tenses and gerunds ("hoped" -> "hope", "hopping" -> "hop")
+    fn step_1b(&mut self) {
+        match self.longest_suffix(&["eed", "eedly", "ed", "edly", "ing", "ingly"]) {
+            Some(("eed" | "eedly", start)) if start >= self.p1 => self.replace_from(start, "ee"),
+            Some(("eed" | "eedly", _)) => {},
+            Some((_, start)) => {
+                if !self.buffer[..start].iter().any(|&c| is_vowel(c)) { return; }
+                self.buffer.truncate(start);
+                if self.longest_suffix(&["at", "bl", "iz"]).is_some() {
+                    self.buffer.push('e');
+                } else if self.longest_suffix(&["bb", "dd", "ff", "gg", "mm", "nn", "pp", "rr", "tt"]).is_some() {
+                    self.buffer.pop();
+                } else if self.buffer.len() == self.p1 && self.ends_short_syllable(self.buffer.len()) {
+                    self.buffer.push('e');
+                }
+            },
+            None => {}
+        }
+    }
+
+    /// Step 1c: a final "y" after a consonant becomes "i" ("cry" -> "cri")
+    fn step_1c(&mut self) {
+        let len = self.buffer.len();
+        if len > 2 && matches!(self.buffer[len - 1], 'y' | 'Y') && !is_vowel(self.buffer[len - 2]) {
+            self.buffer[len - 1] = 'i';
+        }
+    }
+
+    /// Step 2: double suffixes in R1 ("relational" -> "relate")
+    fn step_2(&mut self) {
+        const SUFFIXES: &[(&str, &str)] = &[
+            ("tional", "tion"), ("enci", "ence"), ("anci", "ance"), ("abli", "able"),
+            ("entli", "ent"), ("izer", "ize"), ("ization", "ize"), ("ational", "ate"),
+            ("ation", "ate"), ("ator", "ate"), ("alism", "al"), ("aliti", "al"),
+            ("alli", "al"), ("fulness", "ful"), ("ousli", "ous"), ("ousness", "ous"),
+            ("iveness", "ive"), ("iviti", "ive"), ("biliti", "ble"), ("bli", "ble"),
+            ("ogi", "og"), ("fulli", "ful"), ("lessli", "less"), ("li", ""),
+        ];
+        let keys: Vec<&str> = SUFFIXES.iter().map(|&(s, _)| s).collect();
+        let Some((suffix, start)) = self.longest_suffix(&keys) else { return };
+        if start < self.p1 { return; }
+        let with = SUFFIXES.iter().find(|&&(s, _)| s == suffix).map_or("", |&(_, w)| w);
+        match suffix {
+            "ogi" if !self.preceded_by(start, &['l']) => {},
+            "li" if !self.preceded_by(start, VALID_LI) => {},
+            _ => self.replace_from(start, with),
+        }
+    }
+
+    /// Step 3: more suffixes in R1 ("hopeful" -> "hope")
+    fn step_3(&mut self) {
+        let suffixes = ["tional", "ational", "alize", "icate", "iciti", "ical", "ful", "ness", "ative"];
+        let Some((suffix, start)) = self.longest_suffix(&suffixes) else { return };
+        if start < self.p1 { return; }
+        match suffix {
+            "tional" => self.replace_from(start, "tion"),
+            "ational" => self.replace_from(start, "ate"),
+            "alize" => self.replace_from(start, "al"),
+            "icate" | "iciti" | "ical" => self.replace_from(start, "ic"),
+            "ful" | "ness" => self.buffer.truncate(start),
+            _ => if start >= self.p2 { self.buffer.truncate(start) },
+        }
+    }
+
+    /// Step 4: single suffixes in R2 ("adjustment" -> "adjust")
+    fn step_4(&mut self) {
+        let suffixes = [
+            "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent",
+            "ism", "ate", "iti", "ous", "ive", "ize", "ion",
+        ];
+        let Some((suffix, start)) = self.longest_suffix(&suffixes) else { return };
+        if start < self.p2 { return; }
+        if suffix != "ion" || self.preceded_by(start, &['s', 't']) {
+            self.buffer.truncate(start);
+        }
+    }
+
+    /// Step 5: a final "e", or "l" after "l" ("hope" -> "hope", "controll" -> "control")
+    fn step_5(&mut self) {
+        let Some(&last) = self.buffer.last() else { return };
+        let start = self.buffer.len() - 1;
+        let delete = match last {
+            'e' => start >= self.p2 || (start >= self.p1 && !self.ends_short_syllable(start)),
+            'l' => start >= self.p2 && self.preceded_by(start, &['l']),
+            _ => false,
+        };
+        if delete { self.buffer.pop(); }
+    }
+}
+
+impl Stemmer for Porter2Stemmer {
+    fn stem(&mut self, word: &str) -> String {
+        Porter2Stemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_examples() {
+        let mut stemmer = Porter2Stemmer::new();
+        let cases = [
+            ("consign", "consign"), ("consigned", "consign"), ("consigning", "consign"),
+            ("consignment", "consign"), ("consist", "consist"), ("consisted", "consist"),
+            ("consistency", "consist"), ("consistent", "consist"), ("consistently", "consist"),
+            ("consisting", "consist"), ("consists", "consist"), ("consolation", "consol"),
+            ("generously", "generous"), ("saying", "say"), ("skies", "sky"), ("dying", "die"),
+            ("hoped", "hope"), ("hopping", "hop"), ("cried", "cri"), ("ties", "tie"),
+            ("gas", "gas"), ("gaps", "gap"), ("'tis", "tis"), ("dog's", "dog"),
+            ("controll", "control"), ("proceeding", "proceed"), ("by", "by"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_exceptions_are_sorted() {
+        assert!(EXCEPTIONS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_snowball_vocabulary() {
+        // The vocabulary and expected output of the Snowball project's test
+        // data (<https://github.com/snowballstem/snowball-data>)
+        let mut stemmer = Porter2Stemmer::new();
+        let words = include_str!("../test_data/english/voc.txt");
+        let stems = include_str!("../test_data/english/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+    }
+}
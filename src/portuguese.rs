@@ -0,0 +1,256 @@
+//! # Portuguese (Snowball)
+//!
+//! The Snowball Portuguese stemmer
+//! (<https://snowballstem.org/algorithms/portuguese/stemmer.html>), which
+//! covers both European and Brazilian spelling. The nasalized vowels "ã"
+//! and "õ" are written "a~" and "o~" while the steps run, so that "~"
+//! counts as a consonant when the regions are found, and are put back at
+//! the end: "-ção" and "-ções" are matched as "-ça~o" and "-ço~es".
+//!
+//! Besides R1 and R2 it uses the region RV: after the next vowel if the
+//! second letter is a consonant, after the next consonant if the word
+//! starts with two vowels, and after the third letter otherwise.
+
+use crate::fold;
+use crate::snowball::{ends_with, find_suffix, regions, replace_from, rv_region};
+use crate::stemmer::Stemmer;
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'á' | 'é' | 'í' | 'ó' | 'ú' | 'â' | 'ê' | 'ô')
+}
+
+const STANDARD_SUFFIXES: &[&str] = &[
+    "eza", "ezas", "ico", "ica", "icos", "icas", "ismo", "ismos", "ável", "ível", "ista", "istas",
+    "oso", "osa", "osos", "osas", "amento", "amentos", "imento", "imentos", "adora", "ador",
+    "aça~o", "adoras", "adores", "aço~es", "ante", "antes", "ância", "logia", "logias",
+    "uça~o", "uço~es", "ência", "ências", "amente", "mente", "idade", "idades",
+    "iva", "ivo", "ivas", "ivos", "ira", "iras",
+];
+
+const VERB_SUFFIXES: &[&str] = &[
+    "ada", "ida", "ia", "aria", "eria", "iria", "ará", "ara", "erá", "era", "irá", "ava", "asse",
+    "esse", "isse", "aste", "este", "iste", "ei", "arei", "erei", "irei", "am", "iam", "ariam",
+    "eriam", "iriam", "aram", "eram", "iram", "avam", "em", "arem", "erem", "irem", "assem",
+    "essem", "issem", "ado", "ido", "ando", "endo", "indo", "ara~o", "era~o", "ira~o", "ar", "er",
+    "ir", "as", "adas", "idas", "ias", "arias", "erias", "irias", "arás", "aras", "erás", "eras",
+    "irás", "avas", "es", "ardes", "erdes", "irdes", "ares", "eres", "ires", "asses", "esses",
+    "isses", "astes", "estes", "istes", "is", "ais", "eis", "íeis", "aríeis", "eríeis", "iríeis",
+    "áreis", "areis", "éreis", "ereis", "íreis", "ireis", "ásseis", "ésseis", "ísseis", "áveis",
+    "ados", "idos", "ámos", "amos", "íamos", "aríamos", "eríamos", "iríamos", "áramos", "éramos",
+    "íramos", "ávamos", "emos", "aremos", "eremos", "iremos", "ássemos", "êssemos", "íssemos",
+    "imos", "armos", "ermos", "irmos", "eu", "iu", "ou", "ira", "iras",
+];
+
+/// A Snowball Portuguese stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::PortugueseStemmer;
+/// let mut stemmer = PortugueseStemmer::new();
+/// assert_eq!(stemmer.stem("avaliações"), "avali");
+/// assert_eq!(stemmer.stem("produtos"), "produt");
+/// assert_eq!(stemmer.stem("recomendo"), "recom");
+/// ```
+#[derive(Debug, Default)]
+pub struct PortugueseStemmer {
+    /// Word being stemmed
+    buffer: Vec<char>,
+    /// Start of region RV
+    pv: usize,
+    /// Start of region R1
+    p1: usize,
+    /// Start of region R2
+    p2: usize,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl PortugueseStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        PortugueseStemmer { buffer: Vec::new(), pv: 0, p1: 0, p2: 0, fold_diacritics: false }
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    ///
+    /// Folding loses the nasal vowels and the accents of many verb endings,
+    /// so it is only useful for text typed without accents.
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        self.buffer.clear();
+        for c in word.chars().flat_map(char::to_lowercase) {
+            match c {
+                'ã' => self.buffer.extend(['a', '~']),
+                'õ' => self.buffer.extend(['o', '~']),
+                c => self.buffer.push(c),
+            }
+        }
+
+        self.pv = rv_region(&self.buffer, is_vowel);
+        (self.p1, self.p2) = regions(&self.buffer, is_vowel);
+        if self.standard_suffix() || self.verb_suffix() {
+            // "-ci" loses its "i" if the "i" is in RV
+            if ends_with(&self.buffer, "ci") && self.buffer.len() > self.pv {
+                self.buffer.pop();
+            }
+        } else {
+            self.residual_suffix();
+        }
+        self.residual_form();
+
+        let mut stem = String::with_capacity(self.buffer.len());
+        for (i, &c) in self.buffer.iter().enumerate() {
+            match (c, self.buffer.get(i + 1)) {
+                ('a', Some('~')) => stem.push('ã'),
+                ('o', Some('~')) => stem.push('õ'),
+                ('~', _) if i > 0 && matches!(self.buffer[i - 1], 'a' | 'o') => {},
+                (c, _) => stem.push(c),
+            }
+        }
+        stem
+    }
+
+    fn standard_suffix(&mut self) -> bool {
+        let Some((suffix, start)) = find_suffix(&self.buffer, STANDARD_SUFFIXES, 0) else { return false };
+        let r2 = start >= self.p2;
+        match suffix {
+            "logia" | "logias" => {
+                if !r2 { return false; }
+                replace_from(&mut self.buffer, start, "log");
+            },
+            "uça~o" | "uço~es" => {
+                if !r2 { return false; }
+                replace_from(&mut self.buffer, start, "u");
+            },
+            "ência" | "ências" => {
+                if !r2 { return false; }
+                replace_from(&mut self.buffer, start, "ente");
+            },
+            "amente" => {
+                if start < self.p1 { return false; }
+                self.buffer.truncate(start);
+                match find_suffix(&self.buffer, &["iv", "os", "ic", "ad"], 0) {
+                    Some(("iv", s)) if s >= self.p2 => {
+                        self.buffer.truncate(s);
+                        if ends_with(&self.buffer, "at") && s - 2 >= self.p2 { self.buffer.truncate(s - 2); }
+                    },
+                    Some((_, s)) if s >= self.p2 => self.buffer.truncate(s),
+                    _ => {},
+                }
+            },
+            "mente" => {
+                if !r2 { return false; }
+                self.buffer.truncate(start);
+                if let Some((_, s)) = find_suffix(&self.buffer, &["ante", "avel", "ível"], self.p2) {
+                    self.buffer.truncate(s);
+                }
+            },
+            "idade" | "idades" => {
+                if !r2 { return false; }
+                self.buffer.truncate(start);
+                if let Some((_, s)) = find_suffix(&self.buffer, &["abil", "ic", "iv"], self.p2) {
+                    self.buffer.truncate(s);
+                }
+            },
+            "iva" | "ivo" | "ivas" | "ivos" => {
+                if !r2 { return false; }
+                self.buffer.truncate(start);
+                if ends_with(&self.buffer, "at") && start - 2 >= self.p2 { self.buffer.truncate(start - 2); }
+            },
+            "ira" | "iras" => {
+                // "-eira" and "-eiras" are usually not verbs
+                if start < self.pv || start == 0 || self.buffer[start - 1] != 'e' { return false; }
+                replace_from(&mut self.buffer, start, "ir");
+            },
+            _ => {
+                if !r2 { return false; }
+                self.buffer.truncate(start);
+            },
+        }
+        true
+    }
+
+    fn verb_suffix(&mut self) -> bool {
+        let Some((_, start)) = find_suffix(&self.buffer, VERB_SUFFIXES, self.pv) else { return false };
+        self.buffer.truncate(start);
+        true
+    }
+
+    fn residual_suffix(&mut self) {
+        if let Some((_, start)) = find_suffix(&self.buffer, &["os", "a", "i", "o", "á", "í", "ó"], self.pv) {
+            self.buffer.truncate(start);
+        }
+    }
+
+    fn residual_form(&mut self) {
+        match self.buffer.last() {
+            Some('e' | 'é' | 'ê') => {
+                let start = self.buffer.len() - 1;
+                if start < self.pv { return; }
+                self.buffer.pop();
+                // "-gue" and "-cie" also lose the "u" or "i", if it is in RV
+                if (ends_with(&self.buffer, "gu") || ends_with(&self.buffer, "ci")) && start > self.pv {
+                    self.buffer.pop();
+                }
+            },
+            Some('ç') => *self.buffer.last_mut().unwrap() = 'c',
+            _ => {},
+        }
+    }
+}
+
+impl Stemmer for PortugueseStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        PortugueseStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_examples() {
+        let mut stemmer = PortugueseStemmer::new();
+        let cases = [
+            ("boa", "boa"), ("boainain", "boainain"), ("boas", "boas"), ("bôas", "bôas"),
+            ("boassu", "boassu"), ("boataria", "boat"), ("boate", "boat"), ("boates", "boat"),
+            ("boatos", "boat"), ("bob", "bob"), ("boba", "bob"), ("bobagem", "bobag"),
+            ("bobagens", "bobagens"), ("bobalhões", "bobalhõ"), ("bobear", "bob"),
+            ("bobeira", "bobeir"), ("bobinho", "bobinh"), ("bobinhos", "bobinh"), ("bobo", "bob"),
+            ("bobs", "bobs"), ("boca", "boc"), ("bocadas", "boc"), ("bocadinho", "bocadinh"),
+            ("bocado", "boc"), ("bocaiúva", "bocaiúv"), ("boçal", "boçal"), ("bocarra", "bocarr"),
+            ("bocas", "boc"), ("bode", "bod"), ("bodoque", "bodoqu"), ("body", "body"),
+            ("boeing", "boeing"), ("boem", "boem"), ("boemia", "boem"), ("boêmio", "boêmi"),
+            ("boêmios", "boêmi"), ("bogotá", "bogot"), ("boi", "boi"), ("bóia", "bói"),
+            ("boiando", "boi"), ("avaliações", "avali"), ("ã", "ã"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_snowball_vocabulary() {
+        // The vocabulary and expected output of the Snowball project's test
+        // data (<https://github.com/snowballstem/snowball-data>)
+        let mut stemmer = PortugueseStemmer::new();
+        let words = include_str!("../test_data/portuguese/voc.txt");
+        let stems = include_str!("../test_data/portuguese/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+    }
+}
@@ -0,0 +1,154 @@
+//! Progress reports of the command line front end
+//!
+//! Input files are read through [`Counted`], which adds the bytes read to a
+//! process-wide counter and counts a file as done when it reaches its end.
+//! While a [`Reporter`] is alive a thread prints the files done, the
+//! megabytes read, the throughput and the time left to stderr, overwriting
+//! one line on a terminal and printing a line every few seconds otherwise.
+//! Nothing is printed during the first second, so quick runs stay silent.
+
+use std::io::{self, IsTerminal, Read, Write};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+static BYTES_READ: AtomicU64 = AtomicU64::new(0);
+static FILES_DONE: AtomicUsize = AtomicUsize::new(0);
+
+/// Time before the first report, and between reports on a terminal and
+/// elsewhere
+const FIRST_REPORT: Duration = Duration::from_secs(1);
+const TERMINAL_INTERVAL: Duration = Duration::from_millis(250);
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A reader of an input file that counts the bytes read from it
+pub struct Counted<R> {
+    inner: R,
+    done: bool,
+}
+
+impl<R> Counted<R> {
+    pub fn new(inner: R) -> Self {
+        Counted { inner, done: false }
+    }
+}
+
+impl<R: Read> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        BYTES_READ.fetch_add(n as u64, Ordering::Relaxed);
+        if n == 0 && !buf.is_empty() && !self.done {
+            self.done = true;
+            FILES_DONE.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(n)
+    }
+}
+
+/// Counts a file read other than through [`Counted`], such as a mapped one
+#[cfg(all(feature = "mmap", unix))]
+pub fn file_done(len: u64) {
+    BYTES_READ.fetch_add(len, Ordering::Relaxed);
+    FILES_DONE.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The total size of the input, for the percentage and the time left
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Totals {
+    pub files: usize,
+    /// Bytes in all the files, or None if one of them is stdin
+    pub bytes: Option<u64>,
+}
+
+/// Returns the status line for `files` files and `bytes` bytes read after
+/// `elapsed`
+pub fn status(totals: Totals, files: usize, bytes: u64, elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs_f64().max(1e-3);
+    let rate = bytes as f64 / seconds;
+    let mut line = format!("porterstem: {}/{} files, {:.1}", files, totals.files, bytes as f64 / 1e6);
+    match totals.bytes {
+        Some(total) => {
+            line += &format!(" of {:.1} MB, {:.1} MB/s", total as f64 / 1e6, rate / 1e6);
+            if rate > 0.0 && bytes < total {
+                let left = ((total - bytes) as f64 / rate).ceil() as u64;
+                line += &format!(", ETA {}:{:02}", left / 60, left % 60);
+            }
+        },
+        None => line += &format!(" MB, {:.1} MB/s", rate / 1e6),
+    }
+    line
+}
+
+/// Prints progress reports to stderr until dropped
+pub struct Reporter {
+    stop: Sender<()>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Reporter {
+    /// Starts reporting on the reading of an input of the given size,
+    /// counting from zero
+    pub fn start(totals: Totals) -> Self {
+        BYTES_READ.store(0, Ordering::Relaxed);
+        FILES_DONE.store(0, Ordering::Relaxed);
+        let (stop, stopped) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            let terminal = io::stderr().is_terminal();
+            let start = Instant::now();
+            let report = |last: bool| {
+                let (files, bytes) = (FILES_DONE.load(Ordering::Relaxed), BYTES_READ.load(Ordering::Relaxed));
+                let line = status(totals, files, bytes, start.elapsed());
+                // Reports are a courtesy: a closed stderr is no reason to stop
+                let _ = match (terminal, last) {
+                    (true, false) => write!(io::stderr(), "\r{}\x1b[K", line),
+                    (true, true) => writeln!(io::stderr(), "\r{}\x1b[K", line),
+                    (false, _) => writeln!(io::stderr(), "{}", line),
+                };
+            };
+            let mut wait = FIRST_REPORT;
+            let mut reported = false;
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(wait) {
+                report(false);
+                reported = true;
+                wait = if terminal { TERMINAL_INTERVAL } else { LOG_INTERVAL };
+            }
+            if reported {
+                report(true);
+            }
+        });
+        Reporter { stop, thread: Some(thread) }
+    }
+}
+
+impl Drop for Reporter {
+    fn drop(&mut self) {
+        let _ = self.stop.send(());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let totals = Totals { files: 4, bytes: Some(40_000_000) };
+        assert_eq!(
+            status(totals, 1, 10_000_000, Duration::from_secs(2)),
+            "porterstem: 1/4 files, 10.0 of 40.0 MB, 5.0 MB/s, ETA 0:06"
+        );
+        let done = status(totals, 4, 40_000_000, Duration::from_secs(8));
+        assert_eq!(done, "porterstem: 4/4 files, 40.0 of 40.0 MB, 5.0 MB/s");
+        let stdin = Totals { files: 1, bytes: None };
+        assert_eq!(status(stdin, 0, 3_000_000, Duration::from_secs(1)), "porterstem: 0/1 files, 3.0 MB, 3.0 MB/s");
+
+        let (files, bytes) = (FILES_DONE.load(Ordering::Relaxed), BYTES_READ.load(Ordering::Relaxed));
+        let mut text = String::new();
+        Counted::new(&b"ponies"[..]).read_to_string(&mut text).unwrap();
+        assert!(FILES_DONE.load(Ordering::Relaxed) > files && BYTES_READ.load(Ordering::Relaxed) >= bytes + 6);
+    }
+}
@@ -0,0 +1,256 @@
+//! # Romanian (Snowball)
+//!
+//! The Snowball Romanian stemmer
+//! (<https://snowballstem.org/algorithms/romanian/stemmer.html>). Plural
+//! and article endings are normalized first ("-ului", "-elor"), then
+//! compound derivational suffixes are reduced ("-abilitate" to "-abil")
+//! for as long as there are any, and finally one standard suffix, or else
+//! a verb ending, and a final vowel are removed.
+//!
+//! The tables spell "ş" and "ţ" with a cedilla, as the Snowball source
+//! does; the comma-below letters "ș" and "ț" of modern Romanian text are
+//! read as their cedilla forms, so stems are spelled with the cedilla. A
+//! "u" or "i" between vowels is marked as a consonant before the steps run.
+
+use crate::fold;
+use crate::snowball::{find_group, find_suffix, regions, replace_from, rv_region};
+use crate::stemmer::Stemmer;
+
+/// Vowels; the marked consonants `I` and `U` are not among them
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'â' | 'î' | 'ă')
+}
+
+/// Plural and article endings in R1, with what replaces them
+const STEP_0_SUFFIXES: &[(&[&str], &str)] = &[
+    (&["ul", "ului"], ""),
+    (&["aua"], "a"),
+    (&["ea", "ele", "elor"], "e"),
+    (&["ii", "iua", "iei", "iile", "iilor", "ilor"], "i"),
+    (&["ile"], "ile"),
+    (&["atei"], "at"),
+    (&["aţie", "aţia"], "aţi"),
+];
+
+/// Compound suffixes in R1, with the simpler suffix that replaces them
+const COMBO_SUFFIXES: &[(&[&str], &str)] = &[
+    (&["abilitate", "abilitati", "abilităi", "abilităţi"], "abil"),
+    (&["ibilitate"], "ibil"),
+    (&["ivitate", "ivitati", "ivităi", "ivităţi"], "iv"),
+    (
+        &[
+            "icitate", "icitati", "icităi", "icităţi", "icator", "icatori", "iciv", "iciva", "icive",
+            "icivi", "icivă", "ical", "icala", "icale", "icali", "icală",
+        ],
+        "ic",
+    ),
+    (&["ativ", "ativa", "ative", "ativi", "ativă", "aţiune", "atoare", "ator", "atori", "ătoare", "ător", "ători"], "at"),
+    (&["itiv", "itiva", "itive", "itivi", "itivă", "iţiune", "itoare", "itor", "itori"], "it"),
+];
+
+/// Standard suffixes in R2, with what replaces them
+const STANDARD_SUFFIXES: &[(&[&str], &str)] = &[
+    (
+        &[
+            "at", "ata", "ată", "ati", "ate", "ut", "uta", "ută", "uti", "ute", "it", "ita", "ită", "iti",
+            "ite", "ic", "ica", "ice", "ici", "ică", "abil", "abila", "abile", "abili", "abilă", "ibil",
+            "ibila", "ibile", "ibili", "ibilă", "oasa", "oasă", "oase", "os", "osi", "oşi", "ant", "anta",
+            "ante", "anti", "antă", "ator", "atori", "itate", "itati", "ităi", "ităţi", "iv", "iva", "ive",
+            "ivi", "ivă",
+        ],
+        "",
+    ),
+    (&["iune", "iuni"], "iune"),
+    (&["ism", "isme", "ist", "ista", "iste", "isti", "istă", "işti"], "ist"),
+];
+
+/// Verb endings in RV removed after a consonant or "u"
+const VERB_SUFFIXES_CONSONANT: &[&str] = &[
+    "are", "ere", "ire", "âre", "ind", "ând", "indu", "ându", "eze", "ească", "ez", "ezi", "ează",
+    "esc", "eşti", "eşte", "ăsc", "ăşti", "ăşte", "am", "ai", "au", "eam", "eai", "ea", "eaţi", "eau",
+    "iam", "iai", "ia", "iaţi", "iau", "ui", "aşi", "arăm", "arăţi", "ară", "uşi", "urăm", "urăţi",
+    "ură", "işi", "irăm", "irăţi", "iră", "âi", "âşi", "ârăm", "ârăţi", "âră", "asem", "aseşi",
+    "ase", "aserăm", "aserăţi", "aseră", "isem", "iseşi", "ise", "iserăm", "iserăţi", "iseră",
+    "âsem", "âseşi", "âse", "âserăm", "âserăţi", "âseră", "usem", "useşi", "use", "userăm",
+    "userăţi", "useră",
+];
+
+/// Verb endings in RV removed outright
+const VERB_SUFFIXES: &[&str] = &[
+    "ăm", "aţi", "em", "eţi", "im", "iţi", "âm", "âţi", "seşi", "serăm", "serăţi", "seră", "sei",
+    "se", "sesem", "seseşi", "sese", "seserăm", "seserăţi", "seseră",
+];
+
+/// A Snowball Romanian stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::RomanianStemmer;
+/// let mut stemmer = RomanianStemmer::new();
+/// assert_eq!(stemmer.stem("frumoasele"), "frumoas");
+/// assert_eq!(stemmer.stem("posibilităţile"), "posibil");
+/// assert_eq!(stemmer.stem("posibilitățile"), "posibil");
+/// ```
+#[derive(Debug, Default)]
+pub struct RomanianStemmer {
+    /// Word being stemmed
+    buffer: Vec<char>,
+    /// Start of region RV
+    pv: usize,
+    /// Start of region R1
+    p1: usize,
+    /// Start of region R2
+    p2: usize,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl RomanianStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        RomanianStemmer { buffer: Vec::new(), pv: 0, p1: 0, p2: 0, fold_diacritics: false }
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    ///
+    /// Folding loses "ă", "â", "î", "ş" and "ţ", which most endings contain,
+    /// so it is only useful for text typed without them.
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        self.buffer.clear();
+        self.buffer.extend(word.chars().flat_map(char::to_lowercase).map(|c| match c {
+            'ș' => 'ş',
+            'ț' => 'ţ',
+            c => c,
+        }));
+
+        self.prelude();
+        self.pv = rv_region(&self.buffer, is_vowel);
+        (self.p1, self.p2) = regions(&self.buffer, is_vowel);
+        self.step_0();
+        if !self.standard_suffix() {
+            self.verb_suffix();
+        }
+        if let Some((_, start)) = find_suffix(&self.buffer, &["a", "e", "i", "ie", "ă"], 0) {
+            if start >= self.pv { self.buffer.truncate(start); }
+        }
+
+        self.buffer
+            .iter()
+            .map(|&c| match c {
+                'I' => 'i',
+                'U' => 'u',
+                c => c,
+            })
+            .collect()
+    }
+
+    /// Marks "u" and "i" between vowels as consonants by uppercasing them
+    fn prelude(&mut self) {
+        let b = &mut self.buffer;
+        for i in 1..b.len().saturating_sub(1) {
+            if is_vowel(b[i - 1]) && matches!(b[i], 'u' | 'i') && is_vowel(b[i + 1]) {
+                b[i] = b[i].to_ascii_uppercase();
+            }
+        }
+    }
+
+    fn step_0(&mut self) {
+        match find_group(&self.buffer, STEP_0_SUFFIXES, 0) {
+            Some((_, start)) if start < self.p1 => {},
+            // "-ile" is left alone after "ab"
+            Some(("ile", start)) if start >= 2 && self.buffer[start - 2..start] == ['a', 'b'] => {},
+            Some(("ile", start)) => replace_from(&mut self.buffer, start, "i"),
+            Some((with, start)) => replace_from(&mut self.buffer, start, with),
+            None => {},
+        }
+    }
+
+    /// Reduces compound suffixes, then removes a standard suffix; returns
+    /// true if either changed the word
+    fn standard_suffix(&mut self) -> bool {
+        let mut removed = false;
+        while let Some((with, start)) = find_group(&self.buffer, COMBO_SUFFIXES, 0) {
+            if start < self.p1 { break; }
+            replace_from(&mut self.buffer, start, with);
+            removed = true;
+        }
+
+        match find_group(&self.buffer, STANDARD_SUFFIXES, 0) {
+            Some((_, start)) if start < self.p2 => removed,
+            // "-ţiune" becomes "-t"
+            Some(("iune", start)) if start > 0 && self.buffer[start - 1] == 'ţ' => {
+                replace_from(&mut self.buffer, start - 1, "t");
+                true
+            },
+            Some(("iune", _)) => removed,
+            Some((with, start)) => {
+                replace_from(&mut self.buffer, start, with);
+                true
+            },
+            None => removed,
+        }
+    }
+
+    fn verb_suffix(&mut self) {
+        let found = [find_suffix(&self.buffer, VERB_SUFFIXES_CONSONANT, self.pv), find_suffix(&self.buffer, VERB_SUFFIXES, self.pv)];
+        match found {
+            [Some((_, c)), Some((_, start))] if start < c => self.buffer.truncate(start),
+            [Some((_, start)), _] => {
+                if start > self.pv && (!is_vowel(self.buffer[start - 1]) || self.buffer[start - 1] == 'u') {
+                    self.buffer.truncate(start);
+                }
+            },
+            [None, Some((_, start))] => self.buffer.truncate(start),
+            [None, None] => {},
+        }
+    }
+}
+
+impl Stemmer for RomanianStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        RomanianStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_examples() {
+        let mut stemmer = RomanianStemmer::new();
+        let cases = [
+            ("frumoasele", "frumoas"), ("frumoaselor", "frumoas"), ("frumoşi", "frumoş"),
+            ("ocupaţiile", "ocup"), ("ocupațiile", "ocup"), ("copiii", "copii"), ("abilitate", "abil"),
+            ("a", "a"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_snowball_vocabulary() {
+        // The vocabulary and expected output of the Snowball project's test
+        // data (<https://github.com/snowballstem/snowball-data>)
+        let mut stemmer = RomanianStemmer::new();
+        let words = include_str!("../test_data/romanian/voc.txt");
+        let stems = include_str!("../test_data/romanian/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+    }
+}
@@ -0,0 +1,219 @@
+//! # Russian (Snowball)
+//!
+//! The Snowball Russian stemmer
+//! (<https://snowballstem.org/algorithms/russian/stemmer.html>), for text in
+//! Cyrillic script. Endings are removed in classes: a perfective gerund, or
+//! else a reflexive "-ся" followed by an adjectival, verb or noun ending;
+//! then a final "и", the derivational "-ость" in R2, and a superlative
+//! "-ейш", a doubled "н" or a soft sign.
+//!
+//! Every step works inside the region RV, the part of the word after its
+//! first vowel. "ё" is read as "е" first, as the current Snowball release
+//! does.
+
+use crate::fold;
+use crate::snowball::{ends_with, find_suffix, regions};
+use crate::stemmer::Stemmer;
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'а' | 'е' | 'и' | 'о' | 'у' | 'ы' | 'э' | 'ю' | 'я')
+}
+
+/// Perfective gerund endings removed after "а" or "я"
+const GERUND_SUFFIXES_A: &[&str] = &["в", "вши", "вшись"];
+
+/// Perfective gerund endings removed outright
+const GERUND_SUFFIXES: &[&str] = &["ив", "ивши", "ившись", "ыв", "ывши", "ывшись"];
+
+const ADJECTIVE_SUFFIXES: &[&str] = &[
+    "ее", "ие", "ые", "ое", "ими", "ыми", "ей", "ий", "ый", "ой", "ем", "им", "ым", "ом", "его",
+    "ого", "ему", "ому", "их", "ых", "ую", "юю", "ая", "яя", "ою", "ею",
+];
+
+/// Participle endings removed after "а" or "я"
+const PARTICIPLE_SUFFIXES_A: &[&str] = &["ем", "нн", "вш", "ющ", "щ"];
+
+/// Participle endings removed outright
+const PARTICIPLE_SUFFIXES: &[&str] = &["ивш", "ывш", "ующ"];
+
+/// Verb endings removed after "а" or "я"
+const VERB_SUFFIXES_A: &[&str] = &[
+    "ла", "на", "ете", "йте", "ли", "й", "л", "ем", "н", "ло", "но", "ет", "ют", "ны", "ть", "ешь", "нно",
+];
+
+/// Verb endings removed outright
+const VERB_SUFFIXES: &[&str] = &[
+    "ила", "ыла", "ена", "ейте", "уйте", "ите", "или", "ыли", "ей", "уй", "ил", "ыл", "им", "ым",
+    "ен", "ило", "ыло", "ено", "ят", "ует", "уют", "ит", "ыт", "ены", "ить", "ыть", "ишь", "ую", "ю",
+];
+
+const NOUN_SUFFIXES: &[&str] = &[
+    "а", "ев", "ов", "ие", "ье", "е", "иями", "ями", "ами", "еи", "ии", "и", "ией", "ей", "ой",
+    "ий", "й", "иям", "ям", "ием", "ем", "ам", "ом", "о", "у", "ах", "иях", "ях", "ы", "ь", "ию",
+    "ью", "ю", "ия", "ья", "я",
+];
+
+/// A Snowball Russian stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::RussianStemmer;
+/// let mut stemmer = RussianStemmer::new();
+/// assert_eq!(stemmer.stem("Вечерние"), "вечерн");
+/// assert_eq!(stemmer.stem("книгами"), "книг");
+/// assert_eq!(stemmer.stem("прочитавшись"), "прочита");
+/// ```
+#[derive(Debug, Default)]
+pub struct RussianStemmer {
+    /// Word being stemmed
+    buffer: Vec<char>,
+    /// Start of region RV
+    pv: usize,
+    /// Start of region R2
+    p2: usize,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl RussianStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        RussianStemmer { buffer: Vec::new(), pv: 0, p2: 0, fold_diacritics: false }
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    ///
+    /// Cyrillic letters are never folded; the option only affects Latin
+    /// words mixed into the text.
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        self.buffer.clear();
+        self.buffer.extend(word.chars().flat_map(char::to_lowercase).map(|c| if c == 'ё' { 'е' } else { c }));
+
+        self.pv = self.buffer.iter().position(|&c| is_vowel(c)).map_or(self.buffer.len(), |i| i + 1);
+        self.p2 = regions(&self.buffer, is_vowel).1;
+
+        if !self.perfective_gerund() {
+            self.remove(&["ся", "сь"]);
+            let _ = self.adjectival() || self.verb() || self.remove(NOUN_SUFFIXES);
+        }
+        self.remove(&["и"]);
+        if let Some((_, start)) = find_suffix(&self.buffer, &["ост", "ость"], self.pv) {
+            if start >= self.p2 { self.buffer.truncate(start); }
+        }
+        self.tidy_up();
+
+        self.buffer.iter().collect()
+    }
+
+    /// Removes the longest of `suffixes` in RV; returns true if there was one
+    fn remove(&mut self, suffixes: &[&str]) -> bool {
+        let Some((_, start)) = find_suffix(&self.buffer, suffixes, self.pv) else { return false };
+        self.buffer.truncate(start);
+        true
+    }
+
+    /// Removes the longest of `after_a` and `suffixes`, an ending of
+    /// `after_a` only if it follows "а" or "я" in RV; returns true if an
+    /// ending was removed
+    fn remove_classes(&mut self, after_a: &[&str], suffixes: &[&str]) -> bool {
+        let found = [find_suffix(&self.buffer, after_a, self.pv), find_suffix(&self.buffer, suffixes, self.pv)];
+        match found {
+            [Some((_, a)), Some((_, s))] if s < a => self.buffer.truncate(s),
+            [Some((_, start)), _] if start > self.pv && matches!(self.buffer[start - 1], 'а' | 'я') => {
+                self.buffer.truncate(start);
+            },
+            [Some(_), _] => return false,
+            [None, Some((_, start))] => self.buffer.truncate(start),
+            [None, None] => return false,
+        }
+        true
+    }
+
+    fn perfective_gerund(&mut self) -> bool {
+        self.remove_classes(GERUND_SUFFIXES_A, GERUND_SUFFIXES)
+    }
+
+    /// Removes an adjective ending, and a participle ending before it
+    fn adjectival(&mut self) -> bool {
+        if !self.remove(ADJECTIVE_SUFFIXES) { return false; }
+        self.remove_classes(PARTICIPLE_SUFFIXES_A, PARTICIPLE_SUFFIXES);
+        true
+    }
+
+    fn verb(&mut self) -> bool {
+        self.remove_classes(VERB_SUFFIXES_A, VERB_SUFFIXES)
+    }
+
+    /// Removes a superlative "-ейш(е)", undoubles a final "нн" and removes a
+    /// final soft sign
+    fn tidy_up(&mut self) {
+        let undouble = |b: &mut Vec<char>, pv: usize| {
+            if ends_with(b, "нн") && b.len() - 2 >= pv { b.pop(); }
+        };
+        match find_suffix(&self.buffer, &["ейш", "ейше", "н", "ь"], self.pv) {
+            Some(("н", _)) => undouble(&mut self.buffer, self.pv),
+            Some(("ь", start)) => self.buffer.truncate(start),
+            Some((_, start)) => {
+                self.buffer.truncate(start);
+                undouble(&mut self.buffer, self.pv);
+            },
+            None => {},
+        }
+    }
+}
+
+impl Stemmer for RussianStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        RussianStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_examples() {
+        let mut stemmer = RussianStemmer::new();
+        let cases = [
+            ("в", "в"), ("вавиловка", "вавиловк"), ("вагнера", "вагнер"), ("вагон", "вагон"),
+            ("вагона", "вагон"), ("вагоне", "вагон"), ("вагонов", "вагон"), ("вагоном", "вагон"),
+            ("вагоны", "вагон"), ("важная", "важн"), ("важнее", "важн"), ("важнейшие", "важн"),
+            ("важнейшими", "важн"), ("важничал", "важнича"), ("важно", "важн"),
+            ("важного", "важн"), ("важное", "важн"), ("важной", "важн"), ("важном", "важн"),
+            ("важностью", "важност"), ("важную", "важн"), ("важны", "важн"), ("важных", "важн"),
+            ("вазах", "ваз"), ("вазы", "ваз"), ("вакса", "вакс"), ("вакханка", "вакханк"),
+            ("вал", "вал"), ("валандался", "валанда"), ("валентина", "валентин"),
+            ("валериановых", "валерианов"), ("валерию", "валер"), ("валетами", "валет"),
+            ("ёлка", "елк"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_snowball_vocabulary() {
+        // The vocabulary and expected output of the Snowball project's test
+        // data (<https://github.com/snowballstem/snowball-data>)
+        let mut stemmer = RussianStemmer::new();
+        let words = include_str!("../test_data/russian/voc.txt");
+        let stems = include_str!("../test_data/russian/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+    }
+}
@@ -0,0 +1,1307 @@
+//! # Snowball Scripts
+//!
+//! An interpreter for stemmers written in Snowball
+//! (<https://snowballstem.org/compiler/snowball.html>), the string
+//! processing language the Snowball stemmers are defined in, so that a
+//! stemmer can be added as a `.sbl` file at run time instead of being
+//! ported by hand. [`SblStemmer::parse`] reads a script and the stemmer
+//! runs its external `stem` routine on every word.
+//!
+//! The whole language is supported except an `among` that starts with a
+//! command and `reverse` in backward mode; `backwardmode` is accepted but
+//! not needed, since each command runs in the mode it is called in. Strings are
+//! Unicode and sizes are counted in characters. Routines see the word
+//! lowercased, as the compiled Snowball stemmers expect.
+//!
+//! A script that loops forever, such as `repeat true`, makes the stemmer
+//! hang; nothing checks for it.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::fold;
+use crate::stemmer::Stemmer;
+
+/// An error in a Snowball script
+#[derive(Debug, Clone, PartialEq)]
+pub struct SblError {
+    /// Line of the script the error was found on, counting from 1
+    pub line: usize,
+    /// What was wrong
+    pub message: String,
+}
+
+impl fmt::Display for SblError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid Snowball script at line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for SblError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Str(Vec<char>),
+    Number(i64),
+    /// Punctuation and operators, such as `(`, `<-` or `+=`
+    Op(&'static str),
+}
+
+/// Operators, longest first so that `<-` is not read as `<`
+const OPS: &[&str] = &[
+    "<-", "<+", "->", "=>", "==", "!=", ">=", "<=", "+=", "-=", "*=", "/=", "=", ">", "<", "+", "-", "*", "/", "(", ")",
+    "[", "]", "$", "?",
+];
+
+/// Splits a script into tokens, one at a time, as string escapes must be
+/// known before the strings that use them are read
+struct Lexer<'a> {
+    src: &'a [u8],
+    text: &'a str,
+    pos: usize,
+    line: usize,
+    escapes: Option<(char, char)>,
+    defs: HashMap<String, Vec<char>>,
+    peeked: Option<(Token, usize)>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(text: &'a str) -> Self {
+        Lexer { src: text.as_bytes(), text, pos: 0, line: 1, escapes: None, defs: HashMap::new(), peeked: None }
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, SblError> {
+        let line = self.peeked.as_ref().map_or(self.line, |&(_, line)| line);
+        Err(SblError { line, message: message.into() })
+    }
+
+    fn skip_space(&mut self) -> Result<(), SblError> {
+        loop {
+            match self.src.get(self.pos) {
+                Some(b'\n') => {
+                    self.line += 1;
+                    self.pos += 1;
+                },
+                Some(c) if c.is_ascii_whitespace() => self.pos += 1,
+                Some(b'/') if self.src.get(self.pos + 1) == Some(&b'/') => {
+                    while !matches!(self.src.get(self.pos), None | Some(b'\n')) { self.pos += 1; }
+                },
+                Some(b'/') if self.src.get(self.pos + 1) == Some(&b'*') => {
+                    let Some(end) = self.text[self.pos + 2..].find("*/") else { return self.error("unclosed comment") };
+                    self.line += self.text[self.pos..self.pos + 2 + end].matches('\n').count();
+                    self.pos += end + 4;
+                },
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Reads a run of characters up to white space, for the names of string
+    /// escapes and stringdefs, which may contain any punctuation
+    fn raw_word(&mut self) -> Result<&'a str, SblError> {
+        self.skip_space()?;
+        let start = self.pos;
+        while self.src.get(self.pos).is_some_and(|c| !c.is_ascii_whitespace()) { self.pos += 1; }
+        if start == self.pos { return self.error("unexpected end of script"); }
+        Ok(&self.text[start..self.pos])
+    }
+
+    /// Reads one character after white space, for the escape characters of
+    /// `stringescapes`, which may be written `{}` or `{ }`
+    fn raw_char(&mut self) -> Result<char, SblError> {
+        self.skip_space()?;
+        let Some(c) = self.text[self.pos..].chars().next() else { return self.error("expected two escape characters") };
+        self.pos += c.len_utf8();
+        Ok(c)
+    }
+
+    fn peek(&mut self) -> Result<Option<&Token>, SblError> {
+        if self.peeked.is_none() {
+            if let Some(token) = self.lex()? {
+                self.peeked = Some((token, self.line));
+            }
+        }
+        Ok(self.peeked.as_ref().map(|(token, _)| token))
+    }
+
+    fn next(&mut self) -> Result<Option<Token>, SblError> {
+        match self.peeked.take() {
+            Some((token, _)) => Ok(Some(token)),
+            None => self.lex(),
+        }
+    }
+
+    fn lex(&mut self) -> Result<Option<Token>, SblError> {
+        self.skip_space()?;
+        let Some(&c) = self.src.get(self.pos) else { return Ok(None) };
+        if c == b'\'' {
+            return self.string().map(|s| Some(Token::Str(s)));
+        }
+        if c.is_ascii_digit() {
+            let start = self.pos;
+            while self.src.get(self.pos).is_some_and(u8::is_ascii_digit) { self.pos += 1; }
+            return match self.text[start..self.pos].parse() {
+                Ok(n) => Ok(Some(Token::Number(n))),
+                Err(_) => self.error("number out of range"),
+            };
+        }
+        if c.is_ascii_alphabetic() || c == b'_' {
+            let start = self.pos;
+            while self.src.get(self.pos).is_some_and(|&c| c.is_ascii_alphanumeric() || c == b'_') { self.pos += 1; }
+            return Ok(Some(Token::Name(self.text[start..self.pos].to_string())));
+        }
+        match OPS.iter().find(|op| self.text[self.pos..].starts_with(*op)) {
+            Some(op) => {
+                self.pos += op.len();
+                Ok(Some(Token::Op(op)))
+            },
+            None => self.error(format!("unexpected character {:?}", self.text[self.pos..].chars().next().unwrap_or(' '))),
+        }
+    }
+
+    /// Reads a string literal, replacing the escapes in it
+    fn string(&mut self) -> Result<Vec<char>, SblError> {
+        self.pos += 1;
+        let mut s = Vec::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\'' => {
+                    self.pos += i + 1;
+                    return Ok(s);
+                },
+                '\n' => return self.error("unclosed string"),
+                c if Some(c) == self.escapes.map(|e| e.0) => {
+                    let close = self.escapes.map_or(c, |e| e.1);
+                    let name: String = chars.by_ref().map(|(_, c)| c).take_while(|&c| c != close).collect();
+                    match self.defs.get(&name) {
+                        Some(def) => s.extend(def),
+                        None if name == "'" || name.chars().eq([c]) => s.extend(name.chars()),
+                        None => match name.strip_prefix("U+").and_then(|hex| u32::from_str_radix(hex, 16).ok()).and_then(char::from_u32) {
+                            Some(c) => s.push(c),
+                            None => return self.error(format!("unknown string escape {:?}", name)),
+                        },
+                    }
+                },
+                c => s.push(c),
+            }
+        }
+        self.error("unclosed string")
+    }
+}
+
+/// A string operand: a literal or a string variable
+#[derive(Debug, Clone)]
+enum Str {
+    Lit(Vec<char>),
+    Var(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// An arithmetic expression
+#[derive(Debug, Clone)]
+enum Ae {
+    Number(i64),
+    Var(usize),
+    Cursor,
+    Limit,
+    Size,
+    SizeOf(usize),
+    Neg(Box<Ae>),
+    Op(ArithOp, Box<Ae>, Box<Ae>),
+}
+
+#[derive(Debug, Clone)]
+enum Cmd {
+    Seq(Vec<Cmd>),
+    Or(Box<Cmd>, Box<Cmd>),
+    And(Box<Cmd>, Box<Cmd>),
+    Not(Box<Cmd>),
+    Test(Box<Cmd>),
+    Try(Box<Cmd>),
+    Do(Box<Cmd>),
+    Fail(Box<Cmd>),
+    Goto(Box<Cmd>),
+    Gopast(Box<Cmd>),
+    Repeat(Box<Cmd>),
+    Loop(Ae, Box<Cmd>),
+    Atleast(Ae, Box<Cmd>),
+    Backwards(Box<Cmd>),
+    Reverse(Box<Cmd>),
+    Setlimit(Box<Cmd>, Box<Cmd>),
+    Eq(Str),
+    /// Matches and runs an among; the among's matching is already done if
+    /// a `substring` is linked to it
+    Among(usize),
+    Substring(usize),
+    Bra,
+    Ket,
+    SliceFrom(Str),
+    Insert(Str),
+    Attach(Str),
+    Delete,
+    SliceTo(usize),
+    AssignTo(usize),
+    Next,
+    Hop(Ae),
+    Setmark(usize),
+    Tomark(Ae),
+    Atmark(Ae),
+    Tolimit,
+    Atlimit,
+    Assign(usize, Option<ArithOp>, Ae),
+    Compare(CmpOp, Ae, Ae),
+    Set(usize),
+    Unset(usize),
+    Bool(usize),
+    True,
+    False,
+    Call(usize),
+    InGrouping(usize),
+    NonGrouping(usize),
+    /// Runs the command on a string variable instead of the word
+    OnString(usize, Box<Cmd>),
+}
+
+/// One string of an among, with the routine that must also succeed and
+/// the command run when it is the one matched
+#[derive(Debug, Clone)]
+struct Entry {
+    s: Vec<char>,
+    condition: Option<usize>,
+    command: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct Among {
+    entries: Vec<Entry>,
+    commands: Vec<Cmd>,
+    /// Whether a `substring` does the matching
+    linked: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Symbol {
+    Str(usize),
+    Int(usize),
+    Bool(usize),
+    Routine(usize),
+    Grouping(usize),
+}
+
+/// A parsed Snowball script
+#[derive(Debug, Clone, Default)]
+struct Program {
+    symbols: HashMap<String, Symbol>,
+    strings: usize,
+    integers: usize,
+    booleans: usize,
+    routines: Vec<Option<Cmd>>,
+    groupings: Vec<Vec<char>>,
+    amongs: Vec<Among>,
+    stem: usize,
+}
+
+/// Returns `n` and counts one more
+fn bump(n: &mut usize) -> usize {
+    *n += 1;
+    *n - 1
+}
+
+struct Parser<'a> {
+    lexer: Lexer<'a>,
+    program: Program,
+    /// The `substring` waiting for an among to link to
+    substring: Option<usize>,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, SblError> {
+        self.lexer.error(message)
+    }
+
+    fn next(&mut self) -> Result<Token, SblError> {
+        match self.lexer.next()? {
+            Some(token) => Ok(token),
+            None => self.error("unexpected end of script"),
+        }
+    }
+
+    fn next_is(&mut self, op: &str) -> Result<bool, SblError> {
+        if matches!(self.lexer.peek()?, Some(Token::Op(o)) if *o == op) {
+            self.lexer.next()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), SblError> {
+        if self.next_is(op)? { Ok(()) } else { self.error(format!("expected `{}`", op)) }
+    }
+
+    fn name(&mut self) -> Result<String, SblError> {
+        match self.next()? {
+            Token::Name(name) => Ok(name),
+            token => self.error(format!("expected a name, found {:?}", token)),
+        }
+    }
+
+    fn symbol(&mut self) -> Result<Symbol, SblError> {
+        let name = self.name()?;
+        match self.program.symbols.get(&name) {
+            Some(&symbol) => Ok(symbol),
+            None => self.error(format!("undeclared name `{}`", name)),
+        }
+    }
+
+    fn int_var(&mut self) -> Result<usize, SblError> {
+        match self.symbol()? {
+            Symbol::Int(i) => Ok(i),
+            _ => self.error("expected an integer variable"),
+        }
+    }
+
+    fn str_var(&mut self) -> Result<usize, SblError> {
+        match self.symbol()? {
+            Symbol::Str(i) => Ok(i),
+            _ => self.error("expected a string variable"),
+        }
+    }
+
+    fn bool_var(&mut self) -> Result<usize, SblError> {
+        match self.symbol()? {
+            Symbol::Bool(i) => Ok(i),
+            _ => self.error("expected a boolean variable"),
+        }
+    }
+
+    /// A literal, possibly given as `hex` or `decimal` character codes
+    fn literal(&mut self) -> Result<Vec<char>, SblError> {
+        match self.next()? {
+            Token::Str(s) => Ok(s),
+            Token::Name(radix) if radix == "hex" || radix == "decimal" => {
+                let Token::Str(codes) = self.next()? else { return self.error("expected a string") };
+                let codes: String = codes.into_iter().collect();
+                codes
+                    .split_whitespace()
+                    .map(|code| {
+                        u32::from_str_radix(code, if radix == "hex" { 16 } else { 10 }).ok().and_then(char::from_u32)
+                    })
+                    .collect::<Option<Vec<char>>>()
+                    .map_or_else(|| self.error(format!("invalid character code in {:?}", codes)), Ok)
+            },
+            token => self.error(format!("expected a string, found {:?}", token)),
+        }
+    }
+
+    fn string_operand(&mut self) -> Result<Str, SblError> {
+        if matches!(self.lexer.peek()?, Some(Token::Name(name)) if name != "hex" && name != "decimal") {
+            return self.str_var().map(Str::Var);
+        }
+        self.literal().map(Str::Lit)
+    }
+
+    fn declare(&mut self, kind: &str) -> Result<(), SblError> {
+        self.expect("(")?;
+        while !self.next_is(")")? {
+            let name = self.name()?;
+            let p = &mut self.program;
+            let symbol = match kind {
+                "strings" => Symbol::Str(bump(&mut p.strings)),
+                "integers" => Symbol::Int(bump(&mut p.integers)),
+                "booleans" => Symbol::Bool(bump(&mut p.booleans)),
+                "groupings" => {
+                    p.groupings.push(Vec::new());
+                    Symbol::Grouping(p.groupings.len() - 1)
+                },
+                _ => {
+                    p.routines.push(None);
+                    Symbol::Routine(p.routines.len() - 1)
+                },
+            };
+            if p.symbols.insert(name.clone(), symbol).is_some() {
+                return self.error(format!("`{}` is declared twice", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Top-level declarations and definitions, up to `)` or the end
+    fn items(&mut self, nested: bool) -> Result<(), SblError> {
+        loop {
+            let token = match self.lexer.next()? {
+                None if !nested => return Ok(()),
+                None => return self.error("expected `)`"),
+                Some(Token::Op(")")) if nested => return Ok(()),
+                Some(token) => token,
+            };
+            let Token::Name(word) = token else { return self.error(format!("unexpected {:?}", token)) };
+            match word.as_str() {
+                "strings" | "integers" | "booleans" | "routines" | "externals" | "groupings" => self.declare(&word)?,
+                "stringescapes" => {
+                    let open = self.lexer.raw_char()?;
+                    let close = self.lexer.raw_char()?;
+                    self.lexer.escapes = Some((open, close));
+                },
+                "stringdef" => {
+                    let name = self.lexer.raw_word()?.to_string();
+                    let value = self.literal()?;
+                    self.lexer.defs.insert(name, value);
+                },
+                "backwardmode" => {
+                    self.expect("(")?;
+                    self.items(true)?;
+                },
+                "define" => self.define()?,
+                _ => return self.error(format!("unexpected `{}`", word)),
+            }
+        }
+    }
+
+    fn define(&mut self) -> Result<(), SblError> {
+        match self.symbol()? {
+            Symbol::Routine(r) => {
+                if !matches!(self.next()?, Token::Name(as_) if as_ == "as") {
+                    return self.error("expected `as`");
+                }
+                let body = self.command()?;
+                self.program.routines[r] = Some(body);
+            },
+            Symbol::Grouping(g) => {
+                let mut set = self.grouping_operand()?;
+                loop {
+                    if self.next_is("+")? {
+                        for c in self.grouping_operand()? {
+                            if !set.contains(&c) { set.push(c); }
+                        }
+                    } else if self.next_is("-")? {
+                        let minus = self.grouping_operand()?;
+                        set.retain(|c| !minus.contains(c));
+                    } else {
+                        break;
+                    }
+                }
+                self.program.groupings[g] = set;
+            },
+            _ => return self.error("only routines and groupings can be defined"),
+        }
+        Ok(())
+    }
+
+    fn grouping_operand(&mut self) -> Result<Vec<char>, SblError> {
+        if let Some(Token::Name(name)) = self.lexer.peek()? {
+            if name != "hex" && name != "decimal" {
+                return match self.symbol()? {
+                    Symbol::Grouping(g) => Ok(self.program.groupings[g].clone()),
+                    _ => self.error("expected a grouping"),
+                };
+            }
+        }
+        self.literal()
+    }
+
+    /// Commands joined by `or` and `and`, which bind tighter than a
+    /// sequence: `a b or c` is `a (b or c)`
+    fn command(&mut self) -> Result<Cmd, SblError> {
+        let mut cmd = self.unary()?;
+        loop {
+            match self.lexer.peek()? {
+                Some(Token::Name(op)) if op == "or" => {
+                    self.lexer.next()?;
+                    cmd = Cmd::Or(Box::new(cmd), Box::new(self.unary()?));
+                },
+                Some(Token::Name(op)) if op == "and" => {
+                    self.lexer.next()?;
+                    cmd = Cmd::And(Box::new(cmd), Box::new(self.unary()?));
+                },
+                _ => return Ok(cmd),
+            }
+        }
+    }
+
+    fn boxed(&mut self) -> Result<Box<Cmd>, SblError> {
+        self.unary().map(Box::new)
+    }
+
+    fn unary(&mut self) -> Result<Cmd, SblError> {
+        let token = self.next()?;
+        let word = match token {
+            Token::Str(s) => return Ok(Cmd::Eq(Str::Lit(s))),
+            Token::Number(n) => return self.error(format!("unexpected number {}", n)),
+            Token::Op("(") => {
+                let mut seq = Vec::new();
+                while !self.next_is(")")? { seq.push(self.command()?); }
+                return Ok(Cmd::Seq(seq));
+            },
+            Token::Op("[") => return Ok(Cmd::Bra),
+            Token::Op("]") => return Ok(Cmd::Ket),
+            Token::Op("?") => return Ok(Cmd::True),
+            Token::Op("->") => return self.str_var().map(Cmd::SliceTo),
+            Token::Op("=>") => return self.str_var().map(Cmd::AssignTo),
+            Token::Op("<-") => return self.string_operand().map(Cmd::SliceFrom),
+            Token::Op("<+") => return self.string_operand().map(Cmd::Insert),
+            Token::Op("$") => return self.dollar(),
+            Token::Op(op) => return self.error(format!("unexpected `{}`", op)),
+            Token::Name(word) => word,
+        };
+        Ok(match word.as_str() {
+            "not" => Cmd::Not(self.boxed()?),
+            "test" => Cmd::Test(self.boxed()?),
+            "try" => Cmd::Try(self.boxed()?),
+            "do" => Cmd::Do(self.boxed()?),
+            "fail" => Cmd::Fail(self.boxed()?),
+            "goto" => Cmd::Goto(self.boxed()?),
+            "gopast" => Cmd::Gopast(self.boxed()?),
+            "repeat" => Cmd::Repeat(self.boxed()?),
+            "backwards" => Cmd::Backwards(self.boxed()?),
+            "reverse" => Cmd::Reverse(self.boxed()?),
+            "loop" => Cmd::Loop(self.ae()?, self.boxed()?),
+            "atleast" => Cmd::Atleast(self.ae()?, self.boxed()?),
+            "setlimit" => {
+                let limit = self.boxed()?;
+                if !matches!(self.next()?, Token::Name(f) if f == "for") {
+                    return self.error("expected `for`");
+                }
+                Cmd::Setlimit(limit, self.boxed()?)
+            },
+            "among" => self.among()?,
+            "substring" => {
+                if self.substring.is_some() { return self.error("`substring` without an among"); }
+                let a = self.program.amongs.len();
+                self.substring = Some(a);
+                Cmd::Substring(a)
+            },
+            "delete" => Cmd::Delete,
+            "insert" => Cmd::Insert(self.string_operand()?),
+            "attach" => Cmd::Attach(self.string_operand()?),
+            "next" => Cmd::Next,
+            "hop" => Cmd::Hop(self.ae()?),
+            "setmark" => Cmd::Setmark(self.int_var()?),
+            "tomark" => Cmd::Tomark(self.ae()?),
+            "atmark" => Cmd::Atmark(self.ae()?),
+            "tolimit" => Cmd::Tolimit,
+            "atlimit" => Cmd::Atlimit,
+            "set" => Cmd::Set(self.bool_var()?),
+            "unset" => Cmd::Unset(self.bool_var()?),
+            "true" => Cmd::True,
+            "false" => Cmd::False,
+            "non" => {
+                self.next_is("-")?;
+                match self.symbol()? {
+                    Symbol::Grouping(g) => Cmd::NonGrouping(g),
+                    _ => return self.error("expected a grouping after `non`"),
+                }
+            },
+            _ => match self.program.symbols.get(&word) {
+                Some(&Symbol::Routine(r)) => Cmd::Call(r),
+                Some(&Symbol::Grouping(g)) => Cmd::InGrouping(g),
+                Some(&Symbol::Bool(b)) => Cmd::Bool(b),
+                Some(&Symbol::Str(s)) => Cmd::Eq(Str::Var(s)),
+                Some(&Symbol::Int(_)) => return self.error(format!("integer `{}` used as a command", word)),
+                None => return self.error(format!("undeclared name `{}`", word)),
+            },
+        })
+    }
+
+    /// `$i = AE` and the other integer commands, `$(AE < AE)`, or `$s C`
+    fn dollar(&mut self) -> Result<Cmd, SblError> {
+        if self.next_is("(")? {
+            let left = self.ae()?;
+            let op = self.cmp_op()?;
+            let right = self.ae()?;
+            self.expect(")")?;
+            return Ok(Cmd::Compare(op, left, right));
+        }
+        match self.symbol()? {
+            Symbol::Int(i) => {
+                let assign = match self.next()? {
+                    Token::Op("=") => None,
+                    Token::Op("+=") => Some(ArithOp::Add),
+                    Token::Op("-=") => Some(ArithOp::Sub),
+                    Token::Op("*=") => Some(ArithOp::Mul),
+                    Token::Op("/=") => Some(ArithOp::Div),
+                    Token::Op(op) => {
+                        self.lexer.peeked = Some((Token::Op(op), self.lexer.line));
+                        let op = self.cmp_op()?;
+                        return Ok(Cmd::Compare(op, Ae::Var(i), self.ae()?));
+                    },
+                    token => return self.error(format!("unexpected {:?} after an integer", token)),
+                };
+                Ok(Cmd::Assign(i, assign, self.ae()?))
+            },
+            Symbol::Str(s) => Ok(Cmd::OnString(s, self.boxed()?)),
+            _ => self.error("expected an integer or string variable after `$`"),
+        }
+    }
+
+    fn cmp_op(&mut self) -> Result<CmpOp, SblError> {
+        Ok(match self.next()? {
+            Token::Op("==") => CmpOp::Eq,
+            Token::Op("!=") => CmpOp::Ne,
+            Token::Op("<") => CmpOp::Lt,
+            Token::Op("<=") => CmpOp::Le,
+            Token::Op(">") => CmpOp::Gt,
+            Token::Op(">=") => CmpOp::Ge,
+            token => return self.error(format!("expected a comparison, found {:?}", token)),
+        })
+    }
+
+    fn ae(&mut self) -> Result<Ae, SblError> {
+        let mut ae = self.term()?;
+        loop {
+            let op = if self.next_is("+")? {
+                ArithOp::Add
+            } else if self.next_is("-")? {
+                ArithOp::Sub
+            } else {
+                return Ok(ae);
+            };
+            ae = Ae::Op(op, Box::new(ae), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Ae, SblError> {
+        let mut ae = self.factor()?;
+        loop {
+            let op = if self.next_is("*")? {
+                ArithOp::Mul
+            } else if self.next_is("/")? {
+                ArithOp::Div
+            } else {
+                return Ok(ae);
+            };
+            ae = Ae::Op(op, Box::new(ae), Box::new(self.factor()?));
+        }
+    }
+
+    fn factor(&mut self) -> Result<Ae, SblError> {
+        Ok(match self.next()? {
+            Token::Number(n) => Ae::Number(n),
+            Token::Op("-") => Ae::Neg(Box::new(self.factor()?)),
+            Token::Op("(") => {
+                let ae = self.ae()?;
+                self.expect(")")?;
+                ae
+            },
+            Token::Name(name) => match name.as_str() {
+                "cursor" => Ae::Cursor,
+                "limit" => Ae::Limit,
+                "size" | "len" => Ae::Size,
+                "sizeof" | "lenof" => Ae::SizeOf(self.str_var()?),
+                "maxint" => Ae::Number(i64::from(i32::MAX)),
+                "minint" => Ae::Number(i64::from(i32::MIN)),
+                _ => match self.program.symbols.get(&name) {
+                    Some(&Symbol::Int(i)) => Ae::Var(i),
+                    _ => return self.error(format!("`{}` is not an integer", name)),
+                },
+            },
+            token => return self.error(format!("expected a number, found {:?}", token)),
+        })
+    }
+
+    fn among(&mut self) -> Result<Cmd, SblError> {
+        let a = self.program.amongs.len();
+        let linked = self.substring.take().is_some();
+        self.program.amongs.push(Among { entries: Vec::new(), commands: Vec::new(), linked });
+        self.expect("(")?;
+        let (mut entries, mut commands): (Vec<Entry>, Vec<Cmd>) = (Vec::new(), Vec::new());
+        let mut pending = 0;
+        loop {
+            match self.lexer.peek()? {
+                Some(Token::Op(")")) => {
+                    self.lexer.next()?;
+                    break;
+                },
+                Some(Token::Op("(")) => {
+                    if pending == 0 { return self.error("an among command must follow its strings"); }
+                    let command = self.unary()?;
+                    let start = entries.len() - pending;
+                    for entry in &mut entries[start..] {
+                        entry.command = Some(commands.len());
+                    }
+                    commands.push(command);
+                    pending = 0;
+                },
+                _ => {
+                    let s = self.literal()?;
+                    let condition = match self.lexer.peek()? {
+                        Some(Token::Name(name)) => match self.program.symbols.get(name) {
+                            Some(&Symbol::Routine(r)) => {
+                                self.lexer.next()?;
+                                Some(r)
+                            },
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    entries.push(Entry { s, condition, command: None });
+                    pending += 1;
+                },
+            }
+        }
+        let among = &mut self.program.amongs[a];
+        among.entries = entries;
+        among.commands = commands;
+        Ok(Cmd::Among(a))
+    }
+}
+
+impl Program {
+    fn parse(source: &str) -> Result<Program, SblError> {
+        let mut parser = Parser { lexer: Lexer::new(source), program: Program::default(), substring: None };
+        parser.items(false)?;
+        if parser.substring.is_some() {
+            return parser.error("`substring` without an among");
+        }
+        let mut program = parser.program;
+        let line = parser.lexer.line;
+        let undefined = program.symbols.iter().find(|(_, &s)| matches!(s, Symbol::Routine(r) if program.routines[r].is_none()));
+        if let Some((name, _)) = undefined {
+            return Err(SblError { line, message: format!("routine `{}` is never defined", name) });
+        }
+        match program.symbols.get("stem") {
+            Some(&Symbol::Routine(r)) => program.stem = r,
+            _ => return Err(SblError { line, message: "there is no `stem` routine".into() }),
+        }
+        Ok(program)
+    }
+}
+
+/// The state of a running script, as in the Snowball runtime
+#[derive(Debug, Default)]
+struct Env {
+    current: Vec<char>,
+    c: usize,
+    l: usize,
+    lb: usize,
+    bra: usize,
+    ket: usize,
+    ints: Vec<i64>,
+    strs: Vec<Vec<char>>,
+    bools: Vec<bool>,
+    /// The entry each substring matched, by among
+    matched: Vec<Option<usize>>,
+}
+
+impl Env {
+    fn replace(&mut self, bra: usize, ket: usize, s: &[char]) {
+        let adjustment = s.len() as isize - (ket - bra) as isize;
+        self.current.splice(bra..ket, s.iter().copied());
+        self.l = (self.l as isize + adjustment) as usize;
+        if self.c >= ket {
+            self.c = (self.c as isize + adjustment) as usize;
+        } else if self.c > bra {
+            self.c = bra;
+        }
+    }
+
+    fn string(&self, s: &Str) -> Vec<char> {
+        match s {
+            Str::Lit(s) => s.clone(),
+            Str::Var(v) => self.strs[*v].clone(),
+        }
+    }
+
+    fn ae(&self, ae: &Ae) -> i64 {
+        match ae {
+            Ae::Number(n) => *n,
+            Ae::Var(i) => self.ints[*i],
+            Ae::Cursor => self.c as i64,
+            Ae::Limit => self.l as i64,
+            Ae::Size => self.current.len() as i64,
+            Ae::SizeOf(s) => self.strs[*s].len() as i64,
+            Ae::Neg(ae) => -self.ae(ae),
+            Ae::Op(op, a, b) => {
+                let (a, b) = (self.ae(a), self.ae(b));
+                match op {
+                    ArithOp::Add => a.wrapping_add(b),
+                    ArithOp::Sub => a.wrapping_sub(b),
+                    ArithOp::Mul => a.wrapping_mul(b),
+                    ArithOp::Div => a.checked_div(b).unwrap_or(0),
+                }
+            },
+        }
+    }
+
+    /// The cursor, to be restored by [`Env::restore`]; backward it is kept
+    /// as the distance from the limit, which stays put when the word
+    /// changes before the cursor
+    fn save(&self, back: bool) -> usize {
+        if back { self.l - self.c } else { self.c }
+    }
+
+    fn restore(&mut self, saved: usize, back: bool) {
+        self.c = if back { self.l - saved } else { saved };
+    }
+
+    /// Runs `cmd` forward, or backward from the end of the word towards the
+    /// backward limit, and returns whether it succeeded
+    fn run(&mut self, p: &Program, cmd: &Cmd, back: bool) -> bool {
+        match cmd {
+            Cmd::Seq(cmds) => cmds.iter().all(|cmd| self.run(p, cmd, back)),
+            Cmd::Or(a, b) => {
+                let c = self.save(back);
+                self.run(p, a, back) || {
+                    self.restore(c, back);
+                    self.run(p, b, back)
+                }
+            },
+            Cmd::And(a, b) => {
+                let c = self.save(back);
+                self.run(p, a, back) && {
+                    self.restore(c, back);
+                    self.run(p, b, back)
+                }
+            },
+            Cmd::Not(cmd) => {
+                let c = self.save(back);
+                if self.run(p, cmd, back) { return false; }
+                self.restore(c, back);
+                true
+            },
+            Cmd::Test(cmd) => {
+                let c = self.save(back);
+                let ok = self.run(p, cmd, back);
+                self.restore(c, back);
+                ok
+            },
+            Cmd::Try(cmd) => {
+                let c = self.save(back);
+                if !self.run(p, cmd, back) { self.restore(c, back); }
+                true
+            },
+            Cmd::Do(cmd) => {
+                let c = self.save(back);
+                self.run(p, cmd, back);
+                self.restore(c, back);
+                true
+            },
+            Cmd::Fail(cmd) => {
+                self.run(p, cmd, back);
+                false
+            },
+            Cmd::Goto(inner) | Cmd::Gopast(inner) => loop {
+                let c = self.save(back);
+                if self.run(p, inner, back) {
+                    if matches!(cmd, Cmd::Goto(_)) { self.restore(c, back); }
+                    break true;
+                }
+                self.restore(c, back);
+                if !self.step(back) { break false; }
+            },
+            Cmd::Repeat(cmd) => {
+                loop {
+                    let c = self.save(back);
+                    if !self.run(p, cmd, back) {
+                        self.restore(c, back);
+                        break;
+                    }
+                }
+                true
+            },
+            Cmd::Loop(n, cmd) => (0..self.ae(n)).all(|_| self.run(p, cmd, back)),
+            Cmd::Atleast(n, cmd) => (0..self.ae(n)).all(|_| self.run(p, cmd, back)) && self.run(p, &Cmd::Repeat(cmd.clone()), back),
+            Cmd::Backwards(cmd) if !back => {
+                let lb = self.lb;
+                self.lb = self.c;
+                self.c = self.l;
+                let ok = self.run(p, cmd, true);
+                self.c = self.lb;
+                self.lb = lb;
+                ok
+            },
+            Cmd::Backwards(cmd) => self.run(p, cmd, true),
+            Cmd::Reverse(cmd) => self.run(p, &Cmd::Backwards(cmd.clone()), back),
+            Cmd::Setlimit(limit, cmd) => {
+                let c = self.save(back);
+                if !self.run(p, limit, back) { return false; }
+                // Forward, the limit is restored at the same distance from the
+                // end, as the word may change length in between
+                let saved = if back { std::mem::replace(&mut self.lb, self.c) } else { self.l - self.c };
+                if !back { self.l = self.c; }
+                self.restore(c, back);
+                let ok = self.run(p, cmd, back);
+                if back { self.lb = saved } else { self.l += saved }
+                ok
+            },
+            Cmd::Eq(s) => {
+                let s = self.string(s);
+                self.eq(&s, back)
+            },
+            Cmd::Among(a) => {
+                let among = &p.amongs[*a];
+                let matched = if among.linked { self.matched.get(*a).copied().flatten() } else { self.find_among(p, *a, back) };
+                match matched.and_then(|e| among.entries[e].command) {
+                    Some(command) => self.run(p, &among.commands[command], back),
+                    None => matched.is_some(),
+                }
+            },
+            Cmd::Substring(a) => {
+                let matched = self.find_among(p, *a, back);
+                if self.matched.len() <= *a { self.matched.resize(*a + 1, None); }
+                self.matched[*a] = matched;
+                matched.is_some()
+            },
+            Cmd::Bra => {
+                if back { self.ket = self.c } else { self.bra = self.c }
+                true
+            },
+            Cmd::Ket => {
+                if back { self.bra = self.c } else { self.ket = self.c }
+                true
+            },
+            Cmd::SliceFrom(s) => {
+                let s = self.string(s);
+                self.slice_ok() && {
+                    self.replace(self.bra, self.ket, &s);
+                    true
+                }
+            },
+            Cmd::Delete => {
+                self.slice_ok() && {
+                    self.replace(self.bra, self.ket, &[]);
+                    true
+                }
+            },
+            Cmd::Insert(s) | Cmd::Attach(s) => {
+                let s = self.string(s);
+                let c = self.c;
+                self.replace(c, c, &s);
+                if self.bra >= c { self.bra += s.len(); }
+                if self.ket >= c { self.ket += s.len(); }
+                if matches!(cmd, Cmd::Attach(_)) != back { self.c = c; }
+                true
+            },
+            Cmd::SliceTo(v) => {
+                self.slice_ok() && {
+                    self.strs[*v] = self.current[self.bra..self.ket].to_vec();
+                    true
+                }
+            },
+            Cmd::AssignTo(v) => {
+                self.strs[*v] = self.current[self.c.min(self.l)..self.l].to_vec();
+                true
+            },
+            Cmd::Next => self.step(back),
+            Cmd::Hop(n) => {
+                let n = self.ae(n);
+                let to = if back { self.c as i64 - n } else { self.c as i64 + n };
+                if n < 0 || to < self.lb as i64 || to > self.l as i64 { return false; }
+                self.c = to as usize;
+                true
+            },
+            Cmd::Setmark(i) => {
+                self.ints[*i] = self.c as i64;
+                true
+            },
+            Cmd::Tomark(ae) => {
+                let to = self.ae(ae);
+                let ok = if back { to <= self.c as i64 && to >= self.lb as i64 } else { to >= self.c as i64 && to <= self.l as i64 };
+                if ok { self.c = to as usize; }
+                ok
+            },
+            Cmd::Atmark(ae) => self.ae(ae) == self.c as i64,
+            Cmd::Tolimit => {
+                self.c = if back { self.lb } else { self.l };
+                true
+            },
+            Cmd::Atlimit => self.c == if back { self.lb } else { self.l },
+            Cmd::Assign(i, op, ae) => {
+                let (a, b) = (self.ints[*i], self.ae(ae));
+                self.ints[*i] = match op {
+                    None => b,
+                    Some(ArithOp::Add) => a.wrapping_add(b),
+                    Some(ArithOp::Sub) => a.wrapping_sub(b),
+                    Some(ArithOp::Mul) => a.wrapping_mul(b),
+                    Some(ArithOp::Div) => a.checked_div(b).unwrap_or(0),
+                };
+                true
+            },
+            Cmd::Compare(op, a, b) => {
+                let (a, b) = (self.ae(a), self.ae(b));
+                match op {
+                    CmpOp::Eq => a == b,
+                    CmpOp::Ne => a != b,
+                    CmpOp::Lt => a < b,
+                    CmpOp::Le => a <= b,
+                    CmpOp::Gt => a > b,
+                    CmpOp::Ge => a >= b,
+                }
+            },
+            Cmd::Set(b) | Cmd::Unset(b) => {
+                self.bools[*b] = matches!(cmd, Cmd::Set(_));
+                true
+            },
+            Cmd::Bool(b) => self.bools[*b],
+            Cmd::True => true,
+            Cmd::False => false,
+            Cmd::Call(r) => match &p.routines[*r] {
+                Some(body) => self.run(p, body, back),
+                None => false,
+            },
+            Cmd::InGrouping(g) | Cmd::NonGrouping(g) => {
+                let i = if back { self.c.checked_sub(1).filter(|&i| i >= self.lb) } else { Some(self.c).filter(|&i| i < self.l) };
+                let Some(i) = i else { return false };
+                if p.groupings[*g].contains(&self.current[i]) != matches!(cmd, Cmd::InGrouping(_)) { return false; }
+                self.c = if back { i } else { i + 1 };
+                true
+            },
+            Cmd::OnString(v, cmd) => {
+                let word = std::mem::replace(&mut self.current, std::mem::take(&mut self.strs[*v]));
+                let saved = (self.c, self.l, self.lb, self.bra, self.ket);
+                (self.c, self.l, self.lb, self.bra, self.ket) = (0, self.current.len(), 0, 0, self.current.len());
+                let ok = self.run(p, cmd, false);
+                self.strs[*v] = std::mem::replace(&mut self.current, word);
+                (self.c, self.l, self.lb, self.bra, self.ket) = saved;
+                ok
+            },
+        }
+    }
+
+    /// Moves the cursor one character, unless it is at the limit
+    fn step(&mut self, back: bool) -> bool {
+        if back {
+            if self.c <= self.lb { return false; }
+            self.c -= 1;
+        } else {
+            if self.c >= self.l { return false; }
+            self.c += 1;
+        }
+        true
+    }
+
+    fn eq(&mut self, s: &[char], back: bool) -> bool {
+        if back {
+            if self.c < self.lb + s.len() || self.current[self.c - s.len()..self.c] != *s { return false; }
+            self.c -= s.len();
+        } else {
+            if self.c + s.len() > self.l || self.current[self.c..self.c + s.len()] != *s { return false; }
+            self.c += s.len();
+        }
+        true
+    }
+
+    fn slice_ok(&self) -> bool {
+        self.bra <= self.ket && self.ket <= self.current.len()
+    }
+
+    /// The longest entry of among `a` at the cursor whose condition holds,
+    /// with the cursor moved past it
+    fn find_among(&mut self, p: &Program, a: usize, back: bool) -> Option<usize> {
+        let c = self.c;
+        let mut found: Vec<usize> = (0..p.amongs[a].entries.len())
+            .filter(|&e| {
+                let s = &p.amongs[a].entries[e].s;
+                if back {
+                    c >= self.lb + s.len() && self.current[c - s.len()..c] == **s
+                } else {
+                    c + s.len() <= self.l && self.current[c..c + s.len()] == **s
+                }
+            })
+            .collect();
+        found.sort_by_key(|&e| std::cmp::Reverse(p.amongs[a].entries[e].s.len()));
+        for e in found {
+            let entry = &p.amongs[a].entries[e];
+            let after = if back { c - entry.s.len() } else { c + entry.s.len() };
+            self.c = after;
+            let ok = entry.condition.is_none_or(|r| self.run(p, &Cmd::Call(r), back));
+            self.c = after;
+            if ok { return Some(e); }
+        }
+        self.c = c;
+        None
+    }
+}
+
+/// A stemmer defined by a Snowball script
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::sbl::SblStemmer;
+/// let script = "
+///     externals ( stem )
+///     define stem as backwards ( [substring] among ( 'ed' 'ing' (delete) 'ies' (<- 'y') ) )
+/// ";
+/// let mut stemmer = SblStemmer::parse(script).unwrap();
+/// assert_eq!(stemmer.stem("Jumped"), "jump");
+/// assert_eq!(stemmer.stem("ponies"), "pony");
+/// ```
+#[derive(Debug)]
+pub struct SblStemmer {
+    program: Program,
+    env: Env,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl SblStemmer {
+    /// Parses a Snowball script into a stemmer
+    pub fn parse(source: &str) -> Result<Self, SblError> {
+        Ok(SblStemmer { program: Program::parse(source)?, env: Env::default(), fold_diacritics: false })
+    }
+
+    /// Loads a Snowball script file; an invalid script is an
+    /// [`io::ErrorKind::InvalidData`] error
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        let p = &self.program;
+        let env = &mut self.env;
+        env.current.clear();
+        env.current.extend(word.chars().flat_map(char::to_lowercase));
+        (env.c, env.l, env.lb, env.bra, env.ket) = (0, env.current.len(), 0, 0, env.current.len());
+        env.ints.clear();
+        env.ints.resize(p.integers, 0);
+        env.strs.clear();
+        env.strs.resize(p.strings, Vec::new());
+        env.bools.clear();
+        env.bools.resize(p.booleans, false);
+        env.matched.clear();
+        env.run(p, &Cmd::Call(p.stem), false);
+        env.current.iter().collect()
+    }
+}
+
+impl Stemmer for SblStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        SblStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stem(script: &str, word: &str) -> String {
+        SblStemmer::parse(script).unwrap().stem(word)
+    }
+
+    #[test]
+    fn test_porter_script() {
+        // porter.sbl of the Snowball project, edited to depart from the
+        // paper as Martin Porter's C version does, on the vocabulary of
+        // test_data/porter
+        let mut script = SblStemmer::parse(include_str!("../test_data/sbl/porter.sbl")).unwrap();
+        let words = include_str!("../test_data/porter/voc.txt");
+        let stems = include_str!("../test_data/porter/output.txt");
+        crate::assert_vocabulary(&mut script, words, stems);
+    }
+
+    #[test]
+    fn test_snowball_scripts() {
+        // english.sbl and french.sbl as the Snowball project has them, on
+        // the vocabularies of test_data/english and test_data/french
+        let scripts = [
+            (
+                include_str!("../test_data/sbl/english.sbl"),
+                include_str!("../test_data/english/voc.txt"),
+                include_str!("../test_data/english/output.txt"),
+            ),
+            (
+                include_str!("../test_data/sbl/french.sbl"),
+                include_str!("../test_data/french/voc.txt"),
+                include_str!("../test_data/french/output.txt"),
+            ),
+        ];
+        for (source, words, stems) in scripts {
+            let mut script = SblStemmer::parse(source).unwrap();
+            crate::assert_vocabulary(&mut script, words, stems);
+        }
+    }
+
+    #[test]
+    fn test_commands() {
+        let among = "
+            integers ( p )
+            routines ( R )
+            externals ( stem )
+            backwardmode ( define R as $p <= cursor )
+            define stem as ( $p = 2  backwards ( [substring] R among ( 'ing' (delete) 'ng' (<- 'NG') ) ) )
+        ";
+        // The longest match is taken even if the condition after it fails
+        assert_eq!(stem(among, "bring"), "br");
+        assert_eq!(stem(among, "sing"), "sing");
+        // A routine inside the among falls back to a shorter match
+        let condition = among.replace("[substring] R among ( 'ing' (delete)", "[substring] among ( 'ing' R (delete)");
+        assert_eq!(stem(&condition, "sing"), "siNG");
+
+        let replace = "externals ( stem ) define stem as repeat ( goto ( ['a'] ) <- 'o' )";
+        assert_eq!(stem(replace, "banana"), "bonono");
+        let limited = "externals ( stem ) define stem as setlimit hop 3 for repeat ( goto ( ['a'] ) <- 'o' )";
+        assert_eq!(stem(limited, "banana"), "bonana");
+        assert_eq!(stem("externals ( stem ) define stem as ( gopast 'a' insert 'X' )", "banana"), "baXnana");
+
+        let escapes = "
+            stringescapes {}
+            stringdef a\" '{U+00E4}'
+            integers ( p )
+            groupings ( v )
+            externals ( stem )
+            define v 'aeiou{a\"}'
+            define stem as ( gopast v gopast non-v setmark p $(p * 2 + 1 == 7) gopast ( ['{a\"}'] ) <- 'ae' )
+        ";
+        assert_eq!(stem(escapes, "BÄR"), "bär");
+        assert_eq!(stem(escapes, "Rumbär"), "rumbaer");
+        // Snowball's own sources put white space between the escapes
+        assert_eq!(stem(&escapes.replace("{}", "{ }"), "Rumbär"), "rumbaer");
+
+        let strings = "
+            strings ( s )
+            booleans ( found )
+            externals ( stem )
+            define stem as ( do gopast ( ['x'] -> s set found ) found $s ( <+ 'y' ) tolimit <+ s )
+        ";
+        assert_eq!(stem(strings, "axb"), "axbyx");
+        assert_eq!(stem(strings, "ab"), "ab");
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = SblStemmer::parse("externals ( stem )\ndefine stem as ( hop )").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(error.to_string().starts_with("invalid Snowball script at line 2: expected a number"));
+        let error = SblStemmer::parse("externals ( stem )\ndefine stem as ( v )").unwrap_err();
+        assert_eq!(error.message, "undeclared name `v`");
+        let error = SblStemmer::parse("routines ( r )\ndefine r as true").unwrap_err();
+        assert_eq!(error.message, "there is no `stem` routine");
+    }
+}
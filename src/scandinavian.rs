@@ -0,0 +1,271 @@
+//! # Swedish, Norwegian and Danish (Snowball)
+//!
+//! The Snowball stemmers for the three mainland Scandinavian languages
+//! (<https://snowballstem.org/algorithms/swedish/stemmer.html>, and the
+//! Norwegian and Danish pages next to it). They share one structure: a
+//! main suffix is removed from R1, a final consonant pair such as "dt" is
+//! undoubled, and a few derivational suffixes go; only the tables and a
+//! handful of rules differ, so one [`ScandinavianStemmer`] runs all three
+//! from per-language [`Tables`].
+//!
+//! R1 is the standard region, but starts after the third letter at the
+//! earliest.
+
+use crate::fold;
+use crate::snowball::{after_vowel_consonant, ends_with, find_suffix, replace_from};
+use crate::stemmer::Stemmer;
+
+/// What distinguishes one of the languages
+#[derive(Debug)]
+struct Tables {
+    vowels: &'static str,
+    /// Letters before which a final "s" is removed
+    s_endings: &'static str,
+    /// Main suffixes, removed from R1; "s" after one of [`Tables::s_endings`]
+    main_suffixes: &'static [&'static str],
+    /// Main suffixes that are replaced instead of removed
+    main_replacements: &'static [(&'static str, &'static str)],
+    /// Final consonant pairs in R1 whose last letter is removed
+    consonant_pairs: &'static [&'static str],
+    /// Derivational suffixes, removed from R1
+    other_suffixes: &'static [&'static str],
+    /// Derivational suffixes that are replaced instead of removed
+    other_replacements: &'static [(&'static str, &'static str)],
+    /// Norwegian: a final "s" also goes after "k" and a consonant
+    s_after_k: bool,
+    /// Danish: "-igst" becomes "-ig", a removed derivational suffix is
+    /// followed by another consonant pair check, and a final double
+    /// consonant in R1 is undoubled
+    danish: bool,
+}
+
+const SWEDISH: Tables = Tables {
+    vowels: "aeiouyäåö",
+    s_endings: "bcdfghjklmnoprtvy",
+    main_suffixes: &[
+        "a", "arna", "erna", "heterna", "orna", "ad", "e", "ade", "ande", "arne", "are", "aste",
+        "en", "anden", "aren", "heten", "ern", "ar", "er", "heter", "or", "as", "arnas", "ernas",
+        "ornas", "es", "ades", "andes", "ens", "arens", "hetens", "erns", "at", "andet", "het",
+        "ast", "s",
+    ],
+    main_replacements: &[],
+    consonant_pairs: &["dd", "gd", "nn", "dt", "gt", "kt", "tt"],
+    other_suffixes: &["lig", "ig", "els", "löst", "fullt"],
+    other_replacements: &[("löst", "lös"), ("fullt", "full")],
+    s_after_k: false,
+    danish: false,
+};
+
+const NORWEGIAN: Tables = Tables {
+    vowels: "aeiouyæåø",
+    s_endings: "bcdfghjlmnoprtvyz",
+    main_suffixes: &[
+        "a", "e", "ede", "ande", "ende", "ane", "ene", "hetene", "en", "heten", "ar", "er", "heter",
+        "as", "es", "edes", "endes", "enes", "hetenes", "ens", "hetens", "ers", "ets", "et", "het",
+        "ast", "s", "erte", "ert",
+    ],
+    main_replacements: &[("erte", "er"), ("ert", "er")],
+    consonant_pairs: &["dt", "vt"],
+    other_suffixes: &["leg", "eleg", "ig", "eig", "lig", "elig", "els", "lov", "elov", "slov", "hetslov"],
+    other_replacements: &[],
+    s_after_k: true,
+    danish: false,
+};
+
+const DANISH: Tables = Tables {
+    vowels: "aeiouyæåø",
+    s_endings: "abcdfghjklmnoprtvyzå",
+    main_suffixes: &[
+        "hed", "ethed", "ered", "e", "erede", "ende", "erende", "ene", "erne", "ere", "en", "heden",
+        "eren", "er", "heder", "erer", "heds", "es", "endes", "erendes", "enes", "ernes", "eres",
+        "ens", "hedens", "erens", "ers", "ets", "erets", "et", "eret", "s",
+    ],
+    main_replacements: &[],
+    consonant_pairs: &["gd", "dt", "gt", "kt"],
+    other_suffixes: &["ig", "lig", "elig", "els", "løst"],
+    other_replacements: &[("løst", "løs")],
+    s_after_k: false,
+    danish: true,
+};
+
+/// A Snowball Swedish, Norwegian or Danish stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::ScandinavianStemmer;
+/// assert_eq!(ScandinavianStemmer::swedish().stem("klokheten"), "klok");
+/// assert_eq!(ScandinavianStemmer::norwegian().stem("havnedistrikter"), "havnedistrikt");
+/// assert_eq!(ScandinavianStemmer::danish().stem("undersøgelsen"), "undersøg");
+/// ```
+#[derive(Debug)]
+pub struct ScandinavianStemmer {
+    tables: &'static Tables,
+    /// Word being stemmed
+    buffer: Vec<char>,
+    /// Start of region R1
+    p1: usize,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl ScandinavianStemmer {
+    fn new(tables: &'static Tables) -> Self {
+        ScandinavianStemmer { tables, buffer: Vec::new(), p1: 0, fold_diacritics: false }
+    }
+
+    /// Creates a Swedish stemmer
+    pub fn swedish() -> Self {
+        ScandinavianStemmer::new(&SWEDISH)
+    }
+
+    /// Creates a Norwegian (Bokmål) stemmer
+    pub fn norwegian() -> Self {
+        ScandinavianStemmer::new(&NORWEGIAN)
+    }
+
+    /// Creates a Danish stemmer
+    pub fn danish() -> Self {
+        ScandinavianStemmer::new(&DANISH)
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    ///
+    /// Folding turns "å", "ä", "ö" and "ø" into plain letters, which the
+    /// algorithms then treat differently; it is only useful for text typed
+    /// without them.
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        self.buffer.clear();
+        self.buffer.extend(word.chars().flat_map(char::to_lowercase));
+
+        let len = self.buffer.len();
+        self.p1 = if len < 3 { len } else { after_vowel_consonant(&self.buffer, 0, |c| self.is_vowel(c)).max(3) };
+        self.main_suffix();
+        self.consonant_pair();
+        self.other_suffix();
+        if self.tables.danish { self.undouble(); }
+
+        self.buffer.iter().collect()
+    }
+
+    fn is_vowel(&self, c: char) -> bool {
+        self.tables.vowels.contains(c)
+    }
+
+    fn main_suffix(&mut self) {
+        let t = self.tables;
+        match find_suffix(&self.buffer, t.main_suffixes, self.p1) {
+            Some(("s", start)) => {
+                let b = &self.buffer;
+                let s_ending = start > 0 && t.s_endings.contains(b[start - 1]);
+                let after_k = t.s_after_k && start > 1 && b[start - 1] == 'k' && !self.is_vowel(b[start - 2]);
+                if s_ending || after_k { self.buffer.truncate(start); }
+            },
+            Some((suffix, start)) => match t.main_replacements.iter().find(|&&(s, _)| s == suffix) {
+                Some(&(_, with)) => replace_from(&mut self.buffer, start, with),
+                None => self.buffer.truncate(start),
+            },
+            None => {},
+        }
+    }
+
+    /// Removes the last letter of a final consonant pair in R1
+    fn consonant_pair(&mut self) {
+        if find_suffix(&self.buffer, self.tables.consonant_pairs, self.p1).is_some() {
+            self.buffer.pop();
+        }
+    }
+
+    fn other_suffix(&mut self) {
+        let t = self.tables;
+        if t.danish && ends_with(&self.buffer, "igst") {
+            self.buffer.truncate(self.buffer.len() - 2);
+        }
+        let Some((suffix, start)) = find_suffix(&self.buffer, t.other_suffixes, self.p1) else { return };
+        match t.other_replacements.iter().find(|&&(s, _)| s == suffix) {
+            Some(&(_, with)) => replace_from(&mut self.buffer, start, with),
+            None => {
+                self.buffer.truncate(start);
+                if t.danish { self.consonant_pair(); }
+            },
+        }
+    }
+
+    /// Removes one letter of a final double consonant in R1
+    fn undouble(&mut self) {
+        let b = &self.buffer;
+        let len = b.len();
+        if len >= 2 && len > self.p1 && !self.is_vowel(b[len - 1]) && b[len - 2] == b[len - 1] {
+            self.buffer.pop();
+        }
+    }
+}
+
+impl Stemmer for ScandinavianStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        ScandinavianStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_examples() {
+        let mut stemmer = ScandinavianStemmer::swedish();
+        let cases = [
+            ("jakt", "jakt"), ("jaktbössa", "jaktböss"), ("jakten", "jakt"), ("jakthund", "jakthund"),
+            ("jaktkarl", "jaktkarl"), ("jaktkarlar", "jaktkarl"), ("jaktkarlarne", "jaktkarl"),
+            ("jaktlopp", "jaktlopp"), ("jaktlöst", "jaktlös"),
+            ("klokheten", "klok"), ("klokhet", "klok"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+        let mut stemmer = ScandinavianStemmer::danish();
+        let cases = [
+            ("indtage", "indtag"), ("indtagelse", "indtag"), ("indtager", "indtag"),
+            ("indtages", "indtag"), ("indtaget", "indtag"), ("indtil", "indtil"),
+            ("indtog", "indtog"), ("indtraf", "indtraf"), ("indtryk", "indtryk"),
+            ("indtræder", "indtræd"), ("indtræffe", "indtræf"), ("indtræffer", "indtræf"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_snowball_vocabulary() {
+        // The Norwegian vocabulary and expected output of the Snowball
+        // project's test data (<https://github.com/snowballstem/snowball-data>)
+        let mut stemmer = ScandinavianStemmer::norwegian();
+        let words = include_str!("../test_data/norwegian/voc.txt");
+        let stems = include_str!("../test_data/norwegian/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+
+        // The words of the test data of the lingua crate's Swedish and Danish
+        // language model, each with its stem from the Snowball project's C
+        // libstemmer (<https://snowballstem.org>)
+        let swedish = (include_str!("../test_data/swedish/voc.txt"), include_str!("../test_data/swedish/output.txt"));
+        let danish = (include_str!("../test_data/danish/voc.txt"), include_str!("../test_data/danish/output.txt"));
+        let languages = [(ScandinavianStemmer::swedish(), swedish), (ScandinavianStemmer::danish(), danish)];
+        for (mut stemmer, (words, stems)) in languages {
+            crate::assert_vocabulary(&mut stemmer, words, stems);
+        }
+    }
+}
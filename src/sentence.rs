@@ -0,0 +1,152 @@
+//! # Sentence Splitting
+//!
+//! A lightweight sentence boundary detector feeding per-sentence stemming,
+//! for sentence-scoped term statistics.
+//!
+//! A sentence ends at `.`, `?` or `!` (optionally followed by closing quotes
+//! or brackets) when whitespace follows and the next sentence starts with an
+//! uppercase letter, a digit, an opening quote or bracket, or the input ends.
+//! A blank line always ends a sentence. A period does not end a sentence
+//! after a known abbreviation ("Dr.", "e.g."), a single-letter initial
+//! ("J. R. R. Tolkien") or inside a number ("3.14").
+
+use crate::text::stems;
+use crate::Stemmer;
+
+/// Abbreviations (lowercase, without the final period) that never end a sentence
+const ABBREVIATIONS: &[&str] = &[
+    "al", "approx", "capt", "cf", "co", "col", "corp", "dept", "dr", "e.g",
+    "est", "fig", "gen", "gov", "hon", "i.e", "inc", "jr", "lt", "ltd", "mr",
+    "mrs", "ms", "mt", "no", "prof", "rep", "rev", "sen", "sgt", "sr", "st",
+    "u.k", "u.s", "vol", "vs",
+];
+
+/// Returns true if the word just before a period is an abbreviation or initial
+fn is_abbreviation(before: &str) -> bool {
+    let word_start = before
+        .rfind(|c: char| c.is_whitespace() || matches!(c, '(' | '"' | '\''))
+        .map_or(0, |i| i + 1);
+    let word = &before[word_start..];
+    if word.chars().count() == 1 && word.chars().all(char::is_alphabetic) {
+        return true;
+    }
+    let lower = word.to_lowercase();
+    ABBREVIATIONS.contains(&lower.as_str())
+}
+
+/// Returns true if `c` can be the first character of a new sentence
+fn starts_sentence(c: char) -> bool {
+    c.is_uppercase() || c.is_ascii_digit() || matches!(c, '"' | '\'' | '(' | '[' | '“' | '‘')
+}
+
+/// Adds a sentence, trimmed, unless it is only whitespace
+fn push_trimmed<'a>(sentences: &mut Vec<&'a str>, sentence: &'a str) {
+    let sentence = sentence.trim();
+    if !sentence.is_empty() { sentences.push(sentence); }
+}
+
+/// Splits `text` into sentences, trimmed of surrounding whitespace
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::sentence::split_sentences;
+/// assert_eq!(
+///     split_sentences("Dr. Smith paid $3.50. Why? \"Because!\" he said."),
+///     ["Dr. Smith paid $3.50.", "Why?", "\"Because!\" he said."]
+/// );
+/// ```
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut n = 0;
+
+    while n < chars.len() {
+        let (i, c) = chars[n];
+
+        if c == '\n' {
+            // A blank line ends the sentence whatever precedes it
+            let mut m = n + 1;
+            while m < chars.len() && chars[m].1 != '\n' && chars[m].1.is_whitespace() { m += 1; }
+            if m < chars.len() && chars[m].1 == '\n' {
+                push_trimmed(&mut sentences, &text[start..i]);
+                start = chars[m].0;
+                n = m;
+                continue;
+            }
+        }
+
+        if matches!(c, '.' | '?' | '!') {
+            // Take the whole run of terminators and closing punctuation
+            let mut m = n;
+            while m < chars.len() && matches!(chars[m].1, '.' | '?' | '!') { m += 1; }
+            let single_period = c == '.' && m == n + 1;
+            while m < chars.len() && matches!(chars[m].1, '"' | '\'' | ')' | ']' | '”' | '’') { m += 1; }
+            let end = chars.get(m).map_or(text.len(), |&(j, _)| j);
+
+            let mut next = m;
+            while next < chars.len() && chars[next].1.is_whitespace() { next += 1; }
+            let followed_by_space = next > m || m == chars.len();
+            let next_starts = next == chars.len() || starts_sentence(chars[next].1);
+            let abbreviation = single_period && is_abbreviation(&text[start..i]);
+
+            if followed_by_space && next_starts && !abbreviation {
+                push_trimmed(&mut sentences, &text[start..end]);
+                start = end;
+            }
+            n = m;
+            continue;
+        }
+
+        n += 1;
+    }
+
+    push_trimmed(&mut sentences, &text[start..]);
+    sentences
+}
+
+/// Splits `text` into sentences and returns the stems of each one
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each word
+/// * `text` - Arbitrary input text
+///
+/// # Returns
+/// One list of stems per sentence; sentences without words are left out
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, sentence::stem_sentences};
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(
+///     stem_sentences(&mut stemmer, "Dogs were running. Cats kept jumping!"),
+///     [vec!["dog", "were", "run"], vec!["cat", "kept", "jump"]]
+/// );
+/// ```
+pub fn stem_sentences(stemmer: &mut dyn Stemmer, text: &str) -> Vec<Vec<String>> {
+    split_sentences(text)
+        .into_iter()
+        .map(|sentence| stems(stemmer, sentence))
+        .filter(|stems| !stems.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abbreviations_initials_and_numbers() {
+        assert_eq!(
+            split_sentences("J. R. R. Tolkien wrote it, e.g. in 1937. Pi is 3.14 approx. and that is all"),
+            ["J. R. R. Tolkien wrote it, e.g. in 1937.", "Pi is 3.14 approx. and that is all"]
+        );
+        assert_eq!(split_sentences("Wait... What?! No."), ["Wait...", "What?!", "No."]);
+    }
+
+    #[test]
+    fn test_blank_lines_end_sentences() {
+        assert_eq!(split_sentences("A heading\n\nFirst line\nstill first."), ["A heading", "First line\nstill first."]);
+        assert!(split_sentences("  \n ").is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//! # Serde Field Helper
+//!
+//! `#[serde(with = "m_porter_stemmer_rust::serde_stem")]` on a `String`
+//! field stems it as it is deserialized, so documents ingested from JSON (or
+//! any other serde format) are stored with the field already stemmed. The
+//! value is stemmed as text, word by word, as [`crate::text::stem_text`]
+//! does with the Porter stemmer, and serialized back as it is. The
+//! [`option`] module does the same for an `Option<String>` field. Only
+//! available with the `serde` feature.
+//!
+//! # Examples
+//! ```
+//! use serde::Deserialize;
+//! # use serde::de::{value::{Error, MapDeserializer}, IntoDeserializer};
+//!
+//! #[derive(Deserialize)]
+//! struct Document {
+//!     id: String,
+//!     #[serde(with = "m_porter_stemmer_rust::serde_stem")]
+//!     title: String,
+//!     #[serde(with = "m_porter_stemmer_rust::serde_stem::option", default)]
+//!     summary: Option<String>,
+//! }
+//!
+//! // As serde_json::from_str(r#"{"id": "A-7", "title": "Running ponies"}"#)
+//! let fields = [("id", "A-7"), ("title", "Running ponies")];
+//! let fields = MapDeserializer::<_, Error>::new(fields.into_iter().map(|(k, v)| (k, v.into_deserializer())));
+//! let document = Document::deserialize(fields).unwrap();
+//! assert_eq!((document.id.as_str(), document.title.as_str()), ("A-7", "run poni"));
+//! assert_eq!(document.summary, None);
+//! ```
+
+use std::cell::RefCell;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::text::stem_text;
+use crate::PorterStemmer;
+
+thread_local! {
+    static STEMMER: RefCell<PorterStemmer> = RefCell::new(PorterStemmer::new());
+}
+
+/// Stems `text` with the thread's stemmer
+fn stem(text: &str) -> String {
+    STEMMER.with(|stemmer| stem_text(&mut *stemmer.borrow_mut(), text))
+}
+
+/// Deserializes a string and returns it stemmed
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    Ok(stem(&text))
+}
+
+/// Serializes the (already stemmed) string as it is
+pub fn serialize<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    value.serialize(serializer)
+}
+
+/// The same for `Option<String>` fields: a string is stemmed and a null is
+/// kept; add `default` to the attribute to accept a missing field too
+pub mod option {
+    use super::*;
+    use serde::de::{Error, Visitor};
+
+    /// Accepts a null, or a string with or without an option around it
+    struct OptionVisitor;
+
+    impl<'de> Visitor<'de> for OptionVisitor {
+        type Value = Option<String>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a string or null")
+        }
+
+        fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            super::deserialize(deserializer).map(Some)
+        }
+
+        fn visit_str<E: Error>(self, text: &str) -> Result<Self::Value, E> {
+            Ok(Some(stem(text)))
+        }
+    }
+
+    /// Deserializes an optional string and returns it stemmed
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+        deserializer.deserialize_option(OptionVisitor)
+    }
+
+    /// Serializes the (already stemmed) optional string as it is
+    pub fn serialize<S: Serializer>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::value::{Error, StrDeserializer, StringDeserializer, UnitDeserializer};
+    use serde::de::IntoDeserializer;
+
+    #[test]
+    fn test_deserialize() {
+        let text: StrDeserializer<Error> = "Connected connections".into_deserializer();
+        assert_eq!(deserialize(text).unwrap(), "connect connect");
+        let owned: StringDeserializer<Error> = String::from("hopping").into_deserializer();
+        assert_eq!(deserialize(owned).unwrap(), "hop");
+        let number: serde::de::value::U32Deserializer<Error> = 7u32.into_deserializer();
+        assert!(deserialize(number).is_err());
+
+        let some: StrDeserializer<Error> = "ponies".into_deserializer();
+        assert_eq!(option::deserialize(some).unwrap(), Some("poni".to_string()));
+        assert_eq!(option::deserialize(UnitDeserializer::<Error>::new()).unwrap(), None);
+    }
+}
@@ -0,0 +1,292 @@
+//! The `serve` subcommand of the command line front end
+//!
+//! Built with the `serve` feature, `porterstem serve --port 8080` answers
+//! HTTP/1.1 requests with JSON, so that services written in other languages
+//! can share one stemmer:
+//! - `POST /stem` with `{"word": "running"}` returns
+//!   `{"word":"running","stem":"run"}`
+//! - `POST /stem-batch` with `["running", "ponies"]` (or `{"words": [...]}`)
+//!   returns `{"stems":["run","poni"]}`
+//! - `POST /stem-text` with `{"text": "Running ponies!"}` returns
+//!   `{"text":"run poni!"}`
+//! - `GET /health` returns `{"status":"ok"}`
+//! - `GET /ws` upgrades to a WebSocket that answers each text message with
+//!   its stems, as `/stem-text` would (see [`websocket`](crate::websocket)),
+//!   when built with the `websocket` feature
+//!
+//! Errors come back with a 4xx or 5xx status and `{"error":"..."}`. HTTP is
+//! spoken by tiny_http, which keeps connections open for further requests
+//! unless the client asks to close them and reads bodies sent with a
+//! `Content-Length` or chunked; the bodies are JSON, read by serde_json, of
+//! up to [`MAX_BODY`] bytes. Requests are answered by a thread per CPU, each
+//! with its own stemmer, and each WebSocket on a thread of its own.
+
+use std::io::{self, Read};
+use std::net::TcpListener;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread::{self, Scope};
+
+use m_porter_stemmer_rust::Stemmer;
+use serde_json::{json, Value};
+use tiny_http::{Header, Request, Server};
+
+use crate::daemon;
+#[cfg(feature = "websocket")]
+use crate::websocket;
+
+/// Largest request body accepted
+pub const MAX_BODY: usize = 16 << 20;
+
+/// A status code and JSON body to send back
+#[derive(Debug, PartialEq)]
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Response { status, body: json!({ "error": message }) }
+    }
+}
+
+/// Answers an HTTP request with `stemmer`
+fn handle(stemmer: &mut dyn Stemmer, method: &str, path: &str, body: &[u8]) -> Response {
+    let endpoint = match path {
+        "/health" if method == "GET" => return Response::ok(json!({ "status": "ok" })),
+        "/stem" | "/stem-batch" | "/stem-text" if method == "POST" => &path[1..],
+        "/ws" if method == "GET" && !cfg!(feature = "websocket") => {
+            return Response::error(501, "/ws requires porterstem built with the websocket feature")
+        },
+        "/ws" if method == "GET" => return Response::error(426, "expected a WebSocket upgrade"),
+        "/health" | "/ws" => return Response::error(405, "use GET"),
+        "/stem" | "/stem-batch" | "/stem-text" => return Response::error(405, "use POST"),
+        _ => return Response::error(404, "no such endpoint"),
+    };
+    let params = match serde_json::from_slice(body) {
+        Ok(params) => params,
+        Err(e) => return Response::error(400, &format!("invalid JSON: {}", e)),
+    };
+    match daemon::call(stemmer, endpoint, &params) {
+        Some(Ok(result)) => Response::ok(result),
+        Some(Err(message)) => Response::error(400, message),
+        None => unreachable!("the endpoints are those of call"),
+    }
+}
+
+/// Reads the body of `request`, decoded if it was sent chunked
+fn read_body(request: &mut Request) -> Result<Vec<u8>, Response> {
+    if request.body_length().is_some_and(|length| length > MAX_BODY) {
+        return Err(Response::error(413, "request body too large"));
+    }
+    let mut body = Vec::new();
+    let read = request.as_reader().take(MAX_BODY as u64 + 1).read_to_end(&mut body);
+    match read {
+        Ok(_) if body.len() > MAX_BODY => Err(Response::error(413, "request body too large")),
+        Ok(_) => Ok(body),
+        Err(_) => Err(Response::error(400, "incomplete request body")),
+    }
+}
+
+/// The value of header `name` of `request`, if it has one
+#[cfg(feature = "websocket")]
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request.headers().iter().find(|header| header.field.equiv(name)).map(|header| header.value.as_str())
+}
+
+/// The `Sec-WebSocket-Key` of a request to upgrade `GET /ws` to a WebSocket,
+/// or an error response if it asks for a version other than 13
+#[cfg(feature = "websocket")]
+fn websocket_key(request: &Request) -> Option<Result<String, Response>> {
+    let upgrade = header(request, "Upgrade").is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    if request.method().as_str() != "GET" || path(request) != "/ws" || !upgrade {
+        return None;
+    }
+    let key = header(request, "Sec-WebSocket-Key")?;
+    match header(request, "Sec-WebSocket-Version") {
+        Some("13") => Some(Ok(key.to_string())),
+        _ => Some(Err(Response::error(426, "only WebSocket version 13 is supported"))),
+    }
+}
+
+/// The path of the request's URL, without its query
+fn path(request: &Request) -> &str {
+    request.url().split('?').next().unwrap_or_default()
+}
+
+fn respond(request: Request, response: Response) {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    let response = tiny_http::Response::from_string(response.body.to_string())
+        .with_status_code(response.status)
+        .with_header(content_type);
+    // A client going away mid-request is its own business
+    request.respond(response).ok();
+}
+
+/// Answers a request with `stemmer`
+///
+/// A request that makes the stemmer panic gets a 500 response, and the
+/// requests after it a fresh stemmer from `make_stemmer`.
+fn answer(
+    stemmer: &mut Box<dyn Stemmer>,
+    make_stemmer: &impl Fn() -> Box<dyn Stemmer>,
+    request: &mut Request,
+) -> Response {
+    let body = match read_body(request) {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+        handle(&mut **stemmer, request.method().as_str(), path(request), &body)
+    }));
+    answer.unwrap_or_else(|_| {
+        *stemmer = make_stemmer();
+        Response::error(500, "stemming failed")
+    })
+}
+
+/// Upgrades a request for a WebSocket and serves it on a thread of its own,
+/// which is kept for as long as its user types, or gives the request back
+/// if it is not one
+#[cfg(feature = "websocket")]
+fn upgrade<'scope, F: Fn() -> Box<dyn Stemmer> + Sync>(
+    scope: &'scope Scope<'scope, '_>,
+    request: Request,
+    make_stemmer: &'scope F,
+) -> Option<Request> {
+    match websocket_key(&request) {
+        None => return Some(request),
+        Some(Err(response)) => respond(request, response),
+        Some(Ok(key)) => {
+            let accept = websocket::accept_key(&key);
+            let accept = Header::from_bytes(&b"Sec-WebSocket-Accept"[..], accept.as_bytes()).unwrap();
+            let switching = tiny_http::Response::empty(101).with_header(accept);
+            scope.spawn(move || {
+                let stream = request.upgrade("websocket", switching);
+                websocket::serve_messages(make_stemmer, stream, Vec::new()).ok()
+            });
+        },
+    }
+    None
+}
+
+/// Gives the request back, as only the `websocket` feature upgrades any
+#[cfg(not(feature = "websocket"))]
+fn upgrade<'scope, F>(_: &'scope Scope<'scope, '_>, request: Request, _: &'scope F) -> Option<Request> {
+    Some(request)
+}
+
+/// Answers requests to `server` with a stemmer from `make_stemmer` until
+/// receiving one fails
+fn answer_requests<'scope, F: Fn() -> Box<dyn Stemmer> + Sync>(
+    scope: &'scope Scope<'scope, '_>,
+    server: &'scope Server,
+    make_stemmer: &'scope F,
+) -> io::Error {
+    let mut stemmer = make_stemmer();
+    loop {
+        let request = match server.recv() {
+            Ok(request) => request,
+            Err(e) => return e,
+        };
+        let Some(mut request) = upgrade(scope, request, make_stemmer) else { continue };
+        let response = answer(&mut stemmer, make_stemmer, &mut request);
+        respond(request, response);
+    }
+}
+
+/// Serves connections to `listener` until serving fails, answering requests
+/// with stemmers from `make_stemmer`
+pub fn serve(listener: TcpListener, make_stemmer: impl Fn() -> Box<dyn Stemmer> + Sync) -> io::Error {
+    let server = match Server::from_listener(listener, None) {
+        Ok(server) => server,
+        Err(e) => return io::Error::other(e),
+    };
+    let threads = thread::available_parallelism().map_or(1, usize::from);
+    thread::scope(|scope| {
+        let (server, make_stemmer) = (&server, &make_stemmer);
+        let workers: Vec<_> =
+            (0..threads).map(|_| scope.spawn(move || answer_requests(scope, server, make_stemmer))).collect();
+        // Receiving fails for every worker at once
+        let errors = workers.into_iter().map(|worker| worker.join().unwrap_or_else(|_| io::Error::other("panic")));
+        errors.last().unwrap_or_else(|| io::Error::other("no thread answered requests"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m_porter_stemmer_rust::PorterStemmer;
+    use std::io::Write;
+    use std::net::TcpStream;
+
+    #[test]
+    fn test_handle() {
+        let mut stemmer = PorterStemmer::new();
+        let mut post = |path: &str, body: &str| {
+            let response = handle(&mut stemmer, "POST", path, body.as_bytes());
+            (response.status, response.body.to_string())
+        };
+        assert_eq!(post("/stem", r#"{"word": "running"}"#), (200, r#"{"word":"running","stem":"run"}"#.into()));
+        assert_eq!(post("/stem-batch", r#"["caresses", "ponies"]"#), (200, r#"{"stems":["caress","poni"]}"#.into()));
+        assert_eq!(post("/stem-batch", r#"{"words": []}"#), (200, r#"{"stems":[]}"#.into()));
+        assert_eq!(post("/stem-text", r#"{"text": "Running\nponies"}"#), (200, r#"{"text":"run\nponi"}"#.into()));
+        let error = r#"{"error":"invalid JSON: EOF while parsing an object at line 1 column 1"}"#;
+        assert_eq!(post("/stem", "{"), (400, error.into()));
+        assert_eq!(post("/stem-batch", "[1]").0, 400);
+        assert_eq!(post("/nope", "{}").0, 404);
+        assert_eq!(handle(&mut stemmer, "GET", "/stem", b"").status, 405);
+        assert_eq!(handle(&mut stemmer, "GET", "/health", b"").body, json!({ "status": "ok" }));
+        let status = handle(&mut stemmer, "GET", "/ws", b"").status;
+        assert_eq!(status, if cfg!(feature = "websocket") { 426 } else { 501 });
+    }
+
+    #[test]
+    fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, || Box::new(PorterStemmer::new())));
+
+        // Two requests on one connection, the second with a chunked body
+        // and asking to close it
+        let mut stream = TcpStream::connect(address).unwrap();
+        let body = r#"{"word":"ponies"}"#;
+        write!(stream, "POST /stem HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        write!(stream, "POST /stem-batch?verbose HTTP/1.1\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        write!(stream, "5\r\n[\"cat\r\n5\r\ns\"]\r\n\r\n0\r\n\r\n").unwrap();
+        let mut responses = String::new();
+        stream.read_to_string(&mut responses).unwrap();
+        let (first, second) = responses.split_once("\r\n\r\n{\"word\":\"ponies\",\"stem\":\"poni\"}").unwrap();
+        assert!(first.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(first.contains("\r\nContent-Type: application/json\r\n"));
+        assert!(second.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(second.ends_with("\r\n\r\n{\"stems\":[\"cat\"]}"));
+    }
+
+    #[cfg(feature = "websocket")]
+    #[test]
+    fn test_serve_websocket() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, || Box::new(PorterStemmer::new())));
+
+        // A WebSocket answering one masked message, then closing
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "GET /ws HTTP/1.1\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n").unwrap();
+        write!(stream, "Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n").unwrap();
+        stream.write_all(&[0x81, 0x86, 0, 0, 0, 0]).unwrap();
+        stream.write_all(b"ponies\x88\x80\0\0\0\0").unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        let end = response.windows(4).position(|bytes| bytes == b"\r\n\r\n").unwrap() + 4;
+        let head = String::from_utf8_lossy(&response[..end]);
+        assert!(head.starts_with("HTTP/1.1 101 Switching Protocols\r\n"));
+        assert!(head.contains("\r\nSec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"));
+        assert_eq!(&response[end..], b"\x81\x04poni\x88\x00");
+    }
+}
@@ -0,0 +1,160 @@
+//! # Snowball Helpers
+//!
+//! Pieces shared by the Snowball stemmers: suffix matching on a character
+//! buffer and the R1/R2 regions. In the Snowball languages a suffix is
+//! usually only removed if it lies entirely inside a region, a part of the
+//! word after its first vowel-consonant pair; R1 starts after the first such
+//! pair and R2 after the first one inside R1. Regions are given as the index
+//! at which they start, the word's length if they are empty.
+//!
+//! The Greek stemmer has no regions, but reads Greek letters through
+//! [`greek_base_letter`] and its suffix rules classify them with
+//! [`is_greek_vowel`].
+
+/// Returns true if `word` ends with `suffix`
+pub(crate) fn ends_with(word: &[char], suffix: &str) -> bool {
+    let n = suffix.chars().count();
+    n <= word.len() && word[word.len() - n..].iter().copied().eq(suffix.chars())
+}
+
+/// The longest of `suffixes` that `word` ends with and that starts at or
+/// after `limit`, with the index where it starts
+///
+/// This is Snowball's `[substring] among (...)` in backward mode: the
+/// longest match is chosen without regard to the conditions of its rule,
+/// so a rule whose condition fails does not fall back to a shorter suffix.
+pub(crate) fn find_suffix<'s>(word: &[char], suffixes: &[&'s str], limit: usize) -> Option<(&'s str, usize)> {
+    suffixes
+        .iter()
+        .filter(|s| ends_with(word, s))
+        .map(|&s| (s, word.len() - s.chars().count()))
+        .filter(|&(_, start)| start >= limit)
+        .max_by_key(|&(_, start)| std::cmp::Reverse(start))
+}
+
+/// The longest suffix of any of `groups` that `word` ends with and that
+/// starts at or after `limit`, as the replacement of its group and the
+/// index where it starts
+///
+/// Each group is a list of suffixes and the string they are all replaced
+/// with, as in an `among` whose entries share one command.
+pub(crate) fn find_group<'s>(word: &[char], groups: &[(&[&str], &'s str)], limit: usize) -> Option<(&'s str, usize)> {
+    groups
+        .iter()
+        .filter_map(|&(suffixes, with)| find_suffix(word, suffixes, limit).map(|(_, start)| (with, start)))
+        .min_by_key(|&(_, start)| start)
+}
+
+/// Replaces everything from `start` on with `with`
+pub(crate) fn replace_from(word: &mut Vec<char>, start: usize, with: &str) {
+    word.truncate(start);
+    word.extend(with.chars());
+}
+
+/// The index just past the first vowel followed by a non-vowel at or after
+/// `from`, or the word's length if there is none
+///
+/// This is Snowball's `gopast v gopast non-v`.
+pub(crate) fn after_vowel_consonant(word: &[char], from: usize, is_vowel: impl Fn(char) -> bool) -> usize {
+    (from + 1..word.len())
+        .find(|&i| is_vowel(word[i - 1]) && !is_vowel(word[i]))
+        .map_or(word.len(), |i| i + 1)
+}
+
+/// The standard R1 and R2 regions of `word`
+pub(crate) fn regions(word: &[char], is_vowel: impl Fn(char) -> bool + Copy) -> (usize, usize) {
+    let r1 = after_vowel_consonant(word, 0, is_vowel);
+    (r1, after_vowel_consonant(word, r1, is_vowel))
+}
+
+/// The region RV of the Spanish, Portuguese and Italian stemmers
+///
+/// If the second letter is a consonant, RV is the region after the next
+/// vowel; if the first two letters are vowels, it is the region after the
+/// next consonant; otherwise it is the region after the third letter. As
+/// in the Snowball source, a word that starts with a vowel and a consonant
+/// but has no later vowel has RV after its second letter.
+pub(crate) fn rv_region(word: &[char], is_vowel: impl Fn(char) -> bool) -> usize {
+    let len = word.len();
+    if len < 2 { return len; }
+    let vowel = |i: usize| is_vowel(word[i]);
+    let after = |found: Option<usize>| found.map_or(len, |i| i + 1);
+    match (vowel(0), vowel(1)) {
+        (true, false) => (2..len).find(|&i| vowel(i)).map_or(2, |i| i + 1),
+        (false, false) => after((2..len).find(|&i| vowel(i))),
+        (true, true) => after((2..len).find(|&i| !vowel(i))),
+        (false, true) => len.min(3),
+    }
+}
+
+/// Returns true if `c` is one of the seven Greek vowels, unaccented and
+/// lowercase
+pub(crate) fn is_greek_vowel(c: char) -> bool {
+    matches!(c, 'α' | 'ε' | 'η' | 'ι' | 'ο' | 'υ' | 'ω')
+}
+
+/// The unaccented lowercase form of a lowercase Greek letter: the tonos and
+/// dialytika are dropped ("ά", "ϊ" and "ΐ" become "α", "ι" and "ι") and
+/// the final sigma "ς" becomes "σ"
+///
+/// Other characters are returned unchanged.
+pub(crate) fn greek_base_letter(c: char) -> char {
+    match c {
+        'ά' => 'α',
+        'έ' => 'ε',
+        'ή' => 'η',
+        'ί' | 'ϊ' | 'ΐ' => 'ι',
+        'ό' => 'ο',
+        'ύ' | 'ϋ' | 'ΰ' => 'υ',
+        'ώ' => 'ω',
+        'ς' => 'σ',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_vowel(c: char) -> bool {
+        matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+    }
+
+    #[test]
+    fn test_regions() {
+        // The examples of the Snowball documentation
+        let word: Vec<char> = "beautiful".chars().collect();
+        assert_eq!(regions(&word, is_vowel), (5, 7));
+        let word: Vec<char> = "beauty".chars().collect();
+        assert_eq!(regions(&word, is_vowel), (5, 6));
+        let word: Vec<char> = "animadversion".chars().collect();
+        assert_eq!(regions(&word, is_vowel), (2, 4));
+        let word: Vec<char> = "sprinkled".chars().collect();
+        assert_eq!(regions(&word, is_vowel), (5, 9));
+    }
+
+    #[test]
+    fn test_rv_region() {
+        let rv = |word: &str| rv_region(&word.chars().collect::<Vec<_>>(), is_vowel);
+        assert_eq!((rv("macho"), rv("oliva"), rv("trabajo"), rv("áureo")), (3, 3, 3, 3));
+        assert_eq!((rv("ab"), rv("arc"), rv("str"), rv("x")), (2, 2, 3, 1));
+    }
+
+    #[test]
+    fn test_find_suffix() {
+        let word: Vec<char> = "nations".chars().collect();
+        assert_eq!(find_suffix(&word, &["s", "ions", "ations"], 0), Some(("ations", 1)));
+        assert_eq!(find_suffix(&word, &["s", "ions", "ations"], 2), Some(("ions", 3)));
+        assert_eq!(find_suffix(&word, &["ing"], 0), None);
+        let groups: &[(&[&str], &str)] = &[(&["s", "ns"], ""), (&["ions"], "ion")];
+        assert_eq!(find_group(&word, groups, 0), Some(("ion", 3)));
+        assert_eq!(find_group(&word, groups, 4), Some(("", 5)));
+    }
+
+    #[test]
+    fn test_greek_letters() {
+        let word: String = "Ταΐζεις".chars().flat_map(char::to_lowercase).map(greek_base_letter).collect();
+        assert_eq!(word, "ταιζεισ");
+        assert_eq!(word.chars().filter(|&c| is_greek_vowel(c)).count(), 4);
+    }
+}
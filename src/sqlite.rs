@@ -0,0 +1,188 @@
+//! # SQLite Output
+//!
+//! Writes stemming results as an SQLite database, so that they can be
+//! queried with SQL. [`StemDatabase`] collects the documents, tokens and
+//! stems of a corpus in this schema:
+//!
+//! ```sql
+//! CREATE TABLE documents (id INTEGER PRIMARY KEY, path TEXT NOT NULL);
+//! CREATE TABLE stems (id INTEGER PRIMARY KEY, stem TEXT NOT NULL, count INTEGER NOT NULL);
+//! CREATE TABLE tokens (id INTEGER PRIMARY KEY, document_id INTEGER NOT NULL, offset INTEGER NOT NULL,
+//!     token TEXT NOT NULL, stem_id INTEGER NOT NULL);
+//! CREATE TABLE document_stems (document_id INTEGER NOT NULL, stem_id INTEGER NOT NULL, count INTEGER NOT NULL);
+//! CREATE VIEW stem_frequencies AS SELECT stem, count FROM stems ORDER BY count DESC, stem;
+//! ```
+//!
+//! `tokens` has a row for every word of every document, with its byte
+//! offset in the document; `stems` has each distinct stem with its count
+//! in the whole corpus, and `document_stems` its count in each document
+//! that has it. Stems are numbered in order of first appearance. There are
+//! no indexes; `CREATE INDEX` them as the queries need.
+//!
+//! The database is built in memory with the `rusqlite` crate, on the SQLite
+//! library it compiles in, and copied to a file by [`StemDatabase::save`].
+//! Only available with the `sqlite` feature.
+//!
+//! # Examples
+//! ```
+//! # use m_porter_stemmer_rust::{PorterStemmer, sqlite::StemDatabase};
+//! let mut stemmer = PorterStemmer::new();
+//! let mut db = StemDatabase::new().unwrap();
+//! db.add_document(&mut stemmer, "a.txt", "Running ponies".as_bytes()).unwrap();
+//! db.add_document(&mut stemmer, "b.txt", "ponies run".as_bytes()).unwrap();
+//! assert_eq!(db.stem_count("poni"), 2);
+//!
+//! let path = std::env::temp_dir().join(format!("stems-doc-{}.db", std::process::id()));
+//! db.save(&path).unwrap();
+//! assert!(std::fs::read(&path).unwrap().starts_with(b"SQLite format 3\0"));
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::path::Path;
+
+use rusqlite::{params, Connection, MAIN_DB};
+
+use crate::text::{read_chunks, token_stems};
+use crate::Stemmer;
+
+const SCHEMA: &str = "
+    CREATE TABLE documents (id INTEGER PRIMARY KEY, path TEXT NOT NULL);
+    CREATE TABLE stems (id INTEGER PRIMARY KEY, stem TEXT NOT NULL, count INTEGER NOT NULL);
+    CREATE TABLE tokens (id INTEGER PRIMARY KEY, document_id INTEGER NOT NULL, offset INTEGER NOT NULL,
+        token TEXT NOT NULL, stem_id INTEGER NOT NULL);
+    CREATE TABLE document_stems (document_id INTEGER NOT NULL, stem_id INTEGER NOT NULL, count INTEGER NOT NULL);
+    CREATE VIEW stem_frequencies AS SELECT stem, count FROM stems ORDER BY count DESC, stem;
+";
+
+/// An SQLite error, as the `io::Error` the rest of the module returns
+fn sql_error(e: rusqlite::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+/// The documents, tokens and stems of a corpus, in an SQLite database of
+/// the schema above
+#[derive(Debug)]
+pub struct StemDatabase {
+    db: Connection,
+    /// Number and count of each stem, by stem
+    stems: HashMap<String, (i64, u64)>,
+    stem_order: Vec<String>,
+}
+
+impl StemDatabase {
+    /// Creates a database in memory, with no documents
+    pub fn new() -> io::Result<Self> {
+        let db = Connection::open_in_memory().map_err(sql_error)?;
+        db.execute_batch(SCHEMA).map_err(sql_error)?;
+        Ok(StemDatabase { db, stems: HashMap::new(), stem_order: Vec::new() })
+    }
+
+    /// Stems the text read from `input` as the document `path`, adding its
+    /// tokens and stem counts
+    ///
+    /// Input that is not UTF-8 is an `InvalidData` error, and leaves the
+    /// document with the tokens read before it.
+    pub fn add_document(&mut self, stemmer: &mut dyn Stemmer, path: &str, input: impl Read) -> io::Result<()> {
+        let db = self.db.transaction().map_err(sql_error)?;
+        db.execute("INSERT INTO documents (path) VALUES (?1)", [path]).map_err(sql_error)?;
+        let document = db.last_insert_rowid();
+        // Count of each stem in the document, by stem number
+        let mut counts: Vec<(i64, u64)> = Vec::new();
+        let result = {
+            let mut insert = db
+                .prepare("INSERT INTO tokens (document_id, offset, token, stem_id) VALUES (?1, ?2, ?3, ?4)")
+                .map_err(sql_error)?;
+            let mut offset = 0;
+            read_chunks(input, |chunk| {
+                for (token, stem) in token_stems(stemmer, chunk) {
+                    let next = self.stem_order.len() as i64 + 1;
+                    let entry = self.stems.entry(stem).or_insert_with_key(|stem| {
+                        self.stem_order.push(stem.clone());
+                        (next, 0)
+                    });
+                    entry.1 += 1;
+                    let stem_id = entry.0;
+                    let start = (offset + token.start) as i64;
+                    insert.execute(params![document, start, token.text, stem_id]).map_err(sql_error)?;
+                    match counts.binary_search_by_key(&stem_id, |&(id, _)| id) {
+                        Ok(i) => counts[i].1 += 1,
+                        Err(i) => counts.insert(i, (stem_id, 1)),
+                    }
+                }
+                offset += chunk.len();
+                Ok(())
+            })
+        };
+        {
+            let mut insert = db
+                .prepare("INSERT INTO document_stems (document_id, stem_id, count) VALUES (?1, ?2, ?3)")
+                .map_err(sql_error)?;
+            for (stem_id, count) in counts {
+                insert.execute(params![document, stem_id, count as i64]).map_err(sql_error)?;
+            }
+        }
+        db.commit().map_err(sql_error)?;
+        result
+    }
+
+    /// Number of times `stem` was found in all the documents
+    pub fn stem_count(&self, stem: &str) -> u64 {
+        self.stems.get(stem).map_or(0, |&(_, count)| count)
+    }
+
+    /// Returns the database, with the `stems` table filled in now that the
+    /// counts are known, to query or to save elsewhere
+    pub fn into_connection(mut self) -> io::Result<Connection> {
+        let db = self.db.transaction().map_err(sql_error)?;
+        {
+            let mut insert = db.prepare("INSERT INTO stems (id, stem, count) VALUES (?1, ?2, ?3)").map_err(sql_error)?;
+            for stem in &self.stem_order {
+                let (id, count) = self.stems[stem];
+                insert.execute(params![id, stem, count as i64]).map_err(sql_error)?;
+            }
+        }
+        db.commit().map_err(sql_error)?;
+        Ok(self.db)
+    }
+
+    /// Writes the database file to `path`, replacing any file there
+    pub fn save(self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.into_connection()?.backup(MAIN_DB, path, None).map_err(sql_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_stem_database() {
+        let mut stemmer = PorterStemmer::new();
+        let mut db = StemDatabase::new().unwrap();
+        db.add_document(&mut stemmer, "a.txt", "Running ponies ran".as_bytes()).unwrap();
+        db.add_document(&mut stemmer, "b.txt", "ponies run, ponies".as_bytes()).unwrap();
+        let error = db.add_document(&mut stemmer, "c.txt", &b"runs \xFF"[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let db = db.into_connection().unwrap();
+        let query = |sql: &str| -> Vec<(String, i64)> {
+            let mut statement = db.prepare(sql).unwrap();
+            statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap().map(Result::unwrap).collect()
+        };
+        let frequencies = query("SELECT stem, count FROM stem_frequencies");
+        let expected = [("poni", 3), ("run", 2), ("ran", 1)];
+        assert_eq!(frequencies, expected.map(|(stem, count)| (stem.to_string(), count)));
+        // The document is kept, without the chunk holding the bad bytes
+        let documents =
+            query("SELECT path, (SELECT count(*) FROM tokens WHERE document_id = documents.id) FROM documents");
+        assert_eq!(documents.last(), Some(&("c.txt".to_string(), 0)));
+        let per_document = query(
+            "SELECT path, document_stems.count FROM document_stems JOIN documents ON documents.id = document_id \
+             JOIN stems ON stems.id = stem_id WHERE stem = 'poni' ORDER BY path",
+        );
+        assert_eq!(per_document, [("a.txt".to_string(), 1), ("b.txt".to_string(), 2)]);
+    }
+}
@@ -0,0 +1,154 @@
+//! # Corpus Statistics
+//!
+//! Summarizes what a stemmer does to the vocabulary of a corpus: how many
+//! tokens and distinct words there are, how many distinct stems they are
+//! reduced to, and which stems conflate the most distinct words. A quick
+//! look at these is a good sanity check of a stemmer on a new corpus.
+//!
+//! Words are compared lowercased, so "Running" and "running" are one word.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::text::token_stems;
+use crate::Stemmer;
+
+/// Word and stem counts of a corpus, built up text by text
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, stats::CorpusStats};
+/// let mut stemmer = PorterStemmer::new();
+/// let mut stats = CorpusStats::new();
+/// stats.feed(&mut stemmer, "Connected connections connect.");
+/// stats.feed(&mut stemmer, "Running dogs run");
+/// assert_eq!(stats.tokens(), 6);
+/// assert_eq!(stats.unique_words(), 6);
+/// assert_eq!(stats.unique_stems(), 3);
+/// assert_eq!(stats.compression_ratio(), 2.0);
+/// assert_eq!(stats.top_conflations(1), [("connect", vec!["connect", "connected", "connections"])]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CorpusStats {
+    /// Distinct lowercase words of each stem
+    classes: HashMap<String, BTreeSet<String>>,
+    tokens: u64,
+}
+
+impl CorpusStats {
+    /// Creates empty statistics
+    pub fn new() -> Self {
+        CorpusStats { classes: HashMap::new(), tokens: 0 }
+    }
+
+    /// Adds the words of `text`, which must not end in the middle of a word
+    /// that the next text continues (lines and paragraphs are fine)
+    pub fn feed(&mut self, stemmer: &mut dyn Stemmer, text: &str) {
+        for (token, stem) in token_stems(stemmer, text) {
+            self.tokens += 1;
+            self.classes.entry(stem).or_default().insert(token.text.to_lowercase());
+        }
+    }
+
+    /// Number of words in the corpus
+    pub fn tokens(&self) -> u64 {
+        self.tokens
+    }
+
+    /// Number of distinct words
+    pub fn unique_words(&self) -> usize {
+        self.classes.values().map(BTreeSet::len).sum()
+    }
+
+    /// Number of distinct stems
+    pub fn unique_stems(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Distinct words per distinct stem, 1 meaning that stemming merged
+    /// nothing (and 0 for an empty corpus)
+    pub fn compression_ratio(&self) -> f64 {
+        match self.unique_stems() {
+            0 => 0.0,
+            stems => self.unique_words() as f64 / stems as f64,
+        }
+    }
+
+    /// Mean length of the distinct stems, in characters
+    pub fn average_stem_length(&self) -> f64 {
+        match self.unique_stems() {
+            0 => 0.0,
+            stems => self.classes.keys().map(|stem| stem.chars().count()).sum::<usize>() as f64 / stems as f64,
+        }
+    }
+
+    /// Returns the `n` stems that merge the most distinct words, with their
+    /// words in alphabetical order; ties are broken alphabetically by stem
+    pub fn top_conflations(&self, n: usize) -> Vec<(&str, Vec<&str>)> {
+        let mut classes: Vec<(&String, &BTreeSet<String>)> = self.classes.iter().collect();
+        classes.sort_unstable_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+        classes
+            .into_iter()
+            .take(n)
+            .map(|(stem, words)| (stem.as_str(), words.iter().map(String::as_str).collect()))
+            .collect()
+    }
+
+    /// Returns a plain text report of the statistics and the `top` largest
+    /// conflation classes
+    pub fn report(&self, top: usize) -> Report<'_> {
+        Report { stats: self, top }
+    }
+}
+
+/// Plain text report of [`CorpusStats`], see [`CorpusStats::report`]
+#[derive(Debug, Clone, Copy)]
+pub struct Report<'a> {
+    stats: &'a CorpusStats,
+    top: usize,
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = self.stats;
+        writeln!(f, "tokens:              {}", stats.tokens())?;
+        writeln!(f, "unique words:        {}", stats.unique_words())?;
+        writeln!(f, "unique stems:        {}", stats.unique_stems())?;
+        writeln!(f, "compression ratio:   {:.2} words per stem", stats.compression_ratio())?;
+        writeln!(f, "average stem length: {:.2}", stats.average_stem_length())?;
+        let classes = stats.top_conflations(self.top);
+        if !classes.is_empty() {
+            writeln!(f, "top conflation classes:")?;
+            for (stem, words) in classes {
+                writeln!(f, "  {}\t{}\t{}", stem, words.len(), words.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_report() {
+        let mut stemmer = PorterStemmer::new();
+        let mut stats = CorpusStats::new();
+        assert_eq!(stats.compression_ratio(), 0.0);
+        stats.feed(&mut stemmer, "Cats, cat and CATS; the runner runs");
+        let report = stats.report(2).to_string();
+        assert_eq!(
+            report,
+            "tokens:              7\n\
+             unique words:        6\n\
+             unique stems:        5\n\
+             compression ratio:   1.20 words per stem\n\
+             average stem length: 3.60\n\
+             top conflation classes:\n  \
+               cat\t2\tcat cats\n  \
+               and\t1\tand\n"
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! # Stem Keys
+//!
+//! A stem is an index key, not something to show to users: "poni" and
+//! "troubl" are not words. [`StemKey`] wraps a stem so that maps and sets
+//! keyed by stems say so in their type, and so that a stem cannot be passed
+//! where a display string is expected by accident. It deliberately does not
+//! implement `Display`; use [`crate::stem_map::StemMap`] to get readable
+//! words back.
+
+use std::borrow::Borrow;
+
+/// A stem, for use as a `HashMap`/`BTreeMap` key
+///
+/// Maps keyed by `StemKey` can be queried with a plain `&str` stem.
+///
+/// # Examples
+/// ```
+/// # use std::collections::HashMap;
+/// # use m_porter_stemmer_rust::{PorterStemmer, Stemmer};
+/// let mut stemmer = PorterStemmer::new();
+/// let mut postings = HashMap::new();
+/// postings.insert(stemmer.stem_key("running"), vec![0, 3]);
+/// assert_eq!(postings.get(&stemmer.stem_key("runs")), Some(&vec![0, 3]));
+/// assert!(postings.contains_key("run"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct StemKey(String);
+
+impl StemKey {
+    /// Wraps a string that is already a stem
+    pub fn new(stem: impl Into<String>) -> Self {
+        StemKey(stem.into())
+    }
+
+    /// The stem as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Unwraps the stem
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl Borrow<str> for StemKey {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for StemKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<StemKey> for String {
+    fn from(key: StemKey) -> String {
+        key.0
+    }
+}
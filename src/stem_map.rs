@@ -0,0 +1,106 @@
+//! # Stem Map
+//!
+//! Records, for each stem seen in a corpus, every word form that produced
+//! it, so stemmed queries can be shown as the human-readable words they
+//! expand to ("run" -> "run", "running", "runs").
+//!
+//! Word forms are stored in lower case and kept sorted.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::text::tokens_for;
+use crate::Stemmer;
+
+/// A map from stems back to the surface forms seen for them
+#[derive(Debug, Clone, Default)]
+pub struct StemMap {
+    forms: HashMap<String, BTreeSet<String>>,
+}
+
+impl StemMap {
+    /// Creates an empty map
+    pub fn new() -> Self {
+        StemMap { forms: HashMap::new() }
+    }
+
+    /// Builds a map over every word of `text`
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::{PorterStemmer, stem_map::StemMap};
+    /// let mut stemmer = PorterStemmer::new();
+    /// let map = StemMap::from_text(&mut stemmer, "Run! She runs, he is running.");
+    /// let forms: Vec<&str> = map.forms("run").unwrap().iter().map(String::as_str).collect();
+    /// assert_eq!(forms, ["run", "running", "runs"]);
+    /// ```
+    pub fn from_text(stemmer: &mut dyn Stemmer, text: &str) -> Self {
+        let mut map = Self::new();
+        map.add_text(stemmer, text);
+        map
+    }
+
+    /// Records every word of `text`
+    pub fn add_text(&mut self, stemmer: &mut dyn Stemmer, text: &str) {
+        for token in tokens_for(stemmer, text) { self.add_word(stemmer, token.text); }
+    }
+
+    /// Records a single word under its stem, returning the stem
+    pub fn add_word(&mut self, stemmer: &mut dyn Stemmer, word: &str) -> String {
+        let stem = stemmer.stem(word);
+        let forms = self.forms.entry(stem.clone()).or_default();
+        if !forms.contains(word) { forms.insert(word.to_lowercase()); }
+        stem
+    }
+
+    /// The surface forms seen for `stem`, if any
+    pub fn forms(&self, stem: &str) -> Option<&BTreeSet<String>> {
+        self.forms.get(stem)
+    }
+
+    /// The surface forms sharing a stem with `word`, which is stemmed first
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::{PorterStemmer, stem_map::StemMap};
+    /// let mut stemmer = PorterStemmer::new();
+    /// let map = StemMap::from_text(&mut stemmer, "connected connections");
+    /// assert_eq!(map.expand(&mut stemmer, "connecting").len(), 2);
+    /// ```
+    pub fn expand(&self, stemmer: &mut dyn Stemmer, word: &str) -> Vec<&str> {
+        self.forms(&stemmer.stem(word))
+            .map(|forms| forms.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Iterates over the stems and their surface forms, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BTreeSet<String>)> {
+        self.forms.iter().map(|(stem, forms)| (stem.as_str(), forms))
+    }
+
+    /// Number of distinct stems
+    pub fn len(&self) -> usize {
+        self.forms.len()
+    }
+
+    /// Returns true if no word has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.forms.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_forms_are_lowercased_and_deduplicated() {
+        let mut stemmer = PorterStemmer::new();
+        let mut map = StemMap::new();
+        assert_eq!(map.add_word(&mut stemmer, "Cats"), "cat");
+        map.add_text(&mut stemmer, "cats CAT cat");
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.expand(&mut stemmer, "cat"), ["cat", "cats"]);
+        assert!(map.expand(&mut stemmer, "dog").is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+//! # Stem Set
+//!
+//! Collects the distinct stems of a corpus as they arrive, for vocabulary
+//! building without keeping every token around. Each stem is stored once,
+//! together with the order in which it was first seen, so the vocabulary
+//! can be reported in insertion order or sorted.
+
+use std::collections::HashMap;
+
+use crate::text::tokens_for;
+use crate::Stemmer;
+
+/// A deduplicating collector of stems
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, stem_set::StemSet};
+/// let mut stemmer = PorterStemmer::new();
+/// let mut vocabulary = StemSet::new();
+/// vocabulary.add_text(&mut stemmer, "Running dogs chase a running cat");
+/// assert_eq!(vocabulary.insertion_order(), ["run", "dog", "chase", "a", "cat"]);
+/// assert_eq!(vocabulary.sorted(), ["a", "cat", "chase", "dog", "run"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StemSet {
+    /// Each stem and the position at which it was first inserted
+    stems: HashMap<String, usize>,
+}
+
+impl StemSet {
+    /// Creates an empty set
+    pub fn new() -> Self {
+        StemSet { stems: HashMap::new() }
+    }
+
+    /// Adds an already stemmed term, returning true if it was not yet present
+    pub fn insert(&mut self, stem: &str) -> bool {
+        if self.stems.contains_key(stem) { return false; }
+        let position = self.stems.len();
+        self.stems.insert(stem.to_string(), position);
+        true
+    }
+
+    /// Stems `word` and adds its stem, returning true if it was not yet present
+    pub fn add_word(&mut self, stemmer: &mut dyn Stemmer, word: &str) -> bool {
+        self.insert(&stemmer.stem(word))
+    }
+
+    /// Stems and adds every word of `text`
+    ///
+    /// Words are split as in [`crate::text::stems`]; when feeding a large
+    /// corpus piece by piece, cut it at whitespace (lines, for instance).
+    pub fn add_text(&mut self, stemmer: &mut dyn Stemmer, text: &str) {
+        for token in tokens_for(stemmer, text) { self.add_word(stemmer, token.text); }
+    }
+
+    /// Returns true if `stem` has been added
+    pub fn contains(&self, stem: &str) -> bool {
+        self.stems.contains_key(stem)
+    }
+
+    /// Number of distinct stems
+    pub fn len(&self) -> usize {
+        self.stems.len()
+    }
+
+    /// Returns true if no stem has been added
+    pub fn is_empty(&self) -> bool {
+        self.stems.is_empty()
+    }
+
+    /// The stems in the order they were first added
+    pub fn insertion_order(&self) -> Vec<&str> {
+        let mut stems: Vec<(&str, usize)> = self.stems.iter().map(|(s, &i)| (s.as_str(), i)).collect();
+        stems.sort_unstable_by_key(|&(_, i)| i);
+        stems.into_iter().map(|(s, _)| s).collect()
+    }
+
+    /// The stems in lexicographic order
+    pub fn sorted(&self) -> Vec<&str> {
+        let mut stems: Vec<&str> = self.stems.keys().map(String::as_str).collect();
+        stems.sort_unstable();
+        stems
+    }
+
+    /// Consumes the set, returning the stems in the order they were first added
+    pub fn into_insertion_order(self) -> Vec<String> {
+        let mut stems: Vec<(String, usize)> = self.stems.into_iter().collect();
+        stems.sort_unstable_by_key(|&(_, i)| i);
+        stems.into_iter().map(|(s, _)| s).collect()
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for StemSet {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, stems: I) {
+        for stem in stems { self.insert(stem.as_ref()); }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_reports_new_stems() {
+        let mut set = StemSet::new();
+        assert!(set.insert("run"));
+        assert!(!set.insert("run"));
+        set.extend(["walk", "run", "b"]);
+        assert_eq!(set.len(), 3);
+        assert!(set.contains("walk"));
+        assert_eq!(set.clone().into_insertion_order(), ["run", "walk", "b"]);
+        assert_eq!(set.sorted(), ["b", "run", "walk"]);
+    }
+}
@@ -0,0 +1,509 @@
+//! # Stemming Algorithms
+//!
+//! The [`Stemmer`] trait is the interface every algorithm in the crate
+//! implements, and the one all text pipelines take, so a pipeline written
+//! for the original Porter stemmer works unchanged with any other.
+//! [`Algorithm`] names the available English algorithms and [`Language`]
+//! the languages there are stemmers for, for selecting one at run time (the
+//! CLI's `--algorithm` and `--lang` flags). [`stem_with_language`] stems a
+//! word in any of the languages, reusing one stemmer per language.
+
+use crate::error::StemError;
+use crate::fold::NonAsciiPolicy;
+use crate::fuzzy::bounded_distance;
+use crate::lancaster::LancasterStemmer;
+use crate::lucene::LuceneStemmer;
+use crate::porter2::Porter2Stemmer;
+use crate::stem_key::StemKey;
+use crate::uea_lite::UeaLiteStemmer;
+use crate::{
+    FrenchStemmer, GermanStemmer, GreekStemmer, HindiStemmer, HungarianStemmer, PorterStemmer, PortugueseStemmer, RomanianStemmer, RussianStemmer,
+    ScandinavianStemmer, TurkishStemmer,
+};
+
+/// A word stemming algorithm
+///
+/// Only [`Stemmer::stem`] is required; the other methods have default
+/// implementations in terms of it that algorithms may specialize.
+pub trait Stemmer {
+    /// Returns the stem of a single word
+    fn stem(&mut self, word: &str) -> String;
+
+    /// Returns the stem of a single word, or why it has none
+    ///
+    /// Fails on an empty word; stemmers that reject some words, such as a
+    /// [`PorterStemmer`] with [`NonAsciiPolicy::Reject`], fail on those too.
+    fn try_stem(&mut self, word: &str) -> Result<String, StemError> {
+        if word.is_empty() {
+            return Err(StemError::EmptyInput);
+        }
+        Ok(self.stem(word))
+    }
+
+    /// Returns true if the stemmer folds accented Latin letters to ASCII
+    ///
+    /// Text pipelines fold the whole text before splitting it into words
+    /// when this is set, so accented letters join their words.
+    fn folds_diacritics(&self) -> bool {
+        false
+    }
+
+    /// Returns true if the stemmer handles words with non-ASCII letters
+    ///
+    /// Text pipelines then take words to be runs of any letters ("Häuser")
+    /// instead of the ASCII letter runs of the reference C program.
+    fn unicode_words(&self) -> bool {
+        false
+    }
+
+    /// Returns what the stemmer does with words that are not all ASCII
+    ///
+    /// With [`NonAsciiPolicy::Reject`] the `try_` text pipelines fail on
+    /// the first non-ASCII letter of a text, not only of a word, since the
+    /// ASCII tokenizer would otherwise split the word there.
+    fn non_ascii_policy(&self) -> NonAsciiPolicy {
+        NonAsciiPolicy::Stem
+    }
+
+    /// Returns true if `a` and `b` have the same stem
+    fn same_stem(&mut self, a: &str, b: &str) -> bool {
+        self.stem(a) == self.stem(b)
+    }
+
+    /// Returns true if the stems of `a` and `b` are at most `max_dist` edits
+    /// (insertions, deletions or substitutions) apart
+    fn stems_match_fuzzy(&mut self, a: &str, b: &str, max_dist: usize) -> bool {
+        let a: Vec<char> = self.stem(a).chars().collect();
+        let b: Vec<char> = self.stem(b).chars().collect();
+        bounded_distance(&a, &b, max_dist, &mut Vec::new()).is_some()
+    }
+
+    /// Stems `word` into a [`StemKey`] for use as a map or set key
+    fn stem_key(&mut self, word: &str) -> StemKey {
+        StemKey::new(self.stem(word))
+    }
+}
+
+impl<S: Stemmer + ?Sized> Stemmer for Box<S> {
+    fn stem(&mut self, word: &str) -> String {
+        (**self).stem(word)
+    }
+
+    fn try_stem(&mut self, word: &str) -> Result<String, StemError> {
+        (**self).try_stem(word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        (**self).folds_diacritics()
+    }
+
+    fn unicode_words(&self) -> bool {
+        (**self).unicode_words()
+    }
+
+    fn non_ascii_policy(&self) -> NonAsciiPolicy {
+        (**self).non_ascii_policy()
+    }
+
+    fn same_stem(&mut self, a: &str, b: &str) -> bool {
+        (**self).same_stem(a, b)
+    }
+
+    fn stems_match_fuzzy(&mut self, a: &str, b: &str, max_dist: usize) -> bool {
+        (**self).stems_match_fuzzy(a, b, max_dist)
+    }
+
+    fn stem_key(&mut self, word: &str) -> StemKey {
+        (**self).stem_key(word)
+    }
+}
+
+/// The stemming algorithms available in this crate
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::Algorithm;
+/// let algorithm: Algorithm = "porter2".parse().unwrap();
+/// let mut stemmer = algorithm.stemmer(false);
+/// assert_eq!(stemmer.stem("generously"), "generous");
+/// assert_eq!(Algorithm::Porter.stemmer(false).stem("generously"), "gener");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// The original Porter (1980) algorithm, as in the reference C program
+    #[default]
+    Porter,
+    /// Porter2, the Snowball English algorithm
+    Porter2,
+    /// The aggressive Lancaster (Paice/Husk) algorithm
+    Lancaster,
+    /// UEA-Lite, which keeps stems readable as English words
+    UeaLite,
+    /// The original Porter algorithm as Lucene's PorterStemFilter runs it
+    Lucene,
+}
+
+impl Algorithm {
+    /// Every algorithm, in the order they are listed to users
+    pub const ALL: &'static [Algorithm] =
+        &[Algorithm::Porter, Algorithm::Porter2, Algorithm::Lancaster, Algorithm::UeaLite, Algorithm::Lucene];
+
+    /// The name accepted by [`str::parse`] and shown to users
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Porter => "porter",
+            Algorithm::Porter2 => "porter2",
+            Algorithm::Lancaster => "lancaster",
+            Algorithm::UeaLite => "uea-lite",
+            Algorithm::Lucene => "lucene",
+        }
+    }
+
+    /// A one-line description for listings
+    pub fn description(self) -> &'static str {
+        match self {
+            Algorithm::Porter => "the original Porter (1980) algorithm, as in the reference C program",
+            Algorithm::Porter2 => "Porter2, the Snowball English algorithm",
+            Algorithm::Lancaster => "the aggressive Lancaster (Paice/Husk) algorithm",
+            Algorithm::UeaLite => "UEA-Lite, which keeps stems readable as English words",
+            Algorithm::Lucene => "Porter as Lucene and Elasticsearch stem, without lowercasing",
+        }
+    }
+
+    /// Creates a stemmer for this algorithm
+    ///
+    /// # Arguments
+    /// * `fold_diacritics` - Fold accented Latin letters to ASCII first
+    pub fn stemmer(self, fold_diacritics: bool) -> Box<dyn Stemmer> {
+        match self {
+            Algorithm::Porter => Box::new(PorterStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Algorithm::Porter2 => Box::new(Porter2Stemmer::new().with_diacritic_folding(fold_diacritics)),
+            Algorithm::Lancaster => Box::new(LancasterStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Algorithm::UeaLite => Box::new(UeaLiteStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Algorithm::Lucene => Box::new(LuceneStemmer::new().with_diacritic_folding(fold_diacritics)),
+        }
+    }
+}
+
+impl std::str::FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "porter" | "porter1" => Ok(Algorithm::Porter),
+            "porter2" | "snowball" | "english" => Ok(Algorithm::Porter2),
+            "lancaster" | "paice" | "paice-husk" => Ok(Algorithm::Lancaster),
+            "uea-lite" | "uealite" | "uea" => Ok(Algorithm::UeaLite),
+            "lucene" | "elasticsearch" => Ok(Algorithm::Lucene),
+            _ => Err(format!("unknown algorithm: {:?} (expected porter, porter2, lancaster, uea-lite or lucene)", s)),
+        }
+    }
+}
+
+/// The languages there are stemmers for
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::Language;
+/// let language: Language = "sv".parse().unwrap();
+/// assert_eq!(language, Language::Swedish);
+/// assert_eq!(language.stemmer(false).stem("jakten"), "jakt");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+    German,
+    Portuguese,
+    Russian,
+    Swedish,
+    Norwegian,
+    Danish,
+    Hungarian,
+    Romanian,
+    Turkish,
+    Greek,
+    Hindi,
+}
+
+impl Language {
+    /// Every language, in the order they are listed to users
+    pub const ALL: &'static [Language] = &[
+        Language::English, Language::French, Language::German, Language::Portuguese, Language::Russian,
+        Language::Swedish, Language::Norwegian, Language::Danish, Language::Hungarian, Language::Romanian,
+        Language::Turkish, Language::Greek, Language::Hindi,
+    ];
+
+    /// The ISO 639-1 code, which [`str::parse`] accepts along with the
+    /// English name
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Portuguese => "pt",
+            Language::Russian => "ru",
+            Language::Swedish => "sv",
+            Language::Norwegian => "no",
+            Language::Danish => "da",
+            Language::Hungarian => "hu",
+            Language::Romanian => "ro",
+            Language::Turkish => "tr",
+            Language::Greek => "el",
+            Language::Hindi => "hi",
+        }
+    }
+
+    /// The English name of the language, lowercased
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "english",
+            Language::French => "french",
+            Language::German => "german",
+            Language::Portuguese => "portuguese",
+            Language::Russian => "russian",
+            Language::Swedish => "swedish",
+            Language::Norwegian => "norwegian",
+            Language::Danish => "danish",
+            Language::Hungarian => "hungarian",
+            Language::Romanian => "romanian",
+            Language::Turkish => "turkish",
+            Language::Greek => "greek",
+            Language::Hindi => "hindi",
+        }
+    }
+
+    /// Creates the stemmer for this language, the Snowball one where there
+    /// is one; English gets the default [`Algorithm`]
+    ///
+    /// # Arguments
+    /// * `fold_diacritics` - Fold accented Latin letters to ASCII first
+    pub fn stemmer(self, fold_diacritics: bool) -> Box<dyn Stemmer> {
+        match self {
+            Language::English => Algorithm::default().stemmer(fold_diacritics),
+            Language::French => Box::new(FrenchStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Language::German => Box::new(GermanStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Language::Portuguese => Box::new(PortugueseStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Language::Russian => Box::new(RussianStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Language::Swedish => Box::new(ScandinavianStemmer::swedish().with_diacritic_folding(fold_diacritics)),
+            Language::Norwegian => Box::new(ScandinavianStemmer::norwegian().with_diacritic_folding(fold_diacritics)),
+            Language::Danish => Box::new(ScandinavianStemmer::danish().with_diacritic_folding(fold_diacritics)),
+            Language::Hungarian => Box::new(HungarianStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Language::Romanian => Box::new(RomanianStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Language::Turkish => Box::new(TurkishStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Language::Greek => Box::new(GreekStemmer::new().with_diacritic_folding(fold_diacritics)),
+            Language::Hindi => Box::new(HindiStemmer::new().with_diacritic_folding(fold_diacritics)),
+        }
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Language::ALL.iter().copied().find(|l| s == l.code() || s == l.name()).ok_or_else(|| {
+            let codes: Vec<&str> = Language::ALL.iter().map(|l| l.code()).collect();
+            format!("unknown language: {:?} (expected one of {})", s, codes.join(", "))
+        })
+    }
+}
+
+/// Stemmers for any of the [`Language`]s, each created the first time a
+/// word of its language is stemmed and reused after that
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{Language, stemmer::LanguageStemmers};
+/// let mut stemmers = LanguageStemmers::new(false);
+/// assert_eq!(stemmers.stem("jakten", Language::Swedish), "jakt");
+/// assert_eq!(stemmers.stem("running", Language::English), "run");
+/// ```
+#[derive(Default)]
+pub struct LanguageStemmers {
+    /// The stemmers created so far, by [`Language`] discriminant
+    stemmers: Vec<Option<Box<dyn Stemmer>>>,
+    /// The algorithm used for English
+    english: Algorithm,
+    /// Whether the stemmers fold accented Latin letters to ASCII first
+    fold_diacritics: bool,
+}
+
+impl LanguageStemmers {
+    /// Creates an empty set of stemmers
+    ///
+    /// # Arguments
+    /// * `fold_diacritics` - Fold accented Latin letters to ASCII first
+    pub fn new(fold_diacritics: bool) -> Self {
+        LanguageStemmers { stemmers: Vec::new(), english: Algorithm::default(), fold_diacritics }
+    }
+
+    /// Stems English with `algorithm` instead of the default [`Algorithm`]
+    pub fn with_english_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.english = algorithm;
+        self.stemmers.clear();
+        self
+    }
+
+    /// The stemmer for `language`, created if it is the first use
+    pub fn stemmer(&mut self, language: Language) -> &mut dyn Stemmer {
+        let i = language as usize;
+        if self.stemmers.len() <= i {
+            self.stemmers.resize_with(i + 1, || None);
+        }
+        let (english, fold) = (self.english, self.fold_diacritics);
+        &mut **self.stemmers[i].get_or_insert_with(|| match language {
+            Language::English => english.stemmer(fold),
+            language => language.stemmer(fold),
+        })
+    }
+
+    /// Returns the stem of `word` by the stemmer for `language`
+    pub fn stem(&mut self, word: &str, language: Language) -> String {
+        self.stemmer(language).stem(word)
+    }
+}
+
+impl std::fmt::Debug for LanguageStemmers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let created: Vec<Language> =
+            Language::ALL.iter().copied().filter(|&l| matches!(self.stemmers.get(l as usize), Some(Some(_)))).collect();
+        f.debug_struct("LanguageStemmers")
+            .field("created", &created)
+            .field("english", &self.english)
+            .field("fold_diacritics", &self.fold_diacritics)
+            .finish()
+    }
+}
+
+thread_local! {
+    static STEMMERS: std::cell::RefCell<LanguageStemmers> = std::cell::RefCell::new(LanguageStemmers::new(false));
+}
+
+/// Returns the stem of `word` by the stemmer for `language`
+///
+/// The stemmers are kept per thread and reused from call to call, so this
+/// is as fast as keeping a stemmer yourself. English words get the default
+/// [`Algorithm`]; use [`LanguageStemmers`] to choose another or to fold
+/// diacritics.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{stem_with_language, Language};
+/// assert_eq!(stem_with_language("Häusern", Language::German), "haus");
+/// assert_eq!(stem_with_language("caresses", "en".parse().unwrap()), "caress");
+/// ```
+pub fn stem_with_language(word: &str, language: Language) -> String {
+    STEMMERS.with(|stemmers| stemmers.borrow_mut().stem(word, language))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_algorithms_behind_the_trait() {
+        for &algorithm in Algorithm::ALL {
+            let mut stemmer = algorithm.stemmer(true);
+            assert_eq!(algorithm.name().parse(), Ok(algorithm));
+            assert!(stemmer.folds_diacritics());
+            // Lucene's filter expects an analyzer to have lowercased the words
+            let connects = if algorithm == Algorithm::Lucene { "connects" } else { "Connects" };
+            assert!(stemmer.same_stem(connects, "connected"));
+            assert!(stemmer.stems_match_fuzzy("runnning", "runs", 1));
+            assert_eq!(stemmer.stem_key("résumés"), stemmer.stem_key("resumes"));
+            assert_eq!(stemmer.try_stem("connected").unwrap(), stemmer.stem("connected"));
+            assert!(matches!(stemmer.try_stem(""), Err(StemError::EmptyInput)));
+        }
+        assert!("lovins".parse::<Algorithm>().is_err());
+    }
+
+    #[test]
+    fn test_languages() {
+        for &language in Language::ALL {
+            assert_eq!(language.code().parse(), Ok(language));
+            assert_eq!(language.name().parse(), Ok(language));
+            assert!(language.stemmer(true).folds_diacritics());
+        }
+        assert_eq!(Language::Danish.stemmer(false).stem("indtagelse"), "indtag");
+        assert!("xx".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn test_language_stemmers() {
+        let mut stemmers = LanguageStemmers::new(true).with_english_algorithm(Algorithm::Porter2);
+        assert_eq!(stemmers.stem("generously", Language::English), "generous");
+        assert_eq!(stemmers.stem("résumés", Language::English), "resum");
+        assert_eq!(stemmers.stem("jakten", Language::Swedish), "jakt");
+        assert!(format!("{:?}", stemmers).contains("created: [English, Swedish]"));
+        for &language in Language::ALL {
+            assert_eq!(stem_with_language("a", language), Language::stemmer(language, false).stem("a"));
+        }
+    }
+
+    /// The lines of `old` missing from `new` as "-" lines, then the lines of
+    /// `new` missing from `old` as "+" lines
+    fn snapshot_diff(old: &str, new: &str) -> String {
+        let removed = old.lines().filter(|line| !new.lines().any(|l| l == *line)).map(|line| format!("-{}\n", line));
+        let added = new.lines().filter(|line| !old.lines().any(|l| l == *line)).map(|line| format!("+{}\n", line));
+        removed.chain(added).collect()
+    }
+
+    /// Compares `contents` with the snapshot file `name` in
+    /// test_data/snapshots and returns the differences, if any
+    ///
+    /// A mismatch writes `contents` next to the snapshot as <name>.new;
+    /// with UPDATE_SNAPSHOTS set the snapshot is rewritten instead.
+    fn check_snapshot(name: &str, contents: &str) -> String {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data/snapshots");
+        let (path, new_path) = (dir.join(name), dir.join(format!("{}.new", name)));
+        let saved = std::fs::read_to_string(&path).unwrap_or_default();
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&path, contents).unwrap();
+        } else if saved != contents {
+            std::fs::write(&new_path, contents).unwrap();
+            return format!("{}:\n{}", path.display(), snapshot_diff(&saved, contents));
+        }
+        let _ = std::fs::remove_file(&new_path);
+        String::new()
+    }
+
+    #[test]
+    fn test_snapshots() {
+        // Each algorithm's stems of a word list across domains are kept in
+        // test_data/snapshots, so any change in behavior shows up as a diff
+        // to review; UPDATE_SNAPSHOTS=1 cargo test accepts a change.
+        let words = include_str!("../test_data/snapshots/words.txt");
+        let words: Vec<&str> = words.lines().filter(|line| !line.is_empty() && !line.starts_with('#')).collect();
+        let mut diffs = String::new();
+        for &algorithm in Algorithm::ALL {
+            let mut stemmer = algorithm.stemmer(false);
+            let stems: String = words.iter().map(|word| format!("{}\t{}\n", word, stemmer.stem(word))).collect();
+            diffs += &check_snapshot(&format!("{}.tsv", algorithm.name()), &stems);
+        }
+        assert!(diffs.is_empty(), "stems differ from the snapshots (UPDATE_SNAPSHOTS=1 to accept):\n{}", diffs);
+    }
+
+    #[test]
+    fn test_porter_porter2_divergences() {
+        // Every word of Porter's vocabulary the two algorithms stem apart,
+        // for users weighing whether an index built with one serves queries
+        // stemmed with the other
+        let words = include_str!("../test_data/porter/voc.txt");
+        let (mut porter, mut porter2) = (Algorithm::Porter.stemmer(false), Algorithm::Porter2.stemmer(false));
+        let mut rows = String::new();
+        let mut count = 0;
+        for word in words.lines() {
+            let (old, new) = (porter.stem(word), porter2.stem(word));
+            if old != new {
+                rows += &format!("{}\t{}\t{}\n", word, old, new);
+                count += 1;
+            }
+        }
+        let header = "# Words of test_data/porter/voc.txt that Porter and Porter2 stem differently";
+        let total = words.lines().count();
+        let report = format!("{}: {} of {}\n# word\tporter\tporter2\n{}", header, count, total, rows);
+        let diff = check_snapshot("porter-vs-porter2.tsv", &report);
+        assert!(diff.is_empty(), "the divergences changed (UPDATE_SNAPSHOTS=1 to accept):\n{}", diff);
+    }
+}
@@ -0,0 +1,130 @@
+//! # Stopwords
+//!
+//! A set of words that are dropped from the token stream instead of being
+//! stemmed. The built-in English list is the one shipped with NLTK, which
+//! includes the fragments (`don`, `t`, `ll`, ...) that the letter-run
+//! tokenizer produces from contractions such as "don't" and "we'll".
+//!
+//! Matching is case-insensitive and happens before stemming, so the list
+//! holds surface forms ("having"), not stems ("have").
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The built-in English stopword list
+pub const ENGLISH: &[&str] = &[
+    "i", "me", "my", "myself", "we", "our", "ours", "ourselves", "you", "your",
+    "yours", "yourself", "yourselves", "he", "him", "his", "himself", "she",
+    "her", "hers", "herself", "it", "its", "itself", "they", "them", "their",
+    "theirs", "themselves", "what", "which", "who", "whom", "this", "that",
+    "these", "those", "am", "is", "are", "was", "were", "be", "been", "being",
+    "have", "has", "had", "having", "do", "does", "did", "doing", "a", "an",
+    "the", "and", "but", "if", "or", "because", "as", "until", "while", "of",
+    "at", "by", "for", "with", "about", "against", "between", "into",
+    "through", "during", "before", "after", "above", "below", "to", "from",
+    "up", "down", "in", "out", "on", "off", "over", "under", "again",
+    "further", "then", "once", "here", "there", "when", "where", "why", "how",
+    "all", "any", "both", "each", "few", "more", "most", "other", "some",
+    "such", "no", "nor", "not", "only", "own", "same", "so", "than", "too",
+    "very", "s", "t", "can", "will", "just", "don", "should", "now", "d",
+    "ll", "m", "o", "re", "ve", "y", "ain", "aren", "couldn", "didn", "doesn",
+    "hadn", "hasn", "haven", "isn", "ma", "mightn", "mustn", "needn", "shan",
+    "shouldn", "wasn", "weren", "won", "wouldn",
+];
+
+/// A case-insensitive set of stopwords
+#[derive(Debug, Clone, Default)]
+pub struct Stopwords {
+    /// Lowercase stopwords
+    words: HashSet<String>,
+}
+
+impl Stopwords {
+    /// Creates an empty stopword set
+    pub fn new() -> Self {
+        Stopwords { words: HashSet::new() }
+    }
+
+    /// Creates the built-in English stopword set
+    pub fn english() -> Self {
+        Self::from_words(ENGLISH.iter().copied())
+    }
+
+    /// Creates a stopword set from the given words
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut set = Self::new();
+        for word in words { set.insert(word.as_ref()); }
+        set
+    }
+
+    /// Parses a stopword list: words separated by whitespace, with `#` or `|`
+    /// starting a comment that runs to the end of the line
+    ///
+    /// The `|` comment form is the one used by the Snowball stopword files.
+    pub fn parse(list: &str) -> Self {
+        let words = list
+            .lines()
+            .map(|line| line.split(['#', '|']).next().unwrap_or(""))
+            .flat_map(str::split_whitespace);
+        Self::from_words(words)
+    }
+
+    /// Loads a stopword list file in the format accepted by [`Stopwords::parse`]
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Adds a word to the set
+    pub fn insert(&mut self, word: &str) {
+        self.words.insert(word.to_lowercase());
+    }
+
+    /// Returns true if `word` is a stopword, ignoring case
+    pub fn contains(&self, word: &str) -> bool {
+        if word.chars().any(char::is_uppercase) {
+            self.words.contains(&word.to_lowercase())
+        } else {
+            self.words.contains(word)
+        }
+    }
+
+    /// Number of stopwords in the set
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns true if the set holds no stopwords
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_list() {
+        let stopwords = Stopwords::english();
+        assert_eq!(stopwords.len(), ENGLISH.len());
+        assert!(stopwords.contains("the"));
+        assert!(stopwords.contains("The"));
+        assert!(stopwords.contains("don"));
+        assert!(!stopwords.contains("running"));
+    }
+
+    #[test]
+    fn test_parse_custom_list() {
+        let stopwords = Stopwords::parse("foo bar  # common filler\n| snowball comment\nBaz\n");
+        assert_eq!(stopwords.len(), 3);
+        assert!(stopwords.contains("baz"));
+        assert!(!stopwords.contains("common"));
+        assert!(!stopwords.contains("snowball"));
+    }
+}
@@ -0,0 +1,174 @@
+//! # Async Streams
+//!
+//! Adapters for async pipelines, so a service on tokio can stem as tokens
+//! and lines arrive instead of moving the stemmer onto a blocking thread.
+//! Stemming a word takes a microsecond or so, little enough to do on the
+//! runtime's own threads.
+//!
+//! - [`StemmedStream`] wraps a `Stream<Item = String>` of words and yields
+//!   their stems, in order
+//! - [`stem_lines`] reads lines from a `tokio::io::AsyncBufRead` and yields
+//!   each one stemmed, word by word, as [`crate::text::stem_text`] does
+//!
+//! Both use the Porter stemmer unless given another with `with_stemmer`.
+//! Only available with the `async` feature.
+//!
+//! # Examples
+//! ```
+//! # use std::pin::pin;
+//! # use std::task::{Context, Poll, Waker};
+//! # use futures_core::Stream;
+//! use m_porter_stemmer_rust::stream::stem_lines;
+//!
+//! # fn next<S: Stream>(stream: std::pin::Pin<&mut S>) -> Option<S::Item> {
+//! #     match stream.poll_next(&mut Context::from_waker(Waker::noop())) {
+//! #         Poll::Ready(item) => item,
+//! #         Poll::Pending => unreachable!(),
+//! #     }
+//! # }
+//! let mut lines = pin!(stem_lines("Running ponies\ncaresses\n".as_bytes()));
+//! // In an async fn: `while let Some(line) = lines.next().await`
+//! assert_eq!(next(lines.as_mut()).unwrap().unwrap(), "run poni");
+//! assert_eq!(next(lines.as_mut()).unwrap().unwrap(), "caress");
+//! assert!(next(lines.as_mut()).is_none());
+//! ```
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, Lines};
+
+use crate::stemmer::Stemmer;
+use crate::text::stem_text;
+use crate::PorterStemmer;
+
+/// A stream of the stems of another stream's words
+///
+/// The inner stream must be `Unpin`; put one that is not in a `Box::pin`.
+#[derive(Debug)]
+pub struct StemmedStream<S, T = PorterStemmer> {
+    words: S,
+    stemmer: T,
+}
+
+impl<S> StemmedStream<S> {
+    /// Stems the words of `words` with the Porter stemmer
+    pub fn new(words: S) -> Self {
+        Self::with_stemmer(words, PorterStemmer::new())
+    }
+}
+
+impl<S, T> StemmedStream<S, T> {
+    /// Stems the words of `words` with `stemmer`
+    pub fn with_stemmer(words: S, stemmer: T) -> Self {
+        StemmedStream { words, stemmer }
+    }
+
+    /// Returns the inner stream
+    pub fn into_inner(self) -> S {
+        self.words
+    }
+}
+
+impl<S, T> Stream for StemmedStream<S, T>
+where
+    S: Stream<Item = String> + Unpin,
+    T: Stemmer + Unpin,
+{
+    type Item = String;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<String>> {
+        let this = self.get_mut();
+        Pin::new(&mut this.words).poll_next(cx).map(|word| word.map(|word| this.stemmer.stem(&word)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.words.size_hint()
+    }
+}
+
+/// A stream of the stemmed lines of a reader, returned by [`stem_lines`]
+#[derive(Debug)]
+pub struct StemmedLines<R, T = PorterStemmer> {
+    lines: Lines<R>,
+    stemmer: T,
+}
+
+impl<R: AsyncBufRead, T> StemmedLines<R, T> {
+    /// Stems the lines of `reader` with `stemmer`
+    pub fn with_stemmer(reader: R, stemmer: T) -> Self {
+        StemmedLines { lines: reader.lines(), stemmer }
+    }
+}
+
+/// Returns a stream of the lines of `reader`, each stemmed by the Porter
+/// stemmer, without their line endings, or the error that ended reading
+pub fn stem_lines<R: AsyncBufRead>(reader: R) -> StemmedLines<R> {
+    StemmedLines::with_stemmer(reader, PorterStemmer::new())
+}
+
+impl<R, T> Stream for StemmedLines<R, T>
+where
+    R: AsyncBufRead + Unpin,
+    T: Stemmer + Unpin,
+{
+    type Item = io::Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<io::Result<String>>> {
+        let this = self.get_mut();
+        Pin::new(&mut this.lines).poll_next_line(cx).map(|line| match line {
+            Ok(Some(line)) => Some(Ok(stem_text(&mut this.stemmer, &line))),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Waker;
+
+    /// A stream of a vector's items, never pending
+    struct Items(std::vec::IntoIter<String>);
+
+    impl Stream for Items {
+        type Item = String;
+
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<String>> {
+            Poll::Ready(self.0.next())
+        }
+    }
+
+    /// Collects a stream that is never pending
+    fn collect<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item> {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut items = Vec::new();
+        while let Poll::Ready(Some(item)) = Pin::new(&mut stream).poll_next(&mut cx) {
+            items.push(item);
+        }
+        items
+    }
+
+    #[test]
+    fn test_stemmed_stream() {
+        let words = Items(vec!["connections".into(), "Hopping".into(), "ponies".into()].into_iter());
+        assert_eq!(collect(StemmedStream::new(words)), ["connect", "hop", "poni"]);
+
+        let words = Items(vec!["generously".into()].into_iter());
+        let porter2 = crate::Porter2Stemmer::new();
+        assert_eq!(collect(StemmedStream::with_stemmer(words, porter2)), ["generous"]);
+    }
+
+    #[test]
+    fn test_stem_lines() {
+        let lines = collect(stem_lines("Agreed, ponies!\r\n\nflying cats".as_bytes()));
+        let lines: Vec<String> = lines.into_iter().map(Result::unwrap).collect();
+        assert_eq!(lines, ["agre, poni!", "", "fly cat"]);
+
+        let invalid = collect(stem_lines(&b"caf\xe9\n"[..]));
+        assert_eq!(invalid[0].as_ref().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
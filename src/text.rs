@@ -0,0 +1,781 @@
+//! # Text Stemming
+//!
+//! Stems running text the way `stemfile()` in the reference C program does:
+//! every maximal run of ASCII letters is forced to lower case and stemmed,
+//! and every other character is copied through unchanged. Stemmers for
+//! languages written with other letters, such as [`crate::GermanStemmer`],
+//! get runs of any letters instead, with the combining signs of scripts
+//! such as Devanagari that Unicode does not count as letters ("्" in
+//! "बच्चे").
+//!
+//! [`stem_text_filtered`] additionally drops stopwords from the output, and
+//! [`stem_text_with`] takes a [`TextOptions`] covering all pipeline settings.
+//!
+//! If the stemmer has diacritic folding enabled, the whole text is folded
+//! before it is split into words, so accented letters join their words
+//! instead of breaking them apart.
+//!
+//! [`stem_stream`] stems a reader of any length in bounded memory, and
+//! [`read_chunks`] hands out a reader's text in the same pieces for other
+//! uses.
+//!
+//! [`try_stem_text_with`] and [`try_stem_stream`] fail with a
+//! [`StemError`] where the others would pass a word through unstemmed: a
+//! token over the maximum length with [`LongTokenPolicy::Reject`], or a
+//! letter a stemmer with [`NonAsciiPolicy::Reject`] was not written for.
+
+use std::io::{self, Read, Write};
+
+use crate::error::StemError;
+use crate::fold::{fold_diacritics, NonAsciiPolicy};
+use crate::stopwords::Stopwords;
+use crate::token::{symbol_len, DigitPolicy, Disposition, LongTokenPolicy, SymbolPolicy};
+use crate::Stemmer;
+
+/// A word of the input text and the byte offset where it starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// The word itself, a run of ASCII letters
+    pub text: &'a str,
+    /// Byte offset of the word in the input
+    pub start: usize,
+}
+
+impl Token<'_> {
+    /// Byte offset just past the end of the word
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    /// Byte range of the word in the input
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.end()
+    }
+}
+
+/// Returns true if `c` can be part of a word for a stemmer of non-ASCII
+/// words: a letter, or a Devanagari sign (vowel signs, nukta, virama)
+pub(crate) fn is_word_letter(c: char) -> bool {
+    c.is_alphabetic() || matches!(c, '\u{0900}'..='\u{0903}' | '\u{093A}'..='\u{094F}' | '\u{0951}'..='\u{0957}' | '\u{0962}' | '\u{0963}')
+}
+
+/// Iterator over the words of a text, see [`tokens`]
+#[derive(Debug, Clone)]
+pub struct Tokens<'a> {
+    text: &'a str,
+    pos: usize,
+    /// Whether words are runs of any letters rather than ASCII letters
+    unicode: bool,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let letter: fn(char) -> bool = if self.unicode { is_word_letter } else { |c| c.is_ascii_alphabetic() };
+        let Some(offset) = self.text[self.pos..].find(letter) else {
+            self.pos = self.text.len();
+            return None;
+        };
+        let start = self.pos + offset;
+        self.pos = self.text[start..].find(|c| !letter(c)).map_or(self.text.len(), |len| start + len);
+        Some(Token { text: &self.text[start..self.pos], start })
+    }
+}
+
+/// Returns the words of `text`: the same letter runs that [`stem_text`] stems
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::text::tokens;
+/// let words: Vec<(&str, usize)> = tokens("Hi, you!").map(|t| (t.text, t.start)).collect();
+/// assert_eq!(words, [("Hi", 0), ("you", 4)]);
+/// ```
+pub fn tokens(text: &str) -> Tokens<'_> {
+    Tokens { text, pos: 0, unicode: false }
+}
+
+/// Returns the words of `text` as `stemmer` sees them
+///
+/// These are the ASCII letter runs of [`tokens`], or runs of any letters if
+/// the stemmer handles them (see [`Stemmer::unicode_words`]).
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{GermanStemmer, PorterStemmer, text::tokens_for};
+/// let german: Vec<&str> = tokens_for(&GermanStemmer::new(), "Häuser").map(|t| t.text).collect();
+/// assert_eq!(german, ["Häuser"]);
+/// let english: Vec<&str> = tokens_for(&PorterStemmer::new(), "Häuser").map(|t| t.text).collect();
+/// assert_eq!(english, ["H", "user"]);
+/// ```
+pub fn tokens_for<'a>(stemmer: &dyn Stemmer, text: &'a str) -> Tokens<'a> {
+    Tokens { text, pos: 0, unicode: stemmer.unicode_words() }
+}
+
+/// Returns the words of `text` as found before diacritic folding, so that
+/// their offsets are those of `text`: runs of any letters if the stemmer
+/// folds diacritics (it then folds each word as it stems it), otherwise the
+/// words of [`tokens_for`]
+pub(crate) fn unfolded_tokens_for<'a>(stemmer: &dyn Stemmer, text: &'a str) -> Tokens<'a> {
+    Tokens { text, pos: 0, unicode: stemmer.unicode_words() || stemmer.folds_diacritics() }
+}
+
+/// Returns every word of `text` with its stem
+///
+/// Unlike [`stems`], the words are those of `text` itself even if the
+/// stemmer folds diacritics, so their offsets can be used to line the stems
+/// up with the text.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, text::token_stems};
+/// let mut stemmer = PorterStemmer::new().with_diacritic_folding(true);
+/// let pairs: Vec<(&str, usize, String)> =
+///     token_stems(&mut stemmer, "Naïve ponies").into_iter().map(|(t, stem)| (t.text, t.start, stem)).collect();
+/// assert_eq!(pairs, [("Naïve", 0, "naiv".to_string()), ("ponies", 7, "poni".to_string())]);
+/// ```
+pub fn token_stems<'a>(stemmer: &mut dyn Stemmer, text: &'a str) -> Vec<(Token<'a>, String)> {
+    let tokens: Vec<Token<'a>> = unfolded_tokens_for(stemmer, text).collect();
+    tokens.into_iter().map(|token| (token, stemmer.stem(token.text))).collect()
+}
+
+/// Returns the stem of every word in `text`, in order
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, text::stems};
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(stems(&mut stemmer, "Running dogs, jumping cats"), ["run", "dog", "jump", "cat"]);
+/// ```
+pub fn stems(stemmer: &mut dyn Stemmer, text: &str) -> Vec<String> {
+    let folded = if stemmer.folds_diacritics() { fold_diacritics(text) } else { text.into() };
+    tokens_for(stemmer, &folded).map(|token| stemmer.stem(token.text)).collect()
+}
+
+/// Settings for the text pipeline
+///
+/// The defaults reproduce [`stem_text`]: no stopwords, digits split words,
+/// symbols are copied through, words of one or two letters are only
+/// lowercased (the Porter algorithm leaves them alone anyway) and there is
+/// no maximum length.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, stopwords::Stopwords, token::DigitPolicy};
+/// # use m_porter_stemmer_rust::text::{stem_text_with, TextOptions};
+/// let stopwords = Stopwords::english();
+/// let options = TextOptions::new().stopwords(&stopwords).digits(DigitPolicy::Skip);
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(stem_text_with(&mut stemmer, "The 2023 covid19 cases", &options), "case");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TextOptions<'a> {
+    /// Words to drop before stemming
+    pub stopwords: Option<&'a Stopwords>,
+    /// How tokens containing digits are handled
+    pub digits: DigitPolicy,
+    /// How emoji and other symbols between words are handled
+    pub symbols: SymbolPolicy<'a>,
+    /// Whether stems take the case of their words (see [`restore_case`])
+    pub preserve_case: bool,
+    /// Words of fewer characters are lowercased but not stemmed
+    pub min_length: usize,
+    /// Tokens of more characters are handled by `long_tokens`, unstemmed
+    pub max_length: Option<usize>,
+    /// What to do with tokens longer than `max_length`
+    pub long_tokens: LongTokenPolicy,
+}
+
+/// Shortest word stemmed by default, as in the Porter algorithm
+pub const DEFAULT_MIN_LENGTH: usize = 3;
+
+impl Default for TextOptions<'_> {
+    fn default() -> Self {
+        TextOptions {
+            stopwords: None,
+            digits: DigitPolicy::default(),
+            symbols: SymbolPolicy::default(),
+            preserve_case: false,
+            min_length: DEFAULT_MIN_LENGTH,
+            max_length: None,
+            long_tokens: LongTokenPolicy::default(),
+        }
+    }
+}
+
+impl<'a> TextOptions<'a> {
+    /// Creates the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Leaves words shorter than `length` characters unstemmed
+    pub fn min_length(mut self, length: usize) -> Self {
+        self.min_length = length;
+        self
+    }
+
+    /// Passes through or drops, as `policy` says, the tokens longer than
+    /// `length` characters instead of stemming them
+    ///
+    /// # Examples
+    /// ```
+    /// # use m_porter_stemmer_rust::{PorterStemmer, token::LongTokenPolicy};
+    /// # use m_porter_stemmer_rust::text::{stem_text_with, TextOptions};
+    /// let mut stemmer = PorterStemmer::new();
+    /// let pass = TextOptions::new().max_length(8, LongTokenPolicy::Verbatim);
+    /// assert_eq!(stem_text_with(&mut stemmer, "Running AAAAQXJjaGlZQ", &pass), "run AAAAQXJjaGlZQ");
+    /// let drop = TextOptions::new().max_length(8, LongTokenPolicy::Skip);
+    /// assert_eq!(stem_text_with(&mut stemmer, "Running AAAAQXJjaGlZQ now", &drop), "run now");
+    /// ```
+    pub fn max_length(mut self, length: usize, policy: LongTokenPolicy) -> Self {
+        self.max_length = Some(length);
+        self.long_tokens = policy;
+        self
+    }
+
+    /// Drops the given stopwords before stemming
+    pub fn stopwords(mut self, stopwords: &'a Stopwords) -> Self {
+        self.stopwords = Some(stopwords);
+        self
+    }
+
+    /// Sets the policy for tokens containing digits
+    pub fn digits(mut self, policy: DigitPolicy) -> Self {
+        self.digits = policy;
+        self
+    }
+
+    /// Sets the policy for emoji and other symbols
+    pub fn symbols(mut self, policy: SymbolPolicy<'a>) -> Self {
+        self.symbols = policy;
+        self
+    }
+
+    /// Gives each stem the case of its word instead of lowercasing it
+    pub fn preserve_case(mut self, preserve: bool) -> Self {
+        self.preserve_case = preserve;
+        self
+    }
+}
+
+/// Gives `stem` the case of the `word` it was stemmed from, letter by
+/// letter; letters past the end of the word take the case of its last one
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::text::restore_case;
+/// assert_eq!(restore_case("Running", "run"), "Run");
+/// assert_eq!(restore_case("HAPPY", "happi"), "HAPPI");
+/// assert_eq!(restore_case("McDonalds", "mcdonald"), "McDonald");
+/// ```
+pub fn restore_case(word: &str, stem: &str) -> String {
+    let mut upper = word.chars().map(char::is_uppercase);
+    let mut last = false;
+    let mut out = String::with_capacity(stem.len());
+    for c in stem.chars() {
+        last = upper.next().unwrap_or(last);
+        if last {
+            out.extend(c.to_uppercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Stems every word in `text`, passing all non-letters through unchanged
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each word
+/// * `text` - Arbitrary input text
+///
+/// # Returns
+/// The text with each word replaced by its (lowercase) stem
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, text::stem_text};
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(stem_text(&mut stemmer, "Running, jumping!"), "run, jump!");
+/// ```
+pub fn stem_text(stemmer: &mut dyn Stemmer, text: &str) -> String {
+    stem_text_with(stemmer, text, &TextOptions::new())
+}
+
+/// Stems every word in `text` that is not a stopword, dropping the stopwords
+///
+/// A dropped word takes the spaces or tabs that follow it along with it, so
+/// "the cat sat on the mat." becomes "cat sat mat." rather than leaving gaps.
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for each remaining word
+/// * `text` - Arbitrary input text
+/// * `stopwords` - Words to drop, matched case-insensitively before stemming
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, stopwords::Stopwords, text::stem_text_filtered};
+/// let mut stemmer = PorterStemmer::new();
+/// let stopwords = Stopwords::english();
+/// assert_eq!(stem_text_filtered(&mut stemmer, "The cats are running.", &stopwords), "cat run.");
+/// ```
+pub fn stem_text_filtered(stemmer: &mut dyn Stemmer, text: &str, stopwords: &Stopwords) -> String {
+    stem_text_with(stemmer, text, &TextOptions::new().stopwords(stopwords))
+}
+
+/// Stems every word in `text` using the given pipeline options
+///
+/// With [`DigitPolicy::Split`] words are runs of letters, exactly as in
+/// [`stem_text`]; with any other policy they are runs of letters and digits,
+/// which the policy then keeps, drops or strips. Symbols between words are
+/// kept, dropped or replaced according to the symbol policy. Dropped tokens
+/// (stopwords, skipped numbers or symbols) take the spaces or tabs that
+/// follow them along with them.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, token::SymbolPolicy};
+/// # use m_porter_stemmer_rust::text::{stem_text_with, TextOptions};
+/// let mut stemmer = PorterStemmer::new();
+/// let drop = TextOptions::new().symbols(SymbolPolicy::Drop);
+/// assert_eq!(stem_text_with(&mut stemmer, "Loving it 😍🔥 so far", &drop), "love it so far");
+/// let tag = TextOptions::new().symbols(SymbolPolicy::Placeholder("<sym>"));
+/// assert_eq!(stem_text_with(&mut stemmer, "Costs €5 👍🏽", &tag), "cost <sym>5 <sym>");
+/// ```
+pub fn stem_text_with(stemmer: &mut dyn Stemmer, text: &str, options: &TextOptions) -> String {
+    // Only strict stemming fails
+    stem_text_checked(stemmer, text, options, false).unwrap_or_else(|e| unreachable!("{}", e))
+}
+
+/// Stems every word in `text` as [`stem_text_with`] does, but fails where
+/// that would pass something through unstemmed
+///
+/// The errors are a token longer than the options' maximum length with
+/// [`LongTokenPolicy::Reject`], and a non-ASCII letter anywhere in the text
+/// (after folding) if the stemmer has [`NonAsciiPolicy::Reject`], with its
+/// byte offset in `text`.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{fold::NonAsciiPolicy, token::LongTokenPolicy, PorterStemmer, StemError};
+/// # use m_porter_stemmer_rust::text::{try_stem_text_with, TextOptions};
+/// let options = TextOptions::new().max_length(12, LongTokenPolicy::Reject);
+/// let mut stemmer = PorterStemmer::new().with_non_ascii(NonAsciiPolicy::Reject);
+/// assert_eq!(try_stem_text_with(&mut stemmer, "Running ponies", &options).unwrap(), "run poni");
+/// let error = try_stem_text_with(&mut stemmer, "see AAAAQXJjaGlZQ", &options).unwrap_err();
+/// assert!(matches!(error, StemError::TokenTooLong { length: 13, max: 12 }));
+/// let error = try_stem_text_with(&mut stemmer, "a naïve cat", &options).unwrap_err();
+/// assert_eq!(error.to_string(), "non-ASCII character 'ï' at byte 4");
+/// ```
+pub fn try_stem_text_with(stemmer: &mut dyn Stemmer, text: &str, options: &TextOptions) -> Result<String, StemError> {
+    stem_text_checked(stemmer, text, options, true)
+}
+
+/// The body of [`stem_text_with`] and, when `strict`, [`try_stem_text_with`]
+fn stem_text_checked(
+    stemmer: &mut dyn Stemmer,
+    text: &str,
+    options: &TextOptions,
+    strict: bool,
+) -> Result<String, StemError> {
+    let folded;
+    let text = if stemmer.folds_diacritics() {
+        folded = fold_diacritics(text);
+        &*folded
+    } else {
+        text
+    };
+    if strict && stemmer.non_ascii_policy() == NonAsciiPolicy::Reject {
+        if let Some((position, character)) = text.char_indices().find(|&(_, c)| !c.is_ascii() && c.is_alphabetic()) {
+            return Err(StemError::NonAsciiLetter { character, position });
+        }
+    }
+
+    let unicode = stemmer.unicode_words();
+    let in_token = |c: char| match options.digits {
+        DigitPolicy::Split if unicode => is_word_letter(c),
+        DigitPolicy::Split => c.is_ascii_alphabetic(),
+        _ if unicode => is_word_letter(c) || c.is_ascii_digit(),
+        _ => c.is_ascii_alphanumeric(),
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    while let Some(first) = text[i..].chars().next() {
+        let start = i;
+        let is_token = in_token(first);
+        i = text[start..].find(|c| in_token(c) != is_token).map_or(text.len(), |len| start + len);
+        if is_token {
+            let token = &text[start..i];
+            let disposition = if options.stopwords.is_some_and(|s| s.contains(token)) {
+                Disposition::Skip
+            } else if let Some(max) = options.max_length.filter(|&max| longer_than(token, max)) {
+                match options.long_tokens {
+                    LongTokenPolicy::Reject if strict => {
+                        return Err(StemError::TokenTooLong { length: token.chars().count(), max });
+                    },
+                    LongTokenPolicy::Verbatim | LongTokenPolicy::Reject => Disposition::Verbatim,
+                    LongTokenPolicy::Skip => Disposition::Skip,
+                }
+            } else {
+                options.digits.dispose(token)
+            };
+            match disposition {
+                Disposition::Stem(letters) if !longer_than(&letters, options.min_length.saturating_sub(1)) => {
+                    if options.preserve_case {
+                        out.push_str(&letters);
+                    } else {
+                        out.push_str(&letters.to_lowercase());
+                    }
+                },
+                Disposition::Stem(letters) if strict => {
+                    let stem = stemmer.try_stem(&letters).map_err(|e| e.offset(start))?;
+                    out.push_str(&if options.preserve_case { restore_case(&letters, &stem) } else { stem });
+                },
+                Disposition::Stem(letters) if options.preserve_case => {
+                    out.push_str(&restore_case(&letters, &stemmer.stem(&letters)));
+                },
+                Disposition::Stem(letters) => out.push_str(&stemmer.stem(&letters)),
+                Disposition::Verbatim => out.push_str(token),
+                Disposition::Skip => i = drop_token(bytes, i, &mut out),
+            }
+        } else {
+            push_separators(text, start..i, options.symbols, &mut out);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Returns true if `word` has more than `length` characters, without
+/// counting all the characters of a long one
+fn longer_than(word: &str, length: usize) -> bool {
+    word.len() > length && word.chars().nth(length).is_some()
+}
+
+/// Bytes read from the input at a time by [`read_chunks`]
+pub const READ_SIZE: usize = 64 * 1024;
+
+/// Longest piece [`read_chunks`] holds while waiting for a line break
+pub const MAX_CHUNK: usize = 1 << 20;
+
+/// Reads `input` to the end, passing its text to `f` in pieces that end at
+/// a line break, so that no word is split between two of them
+///
+/// A line longer than [`MAX_CHUNK`] bytes is cut after a space or tab, or
+/// failing that between two characters, so memory use stays bounded
+/// whatever the input; only the spacing around a stopword dropped at such
+/// a cut may then differ from stemming the line whole. Input that is not
+/// UTF-8 is an `InvalidData` error, like reading it with
+/// `BufRead::read_line`.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::text::read_chunks;
+/// let mut lines = 0;
+/// read_chunks("one\ntwo\nthree".as_bytes(), |chunk| {
+///     lines += chunk.lines().count();
+///     Ok(())
+/// })
+/// .unwrap();
+/// assert_eq!(lines, 3);
+/// ```
+pub fn read_chunks(mut input: impl Read, mut f: impl FnMut(&str) -> io::Result<()>) -> io::Result<()> {
+    let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+    let mut buf = Vec::new();
+    let mut block = vec![0; READ_SIZE];
+    loop {
+        let n = match input.read(&mut block) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        buf.extend_from_slice(&block[..n]);
+        let end = match buf.iter().rposition(|&b| b == b'\n') {
+            Some(i) => i + 1,
+            None if buf.len() < MAX_CHUNK => continue,
+            None => match buf.iter().rposition(|&b| matches!(b, b' ' | b'\t')) {
+                Some(i) => i + 1,
+                None => match std::str::from_utf8(&buf) {
+                    Ok(_) => buf.len(),
+                    Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                    Err(e) => return Err(invalid(e)),
+                },
+            },
+        };
+        f(std::str::from_utf8(&buf[..end]).map_err(invalid)?)?;
+        buf.drain(..end);
+    }
+    if buf.is_empty() {
+        return Ok(());
+    }
+    f(std::str::from_utf8(&buf).map_err(invalid)?)
+}
+
+/// Stems the text read from `input` with the given options, writing the
+/// output a piece at a time as [`read_chunks`] reads it, so that inputs far
+/// larger than memory can be stemmed
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::PorterStemmer;
+/// # use m_porter_stemmer_rust::text::{stem_stream, TextOptions};
+/// let mut output = Vec::new();
+/// stem_stream(&mut PorterStemmer::new(), "Running ponies\n".as_bytes(), &mut output, &TextOptions::new()).unwrap();
+/// assert_eq!(output, b"run poni\n");
+/// ```
+pub fn stem_stream(
+    stemmer: &mut dyn Stemmer,
+    input: impl Read,
+    mut output: impl Write,
+    options: &TextOptions,
+) -> io::Result<()> {
+    read_chunks(input, |chunk| output.write_all(stem_text_with(stemmer, chunk, options).as_bytes()))?;
+    output.flush()
+}
+
+/// Stems the text read from `input` as [`stem_stream`] does, failing as
+/// [`try_stem_text_with`] does, with error positions counted from the start
+/// of the input
+///
+/// The pieces before the one that fails are written.
+pub fn try_stem_stream(
+    stemmer: &mut dyn Stemmer,
+    input: impl Read,
+    mut output: impl Write,
+    options: &TextOptions,
+) -> Result<(), StemError> {
+    let mut offset = 0;
+    let mut failed = None;
+    let read = read_chunks(input, |chunk| {
+        match try_stem_text_with(stemmer, chunk, options) {
+            Ok(stemmed) => output.write_all(stemmed.as_bytes())?,
+            Err(e) => {
+                failed = Some(e.offset(offset));
+                return Err(io::Error::other("stemming failed"));
+            },
+        }
+        offset += chunk.len();
+        Ok(())
+    });
+    if let Some(e) = failed {
+        return Err(e);
+    }
+    read?;
+    Ok(output.flush()?)
+}
+
+/// Copies the non-word text in `range`, applying the symbol policy to any
+/// symbols in it
+///
+/// A dropped symbol directly before a letter or digit (`$5`) keeps the space
+/// before it, so what follows does not join the text in front.
+fn push_separators(text: &str, range: std::ops::Range<usize>, policy: SymbolPolicy, out: &mut String) {
+    if policy == SymbolPolicy::Preserve {
+        out.push_str(&text[range]);
+        return;
+    }
+
+    let mut pos = range.start;
+    let mut copied = range.start;
+    while pos < range.end {
+        match symbol_len(&text[pos..]) {
+            Some(len) => {
+                out.push_str(&text[copied..pos]);
+                pos = match policy {
+                    SymbolPolicy::Placeholder(placeholder) => {
+                        out.push_str(placeholder);
+                        pos + len
+                    },
+                    _ => {
+                        // Drop a run of adjacent symbols ("😍🔥") as one
+                        let mut end = pos + len;
+                        while let Some(len) = symbol_len(&text[end..range.end]) { end += len; }
+                        if text[end..].starts_with(char::is_alphanumeric) {
+                            end
+                        } else {
+                            drop_token(&text.as_bytes()[..range.end], end, out)
+                        }
+                    },
+                };
+                copied = pos;
+            },
+            None => pos += text[pos..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    out.push_str(&text[copied..range.end]);
+}
+
+/// Removes the spacing around a dropped token ending at `end`
+///
+/// Skips the spaces or tabs that follow the token; if there are none, trims
+/// the spaces or tabs already written before it. Returns the new position.
+fn drop_token(bytes: &[u8], end: usize, out: &mut String) -> usize {
+    let mut i = end;
+    if matches!(bytes.get(i), Some(b' ' | b'\t')) {
+        while matches!(bytes.get(i), Some(b' ' | b'\t')) { i += 1; }
+    } else {
+        out.truncate(out.trim_end_matches([' ', '\t']).len());
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_stem_text_passes_separators_through() {
+        let mut stemmer = PorterStemmer::new();
+        assert_eq!(
+            stem_text(&mut stemmer, "Caresses and ponies: 3 cats.\n"),
+            "caress and poni: 3 cat.\n"
+        );
+        assert_eq!(stem_text(&mut stemmer, ""), "");
+        assert_eq!(stem_text(&mut stemmer, "naïve"), "naïve");
+
+        let mut folding = PorterStemmer::new().with_diacritic_folding(true);
+        assert_eq!(stem_text(&mut folding, "Naïve résumés."), "naiv resum.");
+    }
+
+    #[test]
+    fn test_tokens_match_stem_text_words() {
+        let text = "--Caresses, 3 ponies--";
+        let words: Vec<_> = tokens(text).map(|t| (t.text, t.range())).collect();
+        assert_eq!(words, [("Caresses", 2..10), ("ponies", 14..20)]);
+        assert_eq!(tokens("").count(), 0);
+        let words: Vec<_> = tokens_for(&crate::FrenchStemmer::new(), "«Été», dit-il").map(|t| t.text).collect();
+        assert_eq!(words, ["Été", "dit", "il"]);
+        let words: Vec<_> = tokens_for(&crate::RussianStemmer::new(), "1 «Книги»").map(|t| (t.text, t.range())).collect();
+        assert_eq!(words, [("Книги", 4..14)]);
+        let words: Vec<_> = tokens_for(&crate::HindiStemmer::new(), "बच्चे, किताबें।").map(|t| t.text).collect();
+        assert_eq!(words, ["बच्चे", "किताबें"]);
+    }
+
+    #[test]
+    fn test_stem_text_filtered_drops_stopwords() {
+        let mut stemmer = PorterStemmer::new();
+        let stopwords = Stopwords::english();
+        assert_eq!(
+            stem_text_filtered(&mut stemmer, "The cat sat on the mat.\nDon't stop (the running)\n", &stopwords),
+            "cat sat mat.\n'stop (run)\n"
+        );
+        assert_eq!(stem_text_filtered(&mut stemmer, "cats", &Stopwords::new()), "cat");
+    }
+
+    #[test]
+    fn test_digit_policies_in_text() {
+        let mut stemmer = PorterStemmer::new();
+        let text = "In 2023, covid19 cases (b2b) rose";
+        let run = |stemmer: &mut dyn Stemmer, policy| {
+            stem_text_with(stemmer, text, &TextOptions::new().digits(policy))
+        };
+        assert_eq!(run(&mut stemmer, DigitPolicy::Split), "in 2023, covid19 case (b2b) rose");
+        assert_eq!(run(&mut stemmer, DigitPolicy::Verbatim), "in 2023, covid19 case (b2b) rose");
+        assert_eq!(run(&mut stemmer, DigitPolicy::Skip), "in, case () rose");
+        assert_eq!(run(&mut stemmer, DigitPolicy::StripDigits), "in, covid case (bb) rose");
+    }
+
+    #[test]
+    fn test_symbol_policies_in_text() {
+        let mut stemmer = PorterStemmer::new();
+        let text = "Great job 👍🏽! Price: $5 + tax ✨";
+        let run = |stemmer: &mut dyn Stemmer, policy| {
+            stem_text_with(stemmer, text, &TextOptions::new().symbols(policy))
+        };
+        assert_eq!(run(&mut stemmer, SymbolPolicy::Preserve), "great job 👍🏽! price: $5 + tax ✨");
+        assert_eq!(run(&mut stemmer, SymbolPolicy::Drop), "great job! price: 5 tax");
+        assert_eq!(run(&mut stemmer, SymbolPolicy::Placeholder("_")), "great job _! price: _5 _ tax _");
+    }
+
+    #[test]
+    fn test_preserve_case() {
+        let mut stemmer = PorterStemmer::new();
+        let options = TextOptions::new().preserve_case(true);
+        assert_eq!(stem_text_with(&mut stemmer, "Running quickly, NASA's PONIES", &options), "Run quickli, NASA's PONI");
+        assert_eq!(restore_case("Ab", "abcd"), "Abcd");
+        assert_eq!(restore_case("AB", "abcd"), "ABCD");
+    }
+
+    #[test]
+    fn test_length_limits() {
+        let mut stemmer = PorterStemmer::new();
+        let text = "Cats sat on mats, generalizations";
+        let min = TextOptions::new().min_length(5);
+        assert_eq!(stem_text_with(&mut stemmer, text, &min), "cats sat on mats, gener");
+        assert_eq!(stem_text_with(&mut stemmer, text, &TextOptions::new().min_length(0)), stem_text(&mut stemmer, text));
+
+        let long = format!("see {} here", "X".repeat(MAX_CHUNK));
+        let pass = TextOptions::new().max_length(20, LongTokenPolicy::Verbatim);
+        assert_eq!(stem_text_with(&mut stemmer, &long, &pass), long);
+        let drop = TextOptions::new().max_length(20, LongTokenPolicy::Skip);
+        assert_eq!(stem_text_with(&mut stemmer, &long, &drop), "see here");
+        // Characters are counted, not bytes
+        let accented = TextOptions::new().max_length(7, LongTokenPolicy::Skip);
+        assert_eq!(stem_text_with(&mut stemmer, "naïveté", &accented), stem_text(&mut stemmer, "naïveté"));
+    }
+
+    #[test]
+    fn test_try_stem_text() {
+        let mut stemmer = PorterStemmer::new();
+        let options = TextOptions::new().max_length(8, LongTokenPolicy::Reject);
+        let text = "Running ponies, naïve AAAAQXJjaGlZQ";
+        assert_eq!(stem_text_with(&mut stemmer, text, &options), "run poni, naïve AAAAQXJjaGlZQ");
+        let error = try_stem_text_with(&mut stemmer, text, &options).unwrap_err();
+        assert!(matches!(error, StemError::TokenTooLong { length: 13, max: 8 }));
+        let options = TextOptions::new();
+        assert_eq!(try_stem_text_with(&mut stemmer, text, &options).unwrap(), stem_text(&mut stemmer, text));
+
+        let mut rejecting = PorterStemmer::new().with_non_ascii(NonAsciiPolicy::Reject);
+        assert_eq!(stem_text_with(&mut rejecting, text, &options), stem_text(&mut stemmer, text));
+        let error = try_stem_text_with(&mut rejecting, text, &options).unwrap_err();
+        assert!(matches!(error, StemError::NonAsciiLetter { character: 'ï', position: 18 }));
+        // Symbols are not letters
+        assert_eq!(try_stem_text_with(&mut rejecting, "cats → dogs", &options).unwrap(), "cat → dog");
+
+        let input = format!("{}naïve\n", "cats\n".repeat(MAX_CHUNK / 4));
+        let mut output = Vec::new();
+        let error = try_stem_stream(&mut rejecting, input.as_bytes(), &mut output, &options).unwrap_err();
+        assert!(matches!(error, StemError::NonAsciiLetter { position, .. } if position == input.len() - 5));
+        assert!(!output.is_empty() && output.iter().all(|&b| b != 0xC3));
+        let error = try_stem_stream(&mut stemmer, &b"ok \xFF\n"[..], io::sink(), &options).unwrap_err();
+        assert!(matches!(error, StemError::IoError(e) if e.kind() == io::ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn test_stem_stream() {
+        let mut stemmer = PorterStemmer::new();
+        let mut output = Vec::new();
+        let input = "Caresses, ponies!\n\trunning--\n42 x";
+        stem_stream(&mut stemmer, input.as_bytes(), &mut output, &TextOptions::new()).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "caress, poni!\n\trun--\n42 x");
+
+        // A line with no break in sight is still cut, between words
+        let long = "ponies ".repeat(MAX_CHUNK / 3);
+        let mut pieces = Vec::new();
+        read_chunks(long.as_bytes(), |chunk| {
+            pieces.push(chunk.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert!(pieces.len() > 1 && pieces.iter().all(|p| p.len() <= MAX_CHUNK + READ_SIZE && p.ends_with("ponies ")));
+        assert_eq!(pieces.concat(), long);
+
+        // or between characters if it has no spaces either
+        let word = "é".repeat(MAX_CHUNK);
+        let mut total = 0;
+        read_chunks(word.as_bytes(), |chunk| {
+            total += chunk.len();
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(total, word.len());
+        let error = read_chunks(&b"ok \xFF\n"[..], |_| Ok(())).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
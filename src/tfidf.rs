@@ -0,0 +1,174 @@
+//! # TF-IDF
+//!
+//! Stems documents as they are added, keeps their term counts and the
+//! document frequency of every stem, and computes TF-IDF vectors keyed by
+//! stem.
+//!
+//! Term frequency is the count of a stem divided by the number of counted
+//! words in the document. Inverse document frequency is smoothed as
+//! `ln((1 + N) / (1 + df)) + 1`, so a stem found in every document still
+//! has weight 1 and an unseen stem never divides by zero.
+
+use std::collections::HashMap;
+
+use crate::freq::FrequencyCounter;
+use crate::stopwords::Stopwords;
+use crate::Stemmer;
+
+/// The stem counts of one document
+#[derive(Debug, Clone, Default)]
+struct Document {
+    counts: HashMap<String, u64>,
+    len: u64,
+}
+
+/// A collection of stemmed documents with their document frequencies
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, tfidf::TfIdf};
+/// let mut stemmer = PorterStemmer::new();
+/// let mut tfidf = TfIdf::new();
+/// let cats = tfidf.add_document(&mut stemmer, "cats chase mice");
+/// tfidf.add_document(&mut stemmer, "dogs chase cats");
+/// let vector = tfidf.vector(cats);
+/// assert!(vector["mice"] > vector["chase"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TfIdf {
+    documents: Vec<Document>,
+    doc_freq: HashMap<String, u64>,
+    stopwords: Option<Stopwords>,
+}
+
+impl TfIdf {
+    /// Creates an empty collection that counts every word
+    pub fn new() -> Self {
+        TfIdf { documents: Vec::new(), doc_freq: HashMap::new(), stopwords: None }
+    }
+
+    /// Drops the given stopwords from documents added from now on
+    pub fn with_stopwords(mut self, stopwords: Stopwords) -> Self {
+        self.stopwords = Some(stopwords);
+        self
+    }
+
+    /// Stems and adds a document, returning its index
+    pub fn add_document(&mut self, stemmer: &mut dyn Stemmer, text: &str) -> usize {
+        let mut counter = FrequencyCounter::new();
+        if let Some(stopwords) = &self.stopwords { counter = counter.stopwords(stopwords); }
+        counter.feed(stemmer, text);
+        self.add_counts(counter.finish(stemmer))
+    }
+
+    /// Adds a document from its stem counts, as kept by a saved index,
+    /// returning its index
+    pub fn add_counts(&mut self, counts: HashMap<String, u64>) -> usize {
+        for stem in counts.keys() {
+            *self.doc_freq.entry(stem.clone()).or_insert(0) += 1;
+        }
+        let len = counts.values().sum();
+        self.documents.push(Document { counts, len });
+        self.documents.len() - 1
+    }
+
+    /// Number of documents
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Returns true if no document has been added
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Number of documents containing `stem`
+    pub fn document_frequency(&self, stem: &str) -> u64 {
+        self.doc_freq.get(stem).copied().unwrap_or(0)
+    }
+
+    /// Number of times `stem` occurs in document `doc`
+    ///
+    /// # Panics
+    /// Panics if `doc` is not the index of an added document
+    pub fn term_count(&self, doc: usize, stem: &str) -> u64 {
+        self.documents[doc].counts.get(stem).copied().unwrap_or(0)
+    }
+
+    /// The count of every stem in document `doc`
+    ///
+    /// # Panics
+    /// Panics if `doc` is not the index of an added document
+    pub fn counts(&self, doc: usize) -> &HashMap<String, u64> {
+        &self.documents[doc].counts
+    }
+
+    /// Number of counted words in document `doc`
+    ///
+    /// # Panics
+    /// Panics if `doc` is not the index of an added document
+    pub fn document_len(&self, doc: usize) -> u64 {
+        self.documents[doc].len
+    }
+
+    /// Mean number of counted words per document, 0 if there are none
+    pub fn average_document_len(&self) -> f64 {
+        if self.documents.is_empty() { return 0.0; }
+        let total: u64 = self.documents.iter().map(|d| d.len).sum();
+        total as f64 / self.documents.len() as f64
+    }
+
+    /// Smoothed inverse document frequency of `stem`
+    pub fn idf(&self, stem: &str) -> f64 {
+        let n = self.documents.len() as f64;
+        let df = self.document_frequency(stem) as f64;
+        ((1.0 + n) / (1.0 + df)).ln() + 1.0
+    }
+
+    /// TF-IDF weight of every stem in document `doc`
+    ///
+    /// # Panics
+    /// Panics if `doc` is not the index of an added document
+    pub fn vector(&self, doc: usize) -> HashMap<String, f64> {
+        let document = &self.documents[doc];
+        document
+            .counts
+            .iter()
+            .map(|(stem, &count)| {
+                let tf = count as f64 / document.len as f64;
+                (stem.clone(), tf * self.idf(stem))
+            })
+            .collect()
+    }
+
+    /// TF-IDF vectors of all documents, in the order they were added
+    pub fn vectors(&self) -> Vec<HashMap<String, f64>> {
+        (0..self.documents.len()).map(|doc| self.vector(doc)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_weights() {
+        let mut stemmer = PorterStemmer::new();
+        let mut tfidf = TfIdf::new().with_stopwords(Stopwords::english());
+        tfidf.add_document(&mut stemmer, "The cat sat");
+        tfidf.add_document(&mut stemmer, "The cats ran and ran");
+        assert_eq!(tfidf.len(), 2);
+        assert_eq!(tfidf.document_frequency("cat"), 2);
+        assert_eq!(tfidf.document_frequency("the"), 0);
+        assert_eq!(tfidf.term_count(1, "ran"), 2);
+        assert_eq!(tfidf.document_len(1), 3);
+        assert_eq!(tfidf.average_document_len(), 2.5);
+
+        let vector = tfidf.vector(1);
+        assert!((vector["cat"] - 1.0 / 3.0).abs() < 1e-12);
+        let idf_ran = (3.0f64 / 2.0).ln() + 1.0;
+        assert!((vector["ran"] - 2.0 / 3.0 * idf_ran).abs() < 1e-12);
+        assert!(TfIdf::new().vectors().is_empty());
+    }
+}
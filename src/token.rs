@@ -0,0 +1,293 @@
+//! # Token Classification
+//!
+//! The Porter algorithm is only defined over letters, so tokens containing
+//! digits ("2023", "covid19", "b2b") need an explicit policy. The same
+//! [`DigitPolicy`] is applied by the word-level [`stem_token`] and by the text
+//! pipeline ([`crate::text::TextOptions`]), so both give the same answer for
+//! the same token.
+//!
+//! Emoji and other symbols between the words of running text are covered
+//! by a [`SymbolPolicy`] in the same way.
+
+use std::borrow::Cow;
+
+use crate::Stemmer;
+
+/// The kind of characters an ASCII alphanumeric token is made of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenClass {
+    /// Letters only, e.g. "running"
+    Alphabetic,
+    /// Digits only, e.g. "2023"
+    Numeric,
+    /// Letters and digits, e.g. "covid19" or "b2b"
+    Alphanumeric,
+}
+
+/// Classifies a token by whether it contains letters, digits or both
+///
+/// Characters other than ASCII letters and digits are counted as letters.
+pub fn classify(token: &str) -> TokenClass {
+    let digits = token.bytes().filter(u8::is_ascii_digit).count();
+    if digits == 0 {
+        TokenClass::Alphabetic
+    } else if digits == token.len() {
+        TokenClass::Numeric
+    } else {
+        TokenClass::Alphanumeric
+    }
+}
+
+/// What to do with tokens that contain digits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DigitPolicy {
+    /// Digits separate words, as in the reference C program: the letter
+    /// runs of "covid19" are stemmed and the digits are kept ("covid19")
+    #[default]
+    Split,
+    /// Drop numeric and alphanumeric tokens entirely
+    Skip,
+    /// Pass numeric and alphanumeric tokens through verbatim, unstemmed
+    Verbatim,
+    /// Remove the digits and stem the letters that remain ("covid19" ->
+    /// "covid"); a purely numeric token is dropped
+    StripDigits,
+}
+
+impl std::str::FromStr for DigitPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "split" => Ok(DigitPolicy::Split),
+            "skip" => Ok(DigitPolicy::Skip),
+            "verbatim" => Ok(DigitPolicy::Verbatim),
+            "strip" | "strip-digits" => Ok(DigitPolicy::StripDigits),
+            _ => Err(format!("unknown digit policy: {:?} (expected split, skip, verbatim or strip)", s)),
+        }
+    }
+}
+
+/// A token as rewritten by a [`DigitPolicy`], before stemming
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Disposition<'a> {
+    /// Stem these letters
+    Stem(Cow<'a, str>),
+    /// Emit the token unchanged
+    Verbatim,
+    /// Drop the token
+    Skip,
+}
+
+impl DigitPolicy {
+    /// Decides how a single non-split alphanumeric token is handled
+    pub(crate) fn dispose(self, token: &str) -> Disposition<'_> {
+        if classify(token) == TokenClass::Alphabetic {
+            return Disposition::Stem(Cow::Borrowed(token));
+        }
+        match self {
+            DigitPolicy::Skip => Disposition::Skip,
+            DigitPolicy::Verbatim | DigitPolicy::Split => Disposition::Verbatim,
+            DigitPolicy::StripDigits => {
+                let letters: String = token.chars().filter(|c| !c.is_ascii_digit()).collect();
+                if letters.is_empty() {
+                    Disposition::Skip
+                } else {
+                    Disposition::Stem(Cow::Owned(letters))
+                }
+            },
+        }
+    }
+}
+
+/// Stems a single alphanumeric token according to `policy`
+///
+/// # Arguments
+/// * `stemmer` - Stemmer used for the letters of the token
+/// * `token` - A word made of ASCII letters and digits
+/// * `policy` - How digits in the token are treated
+///
+/// # Returns
+/// The stemmed token, or None if the policy drops it
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::{PorterStemmer, token::{stem_token, DigitPolicy}};
+/// let mut stemmer = PorterStemmer::new();
+/// assert_eq!(stem_token(&mut stemmer, "vaccines2021", DigitPolicy::Split).as_deref(), Some("vaccin2021"));
+/// assert_eq!(stem_token(&mut stemmer, "vaccines2021", DigitPolicy::StripDigits).as_deref(), Some("vaccin"));
+/// assert_eq!(stem_token(&mut stemmer, "vaccines2021", DigitPolicy::Verbatim).as_deref(), Some("vaccines2021"));
+/// assert_eq!(stem_token(&mut stemmer, "2021", DigitPolicy::Skip), None);
+/// ```
+pub fn stem_token(stemmer: &mut dyn Stemmer, token: &str, policy: DigitPolicy) -> Option<String> {
+    if policy == DigitPolicy::Split {
+        let mut out = String::with_capacity(token.len());
+        let mut rest = token;
+        while !rest.is_empty() {
+            let digit_run = rest.bytes().take_while(u8::is_ascii_digit).count();
+            out.push_str(&rest[..digit_run]);
+            rest = &rest[digit_run..];
+            let letter_run = rest.bytes().take_while(|b| !b.is_ascii_digit()).count();
+            if letter_run > 0 { out.push_str(&stemmer.stem(&rest[..letter_run])); }
+            rest = &rest[letter_run..];
+        }
+        return Some(out);
+    }
+
+    match policy.dispose(token) {
+        Disposition::Stem(letters) => Some(stemmer.stem(&letters)),
+        Disposition::Verbatim => Some(token.to_string()),
+        Disposition::Skip => None,
+    }
+}
+
+/// What to do with tokens longer than the maximum length of
+/// [`TextOptions`](crate::text::TextOptions)
+///
+/// Such tokens are rarely words: base64 blobs, URLs run together or markup
+/// scraped with the text. Stemming one a megabyte long would take a
+/// megabyte-sized buffer (or four, for `char`-based stemmers) for nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LongTokenPolicy {
+    /// Pass the token through unchanged, unstemmed and not lowercased
+    #[default]
+    Verbatim,
+    /// Drop the token, along with the spaces or tabs that follow it
+    Skip,
+    /// Pass it through in the infallible functions, and fail with
+    /// [`crate::StemError::TokenTooLong`] in the `try_` ones
+    Reject,
+}
+
+impl std::str::FromStr for LongTokenPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "pass" | "verbatim" => Ok(LongTokenPolicy::Verbatim),
+            "drop" | "skip" => Ok(LongTokenPolicy::Skip),
+            "fail" | "reject" => Ok(LongTokenPolicy::Reject),
+            _ => Err(format!("unknown long token policy: {:?} (expected pass, drop or fail)", s)),
+        }
+    }
+}
+
+/// What to do with emoji and other symbols in running text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SymbolPolicy<'a> {
+    /// Copy symbols through unchanged, as the reference C program does
+    #[default]
+    Preserve,
+    /// Remove symbols, along with the spaces or tabs that follow them
+    Drop,
+    /// Replace each symbol with the given text, e.g. `"<emoji>"`
+    Placeholder(&'a str),
+}
+
+/// Returns true if `c` is a symbol rather than a letter, digit, space or
+/// punctuation mark: an emoji, a currency sign, a math or other symbol
+///
+/// This approximates the Unicode symbol categories (Sm, Sc, Sk, So) without
+/// the full tables: the ASCII symbols `$+<=>^`|~`, and any other non-ASCII
+/// character that is not alphanumeric, whitespace, a common punctuation
+/// mark, a combining mark or an invisible format character.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::token::is_symbol;
+/// assert!(is_symbol('🎉') && is_symbol('€') && is_symbol('+') && is_symbol('©'));
+/// assert!(!is_symbol('a') && !is_symbol('é') && !is_symbol('!') && !is_symbol('«'));
+/// ```
+pub fn is_symbol(c: char) -> bool {
+    if c.is_ascii() {
+        return matches!(c, '$' | '+' | '<' | '=' | '>' | '^' | '`' | '|' | '~');
+    }
+    !(c.is_alphanumeric()
+        || c.is_whitespace()
+        || c.is_control()
+        || matches!(c,
+            // Latin-1 punctuation
+            '¡' | '§' | '«' | '¶' | '·' | '»' | '¿'
+            // combining marks
+            | '\u{300}'..='\u{36F}' | '\u{1AB0}'..='\u{1AFF}' | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}' | '\u{FE20}'..='\u{FE2F}'
+            // general punctuation, including dashes, quotes and format characters
+            | '\u{2000}'..='\u{206F}'
+            // CJK and fullwidth punctuation
+            | '\u{3000}'..='\u{3003}' | '\u{3008}'..='\u{3011}' | '\u{3014}'..='\u{301F}'
+            | '\u{FF01}'..='\u{FF0F}' | '\u{FF1A}'..='\u{FF20}'
+            // variation selectors, byte order mark and emoji tags
+            | '\u{FE00}'..='\u{FE0F}' | '\u{FEFF}' | '\u{E0020}'..='\u{E007F}'))
+}
+
+/// Returns true if `c` attaches to the symbol before it: variation
+/// selectors, the keycap mark, skin-tone modifiers and emoji tag characters
+fn extends_symbol(c: char) -> bool {
+    matches!(c, '\u{FE0E}' | '\u{FE0F}' | '\u{20E3}' | '\u{1F3FB}'..='\u{1F3FF}' | '\u{E0020}'..='\u{E007F}')
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+/// If `text` starts with a symbol, returns its length in bytes
+///
+/// A symbol is taken together with what visually belongs to it: modifiers,
+/// zero-width-joined sequences ("👨‍👩‍👧") and flag pairs ("🇫🇷").
+pub(crate) fn symbol_len(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().peekable();
+    let (_, first) = chars.next().filter(|&(_, c)| is_symbol(c))?;
+    let mut end = first.len_utf8();
+    let mut flag_pair = is_regional_indicator(first);
+
+    while let Some((i, c)) = chars.next() {
+        if extends_symbol(c) {
+            end = i + c.len_utf8();
+        } else if flag_pair && is_regional_indicator(c) {
+            end = i + c.len_utf8();
+            flag_pair = false;
+        } else if c == '\u{200D}' && chars.peek().is_some_and(|&(_, next)| is_symbol(next)) {
+            let (j, next) = chars.next().unwrap_or((i, c));
+            end = j + next.len_utf8();
+            flag_pair = false;
+        } else {
+            break;
+        }
+    }
+    Some(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PorterStemmer;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("running"), TokenClass::Alphabetic);
+        assert_eq!(classify("2023"), TokenClass::Numeric);
+        assert_eq!(classify("covid19"), TokenClass::Alphanumeric);
+        assert_eq!(classify("b2b"), TokenClass::Alphanumeric);
+    }
+
+    #[test]
+    fn test_policies_on_alphabetic_tokens_agree() {
+        let mut stemmer = PorterStemmer::new();
+        for policy in [DigitPolicy::Split, DigitPolicy::Skip, DigitPolicy::Verbatim, DigitPolicy::StripDigits] {
+            assert_eq!(stem_token(&mut stemmer, "ponies", policy).as_deref(), Some("poni"));
+        }
+        assert_eq!(stem_token(&mut stemmer, "2023", DigitPolicy::StripDigits), None);
+        assert_eq!(stem_token(&mut stemmer, "b2bs", DigitPolicy::Split).as_deref(), Some("b2bs"));
+        assert_eq!("strip".parse::<DigitPolicy>(), Ok(DigitPolicy::StripDigits));
+    }
+
+    #[test]
+    fn test_symbol_len_keeps_sequences_together() {
+        assert_eq!(symbol_len("👍🏽 ok"), Some("👍🏽".len()));
+        assert_eq!(symbol_len("👨\u{200D}👩\u{200D}👧!"), Some("👨\u{200D}👩\u{200D}👧".len()));
+        assert_eq!(symbol_len("🇫🇷🇩🇪"), Some("🇫🇷".len()));
+        assert_eq!(symbol_len("❤\u{FE0F}"), Some("❤\u{FE0F}".len()));
+        assert_eq!(symbol_len("ok 👍"), None);
+        assert_eq!(symbol_len(""), None);
+    }
+}
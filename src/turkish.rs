@@ -0,0 +1,514 @@
+//! # Turkish (Snowball)
+//!
+//! The Snowball Turkish stemmer
+//! (<https://snowballstem.org/algorithms/turkish/stemmer.html>), after
+//! Eryiğit and Adalı's affix-stripping analyzer. Turkish words are chains
+//! of suffixes, so the stemmer removes them from right to left along the
+//! chains the language allows: first the nominal verb suffixes ("-sunuz",
+//! "-mış"), then the noun suffixes ("-lar", "-ında", "-ki"). A suffix is
+//! only removed if its vowel agrees with the vowels of the word (vowel
+//! harmony), and words of one syllable are left alone.
+//!
+//! Because a failed step of a chain backtracks to where it started, the
+//! steps are written as in the Snowball source, moving a cursor back
+//! through the word and deleting marked slices, rather than as a table of
+//! suffixes.
+//!
+//! Turkish distinguishes dotted and dotless "i" in both cases, so words are
+//! lowercased with [`turkish_lowercase`] ("I" to "ı", "İ" to "i") instead
+//! of the default Unicode mapping.
+
+use crate::fold;
+use crate::snowball::find_suffix;
+use crate::stemmer::Stemmer;
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'ı' | 'i' | 'o' | 'ö' | 'u' | 'ü')
+}
+
+type Group = fn(char) -> bool;
+
+/// The high vowels, written "U" in the suffixes
+fn is_u(c: char) -> bool {
+    matches!(c, 'ı' | 'i' | 'u' | 'ü')
+}
+
+/// Returns `word` lowercased by the Turkish rules
+///
+/// "I" becomes the dotless "ı" and "İ" the plain "i"; everything else is
+/// lowercased as by [`str::to_lowercase`], which would turn "I" into "i"
+/// and "İ" into "i" followed by a combining dot.
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::turkish::turkish_lowercase;
+/// assert_eq!(turkish_lowercase("IRMAK"), "ırmak");
+/// assert_eq!(turkish_lowercase("İstanbul"), "istanbul");
+/// ```
+pub fn turkish_lowercase(word: &str) -> String {
+    word.chars()
+        .flat_map(|c| match c {
+            'I' => 'ı'.to_lowercase(),
+            'İ' => 'i'.to_lowercase(),
+            c => c.to_lowercase(),
+        })
+        .collect()
+}
+
+/// A letter that joins a suffix to a stem ending in a vowel ("kapı-y-ı")
+#[derive(Debug, Clone, Copy)]
+enum Joiner {
+    None,
+    Y,
+    N,
+    S,
+    /// A high vowel joining a suffix to a stem ending in a consonant
+    U,
+}
+
+/// A class of suffixes, such as the plural "-lAr"
+#[derive(Debug)]
+struct Suffix {
+    /// Whether the suffix's vowel must agree with the stem's
+    harmony: bool,
+    endings: &'static [&'static str],
+    joiner: Joiner,
+}
+
+const fn suffix(harmony: bool, endings: &'static [&'static str], joiner: Joiner) -> Suffix {
+    Suffix { harmony, endings, joiner }
+}
+
+const POSSESSIVES: Suffix =
+    suffix(false, &["mız", "miz", "muz", "müz", "nız", "niz", "nuz", "nüz", "m", "n"], Joiner::U);
+const SU: Suffix = suffix(true, &["ı", "i", "u", "ü"], Joiner::S);
+const LARI: Suffix = suffix(false, &["leri", "ları"], Joiner::None);
+const YU: Suffix = suffix(true, &["ı", "i", "u", "ü"], Joiner::Y);
+const NU: Suffix = suffix(true, &["nı", "ni", "nu", "nü"], Joiner::None);
+const NUN: Suffix = suffix(true, &["ın", "in", "un", "ün"], Joiner::N);
+const YA: Suffix = suffix(true, &["a", "e"], Joiner::Y);
+const NA: Suffix = suffix(true, &["na", "ne"], Joiner::None);
+const DA: Suffix = suffix(true, &["da", "de", "ta", "te"], Joiner::None);
+const NDA: Suffix = suffix(true, &["nda", "nde"], Joiner::None);
+const DAN: Suffix = suffix(true, &["dan", "den", "tan", "ten"], Joiner::None);
+const NDAN: Suffix = suffix(true, &["ndan", "nden"], Joiner::None);
+const YLA: Suffix = suffix(true, &["la", "le"], Joiner::Y);
+const KI: Suffix = suffix(false, &["ki"], Joiner::None);
+const NCA: Suffix = suffix(true, &["ca", "ce"], Joiner::N);
+const YUM: Suffix = suffix(true, &["ım", "im", "um", "üm"], Joiner::Y);
+const SUN: Suffix = suffix(true, &["sın", "sin", "sun", "sün"], Joiner::None);
+const YUZ: Suffix = suffix(true, &["ız", "iz", "uz", "üz"], Joiner::Y);
+const SUNUZ: Suffix = suffix(false, &["sınız", "siniz", "sunuz", "sünüz"], Joiner::None);
+const LAR: Suffix = suffix(true, &["ler", "lar"], Joiner::None);
+const NUZ: Suffix = suffix(true, &["nız", "niz", "nuz", "nüz"], Joiner::None);
+const DUR: Suffix = suffix(true, &["tır", "tir", "tur", "tür", "dır", "dir", "dur", "dür"], Joiner::None);
+const CASINA: Suffix = suffix(false, &["casına", "cesine"], Joiner::None);
+const YDU: Suffix = suffix(
+    true,
+    &[
+        "tım", "tim", "tum", "tüm", "dım", "dim", "dum", "düm", "tın", "tin", "tun", "tün", "dın", "din",
+        "dun", "dün", "tık", "tik", "tuk", "tük", "dık", "dik", "duk", "dük", "tı", "ti", "tu", "tü", "dı",
+        "di", "du", "dü",
+    ],
+    Joiner::Y,
+);
+const YSA: Suffix = suffix(false, &["sam", "san", "sak", "sem", "sen", "sek", "sa", "se"], Joiner::Y);
+const YMUS: Suffix = suffix(true, &["mış", "miş", "muş", "müş"], Joiner::Y);
+const YKEN: Suffix = suffix(false, &["ken"], Joiner::Y);
+
+/// A Snowball Turkish stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::TurkishStemmer;
+/// let mut stemmer = TurkishStemmer::new();
+/// assert_eq!(stemmer.stem("kitaplarımızdaki"), "kitap");
+/// assert_eq!(stemmer.stem("doktoruymuşsunuz"), "doktor");
+/// assert_eq!(stemmer.stem("IRMAKLAR"), "ırmak");
+/// ```
+#[derive(Debug, Default)]
+pub struct TurkishStemmer {
+    /// Word being stemmed
+    buffer: Vec<char>,
+    /// Position the steps work back from
+    cursor: usize,
+    /// Start and end of the slice to delete
+    bra: usize,
+    ket: usize,
+    /// Cleared when a plural "-lAr" ends the nominal verb suffixes
+    continue_noun_suffixes: bool,
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl TurkishStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        TurkishStemmer::default()
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    ///
+    /// Folding loses "ı", "ö", "ü", "ç", "ş" and "ğ", on which vowel harmony
+    /// and many suffixes depend, so it is only useful for text typed without
+    /// them.
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, which is lowercased first
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        self.buffer.clear();
+        self.buffer.extend(turkish_lowercase(&word).chars());
+
+        if self.buffer.iter().filter(|&&c| is_vowel(c)).count() < 2 {
+            return self.buffer.iter().collect();
+        }
+        self.cursor = self.buffer.len();
+        self.nominal_verb_suffixes();
+        if self.continue_noun_suffixes {
+            self.cursor = self.buffer.len();
+            self.noun_suffixes();
+            self.postlude();
+        }
+
+        self.buffer.iter().collect()
+    }
+
+    /// Runs `step`, moving the cursor back to where it was if it fails
+    fn attempt(&mut self, step: impl FnOnce(&mut Self) -> bool) -> bool {
+        let from_end = self.buffer.len() - self.cursor;
+        let found = step(self);
+        if !found { self.cursor = self.buffer.len() - from_end; }
+        found
+    }
+
+    /// Runs `step` and moves the cursor back to where it was
+    fn test(&mut self, step: impl FnOnce(&mut Self) -> bool) -> bool {
+        let from_end = self.buffer.len() - self.cursor;
+        let found = step(self);
+        self.cursor = self.buffer.len() - from_end;
+        found
+    }
+
+    /// Moves back over a letter of `group`
+    fn letter(&mut self, group: impl Fn(char) -> bool) -> bool {
+        if self.cursor == 0 || !group(self.buffer[self.cursor - 1]) { return false; }
+        self.cursor -= 1;
+        true
+    }
+
+    /// Moves back to just after the nearest letter of `group`
+    fn goto(&mut self, group: impl Fn(char) -> bool) -> bool {
+        match (1..=self.cursor).rev().find(|&i| group(self.buffer[i - 1])) {
+            Some(i) => {
+                self.cursor = i;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Marks the cursor as the end of the slice to delete
+    fn start_slice(&mut self) -> bool {
+        self.ket = self.cursor;
+        true
+    }
+
+    /// Deletes the slice from the cursor to its marked end
+    fn delete_slice(&mut self) -> bool {
+        self.bra = self.cursor;
+        self.buffer.drain(self.bra..self.ket);
+        true
+    }
+
+    /// Moves back over a suffix of `class`, with its joining letter
+    fn mark(&mut self, class: &Suffix) -> bool {
+        self.attempt(|s| {
+            if class.harmony && !s.vowel_harmony() { return false; }
+            let Some((_, start)) = find_suffix(&s.buffer[..s.cursor], class.endings, 0) else { return false };
+            s.cursor = start;
+            s.joiner(class.joiner)
+        })
+    }
+
+    /// Moves back over a suffix of the first of `classes` there is one of
+    fn mark_any(&mut self, classes: &[&Suffix]) -> bool {
+        classes.iter().any(|class| self.mark(class))
+    }
+
+    /// Returns true if the last vowel before the cursor is one that the
+    /// vowels before it allow
+    fn vowel_harmony(&mut self) -> bool {
+        self.test(|s| {
+            if !s.goto(is_vowel) { return false; }
+            s.cursor -= 1;
+            let allowed = match s.buffer[s.cursor] {
+                'a' => "aıou",
+                'e' => "eiöü",
+                'ı' => "aı",
+                'i' => "ei",
+                'o' | 'u' => "ou",
+                _ => "öü",
+            };
+            s.goto(|c| allowed.contains(c))
+        })
+    }
+
+    /// Moves back over `joiner` if it follows a vowel (or, for a vowel
+    /// joiner, a consonant); fails if the letter before the cursor is a
+    /// different joiner
+    fn joiner(&mut self, joiner: Joiner) -> bool {
+        let (letter, context): (Group, Group) = match joiner {
+            Joiner::None => return true,
+            Joiner::Y => (|c| c == 'y', is_vowel),
+            Joiner::N => (|c| c == 'n', is_vowel),
+            Joiner::S => (|c| c == 's', is_vowel),
+            Joiner::U => (is_u, |c| !is_vowel(c)),
+        };
+        self.attempt(|s| s.letter(letter) && s.test(|s| s.letter(context)))
+            || (!self.test(|s| s.letter(letter))
+                && self.test(|s| s.cursor > 0 && {
+                    s.cursor -= 1;
+                    s.letter(context)
+                }))
+    }
+
+    /// Removes a "-lAr" and the "-ki" chain before it, if any
+    fn plural_and_chain(&mut self) -> bool {
+        self.attempt(|s| s.start_slice() && s.mark(&LAR) && s.delete_slice() && s.suffix_chain_before_ki())
+    }
+
+    /// Removes a possessive or "-sU", and a "-lAr" chain before it, if any
+    fn possessive_and_chain(&mut self) -> bool {
+        self.attempt(|s| {
+            s.start_slice() && s.mark_any(&[&POSSESSIVES, &SU]) && s.delete_slice() && {
+                s.plural_and_chain();
+                true
+            }
+        })
+    }
+
+    fn nominal_verb_suffixes(&mut self) -> bool {
+        self.attempt(|s| {
+            s.start_slice();
+            s.continue_noun_suffixes = true;
+            let found = s.mark_any(&[&YMUS, &YDU, &YSA, &YKEN])
+                || s.attempt(|s| {
+                    s.mark(&CASINA) && {
+                        s.mark_any(&[&SUNUZ, &LAR, &YUM, &SUN, &YUZ]);
+                        s.mark(&YMUS)
+                    }
+                })
+                || s.attempt(|s| {
+                    s.mark(&LAR) && s.delete_slice() && {
+                        s.attempt(|s| s.start_slice() && s.mark_any(&[&DUR, &YDU, &YSA, &YMUS]));
+                        s.continue_noun_suffixes = false;
+                        true
+                    }
+                })
+                || s.attempt(|s| s.mark(&NUZ) && s.mark_any(&[&YDU, &YSA]))
+                || s.attempt(|s| {
+                    s.mark_any(&[&SUNUZ, &YUZ, &SUN, &YUM]) && s.delete_slice() && {
+                        s.attempt(|s| s.start_slice() && s.mark(&YMUS));
+                        true
+                    }
+                })
+                || s.attempt(|s| {
+                    s.mark(&DUR) && s.delete_slice() && {
+                        s.attempt(|s| {
+                            s.start_slice();
+                            s.mark_any(&[&SUNUZ, &LAR, &YUM, &SUN, &YUZ]);
+                            s.mark(&YMUS)
+                        });
+                        true
+                    }
+                });
+            found && s.delete_slice()
+        })
+    }
+
+    /// Removes a chain of noun suffixes ending with "-ki"
+    fn suffix_chain_before_ki(&mut self) -> bool {
+        self.attempt(|s| {
+            s.start_slice() && s.mark(&KI) && {
+                s.attempt(|s| {
+                    s.mark(&DA) && s.delete_slice() && {
+                        s.attempt(|s| {
+                            s.start_slice();
+                            s.attempt(|s| s.mark(&LAR) && s.delete_slice() && {
+                                s.suffix_chain_before_ki();
+                                true
+                            }) || s.attempt(|s| s.mark(&POSSESSIVES) && s.delete_slice() && s.plural_and_chain())
+                        });
+                        true
+                    }
+                }) || s.attempt(|s| {
+                    s.mark(&NUN) && s.delete_slice() && {
+                        s.attempt(|s| {
+                            s.start_slice();
+                            s.attempt(|s| s.mark(&LARI) && s.delete_slice())
+                                || s.possessive_and_chain()
+                                || s.suffix_chain_before_ki()
+                        });
+                        true
+                    }
+                }) || s.attempt(|s| {
+                    s.mark(&NDA)
+                        && (s.attempt(|s| s.mark(&LARI) && s.delete_slice())
+                            || s.attempt(|s| s.mark(&SU) && s.delete_slice() && {
+                                s.plural_and_chain();
+                                true
+                            })
+                            || s.suffix_chain_before_ki())
+                })
+            }
+        })
+    }
+
+    fn noun_suffixes(&mut self) -> bool {
+        self.attempt(|s| s.start_slice() && s.mark(&LAR) && s.delete_slice() && {
+            s.suffix_chain_before_ki();
+            true
+        }) || self.attempt(|s| {
+            s.start_slice() && s.mark(&NCA) && s.delete_slice() && {
+                let _ = s.attempt(|s| s.start_slice() && s.mark(&LARI) && s.delete_slice())
+                    || s.possessive_and_chain()
+                    || s.plural_and_chain();
+                true
+            }
+        }) || self.attempt(|s| {
+            s.start_slice()
+                && s.mark_any(&[&NDA, &NA])
+                && (s.attempt(|s| s.mark(&LARI) && s.delete_slice())
+                    || s.attempt(|s| s.mark(&SU) && s.delete_slice() && {
+                        s.plural_and_chain();
+                        true
+                    })
+                    || s.suffix_chain_before_ki())
+        }) || self.attempt(|s| {
+            s.start_slice()
+                && s.mark_any(&[&NDAN, &NU])
+                && (s.attempt(|s| s.mark(&SU) && s.delete_slice() && {
+                    s.plural_and_chain();
+                    true
+                }) || s.mark(&LARI))
+        }) || self.attempt(|s| {
+            s.start_slice() && s.mark(&DAN) && s.delete_slice() && {
+                s.attempt(|s| {
+                    s.start_slice();
+                    s.attempt(|s| s.mark(&POSSESSIVES) && s.delete_slice() && {
+                        s.plural_and_chain();
+                        true
+                    }) || s.attempt(|s| s.mark(&LAR) && s.delete_slice() && {
+                        s.suffix_chain_before_ki();
+                        true
+                    }) || s.suffix_chain_before_ki()
+                });
+                true
+            }
+        }) || self.attempt(|s| {
+            s.start_slice() && s.mark_any(&[&NUN, &YLA]) && s.delete_slice() && {
+                let _ = s.plural_and_chain() || s.possessive_and_chain() || s.suffix_chain_before_ki();
+                true
+            }
+        }) || self.attempt(|s| s.start_slice() && s.mark(&LARI) && s.delete_slice())
+            || self.suffix_chain_before_ki()
+            || self.attempt(|s| {
+                s.start_slice() && s.mark_any(&[&DA, &YU, &YA]) && s.delete_slice() && {
+                    s.attempt(|s| {
+                        s.start_slice()
+                            && (s.attempt(|s| s.mark(&POSSESSIVES) && s.delete_slice() && {
+                                s.attempt(|s| s.start_slice() && s.mark(&LAR));
+                                true
+                            }) || s.mark(&LAR))
+                            && s.delete_slice()
+                            && s.start_slice()
+                            && s.suffix_chain_before_ki()
+                    });
+                    true
+                }
+            })
+            || self.possessive_and_chain()
+    }
+
+    /// Restores a vowel after a final "d" or "g" ("kedim" to "kedi", not
+    /// "ked") and devoices a final "b", "c", "d" or "ğ"; "ad" and "soyad"
+    /// are left as they are
+    fn postlude(&mut self) {
+        let b = &mut self.buffer;
+        if b[..] == ['a', 'd'] || b[..] == ['s', 'o', 'y', 'a', 'd'] { return; }
+        if matches!(b.last(), Some('d' | 'g')) {
+            let vowel = match b.iter().rev().find(|&&c| is_vowel(c)) {
+                Some('a' | 'ı') => Some('ı'),
+                Some('e' | 'i') => Some('i'),
+                Some('o' | 'u') => Some('u'),
+                Some('ö' | 'ü') => Some('ü'),
+                _ => None,
+            };
+            b.extend(vowel);
+        }
+        if let Some(last) = b.last_mut() {
+            *last = match *last {
+                'b' => 'p',
+                'c' => 'ç',
+                'd' => 't',
+                'ğ' => 'k',
+                c => c,
+            };
+        }
+    }
+}
+
+impl Stemmer for TurkishStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        TurkishStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    fn unicode_words(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suffix_chains() {
+        // Checked against the Snowball reference implementation
+        let mut stemmer = TurkishStemmer::new();
+        let cases = [
+            ("kitap", "kitap"), ("kitaplar", "kitap"), ("kitabı", "kitap"), ("kitaplarımızdaki", "kitap"),
+            ("doktoruymuşsunuz", "doktor"), ("evlerinden", "ev"), ("kedim", "kedi"), ("ad", "ad"),
+            ("gel", "gel"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_turkish_lowercase() {
+        assert_eq!(turkish_lowercase("IŞIK"), "ışık");
+        assert_eq!(turkish_lowercase("İNCİ"), "inci");
+        assert_eq!(TurkishStemmer::new().stem("KİTAPLARI"), TurkishStemmer::new().stem("kitapları"));
+    }
+
+    #[test]
+    fn test_snowball_vocabulary() {
+        // The words of the test data of the lingua crate's Turkish
+        // language model, each with its stem from the Snowball project's C
+        // libstemmer (<https://snowballstem.org>)
+        let mut stemmer = TurkishStemmer::new();
+        let words = include_str!("../test_data/turkish/voc.txt");
+        let stems = include_str!("../test_data/turkish/output.txt");
+        crate::assert_vocabulary(&mut stemmer, words, stems);
+    }
+}
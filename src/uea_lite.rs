@@ -0,0 +1,162 @@
+//! # UEA-Lite
+//!
+//! A conservative stemmer after UEA-Lite (Jenkins and Smith, University of
+//! East Anglia, 2005), which aims to produce real English words instead of
+//! truncated stems: "studies" becomes "study" and "provided" "provide",
+//! where the Porter stemmer gives "studi" and "provid". That makes its
+//! output suitable for showing to end users, at the cost of conflating
+//! fewer forms ("connection" and "connected" stay apart).
+//!
+//! Each rule replaces one ending: it removes a number of letters and may
+//! append others. The first rule of the table whose ending the word has is
+//! applied, and only once; a rule is skipped if no vowel would be left
+//! before the ending, so "bed" and "sing" are left alone. Before the
+//! rules run:
+//!
+//! - a handful of common words ending in "s" are left alone ("this", "has")
+//! - acronyms are kept whole ("SOS"), and their plurals lose the "s" ("CDs")
+//! - words with digits or other non-letters are left alone
+//! - a possessive "'s" is removed
+
+use crate::fold;
+use crate::stemmer::Stemmer;
+
+/// Words that look like plurals but are not
+const PROBLEM_WORDS: &[&str] = &["as", "during", "has", "is", "this", "was"];
+
+/// The rule table, as (ending, letters to remove, letters to append)
+///
+/// Longer and more specific endings come before the general ones they
+/// would otherwise be caught by.
+const RULES: &[(&str, usize, &str)] = &[
+    // Plural and third person "-s"
+    ("aceous", 6, ""), ("ces", 1, ""), ("cs", 0, ""), ("sis", 0, ""), ("tis", 0, ""),
+    ("ss", 0, ""), ("ous", 0, ""), ("us", 0, ""), ("is", 0, ""),
+    ("eeds", 1, ""), ("ues", 1, ""), ("ees", 1, ""), ("iases", 2, ""), ("uses", 1, ""),
+    ("sses", 2, ""), ("ses", 1, ""), ("anges", 1, ""), ("ies", 3, "y"), ("ings", 4, ""),
+    ("ys", 1, ""), ("s", 1, ""),
+    // Past tense "-ed"
+    ("eed", 0, ""), ("ued", 1, ""), ("ied", 3, "y"),
+    ("tled", 1, ""), ("pled", 1, ""), ("bled", 1, ""), ("eled", 2, ""), ("lled", 2, ""), ("led", 2, ""),
+    ("ened", 2, ""), ("ained", 2, ""), ("erned", 2, ""), ("rned", 2, ""), ("nned", 3, ""),
+    ("oned", 2, ""), ("gned", 2, ""), ("ned", 1, ""),
+    ("ifted", 2, ""), ("ected", 2, ""), ("ided", 1, ""), ("ved", 1, ""), ("ced", 1, ""),
+    ("erred", 3, ""), ("urred", 3, ""), ("lored", 1, ""), ("eared", 2, ""), ("tored", 1, ""),
+    ("ered", 2, ""), ("red", 1, ""),
+    ("tted", 3, ""), ("noted", 1, ""), ("leted", 1, ""), ("ated", 1, ""), ("uted", 1, ""), ("ted", 2, ""),
+    ("pped", 3, ""), ("mmed", 3, ""), ("gged", 3, ""), ("bbed", 3, ""),
+    ("ssed", 2, ""), ("sed", 1, ""), ("zed", 1, ""), ("ged", 1, ""), ("ed", 2, ""),
+    // Present participle "-ing"
+    ("aining", 3, ""), ("ying", 3, ""), ("ving", 3, "e"), ("cing", 3, "e"), ("ating", 3, "e"),
+    ("uting", 3, "e"), ("tting", 4, ""), ("pping", 4, ""), ("nning", 4, ""), ("mming", 4, ""),
+    ("gging", 4, ""), ("ing", 3, ""),
+];
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+/// Returns true if `word` is an acronym such as "NASA" or, with `plural`,
+/// "CDs"
+fn is_acronym(word: &str, plural: bool) -> bool {
+    let letters = if plural { word.strip_suffix('s') } else { Some(word) };
+    matches!(letters, Some(l) if l.len() >= 2 && l.bytes().all(|b| b.is_ascii_uppercase()))
+}
+
+/// A UEA-Lite stemmer
+///
+/// # Examples
+/// ```
+/// # use m_porter_stemmer_rust::UeaLiteStemmer;
+/// let mut stemmer = UeaLiteStemmer::new();
+/// assert_eq!(stemmer.stem("studies"), "study");
+/// assert_eq!(stemmer.stem("provided"), "provide");
+/// assert_eq!(stemmer.stem("running"), "run");
+/// ```
+#[derive(Debug, Default)]
+pub struct UeaLiteStemmer {
+    /// Whether accented Latin letters are folded to ASCII before stemming
+    fold_diacritics: bool,
+}
+
+impl UeaLiteStemmer {
+    /// Creates a new stemmer
+    pub fn new() -> Self {
+        UeaLiteStemmer { fold_diacritics: false }
+    }
+
+    /// Enables or disables folding of accented Latin letters before stemming
+    pub fn with_diacritic_folding(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Returns the stem of `word`, lowercased
+    pub fn stem(&mut self, word: &str) -> String {
+        let word = if self.fold_diacritics { fold::fold_diacritics(word) } else { word.into() };
+        if is_acronym(&word, false) { return word.to_lowercase(); }
+        if is_acronym(&word, true) { return word[..word.len() - 1].to_lowercase(); }
+
+        let mut word = word.to_lowercase();
+        if let Some(len) = word.strip_suffix("'s").map(str::len) {
+            word.truncate(len);
+        }
+        if PROBLEM_WORDS.contains(&word.as_str()) || !word.chars().all(|c| c.is_alphabetic() || c == '\'') {
+            return word;
+        }
+
+        let rule = RULES.iter().find(|(ending, _, _)| {
+            word.strip_suffix(ending).is_some_and(|before| before.chars().any(is_vowel))
+        });
+        if let Some(&(_, remove, append)) = rule {
+            word.truncate(word.len() - remove);
+            word.push_str(append);
+        }
+        word
+    }
+}
+
+impl Stemmer for UeaLiteStemmer {
+    fn stem(&mut self, word: &str) -> String {
+        UeaLiteStemmer::stem(self, word)
+    }
+
+    fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_produces_words() {
+        let mut stemmer = UeaLiteStemmer::new();
+        let cases = [
+            ("studies", "study"), ("studied", "study"), ("studying", "study"), ("classes", "class"),
+            ("causes", "cause"), ("values", "value"), ("trees", "tree"), ("changes", "change"),
+            ("settled", "settle"), ("called", "call"), ("planned", "plan"), ("mentioned", "mention"),
+            ("provided", "provide"), ("moved", "move"), ("referred", "refer"), ("occurred", "occur"),
+            ("considered", "consider"), ("required", "require"), ("committed", "commit"),
+            ("created", "create"), ("started", "start"), ("stopped", "stop"), ("passed", "pass"),
+            ("used", "use"), ("walked", "walk"), ("moving", "move"), ("creating", "create"),
+            ("getting", "get"), ("walking", "walk"), ("days", "day"), ("analysis", "analysis"),
+            ("dog's", "dog"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(stemmer.stem(word), stem, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_words_left_alone() {
+        let mut stemmer = UeaLiteStemmer::new();
+        for word in ["this", "was", "during", "bed", "sing", "red", "class", "famous", "mp3s"] {
+            assert_eq!(stemmer.stem(word), word);
+        }
+        assert_eq!(stemmer.stem("SOS"), "sos");
+        assert_eq!(stemmer.stem("CDs"), "cd");
+        assert_eq!(stemmer.stem("Needs"), "need");
+    }
+}
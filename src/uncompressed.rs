@@ -0,0 +1,60 @@
+//! Stand-ins for the library's `compress` module, in a binary built without
+//! the `compress` feature: there are no formats, so `--compressed` is
+//! refused and every file is read and written as it is
+
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// A compression format, of which there are none
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {}
+
+impl Compression {
+    pub fn from_path(_path: impl AsRef<Path>) -> Option<Self> {
+        None
+    }
+
+    pub fn decoder<'a, R: BufRead + 'a>(self, _input: R) -> io::Result<Box<dyn Read + 'a>> {
+        match self {}
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Err(format!("unknown compression: {:?} (built without the compress feature)", s))
+    }
+}
+
+pub fn detect_reader<'a, R: BufRead + 'a>(input: R) -> io::Result<Box<dyn Read + 'a>> {
+    Ok(Box::new(input))
+}
+
+/// A writer passing the data through unchanged
+pub struct Encoder<W: Write>(W);
+
+impl<W: Write> Encoder<W> {
+    pub fn new(inner: W, compression: Option<Compression>) -> io::Result<Self> {
+        match compression {
+            None => Ok(Encoder(inner)),
+            Some(compression) => match compression {},
+        }
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.0.flush()?;
+        Ok(self.0)
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
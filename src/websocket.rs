@@ -0,0 +1,116 @@
+//! WebSocket connections of the `serve` subcommand
+//!
+//! Built with the `websocket` feature, `GET /ws` with the upgrade headers of
+//! RFC 6455 turns a connection into a stream of text messages, for
+//! search-as-you-type frontends that stem on every keystroke: each message,
+//! a word or a whole query as typed so far, is answered at once with its
+//! stems as `/stem-text` gives them (`Running` -> `run`, `running sho` ->
+//! `run sho`). The frames are read and written by tungstenite, which answers
+//! pings and returns a close before the connection ends.
+
+use std::io::{self, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+
+use m_porter_stemmer_rust::{text, Stemmer};
+use tungstenite::error::ProtocolError;
+use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::{CloseFrame, Role, WebSocketConfig};
+use tungstenite::{Error, Message, WebSocket};
+
+/// Largest message accepted, whole or in fragments
+pub const MAX_MESSAGE: usize = 1 << 20;
+
+/// Returns the `Sec-WebSocket-Accept` answer to a `Sec-WebSocket-Key`
+pub fn accept_key(key: &str) -> String {
+    tungstenite::handshake::derive_accept_key(key.trim().as_bytes())
+}
+
+/// Answers the messages of an upgraded connection until it is closed,
+/// starting with the bytes `read` of it already read past the request head
+///
+/// A message that makes the stemmer panic is answered with an empty message,
+/// and the messages after it get a fresh stemmer.
+pub fn serve_messages(
+    make_stemmer: &impl Fn() -> Box<dyn Stemmer>,
+    stream: impl Read + Write,
+    read: Vec<u8>,
+) -> io::Result<()> {
+    let config = WebSocketConfig::default().max_message_size(Some(MAX_MESSAGE)).max_frame_size(Some(MAX_MESSAGE));
+    let mut socket = WebSocket::from_partially_read(stream, read, Role::Server, Some(config));
+    let mut stemmer = make_stemmer();
+    let code = loop {
+        match socket.read() {
+            Ok(Message::Text(words)) => {
+                let stems = panic::catch_unwind(AssertUnwindSafe(|| text::stem_text(&mut *stemmer, &words)));
+                let stems = stems.unwrap_or_else(|_| {
+                    stemmer = make_stemmer();
+                    String::new()
+                });
+                socket.send(Message::text(stems)).map_err(io_error)?;
+            },
+            Ok(Message::Binary(_)) => break CloseCode::Unsupported,
+            // Pings are answered, and a close returned, on the next read
+            Ok(_) => {},
+            Err(Error::ConnectionClosed | Error::Protocol(ProtocolError::ResetWithoutClosingHandshake)) => {
+                return Ok(())
+            },
+            Err(Error::Io(e)) => return Err(e),
+            Err(Error::Capacity(_)) => break CloseCode::Size,
+            Err(Error::Utf8(_)) => break CloseCode::Invalid,
+            Err(_) => break CloseCode::Protocol,
+        }
+    };
+    let close = CloseFrame { code, reason: "".into() };
+    socket.close(Some(close)).and_then(|()| socket.flush()).map_err(io_error)
+}
+
+/// A tungstenite error, as the `io::Error` the rest of the binary returns
+fn io_error(e: Error) -> io::Error {
+    match e {
+        Error::Io(e) => e,
+        e => io::Error::other(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m_porter_stemmer_rust::PorterStemmer;
+
+    #[test]
+    fn test_accept_key() {
+        // The example of RFC 6455, section 1.3
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    }
+
+    #[test]
+    fn test_serve_messages() {
+        let masked = |first: u8, payload: &[u8]| {
+            let mask = [1, 2, 3, 4];
+            let mut frame = vec![first, 0x80 | payload.len() as u8];
+            frame.extend_from_slice(&mask);
+            frame.extend(payload.iter().enumerate().map(|(i, byte)| byte ^ mask[i % 4]));
+            frame
+        };
+        let serve = |input: Vec<u8>| {
+            // Reads nothing past `input`, and collects what is written
+            let mut output = io::Cursor::new(Vec::new());
+            let make_stemmer = || Box::new(PorterStemmer::new()) as Box<dyn Stemmer>;
+            serve_messages(&make_stemmer, &mut output, input).unwrap();
+            output.into_inner()
+        };
+        let input = [
+            masked(0x81, b"Running"),
+            masked(0x89, b"hi"),
+            // A message in two fragments
+            masked(0x01, b"running sh"),
+            masked(0x80, b"oes"),
+            masked(0x88, &1000u16.to_be_bytes()),
+        ]
+        .concat();
+        assert_eq!(serve(input), b"\x81\x03run\x8a\x02hi\x81\x08run shoe\x88\x02\x03\xe8");
+
+        // A binary message is unsupported data
+        assert_eq!(serve(masked(0x82, b"pony")), b"\x88\x02\x03\xeb");
+    }
+}
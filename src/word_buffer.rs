@@ -0,0 +1,124 @@
+//! # Inline Word Buffer
+//!
+//! The byte buffer [`crate::PorterStemmer`] stems a word in. Most English
+//! words are under 16 bytes, so the buffer keeps up to [`INLINE`] bytes in
+//! the stemmer itself and only spills to the heap for a longer word: a
+//! stemmer, even a new one, stems typical words without allocating. The
+//! heap buffer keeps its capacity once a long word has grown it.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// Bytes held without allocating
+pub(crate) const INLINE: usize = 32;
+
+/// A byte buffer held inline up to [`INLINE`] bytes, and on the heap beyond
+#[derive(Clone)]
+pub(crate) struct WordBuffer {
+    inline: [u8; INLINE],
+    /// Length of the bytes held inline
+    len: usize,
+    /// The bytes, once they no longer fit inline
+    heap: Vec<u8>,
+    spilled: bool,
+}
+
+impl WordBuffer {
+    pub(crate) fn new() -> Self {
+        WordBuffer { inline: [0; INLINE], len: 0, heap: Vec::new(), spilled: false }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.len = 0;
+        self.heap.clear();
+        self.spilled = false;
+    }
+
+    /// Moves the bytes held inline to the heap
+    fn spill(&mut self) {
+        self.heap.extend_from_slice(&self.inline[..self.len]);
+        self.spilled = true;
+    }
+
+    pub(crate) fn push(&mut self, byte: u8) {
+        if !self.spilled && self.len < INLINE {
+            self.inline[self.len] = byte;
+            self.len += 1;
+            return;
+        }
+        if !self.spilled {
+            self.spill();
+        }
+        self.heap.push(byte);
+    }
+
+    pub(crate) fn extend_from_slice(&mut self, bytes: &[u8]) {
+        if !self.spilled && self.len + bytes.len() <= INLINE {
+            self.inline[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+            self.len += bytes.len();
+            return;
+        }
+        if !self.spilled {
+            self.spill();
+        }
+        self.heap.extend_from_slice(bytes);
+    }
+}
+
+impl Default for WordBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Extend<u8> for WordBuffer {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, bytes: I) {
+        for byte in bytes {
+            self.push(byte);
+        }
+    }
+}
+
+impl Deref for WordBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        if self.spilled { &self.heap } else { &self.inline[..self.len] }
+    }
+}
+
+impl DerefMut for WordBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        if self.spilled { &mut self.heap } else { &mut self.inline[..self.len] }
+    }
+}
+
+impl fmt::Debug for WordBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&String::from_utf8_lossy(self), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spill() {
+        let mut buffer = WordBuffer::new();
+        buffer.extend_from_slice(b"generalizations");
+        buffer.extend(b"generalizations".iter().copied());
+        buffer.push(b's');
+        assert_eq!((&buffer[..], buffer.spilled), (&b"generalizationsgeneralizationss"[..], false));
+        buffer.extend_from_slice(b"es");
+        assert_eq!((&buffer[..], buffer.spilled), (&b"generalizationsgeneralizationsses"[..], true));
+        buffer[0] = b'G';
+        assert_eq!(buffer[..3], *b"Gen");
+
+        buffer.clear();
+        buffer.extend_from_slice(b"cats");
+        assert_eq!((&buffer[..], buffer.spilled), (&b"cats"[..], false));
+        assert!(buffer.heap.capacity() >= 33);
+        assert_eq!(format!("{:?}", buffer), "\"cats\"");
+    }
+}
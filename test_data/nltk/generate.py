@@ -0,0 +1,99 @@
+"""Writes porter_modes.tsv: the stems of each of NLTK's PorterStemmer modes
+for one wordlist, test_data/porter/voc.txt followed by the words of NLTK's
+documentation that it lacks.
+
+    python test_data/nltk/generate.py
+
+ORIGINAL_ALGORITHM and MARTIN_EXTENSIONS are stemmed by Martin Porter's C
+program, test_data/stem.c, compiled with $CC (or cc): MARTIN_EXTENSIONS as
+it is, which NLTK's test_vocabulary_martin_mode holds that mode to, and
+ORIGINAL_ALGORITHM with its three points of DEPARTURE from the paper
+reverted, which is how NLTK made the porter_original_output.txt its
+test_vocabulary_original_mode holds that mode to.
+
+NLTK_EXTENSIONS is stemmed by NLTK itself where it is installed
+(pip install nltk), which also checks the other two columns against NLTK's
+modes. Otherwise only the words with a stem in NLTK's documentation get one,
+and the column has "-" for the rest (an empty stem is the original
+algorithm's stem of "s").
+"""
+
+import os
+import subprocess
+import sys
+import tempfile
+from pathlib import Path
+
+HERE = Path(__file__).resolve().parent
+DATA = HERE.parent
+MODES = ["ORIGINAL_ALGORITHM", "MARTIN_EXTENSIONS", "NLTK_EXTENSIONS"]
+
+# NLTK_EXTENSIONS stems of the pluralised words of nltk/test/stem.doctest,
+# and of the case and "oed" checks of nltk/test/unit/test_stem.py
+DOCUMENTED = {
+    "caresses": "caress", "flies": "fli", "dies": "die", "mules": "mule", "denied": "deni", "died": "die",
+    "agreed": "agre", "owned": "own", "humbled": "humbl", "sized": "size", "meeting": "meet",
+    "stating": "state", "siezing": "siez", "itemization": "item", "sensational": "sensat",
+    "traditional": "tradit", "reference": "refer", "colonizer": "colon", "plotted": "plot", "On": "on",
+    "I": "i", "Github": "github", "oed": "o",
+}
+
+# The lines of stem.c marked DEPARTURE, and what the paper has instead
+DEPARTURES = [
+    ('case \'l\': if (ends("\\03" "bli")) { r("\\03" "ble"); break; }',
+     'case \'l\': if (ends("\\04" "abli")) { r("\\04" "able"); break; }'),
+    ('case \'g\': if (ends("\\04" "logi")) { r("\\03" "log"); break; }', ""),
+    ("if (k <= k0+1) return k;", ""),
+]
+
+
+def c_stems(source, words, tmp):
+    """Compiles `source` as a C program and returns its stems of `words`"""
+    c_file, program = tmp / "stem.c", tmp / "stem"
+    c_file.write_text(source)
+    subprocess.run([os.environ.get("CC", "cc"), "-O2", "-w", "-o", str(program), str(c_file)], check=True)
+    (tmp / "words.txt").write_text("".join(word + "\n" for word in words))
+    output = subprocess.run([str(program), str(tmp / "words.txt")], check=True, capture_output=True, text=True)
+    stems = output.stdout.splitlines()
+    assert len(stems) == len(words), "the C program stemmed %d of %d words" % (len(stems), len(words))
+    return stems
+
+
+def main():
+    words = (DATA / "porter" / "voc.txt").read_text().split()
+    words += [word for word in DOCUMENTED if word not in set(words)]
+
+    martin_source = (DATA / "stem.c").read_text()
+    original_source = martin_source
+    for departure, paper in DEPARTURES:
+        assert departure in original_source, "stem.c has no line " + departure
+        original_source = original_source.replace(departure, paper)
+    with tempfile.TemporaryDirectory() as tmp:
+        columns = {
+            "ORIGINAL_ALGORITHM": c_stems(original_source, words, Path(tmp)),
+            "MARTIN_EXTENSIONS": c_stems(martin_source, words, Path(tmp)),
+        }
+
+    try:
+        from nltk.stem.porter import PorterStemmer
+    except ImportError:
+        print("NLTK is not installed: NLTK_EXTENSIONS has only its documented stems", file=sys.stderr)
+        columns["NLTK_EXTENSIONS"] = [DOCUMENTED.get(word, "-") for word in words]
+    else:
+        for mode in MODES:
+            stemmer = PorterStemmer(mode=getattr(PorterStemmer, mode))
+            stems = [stemmer.stem(word) for word in words]
+            if mode in columns:
+                differ = [word for word, a, b in zip(words, stems, columns[mode]) if a != b]
+                assert not differ, "NLTK's %s differs from the C program for %s" % (mode, differ[:10])
+            columns[mode] = stems
+
+    with open(HERE / "porter_modes.tsv", "w", newline="\n") as out:
+        out.write("# Stems of NLTK's PorterStemmer modes, written by generate.py\n")
+        out.write("word\t" + "\t".join(MODES) + "\n")
+        for i, word in enumerate(words):
+            out.write(word + "\t" + "\t".join(columns[mode][i] for mode in MODES) + "\n")
+
+
+if __name__ == "__main__":
+    main()